@@ -15,6 +15,10 @@ export enum SecretKind {
   AwsAccessKey = "awsAccessKey",
   HelmRepo = "helmRepo",
   AzureServicePrincipal = "azureServicePrincipal",
+  ApiToken = "apiToken",
+  KeyValue = "keyValue",
+  SshKey = "sshKey",
+  TlsCertificate = "tlsCertificate",
 }
 
 export enum SecretVersion {
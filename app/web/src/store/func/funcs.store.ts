@@ -41,6 +41,9 @@ export type FuncWithDetails = FuncSummary & {
   types: string;
   isRevertible: boolean;
   associations?: FuncAssociations;
+  timeoutSecs?: number;
+  memoizable?: boolean;
+  egressPolicy?: "allow" | "deny";
 };
 
 type FuncExecutionState =
@@ -1,4 +1,4 @@
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 
 use council_server::server::config::{Config, ConfigError, ConfigFile, StandardConfigFile};
 
@@ -23,9 +23,41 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) nats_url: Option<String>,
 
+    /// File used to checkpoint in-flight work so it survives a restart
+    #[arg(long)]
+    pub(crate) state_path: Option<String>,
+
+    /// Which council instance this is, when change sets are sharded across several instances
+    #[arg(long, requires = "shard_count")]
+    pub(crate) shard_index: Option<u32>,
+
+    /// How many council instances change sets are sharded across
+    #[arg(long)]
+    pub(crate) shard_count: Option<u32>,
+
     /// Disable OpenTelemetry on startup
     #[arg(long)]
     pub(crate) disable_opentelemetry: bool,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Prints a snapshot of the dependency graphs a running council is holding
+    Introspect {
+        /// Output format of the snapshot
+        #[arg(long, value_enum, default_value_t = IntrospectFormat::Table)]
+        format: IntrospectFormat,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub(crate) enum IntrospectFormat {
+    Dot,
+    Json,
+    Table,
 }
 
 impl TryFrom<Args> for Config {
@@ -36,6 +68,15 @@ impl TryFrom<Args> for Config {
             if let Some(url) = args.nats_url {
                 config_map.set("nats.url", url);
             }
+            if let Some(state_path) = args.state_path {
+                config_map.set("state_path", state_path);
+            }
+            if let Some(shard_index) = args.shard_index {
+                config_map.set("shard_index", i64::from(shard_index));
+            }
+            if let Some(shard_count) = args.shard_count {
+                config_map.set("shard_count", i64::from(shard_count));
+            }
         })?
         .try_into()
     }
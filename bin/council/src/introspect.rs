@@ -0,0 +1,66 @@
+use color_eyre::Result;
+use comfy_table::{presets::UTF8_FULL, Table};
+use council_server::{server::Config, Client, Snapshot};
+use si_data_nats::NatsClient;
+
+use crate::args::IntrospectFormat;
+
+pub(crate) async fn run(config: Config, format: IntrospectFormat) -> Result<()> {
+    let nats = NatsClient::new(config.nats()).await?;
+    let subject_prefix = match config.subject_prefix() {
+        Some(prefix) => format!("{prefix}.council"),
+        None => "council".to_owned(),
+    };
+    let snapshot = Client::introspect(&nats, &subject_prefix).await?;
+
+    match format {
+        IntrospectFormat::Dot => print!("{}", snapshot.to_dot()),
+        IntrospectFormat::Json => println!("{}", serde_json::to_string_pretty(&snapshot)?),
+        IntrospectFormat::Table => print_tables(&snapshot),
+    }
+
+    Ok(())
+}
+
+fn print_tables(snapshot: &Snapshot) {
+    let mut queue = Table::new();
+    queue
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["Value creation", "Reply channel"]);
+    if let Some(processing) = &snapshot.value_creation_processing {
+        queue.add_row(vec!["creating", processing]);
+    }
+    for queued in &snapshot.value_creation_queue {
+        queue.add_row(vec!["queued", queued]);
+    }
+    println!("{queue}");
+
+    for (change_set_id, nodes) in &snapshot.change_sets {
+        let mut nodes = nodes.clone();
+        nodes.sort_by_key(|node| node.status);
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            "Node",
+            "Status",
+            "Processing",
+            "Wanted by",
+            "Depends on",
+        ]);
+        for node in nodes {
+            table.add_row(vec![
+                node.node_id.to_string(),
+                node.status.to_string(),
+                node.processing_reply_channel.unwrap_or_default(),
+                node.wanted_by_reply_channels.join("\n"),
+                node.depends_on
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ]);
+        }
+        println!("Change set {change_set_id}");
+        println!("{table}");
+    }
+}
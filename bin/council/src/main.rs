@@ -5,6 +5,7 @@ use telemetry_application::{
 use tokio::sync::watch;
 
 mod args;
+mod introspect;
 
 const RT_DEFAULT_THREAD_STACK_SIZE: usize = 2 * 1024 * 1024 * 3;
 
@@ -44,7 +45,7 @@ async fn async_main() -> Result<()> {
 }
 
 async fn run(
-    args: args::Args,
+    mut args: args::Args,
     mut telemetry: ApplicationTelemetryClient,
     shutdown_request_rx: watch::Receiver<()>,
 ) -> Result<()> {
@@ -57,7 +58,12 @@ async fn run(
         telemetry.disable_opentelemetry().await?;
     }
 
+    let command = args.command.take();
     let config = council_server::server::Config::try_from(args)?;
+    if let Some(args::Command::Introspect { format }) = command {
+        return introspect::run(config, format).await;
+    }
+
     let server = council_server::Server::new_with_config(config).await?;
     let (subscriber_started_tx, _subscriber_started_rx) = watch::channel(());
     server
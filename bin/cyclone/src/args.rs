@@ -103,6 +103,28 @@ pub(crate) struct Args {
     /// Cyclone decryption key file location [example: /run/cyclone/cyclone.key]
     #[arg(long)]
     pub(crate) decryption_key: PathBuf,
+
+    /// Execution timeout in seconds, used when a request doesn't set a shorter one
+    #[arg(long)]
+    pub(crate) execution_timeout: Option<u64>,
+
+    /// Limits the lang server heap to the given value in MiB
+    #[arg(long)]
+    pub(crate) lang_server_memory_limit: Option<u64>,
+
+    /// Limits the lang server CPU time to the given value in seconds
+    #[arg(long)]
+    pub(crate) lang_server_cpu_limit: Option<u64>,
+
+    /// Limits the output of an execution to the given value in bytes
+    #[arg(long)]
+    pub(crate) output_limit_bytes: Option<usize>,
+
+    /// Enables running lang servers denied network access in their own network namespace, which
+    /// needs permission to create user namespaces. Without it, only `fetch` is blocked by the
+    /// lang server
+    #[arg(long)]
+    pub(crate) enable_lang_server_network_isolation: bool,
 }
 
 impl TryFrom<Args> for Config {
@@ -144,6 +166,22 @@ impl TryFrom<Args> for Config {
             builder.limit_requests(limit_requests);
         }
 
+        if let Some(execution_timeout) = args.execution_timeout {
+            builder.execution_timeout(Duration::from_secs(execution_timeout));
+        }
+        if let Some(lang_server_memory_limit) = args.lang_server_memory_limit {
+            builder.lang_server_memory_limit_mib(lang_server_memory_limit);
+        }
+        if let Some(lang_server_cpu_limit) = args.lang_server_cpu_limit {
+            builder.lang_server_cpu_limit(Duration::from_secs(lang_server_cpu_limit));
+        }
+        if let Some(output_limit_bytes) = args.output_limit_bytes {
+            builder.output_limit_bytes(output_limit_bytes);
+        }
+        if args.enable_lang_server_network_isolation {
+            builder.lang_server_network_isolation(true);
+        }
+
         builder.build().map_err(Into::into)
     }
 }
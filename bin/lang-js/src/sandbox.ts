@@ -21,6 +21,25 @@ export class UnknownSandboxKind extends Error {
     }
 }
 
+export class EgressBlocked extends Error {
+    constructor() {
+        const message =
+            "function execution attempted to reach the network, which its egress policy denies";
+        super(message);
+        this.name = "EgressBlocked";
+    }
+}
+
+// Set by cyclone when the function being executed isn't allowed network access
+function egressDenied(): boolean {
+    return process.env.SI_EGRESS_POLICY === "deny";
+}
+
+// Throws right away rather than rejecting, so that the failure reaches the function result
+function blockedFetch(): never {
+    throw new EgressBlocked();
+}
+
 function commonSandbox(executionId: string): Sandbox {
     return {
         console: makeConsole(executionId),
@@ -32,7 +51,7 @@ function resolverFunctionSandbox(executionId: string): Sandbox {
     return {
         // Is there any risk leaking this function plainly here? It smells like a risk for RCE outside of the sandbox
         YAML: { stringify: yaml.dump },
-        fetch,
+        fetch: egressDenied() ? blockedFetch : fetch,
         // definitely a risk
         // lol
         siExec: makeExec(executionId),
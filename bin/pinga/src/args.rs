@@ -57,12 +57,30 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) concurrency: Option<u32>,
 
+    /// The number of concurrent jobs a single workspace can have processed [default: unlimited]
+    #[arg(long)]
+    pub(crate) workspace_concurrency: Option<u32>,
+
     /// Instance ID [example: 01GWEAANW5BVFK5KDRVS6DEY0F"]
     ///
     /// And instance ID is used when tracking the execution of jobs in a way that can be traced
     /// back to an instance of a Pinga service.
     #[arg(long)]
     pub(crate) instance_id: Option<String>,
+
+    /// Consume and enqueue jobs through the durable JetStream job queue
+    ///
+    /// Jobs on the durable queue are only removed once a Pinga instance has finished running
+    /// them, so they survive Pinga restarting mid-job.
+    #[arg(long)]
+    pub(crate) durable_queue: bool,
+
+    /// Replay the jobs on the dead letter queue, then exit
+    ///
+    /// Each job that exhausted its retries is put back on the job queue (the durable one, with
+    /// --durable-queue) with a fresh set of attempts.
+    #[arg(long)]
+    pub(crate) replay_dead_letters: bool,
 }
 
 impl TryFrom<Args> for Config {
@@ -94,9 +112,18 @@ impl TryFrom<Args> for Config {
             if let Some(concurrency) = args.concurrency {
                 config_map.set("concurrency_limit", i64::from(concurrency));
             }
+            if let Some(workspace_concurrency) = args.workspace_concurrency {
+                config_map.set(
+                    "workspace_concurrency_limit",
+                    i64::from(workspace_concurrency),
+                );
+            }
             if let Some(instance_id) = args.instance_id {
                 config_map.set("instance_id", instance_id);
             }
+            if args.durable_queue {
+                config_map.set("durable_queue", true);
+            }
 
             config_map.set("pg.application_name", NAME);
         })?
@@ -47,10 +47,17 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         telemetry.disable_opentelemetry().await?;
     }
 
+    let replay_dead_letters = args.replay_dead_letters;
     let config = Config::try_from(args)?;
 
     start_tracing_level_signal_handler_task(&telemetry)?;
 
+    if replay_dead_letters {
+        let replayed = Server::replay_dead_letters(config).await?;
+        info!(replayed, "replayed dead letters");
+        return Ok(());
+    }
+
     Server::from_config(config).await?.run().await?;
 
     Ok(())
@@ -73,6 +73,12 @@ pub(crate) struct Args {
 
     /// Location on disk of available packages
     pub(crate) pkgs_path: Option<String>,
+
+    /// Enqueue jobs onto the durable JetStream job queue
+    ///
+    /// Pinga must be consuming the durable queue too (see its --durable-queue).
+    #[arg(long)]
+    pub(crate) durable_queue: bool,
 }
 
 impl TryFrom<Args> for Config {
@@ -107,6 +113,9 @@ impl TryFrom<Args> for Config {
             if let Some(pkgs_path) = args.pkgs_path {
                 config_map.set("pkgs_path", pkgs_path);
             }
+            if args.durable_queue {
+                config_map.set("durable_queue", true);
+            }
 
             config_map.set("pg.application_name", NAME);
         })?
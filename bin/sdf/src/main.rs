@@ -3,9 +3,7 @@
 use std::path::PathBuf;
 
 use color_eyre::Result;
-use sdf_server::{
-    Config, IncomingStream, JobProcessorClientCloser, JobProcessorConnector, MigrationMode, Server,
-};
+use sdf_server::{Config, IncomingStream, JobProcessorClientCloser, MigrationMode, Server};
 use telemetry_application::{
     prelude::*, start_tracing_level_signal_handler_task, ApplicationTelemetryClient,
     TelemetryClient, TelemetryConfig,
@@ -13,8 +11,6 @@ use telemetry_application::{
 
 mod args;
 
-type JobProcessor = sdf_server::NatsProcessor;
-
 const RT_DEFAULT_THREAD_STACK_SIZE: usize = 2 * 1024 * 1024 * 10;
 
 fn main() -> Result<()> {
@@ -77,10 +73,13 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     let nats = Server::connect_to_nats(config.nats()).await?;
 
-    let (job_client, job_processor) = JobProcessor::connect(&config).await?;
+    let (job_client, job_processor) = Server::connect_job_processor(&config).await?;
 
-    let (_resource_job_client, resource_job_processor) = JobProcessor::connect(&config).await?;
-    let (_, status_receiver_job_processor) = JobProcessor::connect(&config).await?;
+    let (_resource_job_client, resource_job_processor) =
+        Server::connect_job_processor(&config).await?;
+    let (_, status_receiver_job_processor) = Server::connect_job_processor(&config).await?;
+    let (_, garbage_collector_job_processor) = Server::connect_job_processor(&config).await?;
+    let (_, history_event_purger_job_processor) = Server::connect_job_processor(&config).await?;
 
     let pg_pool = Server::create_pg_pool(config.pg_pool()).await?;
 
@@ -116,6 +115,10 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     let posthog_client = Server::start_posthog(config.posthog()).await?;
 
+    let resource_scheduler_config = config.resource_scheduler().clone();
+    let garbage_collector_config = config.change_set_garbage_collector().clone();
+    let history_event_purger_config = config.history_event_purger().clone();
+
     match config.incoming_stream() {
         IncomingStream::HTTPSocket(_) => {
             let (server, initial_shutdown_broadcast_rx) = Server::http(
@@ -131,13 +134,38 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 module_index_url,
             )?;
             let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let third_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fourth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+
+            Server::start_history_event_purger(
+                pg_pool.clone(),
+                nats.clone(),
+                history_event_purger_job_processor,
+                veritech.clone(),
+                encryption_key,
+                history_event_purger_config,
+                fourth_shutdown_broadcast_rx,
+            )
+            .await;
 
-            Server::start_resource_refresh_scheduler(
+            Server::start_change_set_garbage_collector(
+                pg_pool.clone(),
+                nats.clone(),
+                garbage_collector_job_processor,
+                veritech.clone(),
+                encryption_key,
+                garbage_collector_config,
+                third_shutdown_broadcast_rx,
+            )
+            .await;
+
+            let resource_scheduler = Server::start_resource_refresh_scheduler(
                 pg_pool.clone(),
                 nats.clone(),
                 resource_job_processor,
                 veritech.clone(),
                 encryption_key,
+                resource_scheduler_config,
                 initial_shutdown_broadcast_rx,
             )
             .await;
@@ -153,6 +181,14 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
             .await?;
 
             server.run().await?;
+
+            let status = resource_scheduler.status().await;
+            info!(
+                last_tick_at = ?status.last_tick_at,
+                last_error = ?status.last_error,
+                tracked_components = status.components.len(),
+                "resource refresh scheduler stopped"
+            );
         }
         IncomingStream::UnixDomainSocket(_) => {
             let (server, initial_shutdown_broadcast_rx) = Server::uds(
@@ -169,13 +205,38 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
             )
             .await?;
             let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let third_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fourth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+
+            Server::start_history_event_purger(
+                pg_pool.clone(),
+                nats.clone(),
+                history_event_purger_job_processor,
+                veritech.clone(),
+                encryption_key,
+                history_event_purger_config,
+                fourth_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_change_set_garbage_collector(
+                pg_pool.clone(),
+                nats.clone(),
+                garbage_collector_job_processor,
+                veritech.clone(),
+                encryption_key,
+                garbage_collector_config,
+                third_shutdown_broadcast_rx,
+            )
+            .await;
 
-            Server::start_resource_refresh_scheduler(
+            let resource_scheduler = Server::start_resource_refresh_scheduler(
                 pg_pool.clone(),
                 nats.clone(),
                 resource_job_processor,
                 veritech.clone(),
                 encryption_key,
+                resource_scheduler_config,
                 initial_shutdown_broadcast_rx,
             )
             .await;
@@ -191,6 +252,14 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
             .await?;
 
             server.run().await?;
+
+            let status = resource_scheduler.status().await;
+            info!(
+                last_tick_at = ?status.last_tick_at,
+                last_error = ?status.last_error,
+                tracked_components = status.components.len(),
+                "resource refresh scheduler stopped"
+            );
         }
     }
 
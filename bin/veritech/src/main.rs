@@ -42,6 +42,12 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         CycloneSpec::LocalUds(_) => {
             Server::for_cyclone_uds(config).await?.run().await?;
         }
+        CycloneSpec::SandboxedUds(_) => {
+            Server::for_cyclone_sandboxed_uds(config)
+                .await?
+                .run()
+                .await?;
+        }
     }
 
     Ok(())
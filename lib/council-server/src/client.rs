@@ -1,9 +1,28 @@
 use futures::StreamExt;
 use si_data_nats::{NatsClient, Subscriber};
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use telemetry::prelude::*;
 
-use crate::{Graph, Id, Request, Response};
+use crate::{
+    server::{introspection_subject, shard_for, shard_subject},
+    Graph, Id, Request, Response, Snapshot,
+};
+
+/// How often a job should let council know it is still alive. Council evicts jobs it hasn't
+/// heard from within its configured job lease, so this must be comfortably shorter than that.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait for each council shard to answer an introspection request.
+const INTROSPECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The shard holding the value creation queue.
+const COORDINATOR_SHARD: u32 = 0;
 
 #[remain::sorted]
 #[derive(Debug)]
@@ -12,74 +31,125 @@ pub enum State {
     Shutdown,
 }
 
+/// Sends a job's requests to council, routing each one to the shards it concerns: value creation
+/// is handled by the coordinator and the dependency graph by the shard owning the change set.
 #[derive(Debug, Clone)]
 pub struct PubClient {
     change_set_id: Id,
-    pub_channel: String,
+    job_id: Id,
+    council_subject: String,
     reply_channel: String,
+    /// Told by the coordinator when it lets the job create values, zero until then.
+    shard_count: Arc<AtomicU32>,
+    /// Whether we've registered a dependency graph with the shard owning our change set.
+    graph_registered: Arc<AtomicBool>,
     nats: NatsClient,
 }
 
 impl PubClient {
     pub async fn finished_creating_values(&self) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ValueCreationDone)?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
-        Ok(())
+        self.publish(&[COORDINATOR_SHARD], &Request::ValueCreationDone)
+            .await
     }
 
     pub async fn register_dependency_graph(&self, dependency_graph: Graph) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ValueDependencyGraph {
-            change_set_id: self.change_set_id,
-            dependency_graph,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
+        // The coordinator needs to see it too, in case we still hold value creation
+        self.publish(
+            &[COORDINATOR_SHARD, self.owner_shard()],
+            &Request::ValueDependencyGraph {
+                change_set_id: self.change_set_id,
+                dependency_graph,
+            },
+        )
+        .await?;
+        self.graph_registered.store(true, Ordering::Relaxed);
         Ok(())
     }
 
     pub async fn processed_value(&self, node_id: Id) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ProcessedValue {
-            change_set_id: self.change_set_id,
-            node_id,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
-        Ok(())
+        self.publish(
+            &[self.owner_shard()],
+            &Request::ProcessedValue {
+                change_set_id: self.change_set_id,
+                node_id,
+            },
+        )
+        .await
     }
 
     pub async fn failed_processing_value(&self, node_id: Id) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ValueProcessingFailed {
-            change_set_id: self.change_set_id,
-            node_id,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
-        Ok(())
+        self.publish(
+            &[self.owner_shard()],
+            &Request::ValueProcessingFailed {
+                change_set_id: self.change_set_id,
+                node_id,
+            },
+        )
+        .await
+    }
+
+    /// Renews our lease with the shard we're waiting on: the coordinator until we've registered
+    /// our graph, the shard owning our change set afterwards. A shard that doesn't know us
+    /// answers with [`Response::Restarted`].
+    pub async fn heartbeat(&self) -> Result<()> {
+        let shard = if self.graph_registered.load(Ordering::Relaxed) {
+            self.owner_shard()
+        } else {
+            COORDINATOR_SHARD
+        };
+        self.publish(
+            &[shard],
+            &Request::Heartbeat {
+                change_set_id: self.change_set_id,
+            },
+        )
+        .await
     }
 
     pub async fn bye(self) -> Result<()> {
-        let message = serde_json::to_vec(&Request::Bye {
-            change_set_id: self.change_set_id,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
+        self.publish(
+            &[COORDINATOR_SHARD, self.owner_shard()],
+            &Request::Bye {
+                change_set_id: self.change_set_id,
+            },
+        )
+        .await
+    }
+
+    /// The shard owning our change set. Until the coordinator has told us how many shards there
+    /// are, we haven't registered anything with it yet and only talk to the coordinator.
+    fn owner_shard(&self) -> u32 {
+        match self.shard_count.load(Ordering::Relaxed) {
+            0 => COORDINATOR_SHARD,
+            shard_count => shard_for(self.change_set_id, shard_count),
+        }
+    }
+
+    async fn publish(&self, shards: &[u32], request: &Request) -> Result<()> {
+        let message = serde_json::to_vec(request)?;
+        let mut published = Vec::with_capacity(shards.len());
+        for shard in shards {
+            if published.contains(shard) {
+                continue;
+            }
+            let channel = format!(
+                "{}.{}",
+                shard_subject(&self.council_subject, *shard),
+                self.job_id
+            );
+            self.nats
+                .publish_with_reply(channel, &self.reply_channel, message.clone())
+                .await?;
+            published.push(*shard);
+        }
         Ok(())
     }
 }
 
 #[derive(Debug)]
 pub struct Client {
-    change_set_id: Id,
-    pub_channel: String,
-    reply_channel: String,
+    pub_client: PubClient,
     subscriber: Subscriber,
-    nats: NatsClient,
 }
 
 impl Client {
@@ -89,24 +159,70 @@ impl Client {
         id: Id,
         change_set_id: Id,
     ) -> Result<Self> {
-        let pub_channel = format!("{subject_prefix}.{id}");
-        let reply_channel = format!("{pub_channel}.reply");
+        let reply_channel = format!("{subject_prefix}.{id}.reply");
         Ok(Self {
-            pub_channel,
-            change_set_id,
             subscriber: nats.subscribe(&reply_channel).await?,
-            reply_channel,
-            nats,
+            pub_client: PubClient {
+                change_set_id,
+                job_id: id,
+                council_subject: subject_prefix.to_owned(),
+                reply_channel,
+                shard_count: Default::default(),
+                graph_registered: Default::default(),
+                nats,
+            },
         })
     }
 
-    pub fn clone_into_pub(&self) -> PubClient {
-        PubClient {
-            pub_channel: self.pub_channel.clone(),
-            reply_channel: self.reply_channel.clone(),
-            change_set_id: self.change_set_id,
-            nats: self.nats.clone(),
+    /// Asks council for a [`Snapshot`] of everything it is currently holding, merging the
+    /// answers of every shard. This doesn't need a job, so it can be used by tooling to inspect
+    /// a live council. If some shard doesn't answer in time the snapshot is returned without it.
+    pub async fn introspect(nats: &NatsClient, subject_prefix: &str) -> Result<Snapshot> {
+        let reply_channel = nats.new_inbox();
+        let mut subscriber = nats.subscribe(&reply_channel).await?;
+        let message = serde_json::to_vec(&Request::Introspect)?;
+        nats.publish_with_reply(
+            introspection_subject(subject_prefix),
+            &reply_channel,
+            message,
+        )
+        .await?;
+
+        let mut snapshot: Option<Snapshot> = None;
+        loop {
+            let msg = match tokio::time::timeout(INTROSPECTION_TIMEOUT, subscriber.next()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) | Err(_) => break,
+            };
+            if msg.payload().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_slice::<Response>(msg.payload())? {
+                Response::Introspection {
+                    snapshot: shard_snapshot,
+                } => match snapshot.as_mut() {
+                    Some(snapshot) => snapshot.merge(shard_snapshot),
+                    None => snapshot = Some(shard_snapshot),
+                },
+                response => return Err(Error::UnexpectedResponse(format!("{response:?}"))),
+            }
+
+            if snapshot
+                .as_ref()
+                .map(Snapshot::is_complete)
+                .unwrap_or(false)
+            {
+                break;
+            }
         }
+        subscriber.unsubscribe().await?;
+
+        snapshot.ok_or(Error::NoListenerAvailable)
+    }
+
+    pub fn clone_into_pub(&self) -> PubClient {
+        self.pub_client.clone()
     }
 
     // None means subscriber has been unsubscribed or that the connection has been closed
@@ -119,7 +235,7 @@ impl Client {
             match res {
                 Ok(msg) => break msg,
                 Err(_) => {
-                    warn!(change_set_id = ?self.change_set_id, pub_channel = ?self.pub_channel, reply_channel = ?self.reply_channel, "Council client waiting for response for 60 seconds");
+                    warn!(change_set_id = ?self.pub_client.change_set_id, council_subject = ?self.pub_client.council_subject, reply_channel = ?self.pub_client.reply_channel, "Council client waiting for response for 60 seconds");
                 }
             }
         };
@@ -136,15 +252,28 @@ impl Client {
     }
 
     pub async fn wait_to_create_values(&mut self) -> Result<State> {
-        let message = serde_json::to_vec(&Request::CreateValues)?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
+        self.pub_client
+            .publish(&[COORDINATOR_SHARD], &Request::CreateValues)
             .await?;
 
-        match self.fetch_response().await? {
-            Some(Response::OkToCreate) => Ok(State::Continue),
-            Some(Response::Shutdown) => Ok(State::Shutdown),
-            resp => unreachable!("{:?}", resp),
+        loop {
+            match self.fetch_response().await? {
+                Some(Response::OkToCreate { shard_count }) => {
+                    self.pub_client
+                        .shard_count
+                        .store(shard_count, Ordering::Relaxed);
+                    return Ok(State::Continue);
+                }
+                Some(Response::Shutdown) => return Ok(State::Shutdown),
+                // Council may have lost our place in the queue, so ask again. This is a no-op if
+                // it restored our place from its checkpoint.
+                Some(Response::Restarted { .. }) => {
+                    self.pub_client
+                        .publish(&[COORDINATOR_SHARD], &Request::CreateValues)
+                        .await?;
+                }
+                resp => unreachable!("{:?}", resp),
+            }
         }
     }
 
@@ -178,4 +307,6 @@ pub enum Error {
     NoListenerAvailable,
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
+    #[error("unexpected response from council: {0}")]
+    UnexpectedResponse(String),
 }
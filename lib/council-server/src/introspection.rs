@@ -0,0 +1,102 @@
+//! Point-in-time views of what council is holding, used to debug stuck updates.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::Id;
+
+#[remain::sorted]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeStatus {
+    /// A job has been told to process the node and council is waiting for its result.
+    Processing,
+    /// Every dependency is done, the node will be handed out on the next pass.
+    Ready,
+    /// The node still depends on nodes that haven't been processed.
+    Waiting,
+}
+
+impl std::fmt::Display for NodeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Processing => write!(f, "processing"),
+            Self::Ready => write!(f, "ready"),
+            Self::Waiting => write!(f, "waiting"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodeSnapshot {
+    pub node_id: Id,
+    pub status: NodeStatus,
+    pub processing_reply_channel: Option<String>,
+    pub wanted_by_reply_channels: Vec<String>,
+    pub depends_on: Vec<Id>,
+}
+
+/// Everything council is holding at the time of the request. Nodes that are done are removed
+/// from the graph as soon as they're processed, so they never show up here.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Snapshot {
+    /// The shards that contributed to this snapshot.
+    pub shards: Vec<u32>,
+    pub shard_count: u32,
+    pub value_creation_processing: Option<String>,
+    pub value_creation_queue: Vec<String>,
+    pub change_sets: HashMap<Id, Vec<NodeSnapshot>>,
+}
+
+impl Snapshot {
+    /// Whether every shard has contributed to this snapshot.
+    pub fn is_complete(&self) -> bool {
+        self.shards.len() as u32 >= self.shard_count
+    }
+
+    /// Folds the snapshot of another shard into this one. Shards own disjoint change sets and
+    /// only the coordinator holds the value creation queue, so nothing needs reconciling.
+    pub fn merge(&mut self, other: Snapshot) {
+        if !self.shards.is_empty() && other.shards.iter().any(|s| self.shards.contains(s)) {
+            return;
+        }
+
+        self.shards.extend(other.shards);
+        self.shard_count = self.shard_count.max(other.shard_count);
+        if other.value_creation_processing.is_some() {
+            self.value_creation_processing = other.value_creation_processing;
+        }
+        self.value_creation_queue.extend(other.value_creation_queue);
+        self.change_sets.extend(other.change_sets);
+    }
+
+    /// Renders the dependency graphs in the DOT language, with one cluster per change set.
+    /// Edges point from a node to the nodes it depends on.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph council {\n");
+        for (change_set_id, nodes) in &self.change_sets {
+            dot.push_str(&format!("  subgraph \"cluster_{change_set_id}\" {{\n"));
+            dot.push_str(&format!("    label = \"change set {change_set_id}\";\n"));
+            for node in nodes {
+                let color = match node.status {
+                    NodeStatus::Processing => "orange",
+                    NodeStatus::Ready => "green",
+                    NodeStatus::Waiting => "gray",
+                };
+                let owner = node.processing_reply_channel.as_deref().unwrap_or("-");
+                dot.push_str(&format!(
+                    "    \"{change_set_id}/{}\" [label = \"{}\\n{}\\n{owner}\", color = {color}];\n",
+                    node.node_id, node.node_id, node.status
+                ));
+                for dependency in &node.depends_on {
+                    dot.push_str(&format!(
+                        "    \"{change_set_id}/{}\" -> \"{change_set_id}/{dependency}\";\n",
+                        node.node_id
+                    ));
+                }
+            }
+            dot.push_str("  }\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -3,9 +3,11 @@ use std::collections::HashMap;
 use ulid::Ulid;
 
 pub mod client;
+pub mod introspection;
 pub mod server;
 
 pub use client::{Client, PubClient};
+pub use introspection::{NodeSnapshot, NodeStatus, Snapshot};
 pub use server::Server;
 
 #[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash)]
@@ -60,6 +62,10 @@ pub enum Request {
         change_set_id: Id,
     },
     CreateValues,
+    Heartbeat {
+        change_set_id: Id,
+    },
+    Introspect,
     ProcessedValue {
         change_set_id: Id,
         node_id: Id,
@@ -81,7 +87,9 @@ pub enum Request {
 pub enum Response {
     BeenProcessed { node_id: Id },
     Failed { node_id: Id },
-    OkToCreate,
+    Introspection { snapshot: Snapshot },
+    OkToCreate { shard_count: u32 },
     OkToProcess { node_ids: Vec<Id> },
+    Restarted { resumed_graph: bool },
     Shutdown,
 }
@@ -1,49 +1,119 @@
-use crate::{Graph, Id, Request, Response};
-use std::time::Duration;
+use crate::{Graph, Id, Request, Response, Snapshot};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
-use futures::StreamExt;
+use futures::{stream, StreamExt};
 use si_data_nats::NatsClient;
 use telemetry::prelude::*;
 use tokio::{signal, sync::watch};
 
 pub mod config;
 mod graph;
+mod lease;
+mod shard;
+mod state;
 pub use config::Config;
 
 use graph::{ChangeSetGraph, ValueCreationQueue};
+use lease::JobLeases;
+pub use shard::{shard_for, Shard};
+use state::StateStore;
+
+/// How often council looks for jobs and nodes whose lease has run out.
+const LEASE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// How long council may go without any messages before it complains about pending work.
+const IDLE_WARNING_INTERVAL: Duration = Duration::from_secs(60);
+/// How long council waits after a change before checkpointing it, so that a burst of messages
+/// results in a single write. Jobs that registered within this window before a crash aren't in
+/// the checkpoint, and are told about the restart the next time they contact us instead.
+const CHECKPOINT_DEBOUNCE: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone)]
 pub struct Server {
     nats: NatsClient,
+    state_store: Option<StateStore>,
+    node_lease: Duration,
+    job_lease: Duration,
+    shard: Shard,
 }
 
 impl Server {
     pub async fn new_with_config(config: config::Config) -> Result<Self> {
+        let shard =
+            Shard::new(config.shard_index(), config.shard_count()).ok_or(Error::InvalidShard {
+                index: config.shard_index(),
+                count: config.shard_count(),
+            })?;
+
         Ok(Self {
             nats: NatsClient::new(config.nats()).await?,
+            state_store: config.state_path().map(StateStore::new),
+            node_lease: config.node_lease(),
+            job_lease: config.job_lease(),
+            shard,
         })
     }
 
+    /// Restores the last checkpoint, if one exists, and tells every job it references that
+    /// council has restarted so they can re-register anything that may have been lost. Jobs whose
+    /// dependency graph was restored are told so, as sending it again would process it twice.
+    async fn restore_state(&self) -> Result<(ValueCreationQueue, ChangeSetGraph)> {
+        let state = match &self.state_store {
+            Some(state_store) => state_store.load().await?,
+            None => None,
+        };
+        let Some(mut state) = state else {
+            return Ok((ValueCreationQueue::default(), ChangeSetGraph::default()));
+        };
+
+        // Results reported while we were down never reached us, so every node that was being
+        // processed must be handed out again.
+        state.change_set_graph.reset_processing();
+
+        let graph_reply_channels = state.change_set_graph.reply_channels();
+        let mut reply_channels = state.value_creation_queue.reply_channels();
+        reply_channels.extend(graph_reply_channels.iter().cloned());
+        info!(
+            jobs = reply_channels.len(),
+            "Restored council state from checkpoint"
+        );
+        for reply_channel in reply_channels {
+            let resumed_graph = graph_reply_channels.contains(&reply_channel);
+            self.nats
+                .publish(
+                    reply_channel,
+                    serde_json::to_vec(&Response::Restarted { resumed_graph })?,
+                )
+                .await?;
+        }
+
+        Ok((state.value_creation_queue, state.change_set_graph))
+    }
+
     pub async fn run(
         self,
         subscriber_started_tx: watch::Sender<()>,
         mut shutdown_request_rx: watch::Receiver<()>,
     ) -> Result<()> {
-        let channel_suffix = "council.*";
-        let subscriber_channel = if let Some(prefix) = self.nats.metadata().subject_prefix() {
-            format!("{}.{}", prefix, channel_suffix)
+        let council_subject = if let Some(prefix) = self.nats.metadata().subject_prefix() {
+            format!("{prefix}.council")
         } else {
-            channel_suffix.to_string()
-        };
-        let mut subscriber = loop {
-            match self.nats.subscribe(subscriber_channel.clone()).await {
-                Ok(sub) => break sub,
-                Err(err) => {
-                    error!("Unable to subscribe to the council request channel on nats: {err}");
-                    tokio::time::sleep(Duration::from_millis(1000)).await;
-                }
-            }
+            "council".to_string()
         };
+        // Jobs send their requests straight to the shards they concern, while introspection
+        // requests go to every shard.
+        let shard_subscriber = self
+            .subscribe(format!(
+                "{}.*",
+                shard_subject(&council_subject, self.shard.index())
+            ))
+            .await;
+        let introspection_subscriber = self
+            .subscribe(introspection_subject(&council_subject))
+            .await;
+        let mut subscriber = stream::select(shard_subscriber, introspection_subscriber);
         let _ = subscriber_started_tx.send(());
 
         let mut sigterm_watcher = signal::unix::signal(signal::unix::SignalKind::terminate())?;
@@ -68,21 +138,59 @@ impl Server {
             }
         });
 
-        let mut value_create_queue = ValueCreationQueue::default();
-        let mut complete_graph = ChangeSetGraph::default();
+        let (mut value_create_queue, mut complete_graph) = self.restore_state().await?;
+        let mut job_leases = JobLeases::new(self.job_lease);
+        // Jobs restored from a checkpoint get a full lease to prove they're still around.
+        for reply_channel in value_create_queue
+            .reply_channels()
+            .into_iter()
+            .chain(complete_graph.reply_channels())
+        {
+            job_leases.renew(&reply_channel, Instant::now());
+        }
+        let mut last_lease_check = Instant::now();
+        let mut idle_since = Instant::now();
+        // Whether the state changed since the last checkpoint, and when that checkpoint was taken.
+        let mut dirty = false;
+        let mut last_checkpoint = Instant::now();
         loop {
+            if last_lease_check.elapsed() >= LEASE_CHECK_INTERVAL {
+                match expire_leases(
+                    &self.nats,
+                    &mut complete_graph,
+                    &mut value_create_queue,
+                    &mut job_leases,
+                    self.node_lease,
+                    Instant::now(),
+                )
+                .await
+                {
+                    Ok(expired) => dirty |= expired,
+                    Err(err) => {
+                        dirty = true;
+                        error!("Unable to expire leases: {err}");
+                    }
+                }
+                last_lease_check = Instant::now();
+            }
+
             if let Some(reply_channel) = value_create_queue.fetch_next() {
+                dirty = true;
                 info!(%reply_channel, "OK to create AttributeValues");
                 self.nats
                     .publish(
                         reply_channel,
-                        serde_json::to_vec(&Response::OkToCreate).unwrap(),
+                        serde_json::to_vec(&Response::OkToCreate {
+                            shard_count: self.shard.count(),
+                        })
+                        .unwrap(),
                     )
                     .await
                     .unwrap();
             }
 
             for (reply_channel, node_id) in complete_graph.fetch_all_available() {
+                dirty = true;
                 info!(%reply_channel, %node_id, "Ok to process AttributeValue");
                 self.nats
                     .publish(
@@ -96,16 +204,30 @@ impl Server {
                     .unwrap();
             }
 
-            let sleep = tokio::time::sleep(Duration::from_secs(60));
+            if dirty && last_checkpoint.elapsed() >= CHECKPOINT_DEBOUNCE {
+                self.checkpoint(&value_create_queue, &complete_graph).await;
+                dirty = false;
+                last_checkpoint = Instant::now();
+            }
+
+            // Wake up in time to checkpoint pending changes even if no message comes in.
+            let sleep_for = if dirty {
+                CHECKPOINT_DEBOUNCE.saturating_sub(last_checkpoint.elapsed())
+            } else {
+                LEASE_CHECK_INTERVAL
+            };
+            let sleep = tokio::time::sleep(sleep_for);
             tokio::pin!(sleep);
-            // FIXME: handle timeouts
             let (reply_channel, request) = tokio::select! {
                 _ = &mut sleep => {
-                    if value_create_queue.is_busy() {
-                        warn!(?value_create_queue, "Council is waiting for a job to create values for at least 60 seconds");
-                    }
-                    if !complete_graph.is_empty() {
-                        warn!(?complete_graph, "Council has values in graph but has been waiting for messages for 60 seconds");
+                    if idle_since.elapsed() >= IDLE_WARNING_INTERVAL {
+                        if value_create_queue.is_busy() {
+                            warn!(?value_create_queue, "Council is waiting for a job to create values for at least 60 seconds");
+                        }
+                        if !complete_graph.is_empty() {
+                            warn!(?complete_graph, "Council has values in graph but has been waiting for messages for 60 seconds");
+                        }
+                        idle_since = Instant::now();
                     }
                     continue;
                 }
@@ -135,33 +257,81 @@ impl Server {
                 else => unreachable!(),
             };
 
-            match request {
+            if !self.shard.handles(&request) {
+                warn!(%reply_channel, ?request, shard = self.shard.index(), "Received a request meant for another shard, ignoring it");
+                continue;
+            }
+
+            // Introspection requests come from operators rather than jobs, so they shouldn't
+            // hold a lease or count as activity.
+            if let Request::Introspect = request {
+                if let Err(err) = introspect(
+                    &self.nats,
+                    self.shard,
+                    &complete_graph,
+                    &value_create_queue,
+                    reply_channel,
+                )
+                .await
+                {
+                    error!("Unable to handle introspection request: {err}");
+                }
+                continue;
+            }
+
+            // A job we hold no lease for registered with a council that went away before
+            // checkpointing it (or was evicted), so whatever it's waiting on will never come.
+            if !job_leases.contains(&reply_channel) && assumes_registered_job(&request) {
+                warn!(%reply_channel, ?request, "Received a request from an unknown job, telling it we restarted");
+                if let Err(err) = self
+                    .nats
+                    .publish(
+                        reply_channel.clone(),
+                        serde_json::to_vec(&Response::Restarted {
+                            resumed_graph: false,
+                        })
+                        .unwrap(),
+                    )
+                    .await
+                {
+                    error!(%reply_channel, "Unable to tell job we restarted: {err}");
+                }
+            }
+
+            idle_since = Instant::now();
+            job_leases.renew(&reply_channel, idle_since);
+            // Heartbeats only renew the lease, which isn't part of the checkpoint.
+            dirty |= !matches!(request, Request::Heartbeat { .. });
+
+            let result = match request {
                 Request::CreateValues => {
-                    // Move to a falible wrapper
                     job_would_like_to_create_attribute_values(
                         &mut value_create_queue,
                         reply_channel,
                     )
                     .await
-                    .unwrap();
                 }
                 Request::ValueCreationDone => {
-                    job_finished_value_creation(&mut value_create_queue, reply_channel)
-                        .await
-                        .unwrap();
+                    job_finished_value_creation(&mut value_create_queue, reply_channel).await
                 }
                 Request::ValueDependencyGraph {
                     change_set_id,
                     dependency_graph,
                 } => {
-                    register_graph_from_job(
-                        &mut complete_graph,
-                        reply_channel,
-                        change_set_id,
-                        dependency_graph,
-                    )
-                    .await
-                    .unwrap();
+                    release_value_creation_if_held(&mut value_create_queue, &reply_channel);
+                    if self.shard.owns(change_set_id) {
+                        register_graph_from_job(
+                            &mut complete_graph,
+                            reply_channel,
+                            change_set_id,
+                            dependency_graph,
+                        )
+                        .await
+                    } else {
+                        // The job only heartbeats the owning shard from now on.
+                        job_leases.release(&reply_channel);
+                        Ok(())
+                    }
                 }
                 Request::ProcessedValue {
                     change_set_id,
@@ -175,9 +345,11 @@ impl Server {
                         node_id,
                     )
                     .await
-                    .unwrap();
                 }
+                Request::Heartbeat { .. } => Ok(()),
+                Request::Introspect => unreachable!("introspection requests are handled above"),
                 Request::Bye { change_set_id } => {
+                    job_leases.release(&reply_channel);
                     job_is_going_away(
                         &mut complete_graph,
                         &mut value_create_queue,
@@ -185,7 +357,6 @@ impl Server {
                         change_set_id,
                     )
                     .await
-                    .unwrap();
                 }
                 Request::ValueProcessingFailed {
                     change_set_id,
@@ -199,13 +370,45 @@ impl Server {
                         node_id,
                     )
                     .await
-                    .unwrap();
                 }
             };
+            if let Err(err) = result {
+                // A job that is out of sync with us (e.g. after a restart) must not take
+                // council down with it.
+                error!("Unable to handle request: {err}");
+            }
+        }
+
+        if dirty {
+            self.checkpoint(&value_create_queue, &complete_graph).await;
         }
 
         Ok(())
     }
+
+    async fn subscribe(&self, subject: String) -> si_data_nats::Subscriber {
+        loop {
+            match self.nats.subscribe(subject.clone()).await {
+                Ok(sub) => break sub,
+                Err(err) => {
+                    error!(%subject, "Unable to subscribe to a council request channel on nats: {err}");
+                    tokio::time::sleep(Duration::from_millis(1000)).await;
+                }
+            }
+        }
+    }
+
+    async fn checkpoint(
+        &self,
+        value_create_queue: &ValueCreationQueue,
+        complete_graph: &ChangeSetGraph,
+    ) {
+        if let Some(state_store) = &self.state_store {
+            if let Err(err) = state_store.save(value_create_queue, complete_graph).await {
+                error!(path = %state_store.path().display(), "Unable to checkpoint council state: {err}");
+            }
+        }
+    }
 }
 
 // Note: All messages from Pinga include the change set ID.
@@ -231,6 +434,32 @@ impl Server {
 // |                                                                            |                                                                                     |
 // | Goto: Wait                                                                 | Goto: Check graph data.                                                             |
 
+/// Whether `request` only makes sense from a job that has already registered with us, as opposed
+/// to the requests a job registers with. `ValueCreationDone` is excluded since the job registers
+/// its graph right after sending it, which recovers anything we lost.
+fn assumes_registered_job(request: &Request) -> bool {
+    match request {
+        Request::Heartbeat { .. }
+        | Request::ProcessedValue { .. }
+        | Request::ValueProcessingFailed { .. } => true,
+        Request::Bye { .. }
+        | Request::CreateValues
+        | Request::Introspect
+        | Request::ValueCreationDone
+        | Request::ValueDependencyGraph { .. } => false,
+    }
+}
+
+/// The subject a shard receives job requests on, each under the id of the job sending it.
+pub fn shard_subject(council_subject: &str, shard_index: u32) -> String {
+    format!("{council_subject}.shard.{shard_index}")
+}
+
+/// The subject every shard listens on for introspection requests.
+pub fn introspection_subject(council_subject: &str) -> String {
+    format!("{council_subject}.introspect")
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[remain::sorted]
@@ -238,14 +467,20 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 pub enum Error {
     #[error(transparent)]
     Config(#[from] config::ConfigError),
+    #[error("shard index {index} is out of range for {count} shards")]
+    InvalidShard { index: u32, count: u32 },
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Nats(#[from] si_data_nats::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
     #[error("Job reported finishing processing, but we expected a different job to be processing")]
     ShouldNotBeProcessingByJob,
     #[error("Unexpected JobId")]
     UnexpectedJobId,
+    #[error("Unknown ChangeSetId")]
+    UnknownChangeSetId,
     #[error("Unknown NodeId")]
     UnknownNodeId,
 }
@@ -273,6 +508,18 @@ pub async fn job_finished_value_creation(
     value_create_queue.finished_processing(&reply_channel)
 }
 
+/// Jobs only register their graph after they're done creating values, so if we still think
+/// the job is creating values its `ValueCreationDone` got lost (e.g. across a restart).
+pub fn release_value_creation_if_held(
+    value_create_queue: &mut ValueCreationQueue,
+    reply_channel: &str,
+) {
+    if value_create_queue.is_processing(reply_channel) {
+        warn!(%reply_channel, "Job registered its graph while still holding value creation, releasing it");
+        value_create_queue.remove(reply_channel);
+    }
+}
+
 #[instrument(level = "info")]
 pub async fn register_graph_from_job(
     complete_graph: &mut ChangeSetGraph,
@@ -349,3 +596,248 @@ pub async fn job_is_going_away(
 
     Ok(())
 }
+
+#[instrument(level = "info", skip(nats, complete_graph, value_create_queue))]
+pub async fn introspect(
+    nats: &NatsClient,
+    shard: Shard,
+    complete_graph: &ChangeSetGraph,
+    value_create_queue: &ValueCreationQueue,
+    reply_channel: String,
+) -> Result<(), Error> {
+    let snapshot = Snapshot {
+        shards: vec![shard.index()],
+        shard_count: shard.count(),
+        value_creation_processing: value_create_queue.processing().cloned(),
+        value_creation_queue: value_create_queue.queued(),
+        change_sets: complete_graph.snapshot(),
+    };
+    nats.publish(
+        reply_channel,
+        serde_json::to_vec(&Response::Introspection { snapshot })?,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Fails every node that has been processing for longer than `node_lease`, and evicts every job
+/// whose lease has run out, failing the nodes it was processing. Failures propagate to dependent
+/// nodes and get reported to every subscriber, just like `ValueProcessingFailed`.
+///
+/// Returns whether anything was expired.
+#[instrument(level = "debug", skip_all)]
+pub async fn expire_leases(
+    nats: &NatsClient,
+    complete_graph: &mut ChangeSetGraph,
+    value_create_queue: &mut ValueCreationQueue,
+    job_leases: &mut JobLeases,
+    node_lease: Duration,
+    now: Instant,
+) -> Result<bool, Error> {
+    let (expired, failure_notifications) = evict_expired(
+        complete_graph,
+        value_create_queue,
+        job_leases,
+        node_lease,
+        now,
+    );
+
+    for (reply_channel, node_id) in failure_notifications {
+        nats.publish(
+            reply_channel,
+            serde_json::to_vec(&Response::Failed { node_id })?,
+        )
+        .await?;
+    }
+
+    Ok(expired)
+}
+
+/// Removes everything whose lease has run out by `now` from the graph and the value creation
+/// queue, returning whether anything expired along with the `Failed` notifications to send.
+fn evict_expired(
+    complete_graph: &mut ChangeSetGraph,
+    value_create_queue: &mut ValueCreationQueue,
+    job_leases: &mut JobLeases,
+    node_lease: Duration,
+    now: Instant,
+) -> (bool, Vec<(String, Id)>) {
+    let evicted_reply_channels = job_leases.take_expired(now);
+
+    let mut expired_nodes: HashSet<(Id, Id, String)> =
+        HashSet::from_iter(complete_graph.expired_nodes(node_lease, now));
+    for reply_channel in &evicted_reply_channels {
+        warn!(%reply_channel, "Job lease expired, evicting job");
+        for (change_set_id, node_id) in complete_graph.nodes_processed_by(reply_channel) {
+            expired_nodes.insert((change_set_id, node_id, reply_channel.clone()));
+        }
+    }
+    let expired = !evicted_reply_channels.is_empty() || !expired_nodes.is_empty();
+
+    let mut failure_notifications = Vec::new();
+    for (change_set_id, node_id, reply_channel) in expired_nodes {
+        warn!(%reply_channel, %change_set_id, %node_id, "Lease expired, failing node");
+        // The node may already be gone as a dependent of another expired node, which mustn't
+        // keep us from evicting the rest.
+        match complete_graph.remove_node_and_dependents(reply_channel, change_set_id, node_id) {
+            Ok(notifications) => failure_notifications.extend(notifications),
+            Err(err) => warn!(%change_set_id, %node_id, "Unable to fail expired node: {err}"),
+        }
+    }
+
+    for reply_channel in evicted_reply_channels {
+        value_create_queue.remove(&reply_channel);
+        for change_set_id in complete_graph.change_set_ids() {
+            complete_graph.remove_channel(change_set_id, &reply_channel);
+        }
+    }
+
+    (expired, failure_notifications)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const NODE_LEASE: Duration = Duration::from_secs(60);
+    const JOB_LEASE: Duration = Duration::from_secs(30);
+
+    /// A graph where `job` is processing `node_id`, which `dependent_id` depends on.
+    fn processing_graph(change_set_id: Id, node_id: Id, dependent_id: Id) -> ChangeSetGraph {
+        let mut complete_graph = ChangeSetGraph::default();
+        complete_graph
+            .merge_dependency_graph(
+                "job".to_owned(),
+                HashMap::from([(dependent_id, vec![node_id])]),
+                change_set_id,
+            )
+            .expect("failed to merge graph");
+        assert_eq!(
+            complete_graph.fetch_all_available(),
+            vec![("job".to_owned(), node_id)]
+        );
+        complete_graph
+    }
+
+    #[test]
+    fn renewed_job_keeps_its_nodes() {
+        let start = Instant::now();
+        let (change_set_id, node_id, dependent_id) = (Id::default(), Id::default(), Id::default());
+        let mut complete_graph = processing_graph(change_set_id, node_id, dependent_id);
+        let mut value_create_queue = ValueCreationQueue::default();
+        let mut job_leases = JobLeases::new(JOB_LEASE);
+        job_leases.renew("job", start);
+
+        job_leases.renew("job", start + JOB_LEASE / 2);
+        let (expired, failure_notifications) = evict_expired(
+            &mut complete_graph,
+            &mut value_create_queue,
+            &mut job_leases,
+            NODE_LEASE,
+            start + JOB_LEASE + JOB_LEASE / 4,
+        );
+
+        assert!(!expired);
+        assert!(failure_notifications.is_empty());
+        assert_eq!(
+            complete_graph.nodes_processed_by("job"),
+            vec![(change_set_id, node_id)]
+        );
+    }
+
+    #[test]
+    fn expired_job_is_evicted_once() {
+        let start = Instant::now();
+        let (change_set_id, node_id, dependent_id) = (Id::default(), Id::default(), Id::default());
+        let mut complete_graph = processing_graph(change_set_id, node_id, dependent_id);
+        let mut value_create_queue = ValueCreationQueue::default();
+        value_create_queue.push("job".to_owned());
+        let mut job_leases = JobLeases::new(JOB_LEASE);
+        job_leases.renew("job", start);
+
+        let (expired, failure_notifications) = evict_expired(
+            &mut complete_graph,
+            &mut value_create_queue,
+            &mut job_leases,
+            NODE_LEASE,
+            start + JOB_LEASE + Duration::from_secs(1),
+        );
+
+        assert!(expired);
+        assert_eq!(
+            HashSet::<(String, Id)>::from_iter(failure_notifications),
+            HashSet::from([
+                ("job".to_owned(), node_id),
+                ("job".to_owned(), dependent_id)
+            ])
+        );
+        assert!(complete_graph.is_empty());
+        assert!(value_create_queue.reply_channels().is_empty());
+        assert!(!job_leases.contains("job"));
+
+        let (expired, failure_notifications) = evict_expired(
+            &mut complete_graph,
+            &mut value_create_queue,
+            &mut job_leases,
+            NODE_LEASE,
+            start + JOB_LEASE + Duration::from_secs(2),
+        );
+
+        assert!(!expired);
+        assert!(failure_notifications.is_empty());
+    }
+
+    #[test]
+    fn expired_node_is_failed_once() {
+        let start = Instant::now();
+        let (change_set_id, node_id, dependent_id) = (Id::default(), Id::default(), Id::default());
+        let mut complete_graph = processing_graph(change_set_id, node_id, dependent_id);
+        let mut value_create_queue = ValueCreationQueue::default();
+        // The job itself stays alive, only the node it's processing runs out of time.
+        let mut job_leases = JobLeases::new(NODE_LEASE * 2);
+        job_leases.renew("job", start);
+
+        let (expired, failure_notifications) = evict_expired(
+            &mut complete_graph,
+            &mut value_create_queue,
+            &mut job_leases,
+            NODE_LEASE,
+            start + NODE_LEASE / 2,
+        );
+        assert!(!expired);
+        assert!(failure_notifications.is_empty());
+
+        let (expired, failure_notifications) = evict_expired(
+            &mut complete_graph,
+            &mut value_create_queue,
+            &mut job_leases,
+            NODE_LEASE,
+            start + NODE_LEASE + Duration::from_secs(1),
+        );
+
+        assert!(expired);
+        assert_eq!(
+            HashSet::<(String, Id)>::from_iter(failure_notifications),
+            HashSet::from([
+                ("job".to_owned(), node_id),
+                ("job".to_owned(), dependent_id)
+            ])
+        );
+        assert!(complete_graph.is_empty());
+        assert!(job_leases.contains("job"));
+
+        let (expired, failure_notifications) = evict_expired(
+            &mut complete_graph,
+            &mut value_create_queue,
+            &mut job_leases,
+            NODE_LEASE,
+            start + NODE_LEASE + Duration::from_secs(2),
+        );
+
+        assert!(!expired);
+        assert!(failure_notifications.is_empty());
+    }
+}
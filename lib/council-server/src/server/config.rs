@@ -1,3 +1,8 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
@@ -18,15 +23,53 @@ pub type Result<T, E = ConfigError> = std::result::Result<T, E>;
 pub struct Config {
     #[builder(default = "NatsConfig::default()")]
     nats: NatsConfig,
+
+    #[builder(setter(into, strip_option), default)]
+    state_path: Option<PathBuf>,
+
+    #[builder(default = "default_node_lease()")]
+    node_lease: Duration,
+
+    #[builder(default = "default_job_lease()")]
+    job_lease: Duration,
+
+    #[builder(default = "0")]
+    shard_index: u32,
+
+    #[builder(default = "1")]
+    shard_count: u32,
 }
 
 impl StandardConfig for Config {
     type Builder = ConfigBuilder;
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConfigFile {
     nats: NatsConfig,
+    #[serde(default)]
+    state_path: Option<PathBuf>,
+    #[serde(default = "default_node_lease_secs")]
+    node_lease_secs: u64,
+    #[serde(default = "default_job_lease_secs")]
+    job_lease_secs: u64,
+    #[serde(default)]
+    shard_index: u32,
+    #[serde(default = "default_shard_count")]
+    shard_count: u32,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            nats: Default::default(),
+            state_path: None,
+            node_lease_secs: default_node_lease_secs(),
+            job_lease_secs: default_job_lease_secs(),
+            shard_index: 0,
+            shard_count: default_shard_count(),
+        }
+    }
 }
 
 impl StandardConfigFile for ConfigFile {
@@ -39,6 +82,13 @@ impl TryFrom<ConfigFile> for Config {
     fn try_from(value: ConfigFile) -> Result<Self> {
         let mut config = Config::builder();
         config.nats(value.nats);
+        if let Some(state_path) = value.state_path {
+            config.state_path(state_path);
+        }
+        config.node_lease(Duration::from_secs(value.node_lease_secs));
+        config.job_lease(Duration::from_secs(value.job_lease_secs));
+        config.shard_index(value.shard_index);
+        config.shard_count(value.shard_count);
         config.build().map_err(Into::into)
     }
 }
@@ -54,4 +104,53 @@ impl Config {
     pub fn subject_prefix(&self) -> Option<&str> {
         self.nats.subject_prefix.as_deref()
     }
+
+    /// Gets a reference to the path where council checkpoints its in-flight state, if any.
+    pub fn state_path(&self) -> Option<&Path> {
+        self.state_path.as_deref()
+    }
+
+    /// Gets how long a job may spend processing a single node before the node is failed.
+    #[must_use]
+    pub fn node_lease(&self) -> Duration {
+        self.node_lease
+    }
+
+    /// Gets how long a job may go without contacting council before it is evicted.
+    #[must_use]
+    pub fn job_lease(&self) -> Duration {
+        self.job_lease
+    }
+
+    /// Gets which of the [`Self::shard_count`] council instances this one is.
+    #[must_use]
+    pub fn shard_index(&self) -> u32 {
+        self.shard_index
+    }
+
+    /// Gets how many council instances change sets are partitioned across.
+    #[must_use]
+    pub fn shard_count(&self) -> u32 {
+        self.shard_count
+    }
+}
+
+fn default_shard_count() -> u32 {
+    1
+}
+
+fn default_node_lease_secs() -> u64 {
+    10 * 60
+}
+
+fn default_job_lease_secs() -> u64 {
+    60
+}
+
+fn default_node_lease() -> Duration {
+    Duration::from_secs(default_node_lease_secs())
+}
+
+fn default_job_lease() -> Duration {
+    Duration::from_secs(default_job_lease_secs())
 }
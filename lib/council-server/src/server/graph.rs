@@ -1,18 +1,27 @@
-use crate::{server::Error, Graph, Id};
-use std::collections::{HashMap, HashSet, VecDeque};
+use crate::{server::Error, Graph, Id, NodeSnapshot};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
 
 mod node_metadata;
 
 use node_metadata::NodeMetadata;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ValueCreationQueue {
     processing: Option<String>,
     queue: VecDeque<String>,
 }
 
 impl ValueCreationQueue {
+    /// Queues the job behind `reply_channel`, unless it's already waiting or creating values.
+    /// Jobs ask again when they're told council restarted, which may not have lost them.
     pub fn push(&mut self, reply_channel: String) {
+        if self.processing.as_ref() == Some(&reply_channel) || self.queue.contains(&reply_channel) {
+            return;
+        }
         self.queue.push_back(reply_channel);
     }
 
@@ -40,13 +49,33 @@ impl ValueCreationQueue {
         Ok(())
     }
 
+    pub fn is_processing(&self, reply_channel: &str) -> bool {
+        self.processing.as_deref() == Some(reply_channel)
+    }
+
     pub fn remove(&mut self, reply_channel: &str) {
         self.processing = self.processing.take().filter(|el| *el != reply_channel);
         self.queue.retain(|el| reply_channel != el);
     }
+
+    pub fn processing(&self) -> Option<&String> {
+        self.processing.as_ref()
+    }
+
+    pub fn queued(&self) -> Vec<String> {
+        self.queue.iter().cloned().collect()
+    }
+
+    pub fn reply_channels(&self) -> HashSet<String> {
+        self.processing
+            .iter()
+            .chain(self.queue.iter())
+            .cloned()
+            .collect()
+    }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ChangeSetGraph {
     dependency_data: HashMap<Id, HashMap<Id, NodeMetadata>>,
 }
@@ -56,6 +85,77 @@ impl ChangeSetGraph {
         self.dependency_data.is_empty()
     }
 
+    pub fn reply_channels(&self) -> HashSet<String> {
+        let mut reply_channels = HashSet::new();
+        for graph in self.dependency_data.values() {
+            for metadata in graph.values() {
+                reply_channels.extend(metadata.wanted_by_reply_channels());
+                reply_channels.extend(metadata.processing_reply_channel().cloned());
+            }
+        }
+        reply_channels
+    }
+
+    /// Put every node that was being processed back up for grabs, so it gets handed out again
+    /// by [`Self::fetch_all_available`]. Used after restoring a checkpoint, since any results
+    /// reported while council was down have been lost.
+    pub fn reset_processing(&mut self) {
+        for graph in self.dependency_data.values_mut() {
+            for metadata in graph.values_mut() {
+                metadata.reset_processing();
+            }
+        }
+    }
+
+    /// Every node that has been processing for longer than `lease` by `now`, along with the
+    /// change set it belongs to and the reply channel of the job processing it.
+    pub fn expired_nodes(&self, lease: Duration, now: Instant) -> Vec<(Id, Id, String)> {
+        let mut result = Vec::new();
+        for (change_set_id, graph) in &self.dependency_data {
+            for (node_id, metadata) in graph {
+                if let Some(reply_channel) = metadata.processing_reply_channel() {
+                    if metadata.is_processing_expired(lease, now) {
+                        result.push((*change_set_id, *node_id, reply_channel.clone()));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Every node the job behind `reply_channel` is currently processing, along with the change
+    /// set it belongs to.
+    pub fn nodes_processed_by(&self, reply_channel: &str) -> Vec<(Id, Id)> {
+        let mut result = Vec::new();
+        for (change_set_id, graph) in &self.dependency_data {
+            for (node_id, metadata) in graph {
+                if metadata.processing_reply_channel().map(|p| &**p) == Some(reply_channel) {
+                    result.push((*change_set_id, *node_id));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn snapshot(&self) -> HashMap<Id, Vec<NodeSnapshot>> {
+        self.dependency_data
+            .iter()
+            .map(|(change_set_id, graph)| {
+                (
+                    *change_set_id,
+                    graph
+                        .iter()
+                        .map(|(node_id, metadata)| metadata.snapshot(*node_id))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn change_set_ids(&self) -> Vec<Id> {
+        self.dependency_data.keys().copied().collect()
+    }
+
     pub fn fetch_all_available(&mut self) -> Vec<(String, Id)> {
         let mut result = Vec::new();
         for graph in self.dependency_data.values_mut() {
@@ -113,7 +213,10 @@ impl ChangeSetGraph {
         change_set_id: Id,
         node_id: Id,
     ) -> Result<HashSet<String>, Error> {
-        let change_set_graph_data = self.dependency_data.get_mut(&change_set_id).unwrap();
+        let change_set_graph_data = self
+            .dependency_data
+            .get_mut(&change_set_id)
+            .ok_or(Error::UnknownChangeSetId)?;
 
         let (ok_to_remove_node, wanted_by_reply_channels) =
             if let Some(node_metadata) = change_set_graph_data.get_mut(&node_id) {
@@ -150,6 +253,10 @@ impl ChangeSetGraph {
             for id in to_remove {
                 graph.remove(&id).unwrap();
             }
+
+            if graph.is_empty() {
+                self.dependency_data.remove(&change_set_id);
+            }
         }
     }
 
@@ -164,7 +271,10 @@ impl ChangeSetGraph {
         node_id: Id,
     ) -> Result<Vec<(String, Id)>, Error> {
         let mut failure_notifications = Vec::new();
-        let change_set_graph_data = self.dependency_data.get_mut(&change_set_id).unwrap();
+        let change_set_graph_data = self
+            .dependency_data
+            .get_mut(&change_set_id)
+            .ok_or(Error::UnknownChangeSetId)?;
 
         let mut node_ids_to_fail = VecDeque::new();
         node_ids_to_fail.push_back(node_id);
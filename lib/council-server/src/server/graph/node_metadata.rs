@@ -1,18 +1,24 @@
 use std::{
     collections::{vec_deque::Iter, HashSet, VecDeque},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use crate::{server::Error, Id};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use crate::{server::Error, Id, NodeSnapshot, NodeStatus};
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct NodeMetadata {
     // This should really be an ordered set, to remove duplicates, but we'll deal with
     // that later.
     wanted_by_reply_channels: VecDeque<String>,
     processing_reply_channel: Option<String>,
     depends_on_node_ids: HashSet<Id>,
+    // `Instant`s are only meaningful within a single process, so they are reset when a
+    // checkpoint is restored.
+    #[serde(skip)]
     processing_started_at: Option<Instant>,
+    #[serde(skip, default = "Instant::now")]
     last_updated_at: Instant,
 }
 
@@ -60,6 +66,14 @@ impl NodeMetadata {
         false
     }
 
+    pub fn is_processing_expired(&self, lease: Duration, now: Instant) -> bool {
+        self.processing_started_at
+            .map(|processing_started_at| {
+                now.saturating_duration_since(processing_started_at) > lease
+            })
+            .unwrap_or(false)
+    }
+
     pub fn mark_as_processed(
         &mut self,
         reply_channel: &str,
@@ -127,12 +141,40 @@ impl NodeMetadata {
             .filter(|el| el != reply_channel);
     }
 
+    pub fn reset_processing(&mut self) {
+        if let Some(reply_channel) = self.processing_reply_channel.take() {
+            if !self.wanted_by_reply_channels.contains(&reply_channel) {
+                self.wanted_by_reply_channels.push_front(reply_channel);
+            }
+        }
+        self.processing_started_at = None;
+        self.last_updated_at = Instant::now();
+    }
+
     pub fn remove_dependency(&mut self, node_id: Id) {
         if self.depends_on_node_ids.remove(&node_id) {
             self.last_updated_at = Instant::now();
         };
     }
 
+    pub fn snapshot(&self, node_id: Id) -> NodeSnapshot {
+        let status = if self.processing_reply_channel.is_some() {
+            NodeStatus::Processing
+        } else if self.dependencies_satisfied() {
+            NodeStatus::Ready
+        } else {
+            NodeStatus::Waiting
+        };
+
+        NodeSnapshot {
+            node_id,
+            status,
+            processing_reply_channel: self.processing_reply_channel.clone(),
+            wanted_by_reply_channels: self.wanted_by_reply_channels.iter().cloned().collect(),
+            depends_on: self.depends_on_node_ids.iter().copied().collect(),
+        }
+    }
+
     pub fn wanted_by_reply_channels(&self) -> HashSet<String> {
         HashSet::from_iter(self.wanted_by_reply_channels.iter().cloned())
     }
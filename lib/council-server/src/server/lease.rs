@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Tracks when each job last contacted council, so jobs that die without saying `Bye` can be
+/// evicted once their lease runs out.
+#[derive(Debug)]
+pub struct JobLeases {
+    lease: Duration,
+    last_seen: HashMap<String, Instant>,
+}
+
+impl JobLeases {
+    pub fn new(lease: Duration) -> Self {
+        Self {
+            lease,
+            last_seen: HashMap::new(),
+        }
+    }
+
+    pub fn renew(&mut self, reply_channel: &str, now: Instant) {
+        self.last_seen.insert(reply_channel.to_owned(), now);
+    }
+
+    /// Whether the job behind `reply_channel` holds a lease, i.e. whether we know about it.
+    pub fn contains(&self, reply_channel: &str) -> bool {
+        self.last_seen.contains_key(reply_channel)
+    }
+
+    pub fn release(&mut self, reply_channel: &str) {
+        self.last_seen.remove(reply_channel);
+    }
+
+    /// Removes and returns every job whose lease has run out by `now`.
+    pub fn take_expired(&mut self, now: Instant) -> Vec<String> {
+        let lease = self.lease;
+        let expired: Vec<String> = self
+            .last_seen
+            .iter()
+            .filter(|(_, last_seen)| now.saturating_duration_since(**last_seen) > lease)
+            .map(|(reply_channel, _)| reply_channel.clone())
+            .collect();
+        for reply_channel in &expired {
+            self.last_seen.remove(reply_channel);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEASE: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn renewed_lease_survives() {
+        let start = Instant::now();
+        let mut job_leases = JobLeases::new(LEASE);
+        job_leases.renew("job", start);
+
+        job_leases.renew("job", start + LEASE / 2);
+
+        assert!(job_leases
+            .take_expired(start + LEASE + LEASE / 4)
+            .is_empty());
+        assert!(job_leases.contains("job"));
+    }
+
+    #[test]
+    fn expired_lease_is_taken_once() {
+        let start = Instant::now();
+        let mut job_leases = JobLeases::new(LEASE);
+        job_leases.renew("job", start);
+        job_leases.renew("other-job", start + LEASE);
+
+        assert!(job_leases.take_expired(start + LEASE).is_empty());
+        assert_eq!(
+            job_leases.take_expired(start + LEASE + Duration::from_secs(1)),
+            vec!["job".to_owned()]
+        );
+        assert!(job_leases
+            .take_expired(start + LEASE + Duration::from_secs(2))
+            .is_empty());
+        assert!(!job_leases.contains("job"));
+        assert!(job_leases.contains("other-job"));
+    }
+
+    #[test]
+    fn released_lease_never_expires() {
+        let start = Instant::now();
+        let mut job_leases = JobLeases::new(LEASE);
+        job_leases.renew("job", start);
+
+        job_leases.release("job");
+
+        assert!(job_leases.take_expired(start + LEASE * 2).is_empty());
+    }
+}
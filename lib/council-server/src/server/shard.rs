@@ -0,0 +1,202 @@
+use ulid::Ulid;
+
+use crate::{Id, Request};
+
+/// Which slice of the change sets this council instance is responsible for.
+///
+/// Each instance subscribes to its own subject, and jobs route every request to the instances it
+/// concerns (see [`Client`](crate::Client)). Change sets are assigned with a jump consistent hash,
+/// so growing the number of shards only moves the change sets that have to move.
+///
+/// Value creation is serialized across *all* change sets, so the value creation queue lives on
+/// a single coordinator instance (shard `0`).
+#[derive(Debug, Clone, Copy)]
+pub struct Shard {
+    index: u32,
+    count: u32,
+}
+
+impl Default for Shard {
+    fn default() -> Self {
+        Self { index: 0, count: 1 }
+    }
+}
+
+impl Shard {
+    pub fn new(index: u32, count: u32) -> Option<Self> {
+        if count == 0 || index >= count {
+            return None;
+        }
+        Some(Self { index, count })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// The coordinator owns the value creation queue.
+    pub fn is_coordinator(&self) -> bool {
+        self.index == 0
+    }
+
+    pub fn owns(&self, change_set_id: Id) -> bool {
+        shard_for(change_set_id, self.count) == self.index
+    }
+
+    /// Whether this instance has anything to do with `request`. Jobs only send requests to the
+    /// shards they concern, so this only ever fails for misrouted requests.
+    pub fn handles(&self, request: &Request) -> bool {
+        match request {
+            Request::CreateValues | Request::ValueCreationDone => self.is_coordinator(),
+            Request::Introspect => true,
+            // These may release the value creation queue as well as touch the graph.
+            Request::Bye { change_set_id }
+            | Request::Heartbeat { change_set_id }
+            | Request::ValueDependencyGraph { change_set_id, .. } => {
+                self.is_coordinator() || self.owns(*change_set_id)
+            }
+            Request::ProcessedValue { change_set_id, .. }
+            | Request::ValueProcessingFailed { change_set_id, .. } => self.owns(*change_set_id),
+        }
+    }
+}
+
+/// Maps a change set onto one of `count` shards using Lamping and Veach's jump consistent hash.
+pub fn shard_for(change_set_id: Id, count: u32) -> u32 {
+    let ulid: u128 = Ulid::from(change_set_id).into();
+    let mut key = (ulid as u64) ^ ((ulid >> 64) as u64);
+
+    let mut bucket: i64 = -1;
+    let mut jump: i64 = 0;
+    while jump < i64::from(count) {
+        bucket = jump;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        jump = ((bucket + 1) as f64 * ((1_u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+
+    bucket as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(ulid: &str) -> Id {
+        Id::from_string(ulid).expect("invalid ulid")
+    }
+
+    fn ids() -> Vec<Id> {
+        let mut ids: Vec<Id> = (0..1000).map(|_| Id::default()).collect();
+        ids.push(id("00000000000000000000000000"));
+        ids.push(id("7ZZZZZZZZZZZZZZZZZZZZZZZZZ"));
+        ids
+    }
+
+    #[test]
+    fn shard_for_is_stable() {
+        // Jobs and council instances may run different builds, so these must never change.
+        let counts = [1, 2, 3, 4, 8, 16, 100];
+        for (ulid, shards) in [
+            ("01H7Z6N4X6WJ2A8Q3S5T9V0YBC", [0, 0, 2, 2, 4, 4, 62]),
+            ("01GZZZZZZZZZZZZZZZZZZZZZZZ", [0, 1, 1, 1, 6, 9, 41]),
+            ("01H0000000000000000000000A", [0, 1, 1, 3, 3, 15, 15]),
+        ] {
+            assert_eq!(
+                counts.map(|count| shard_for(id(ulid), count)),
+                shards,
+                "{ulid}"
+            );
+        }
+    }
+
+    #[test]
+    fn shard_for_is_in_range() {
+        for change_set_id in ids() {
+            for count in 1..=64 {
+                assert!(shard_for(change_set_id, count) < count);
+            }
+        }
+    }
+
+    #[test]
+    fn shard_for_only_moves_to_new_shards() {
+        for change_set_id in ids() {
+            for count in 1..64 {
+                let before = shard_for(change_set_id, count);
+                let after = shard_for(change_set_id, count + 1);
+                assert!(after == before || after == count);
+            }
+        }
+    }
+
+    #[test]
+    fn new_rejects_out_of_range_shards() {
+        assert!(Shard::new(0, 0).is_none());
+        assert!(Shard::new(4, 4).is_none());
+        assert!(Shard::new(3, 4).is_some());
+    }
+
+    #[test]
+    fn coordinator_handles_value_creation() {
+        let shards: Vec<Shard> = (0..4)
+            .map(|index| Shard::new(index, 4).expect("invalid shard"))
+            .collect();
+
+        for request in [Request::CreateValues, Request::ValueCreationDone] {
+            let handled_by: Vec<u32> = shards
+                .iter()
+                .filter(|shard| shard.handles(&request))
+                .map(Shard::index)
+                .collect();
+            assert_eq!(handled_by, vec![0], "{request:?}");
+        }
+        assert!(shards
+            .iter()
+            .all(|shard| shard.handles(&Request::Introspect)));
+    }
+
+    #[test]
+    fn owner_handles_graph_requests() {
+        let change_set_id = id("01H7Z6N4X6WJ2A8Q3S5T9V0YBC");
+        let node_id = Id::default();
+        let shards: Vec<Shard> = (0..4)
+            .map(|index| Shard::new(index, 4).expect("invalid shard"))
+            .collect();
+
+        let handled_by = |request: Request| -> Vec<u32> {
+            shards
+                .iter()
+                .filter(|shard| shard.handles(&request))
+                .map(Shard::index)
+                .collect()
+        };
+
+        assert_eq!(
+            handled_by(Request::ProcessedValue {
+                change_set_id,
+                node_id
+            }),
+            vec![2]
+        );
+        assert_eq!(
+            handled_by(Request::ValueProcessingFailed {
+                change_set_id,
+                node_id
+            }),
+            vec![2]
+        );
+        // The coordinator may have to release value creation.
+        assert_eq!(
+            handled_by(Request::ValueDependencyGraph {
+                change_set_id,
+                dependency_graph: Default::default(),
+            }),
+            vec![0, 2]
+        );
+        assert_eq!(handled_by(Request::Bye { change_set_id }), vec![0, 2]);
+    }
+}
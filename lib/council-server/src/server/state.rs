@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::{
+    graph::{ChangeSetGraph, ValueCreationQueue},
+    Result,
+};
+
+/// Everything council needs to pick up in-flight work after a restart.
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct CouncilState {
+    pub value_creation_queue: ValueCreationQueue,
+    pub change_set_graph: ChangeSetGraph,
+}
+
+/// Checkpoints [`CouncilState`] to a file on local disk.
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: PathBuf,
+}
+
+impl StateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the last checkpoint, returning `None` if no checkpoint has been written yet.
+    pub async fn load(&self) -> Result<Option<CouncilState>> {
+        let bytes = match tokio::fs::read(&self.path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    /// Writes a checkpoint, replacing the previous one atomically so a crash mid-write never
+    /// leaves a truncated file behind.
+    #[instrument(level = "debug", skip_all, fields(path = %self.path.display()))]
+    pub async fn save(
+        &self,
+        value_creation_queue: &ValueCreationQueue,
+        change_set_graph: &ChangeSetGraph,
+    ) -> Result<()> {
+        #[derive(Serialize)]
+        struct CouncilStateRef<'a> {
+            value_creation_queue: &'a ValueCreationQueue,
+            change_set_graph: &'a ChangeSetGraph,
+        }
+
+        let bytes = serde_json::to_vec(&CouncilStateRef {
+            value_creation_queue,
+            change_set_graph,
+        })?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ulid::Ulid;
+
+    use super::*;
+    use crate::Id;
+
+    fn state_store() -> StateStore {
+        StateStore::new(std::env::temp_dir().join(format!("council-state-{}.json", Ulid::new())))
+    }
+
+    #[tokio::test]
+    async fn load_without_checkpoint() {
+        let state = state_store().load().await.expect("failed to load state");
+
+        assert!(state.is_none());
+    }
+
+    #[tokio::test]
+    async fn save_and_load_round_trip() {
+        let state_store = state_store();
+        let change_set_id = Id::default();
+        let node_id = Id::default();
+        let dependency_id = Id::default();
+
+        let mut value_creation_queue = ValueCreationQueue::default();
+        value_creation_queue.push("job-1".to_owned());
+        value_creation_queue.push("job-2".to_owned());
+        value_creation_queue.fetch_next();
+        let mut change_set_graph = ChangeSetGraph::default();
+        change_set_graph
+            .merge_dependency_graph(
+                "job-3".to_owned(),
+                HashMap::from([(node_id, vec![dependency_id])]),
+                change_set_id,
+            )
+            .expect("failed to merge graph");
+
+        state_store
+            .save(&value_creation_queue, &change_set_graph)
+            .await
+            .expect("failed to save state");
+        let state = state_store
+            .load()
+            .await
+            .expect("failed to load state")
+            .expect("no state was saved");
+        tokio::fs::remove_file(state_store.path())
+            .await
+            .expect("failed to remove state");
+
+        assert_eq!(
+            state.value_creation_queue.processing().map(String::as_str),
+            Some("job-1")
+        );
+        assert_eq!(state.value_creation_queue.queued(), vec!["job-2"]);
+        assert_eq!(
+            serde_json::to_value(&state.change_set_graph).expect("failed to serialize graph"),
+            serde_json::to_value(&change_set_graph).expect("failed to serialize graph"),
+        );
+    }
+
+    #[tokio::test]
+    async fn restored_processing_nodes_are_handed_out_again() {
+        let state_store = state_store();
+        let change_set_id = Id::default();
+        let node_id = Id::default();
+
+        let mut change_set_graph = ChangeSetGraph::default();
+        change_set_graph
+            .merge_dependency_graph(
+                "job".to_owned(),
+                HashMap::from([(node_id, Vec::new())]),
+                change_set_id,
+            )
+            .expect("failed to merge graph");
+        assert_eq!(
+            change_set_graph.fetch_all_available(),
+            vec![("job".to_owned(), node_id)]
+        );
+
+        state_store
+            .save(&ValueCreationQueue::default(), &change_set_graph)
+            .await
+            .expect("failed to save state");
+        let mut state = state_store
+            .load()
+            .await
+            .expect("failed to load state")
+            .expect("no state was saved");
+        tokio::fs::remove_file(state_store.path())
+            .await
+            .expect("failed to remove state");
+
+        assert_eq!(
+            state.change_set_graph.nodes_processed_by("job"),
+            vec![(change_set_id, node_id)]
+        );
+        assert!(state.change_set_graph.fetch_all_available().is_empty());
+
+        state.change_set_graph.reset_processing();
+
+        assert!(state.change_set_graph.nodes_processed_by("job").is_empty());
+        assert_eq!(
+            state.change_set_graph.fetch_all_available(),
+            vec![("job".to_owned(), node_id)]
+        );
+    }
+}
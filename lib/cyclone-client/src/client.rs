@@ -452,7 +452,8 @@ mod tests {
     use base64::{engine::general_purpose, Engine};
     use buck2_resources::Buck2Resources;
     use cyclone_core::{
-        ComponentKind, ComponentView, FunctionResult, ProgressMessage, ResolverFunctionComponent,
+        ComponentKind, ComponentView, EgressPolicy, ExecutionLimit, FunctionResult,
+        FunctionResultFailureErrorKind, ProgressMessage, ResolverFunctionComponent,
         ValidationRequest,
     };
     use cyclone_server::{Config, ConfigBuilder, DecryptionKey, Server, UdsIncomingStream};
@@ -788,6 +789,7 @@ mod tests {
                     return v;
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -878,6 +880,7 @@ mod tests {
                     return v;
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -951,6 +954,7 @@ mod tests {
                     }
                 }",
             ),
+            timeout_secs: None,
         };
         let mut progress = client
             .execute_validation(req)
@@ -1030,6 +1034,151 @@ mod tests {
         execute_validation(client).await
     }
 
+    async fn execute_validation_past_timeout<C, Strm>(mut client: C)
+    where
+        Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
+        C: CycloneClient<Strm>,
+    {
+        let req = ValidationRequest {
+            execution_id: "1338".to_string(),
+            handler: "validate".to_string(),
+            value: "a string is a sequence of bytes".into(),
+            code_base64: base64_encode(
+                r"function validate(value) {
+                    while (true) {}
+                }",
+            ),
+            timeout_secs: Some(1),
+        };
+        let mut progress = client
+            .execute_validation(req)
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(unexpected) => panic!("output stream should be done: {unexpected:?}"),
+            };
+        }
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert_eq!(
+                    FunctionResultFailureErrorKind::ExecutionLimit(ExecutionLimit::Timeout),
+                    failure.error.kind,
+                    "failure should be a timeout; failure={failure:?}"
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn http_execute_validation_past_timeout() {
+        let (_, key) = gen_keys();
+        let mut builder = Config::builder();
+        let client = http_client_for_running_server(builder.enable_validation(true), key).await;
+
+        execute_validation_past_timeout(client).await
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_validation_past_timeout() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let client =
+            uds_client_for_running_server(builder.enable_validation(true), &tmp_socket, key).await;
+
+        execute_validation_past_timeout(client).await
+    }
+
+    async fn execute_resolver_denied_egress<C, Strm>(mut client: C)
+    where
+        Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
+        C: CycloneClient<Strm>,
+    {
+        let req = ResolverFunctionRequest {
+            execution_id: "1339".to_string(),
+            handler: "doit".to_string(),
+            component: ResolverFunctionComponent {
+                data: ComponentView {
+                    properties: serde_json::json!({}),
+                    kind: ComponentKind::Standard,
+                },
+                parents: vec![],
+            },
+            response_type: cyclone_core::ResolverFunctionResponseType::Object,
+            code_base64: base64_encode(
+                r#"function doit(input) {
+                    fetch("https://www.systeminit.com");
+                    return {};
+                }"#,
+            ),
+            timeout_secs: None,
+        };
+        let mut progress = client
+            .execute_resolver(req)
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Ok(ProgressMessage::OutputStream(_))) => continue,
+                Some(unexpected) => panic!("output stream should be done: {unexpected:?}"),
+            };
+        }
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert_eq!(
+                    FunctionResultFailureErrorKind::EgressBlocked,
+                    failure.error.kind,
+                    "failure should be a blocked connection; failure={failure:?}"
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn http_execute_resolver_denied_egress() {
+        let (_, key) = gen_keys();
+        let mut builder = Config::builder();
+        let client = http_client_for_running_server(builder.enable_resolver(true), key).await;
+
+        execute_resolver_denied_egress(client).await
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_resolver_denied_egress() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let client =
+            uds_client_for_running_server(builder.enable_resolver(true), &tmp_socket, key).await;
+
+        execute_resolver_denied_egress(client).await
+    }
+
     #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
     #[test(tokio::test)]
     async fn http_execute_action_run() {
@@ -1048,6 +1197,8 @@ mod tests {
                     return { status: 'ok' };
                 }"#,
             ),
+            timeout_secs: None,
+            egress_policy: EgressPolicy::Allow,
         };
 
         // Start the protocol
@@ -1123,6 +1274,8 @@ mod tests {
                     return { status: 'ok' };
                 }"#,
             ),
+            timeout_secs: None,
+            egress_policy: EgressPolicy::Allow,
         };
 
         // Start the protocol
@@ -1198,6 +1351,8 @@ mod tests {
                     return { updates: { "myid": true }, actions: ["run"] };
                 }"#,
             ),
+            timeout_secs: None,
+            egress_policy: EgressPolicy::Allow,
         };
 
         // Start the protocol
@@ -1274,6 +1429,8 @@ mod tests {
                     return { updates: { "myid": true }, actions: ["run"] };
                 }"#,
             ),
+            timeout_secs: None,
+            egress_policy: EgressPolicy::Allow,
         };
 
         // Start the protocol
@@ -1353,6 +1510,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1432,6 +1590,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
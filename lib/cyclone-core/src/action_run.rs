@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::EgressPolicy;
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActionRunRequest {
@@ -7,6 +9,11 @@ pub struct ActionRunRequest {
     pub handler: String,
     pub code_base64: String,
     pub args: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Network access the execution is given, allowed when absent.
+    #[serde(default)]
+    pub egress_policy: EgressPolicy,
 }
 
 #[remain::sorted]
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether a function execution may open network connections.
+///
+/// Only action and reconciliation functions talk to the outside world. Every other kind of
+/// function runs with [`EgressPolicy::Deny`], whatever its request says.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EgressPolicy {
+    /// The function may reach the network.
+    #[default]
+    Allow,
+    /// The function runs without network access.
+    Deny,
+}
+
+impl EgressPolicy {
+    /// Returns true if the policy lets the function reach the network.
+    pub fn allows_network(self) -> bool {
+        matches!(self, Self::Allow)
+    }
+}
+
+impl AsRef<str> for EgressPolicy {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+}
@@ -14,12 +14,15 @@
 mod action_run;
 mod canonical_command;
 mod component_view;
+mod egress;
 mod encryption_key;
+mod limits;
 mod liveness;
 pub mod process;
 mod progress;
 mod readiness;
 mod reconciliation;
+mod redaction;
 mod resolver_function;
 mod schema_variant_definition;
 mod sensitive_container;
@@ -28,14 +31,17 @@ mod validation;
 pub use action_run::{ActionRunRequest, ActionRunResultSuccess, ResourceStatus};
 pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView};
+pub use egress::EgressPolicy;
 pub use encryption_key::{EncryptionKey, EncryptionKeyError};
+pub use limits::ExecutionLimit;
 pub use liveness::{LivenessStatus, LivenessStatusParseError};
 pub use progress::{
-    FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message, OutputStream,
-    ProgressMessage,
+    FunctionResult, FunctionResultFailure, FunctionResultFailureError,
+    FunctionResultFailureErrorKind, Message, OutputStream, ProgressMessage,
 };
 pub use readiness::{ReadinessStatus, ReadinessStatusParseError};
 pub use reconciliation::{ReconciliationRequest, ReconciliationResultSuccess};
+pub use redaction::{Redactor, REDACTED};
 pub use resolver_function::{
     ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
     ResolverFunctionResultSuccess,
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// A limit put on a function execution which, when hit, stops the lang server and fails the
+/// execution.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ExecutionLimit {
+    /// The lang server used up its CPU time allowance.
+    Cpu,
+    /// The lang server ran out of heap memory.
+    Memory,
+    /// The function produced more output than allowed.
+    OutputSize,
+    /// The function ran for longer than its timeout.
+    Timeout,
+}
+
+impl ExecutionLimit {
+    /// Returns the error kind of the [`FunctionResultFailure`](crate::FunctionResultFailure)
+    /// reported when the limit is hit.
+    pub fn failure_kind(self) -> &'static str {
+        match self {
+            Self::Cpu => "CpuLimitExceeded",
+            Self::Memory => "MemoryLimitExceeded",
+            Self::OutputSize => "OutputSizeLimitExceeded",
+            Self::Timeout => "ExecutionTimeout",
+        }
+    }
+
+    /// Returns the limit reported under the given error kind, if any.
+    pub fn from_failure_kind(kind: &str) -> Option<Self> {
+        [Self::Cpu, Self::Memory, Self::OutputSize, Self::Timeout]
+            .into_iter()
+            .find(|limit| limit.failure_kind() == kind)
+    }
+}
@@ -1,6 +1,15 @@
 use std::{io, num::TryFromIntError, process::ExitStatus, time::Duration};
 
-use nix::{sys::signal, unistd::Pid};
+use nix::{
+    errno::Errno,
+    sched::{self, CloneFlags},
+    sys::{
+        resource::{self, Resource},
+        signal,
+        wait::{self, WaitStatus},
+    },
+    unistd::{self, ForkResult, Pid, SysconfVar},
+};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{process::Child, time};
@@ -8,6 +17,7 @@ use tokio::{process::Child, time};
 pub use nix::sys::signal::Signal;
 
 const CHILD_WAIT_TIMEOUT_SECS: Duration = Duration::from_secs(10);
+const CHILD_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -60,3 +70,113 @@ pub async fn child_shutdown(
         }
     }
 }
+
+/// Limits the CPU time of the current process, which receives a `SIGXCPU` when reaching the limit
+/// and is killed a second later.
+///
+/// This is meant to be called in a child process, in between forking and executing the program.
+pub fn limit_cpu_time(limit: Duration) -> Result<(), Errno> {
+    let secs = limit.as_secs().max(1);
+    resource::setrlimit(Resource::RLIMIT_CPU, secs, secs.saturating_add(1))
+}
+
+/// Moves the current process into a new network namespace, leaving it with only an unconfigured
+/// loopback interface so that any connection it attempts fails.
+///
+/// Without the privileges to create a network namespace, a user namespace is created alongside
+/// it, which unprivileged processes are usually allowed to do.
+///
+/// This is meant to be called in a child process, in between forking and executing the program.
+pub fn isolate_network() -> Result<(), Errno> {
+    match sched::unshare(CloneFlags::CLONE_NEWNET) {
+        Err(Errno::EPERM) => sched::unshare(CloneFlags::CLONE_NEWUSER | CloneFlags::CLONE_NEWNET),
+        result => result,
+    }
+}
+
+/// Checks that [`isolate_network`] is permitted, by calling it in a short-lived forked process.
+///
+/// Hosts where unprivileged user namespaces are disabled deny it, in which case every execution
+/// isolated with it would fail to spawn.
+pub fn probe_network_isolation() -> Result<(), Errno> {
+    // Safety: the child only makes `unshare` syscalls before exiting, which are async-signal-safe
+    match unsafe { unistd::fork() }? {
+        ForkResult::Child => {
+            let code = match isolate_network() {
+                Ok(()) => 0,
+                Err(errno) => errno as i32,
+            };
+            unistd::_exit(code)
+        }
+        ForkResult::Parent { child } => match wait::waitpid(child, None)? {
+            WaitStatus::Exited(_, 0) => Ok(()),
+            WaitStatus::Exited(_, code) => Err(Errno::from_i32(code)),
+            _ => Err(Errno::ECHILD),
+        },
+    }
+}
+
+/// Returns true if the current process can reach a network interface other than loopback, which
+/// is not the case when it runs in a network namespace without network access.
+pub fn has_network_interfaces() -> io::Result<bool> {
+    let dev = std::fs::read_to_string("/proc/net/dev")?;
+    Ok(network_interfaces(&dev).any(|name| name != "lo"))
+}
+
+/// Returns the names of the interfaces listed in the contents of `/proc/net/dev`, which start
+/// with two header lines.
+fn network_interfaces(dev: &str) -> impl Iterator<Item = &str> {
+    dev.lines()
+        .skip(2)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, _)| name.trim())
+}
+
+/// Returns the CPU time used by a child process, once it has exited but before it is waited on.
+///
+/// An exited child stays in `/proc` until it is reaped, along with the CPU time it used. Returns
+/// [`None`] if the time can't be read, e.g. if the child has already been reaped.
+pub async fn exited_child_cpu_time(child: &Child) -> Option<Duration> {
+    let path = format!("/proc/{}/stat", child.id()?);
+    loop {
+        let stat = tokio::fs::read_to_string(&path).await.ok()?;
+        // The command name, in parentheses, may contain spaces, so fields are counted after it:
+        // the state is the 3rd field of the file, utime and stime the 14th and 15th
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        if fields.first() == Some(&"Z") {
+            let utime: u64 = fields.get(11)?.parse().ok()?;
+            let stime: u64 = fields.get(12)?.parse().ok()?;
+            let ticks_per_sec = unistd::sysconf(SysconfVar::CLK_TCK).ok()??;
+            return Some(Duration::from_secs_f64(
+                (utime + stime) as f64 / ticks_per_sec as f64,
+            ));
+        }
+        time::sleep(CHILD_EXIT_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_interfaces_from_proc_net_dev() {
+        let dev = concat!(
+            "Inter-|   Receive                |  Transmit\n",
+            " face |bytes    packets errs drop|bytes    packets errs drop\n",
+            "    lo:  123456     789    0    0   123456     789    0    0\n",
+            "  eth0:  987654     321    0    0   654321     123    0    0\n",
+        );
+        assert_eq!(
+            vec!["lo", "eth0"],
+            network_interfaces(dev).collect::<Vec<_>>()
+        );
+
+        let isolated = concat!(
+            "Inter-|   Receive                |  Transmit\n",
+            " face |bytes    packets errs drop|bytes    packets errs drop\n",
+            "    lo:       0       0    0    0        0       0    0    0\n",
+        );
+        assert!(network_interfaces(isolated).all(|name| name == "lo"));
+    }
+}
@@ -1,5 +1,9 @@
+use std::fmt;
+
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use crate::ExecutionLimit;
+
 /// A line of output, streamed from an executing function.
 ///
 /// An instance of this type typically maps to a single line of output from a process--either on
@@ -106,11 +110,109 @@ pub struct FunctionResultFailure {
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone)]
 pub struct FunctionResultFailureError {
-    pub kind: String,
+    pub kind: FunctionResultFailureErrorKind,
     pub message: String,
 }
 
+/// What a [`FunctionResultFailure`] is about.
+///
+/// Kinds are sent over the wire as plain strings, so that the lang server can report failures of
+/// its own (e.g. an exception thrown by the function) without cyclone knowing about them.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum FunctionResultFailureErrorKind {
+    /// The function tried to reach the network while its egress policy denies it.
+    EgressBlocked,
+    /// The execution hit one of its limits and the lang server was stopped.
+    ExecutionLimit(ExecutionLimit),
+    /// A failure reported by the lang server, under the kind it was reported with.
+    LangServerFailure(String),
+    /// Veritech could not run the function to completion.
+    VeritechServer,
+}
+
+impl FunctionResultFailureErrorKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::EgressBlocked => "EgressBlocked",
+            Self::ExecutionLimit(limit) => limit.failure_kind(),
+            Self::LangServerFailure(kind) => kind.as_str(),
+            Self::VeritechServer => "veritechServer",
+        }
+    }
+}
+
+impl From<String> for FunctionResultFailureErrorKind {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "EgressBlocked" => Self::EgressBlocked,
+            "veritechServer" => Self::VeritechServer,
+            kind => match ExecutionLimit::from_failure_kind(kind) {
+                Some(limit) => Self::ExecutionLimit(limit),
+                None => Self::LangServerFailure(value),
+            },
+        }
+    }
+}
+
+impl From<&str> for FunctionResultFailureErrorKind {
+    fn from(value: &str) -> Self {
+        value.to_owned().into()
+    }
+}
+
+impl From<FunctionResultFailureErrorKind> for String {
+    fn from(value: FunctionResultFailureErrorKind) -> Self {
+        match value {
+            FunctionResultFailureErrorKind::LangServerFailure(kind) => kind,
+            kind => kind.as_str().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for FunctionResultFailureErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Fail {
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_error_kinds_round_trip_as_strings() {
+        for (kind, kind_str) in [
+            (
+                FunctionResultFailureErrorKind::EgressBlocked,
+                "EgressBlocked",
+            ),
+            (
+                FunctionResultFailureErrorKind::ExecutionLimit(ExecutionLimit::Timeout),
+                "ExecutionTimeout",
+            ),
+            (
+                FunctionResultFailureErrorKind::LangServerFailure("UserCodeException".to_owned()),
+                "UserCodeException",
+            ),
+            (
+                FunctionResultFailureErrorKind::VeritechServer,
+                "veritechServer",
+            ),
+        ] {
+            let json = serde_json::to_value(&kind).expect("failed to serialize kind");
+            assert_eq!(serde_json::json!(kind_str), json);
+            assert_eq!(
+                kind,
+                serde_json::from_value::<FunctionResultFailureErrorKind>(json)
+                    .expect("failed to deserialize kind")
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::EgressPolicy;
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReconciliationRequest {
@@ -8,6 +10,11 @@ pub struct ReconciliationRequest {
     pub handler: String,
     pub code_base64: String,
     pub args: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Network access the execution is given, allowed when absent.
+    #[serde(default)]
+    pub egress_policy: EgressPolicy,
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
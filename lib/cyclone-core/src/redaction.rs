@@ -0,0 +1,209 @@
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+use crate::SensitiveString;
+
+/// The text known secret values are replaced with.
+pub const REDACTED: &str = "[redacted]";
+
+/// Scrubs known secret values out of function output.
+///
+/// Note: this brings a possibility of random substrings being matched out of context, exposing
+/// that we have a secret by censoring it. But trying to infer word boundaries might leak the
+/// plaintext credential, which is arguably worse.
+#[derive(Clone, Debug, Default)]
+pub struct Redactor {
+    /// Sorted longest first, so that a secret containing another one is scrubbed whole.
+    secrets: Vec<SensitiveString>,
+}
+
+impl Redactor {
+    pub fn new(secrets: impl IntoIterator<Item = SensitiveString>) -> Self {
+        let mut redactor = Self::default();
+        for secret in secrets {
+            redactor.insert(secret);
+        }
+        redactor
+    }
+
+    /// Adds a secret value to scrub. Blank values are ignored, as scrubbing them would mangle
+    /// every line of output.
+    pub fn insert(&mut self, secret: impl Into<SensitiveString>) {
+        let secret = secret.into();
+        if secret.trim().is_empty() {
+            return;
+        }
+
+        // Secrets printed as part of a JSON document show up with their special characters
+        // escaped
+        if let Ok(escaped) = serde_json::to_string(secret.as_str()) {
+            let escaped = &escaped[1..escaped.len() - 1];
+            if escaped != secret.as_str() {
+                self.push(escaped.to_owned().into());
+            }
+        }
+        // Output is captured a line at a time, so multi-line secrets (such as private keys) are
+        // also scrubbed line by line
+        if secret.contains('\n') {
+            for line in secret
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+            {
+                self.push(line.to_owned().into());
+            }
+        }
+        self.push(secret);
+    }
+
+    /// Adds every string held by the message of a decrypted secret.
+    pub fn insert_message(&mut self, message: &Value) {
+        let mut work_queue = vec![message];
+        while let Some(work) = work_queue.pop() {
+            match work {
+                Value::Array(values) => work_queue.extend(values),
+                Value::Object(object) => work_queue.extend(object.values()),
+                Value::String(value) => self.insert(value.to_owned()),
+                // For now credentials can only be strings, although we should reconsider it
+                Value::Null | Value::Bool(_) | Value::Number(_) => {}
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    /// Returns the text with every known secret value replaced by [`REDACTED`].
+    pub fn redact_str<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = Cow::Owned(text.replace(secret.as_str(), REDACTED));
+            }
+        }
+        text
+    }
+
+    /// Replaces every known secret value held in the strings and object keys of a JSON value.
+    pub fn redact_value(&self, value: &mut Value) {
+        if self.is_empty() {
+            return;
+        }
+
+        let mut work_queue = vec![value];
+        while let Some(work) = work_queue.pop() {
+            match work {
+                Value::Array(values) => work_queue.extend(values),
+                Value::Object(object) => {
+                    if object
+                        .keys()
+                        .any(|key| matches!(self.redact_str(key), Cow::Owned(_)))
+                    {
+                        let entries = std::mem::take(object);
+                        *object = entries
+                            .into_iter()
+                            .map(|(key, value)| (self.redact_str(&key).into_owned(), value))
+                            .collect::<Map<_, _>>();
+                    }
+                    work_queue.extend(object.values_mut());
+                }
+                Value::String(text) => {
+                    if let Cow::Owned(redacted) = self.redact_str(text) {
+                        *text = redacted;
+                    }
+                }
+                Value::Null | Value::Bool(_) | Value::Number(_) => {}
+            }
+        }
+    }
+
+    fn push(&mut self, secret: SensitiveString) {
+        if !self.secrets.contains(&secret) {
+            self.secrets.push(secret);
+            self.secrets
+                .sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn redactor(secrets: &[&str]) -> Redactor {
+        Redactor::new(secrets.iter().map(|secret| secret.to_string().into()))
+    }
+
+    #[test]
+    fn redacts_plain_text() {
+        let redactor = redactor(&["hunter2"]);
+
+        assert_eq!(
+            "password is [redacted], again [redacted]",
+            redactor.redact_str("password is hunter2, again hunter2")
+        );
+        assert!(matches!(
+            redactor.redact_str("nothing to see"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn ignores_blank_secrets() {
+        let redactor = redactor(&["", "  \n"]);
+
+        assert!(redactor.is_empty());
+        assert_eq!("a b", redactor.redact_str("a b"));
+    }
+
+    #[test]
+    fn redacts_json_escaped() {
+        let secret = "pa\"ss\\word";
+        let redactor = redactor(&[secret]);
+        let printed = serde_json::to_string(&json!({ "token": secret })).expect("serializes");
+
+        assert_eq!(r#"{"token":"[redacted]"}"#, redactor.redact_str(&printed));
+    }
+
+    #[test]
+    fn redacts_multiline_line_by_line() {
+        let redactor = redactor(&["-----BEGIN KEY-----\nc2VjcmV0\n-----END KEY-----\n"]);
+
+        assert_eq!("line: [redacted]", redactor.redact_str("line: c2VjcmV0"));
+        assert_eq!(
+            "[redacted]",
+            redactor.redact_str("-----BEGIN KEY-----\nc2VjcmV0\n-----END KEY-----\n")
+        );
+    }
+
+    #[test]
+    fn redacts_overlapping_secrets_whole() {
+        let redactor = redactor(&["abc", "abcdef"]);
+
+        assert_eq!("x [redacted] y", redactor.redact_str("x abcdef y"));
+        assert_eq!("[redacted]de", redactor.redact_str("abcde"));
+    }
+
+    #[test]
+    fn redacts_values_and_keys() {
+        let redactor = redactor(&["hunter2"]);
+        let mut value = json!({
+            "hunter2": ["my hunter2", 1, null],
+            "nested": { "password": "hunter2" },
+        });
+
+        redactor.redact_value(&mut value);
+
+        assert_eq!(
+            json!({
+                "[redacted]": ["my [redacted]", 1, null],
+                "nested": { "password": "[redacted]" },
+            }),
+            value
+        );
+    }
+}
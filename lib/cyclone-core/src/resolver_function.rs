@@ -11,6 +11,9 @@ pub struct ResolverFunctionRequest {
     pub component: ResolverFunctionComponent,
     pub response_type: ResolverFunctionResponseType,
     pub code_base64: String,
+    /// Timeout of the execution in seconds, capped by the one cyclone is configured with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
@@ -6,6 +6,8 @@ pub struct SchemaVariantDefinitionRequest {
     pub execution_id: String,
     pub handler: String,
     pub code_base64: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
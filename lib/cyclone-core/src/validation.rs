@@ -7,6 +7,8 @@ pub struct ValidationRequest {
     pub handler: String,
     pub value: serde_json::Value,
     pub code_base64: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
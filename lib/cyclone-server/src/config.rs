@@ -54,6 +54,21 @@ pub struct Config {
 
     #[builder(setter(into), default)]
     limit_requests: Option<u32>,
+
+    #[builder(setter(into), default)]
+    execution_timeout: Option<Duration>,
+
+    #[builder(setter(into), default)]
+    lang_server_memory_limit_mib: Option<u64>,
+
+    #[builder(setter(into), default)]
+    lang_server_cpu_limit: Option<Duration>,
+
+    #[builder(setter(into), default)]
+    output_limit_bytes: Option<usize>,
+
+    #[builder(default = "false")]
+    lang_server_network_isolation: bool,
 }
 
 impl Config {
@@ -122,6 +137,38 @@ impl Config {
     pub fn limit_requests(&self) -> Option<u32> {
         self.limit_requests
     }
+
+    /// Gets the config's execution timeout, used when a request doesn't have its own and as a cap
+    /// when it does.
+    #[must_use]
+    pub fn execution_timeout(&self) -> Option<Duration> {
+        self.execution_timeout
+    }
+
+    /// Gets the config's lang server memory limit, in MiB.
+    #[must_use]
+    pub fn lang_server_memory_limit_mib(&self) -> Option<u64> {
+        self.lang_server_memory_limit_mib
+    }
+
+    /// Gets the config's lang server CPU time limit.
+    #[must_use]
+    pub fn lang_server_cpu_limit(&self) -> Option<Duration> {
+        self.lang_server_cpu_limit
+    }
+
+    /// Gets the config's limit on the output size of an execution, in bytes.
+    #[must_use]
+    pub fn output_limit_bytes(&self) -> Option<usize> {
+        self.output_limit_bytes
+    }
+
+    /// Gets whether lang servers denied network access are isolated in their own network
+    /// namespace.
+    #[must_use]
+    pub fn lang_server_network_isolation(&self) -> bool {
+        self.lang_server_network_isolation
+    }
 }
 
 impl ConfigBuilder {
@@ -1,9 +1,14 @@
 use std::{
+    borrow::Cow,
     fmt, io,
     marker::{PhantomData, Unpin},
+    os::unix::process::ExitStatusExt,
     path::PathBuf,
     process::Stdio,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 
@@ -11,38 +16,51 @@ use axum::extract::ws::WebSocket;
 use bytes_lines_codec::BytesLinesCodec;
 use cyclone_core::{
     process::{self, ShutdownError},
-    FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message, OutputStream,
-    SensitiveString,
+    EgressPolicy, ExecutionLimit, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, FunctionResultFailureErrorKind, Message, OutputStream, Redactor,
 };
 use futures::{SinkExt, StreamExt, TryStreamExt};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_json::Value;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
-    time,
+    time::{self, Instant},
 };
 use tokio_serde::{formats::SymmetricalJson, Deserializer, Framed, SymmetricallyFramed};
 use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
 
 use crate::{
-    request::{DecryptRequest, ListSecrets},
+    request::{DecryptRequest, ExecutionMetadata, ListSecrets},
+    state::ExecutionLimits,
     DecryptionKey, DecryptionKeyError, WebSocketMessage,
 };
 
 const TX_TIMEOUT_SECS: Duration = Duration::from_secs(5);
+const CHILD_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lines printed by V8 when it runs out of heap.
+const OUT_OF_MEMORY_MARKERS: &[&str] = &["heap out of memory", "Reached heap limit"];
+
+/// Error codes reported by Node when a connection can't be made, which is what happens to every
+/// connection attempted without network access.
+const NETWORK_ERROR_MARKERS: &[&str] = &["ENETUNREACH", "EAI_AGAIN", "ENOTFOUND", "ECONNREFUSED"];
+
+/// Tells the lang server to leave network access out of the function sandbox.
+const EGRESS_POLICY_ENV: &str = "SI_EGRESS_POLICY";
 
 pub fn new<Request, LangServerSuccess, Success>(
     lang_server_path: impl Into<PathBuf>,
     lang_server_debugging: bool,
     key: Arc<DecryptionKey>,
+    limits: ExecutionLimits,
     command: String,
 ) -> Execution<Request, LangServerSuccess, Success> {
     Execution {
         lang_server_path: lang_server_path.into(),
         lang_server_debugging,
         key,
+        limits,
         command,
         request_marker: PhantomData,
         lang_server_success_marker: PhantomData,
@@ -61,6 +79,8 @@ pub enum ExecutionError {
     ChildSendIO(#[source] io::Error),
     #[error(transparent)]
     ChildShutdown(#[from] ShutdownError),
+    #[error("failed to kill child process")]
+    ChildKill(#[source] io::Error),
     #[error("failed to spawn child process; program={0}")]
     ChildSpawn(#[source] io::Error, PathBuf),
     #[error("failed to deserialize json message")]
@@ -90,6 +110,7 @@ pub struct Execution<Request, LangServerSuccess, Success> {
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<DecryptionKey>,
+    limits: ExecutionLimits,
     command: String,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
@@ -98,7 +119,13 @@ pub struct Execution<Request, LangServerSuccess, Success> {
 
 impl<Request, LangServerSuccess, Success> Execution<Request, LangServerSuccess, Success>
 where
-    Request: DecryptRequest + ListSecrets + Serialize + DeserializeOwned + Unpin + core::fmt::Debug,
+    Request: DecryptRequest
+        + ExecutionMetadata
+        + ListSecrets
+        + Serialize
+        + DeserializeOwned
+        + Unpin
+        + core::fmt::Debug,
     LangServerSuccess: DeserializeOwned,
     Success: Serialize,
 {
@@ -110,7 +137,10 @@ where
         Self::ws_send_start(ws).await?;
         // Now that the server said to start, I am going to read my message!
         let request = Self::read_request(ws).await?;
-        let credentials: Vec<SensitiveString> = request.list_secrets(&self.key)?;
+        let execution_id = request.execution_id().to_owned();
+        let timeout = self.limits.timeout_for(request.timeout());
+        let egress_policy = request.egress_policy();
+        let redactor = Redactor::new(request.list_secrets(&self.key)?);
         let mut command = Command::new(&self.lang_server_path);
         command
             .arg(&self.command)
@@ -120,6 +150,30 @@ where
         if self.lang_server_debugging {
             command.env("DEBUG", "*").env("DEBUG_DEPTH", "5");
         }
+        if let Some(memory_limit_mib) = self.limits.memory_limit_mib {
+            // V8 reserves far more address space than it uses, so the limit is put on its heap
+            // rather than on the process
+            command.env(
+                "NODE_OPTIONS",
+                format!("--max-old-space-size={memory_limit_mib}"),
+            );
+        }
+        if let Some(cpu_limit) = self.limits.cpu_limit {
+            // Safety: the closure only makes a `setrlimit` syscall, which is async-signal-safe
+            unsafe {
+                command
+                    .pre_exec(move || process::limit_cpu_time(cpu_limit).map_err(io::Error::from));
+            }
+        }
+        if !egress_policy.allows_network() {
+            command.env(EGRESS_POLICY_ENV, egress_policy.as_ref());
+            if self.limits.network_isolation {
+                // Safety: the closure only makes `unshare` syscalls, which are async-signal-safe
+                unsafe {
+                    command.pre_exec(|| process::isolate_network().map_err(io::Error::from));
+                }
+            }
+        }
         debug!(cmd = ?command, "spawning child process");
         let mut child = command
             .spawn()
@@ -149,7 +203,11 @@ where
             child,
             stdout,
             stderr,
-            credentials,
+            redactor,
+            execution_id,
+            timeout,
+            egress_policy,
+            limits: self.limits,
             success_marker: self.success_marker,
         })
     }
@@ -211,35 +269,39 @@ pub struct ExecutionStarted<LangServerSuccess, Success> {
     child: Child,
     stdout: SiFramed<SiMessage<LangServerSuccess>>,
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
-    credentials: Vec<SensitiveString>,
+    redactor: Redactor,
+    execution_id: String,
+    timeout: Option<Duration>,
+    egress_policy: EgressPolicy,
+    limits: ExecutionLimits,
     success_marker: PhantomData<Success>,
 }
 
 // TODO: implement shutdown oneshot
 async fn handle_stderr(
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
-    credentials: Vec<SensitiveString>,
+    redactor: Redactor,
+    out_of_memory: Arc<AtomicBool>,
 ) {
     async fn handle_stderr_fallible(
         mut stderr: FramedRead<ChildStderr, BytesLinesCodec>,
-        credentials: Vec<SensitiveString>,
+        redactor: Redactor,
+        out_of_memory: Arc<AtomicBool>,
     ) -> Result<()> {
         while let Some(line) = stderr.next().await {
             let line = line.map_err(ExecutionError::ChildRecvIO)?;
-            let mut line = String::from_utf8_lossy(line.as_ref());
-            for credential in &credentials {
-                // Note: This brings a possibility of random substrings being matched out of
-                // context, exposing that we have a secret by censoring it But trying to infer word
-                // boundary might leak the plaintext credential which is arguably worse
-                if line.contains(credential.as_str()) {
-                    line = line.replace(credential.as_str(), "[redacted]").into();
-                }
+            let line = String::from_utf8_lossy(line.as_ref());
+            if OUT_OF_MEMORY_MARKERS
+                .iter()
+                .any(|marker| line.contains(marker))
+            {
+                out_of_memory.store(true, Ordering::Relaxed);
             }
-            eprintln!("{line}");
+            eprintln!("{}", redactor.redact_str(&line));
         }
         Ok(())
     }
-    if let Err(error) = handle_stderr_fallible(stderr, credentials).await {
+    if let Err(error) = handle_stderr_fallible(stderr, redactor, out_of_memory).await {
         error!("Unable to collect stderr: {}", error);
     }
 }
@@ -252,87 +314,246 @@ where
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
     pub async fn process(self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
-        tokio::spawn(handle_stderr(self.stderr, self.credentials.clone()));
-
-        let mut stream = self
-            .stdout
-            .map(|ls_result| match ls_result {
-                Ok(ls_msg) => match ls_msg {
-                    LangServerMessage::Output(mut output) => {
-                        Self::filter_output(&mut output, &self.credentials)?;
-                        Ok(Message::OutputStream(output.into()))
+        let Self {
+            mut child,
+            stdout,
+            stderr,
+            redactor,
+            execution_id,
+            timeout,
+            egress_policy,
+            limits,
+            success_marker,
+        } = self;
+
+        let out_of_memory = Arc::new(AtomicBool::new(false));
+        let stderr_handle = tokio::spawn(handle_stderr(
+            stderr,
+            redactor.clone(),
+            out_of_memory.clone(),
+        ));
+
+        let mut stream = stdout.map(|ls_result| match ls_result {
+            Ok(ls_msg) => match ls_msg {
+                LangServerMessage::Output(mut output) => {
+                    Self::filter_output(&mut output, &redactor);
+                    Ok(Message::OutputStream(output.into()))
+                }
+                LangServerMessage::Result(mut result) => {
+                    Self::filter_result(&mut result, &redactor)?;
+                    if !egress_policy.allows_network() {
+                        Self::report_blocked_egress(&mut result);
                     }
-                    LangServerMessage::Result(mut result) => {
-                        Self::filter_result(&mut result, &self.credentials)?;
-                        Ok(Message::Result(result.into()))
+                    Ok(Message::Result(result.into()))
+                }
+            },
+            Err(err) => Err(ExecutionError::ChildRecvIO(err)),
+        });
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut output_bytes = 0;
+        let mut sent_result = false;
+        let mut exceeded_limit = None;
+
+        loop {
+            let next = match deadline {
+                Some(deadline) => match time::timeout_at(deadline, stream.try_next()).await {
+                    Ok(next) => next?,
+                    Err(_elapsed) => {
+                        exceeded_limit = Some(ExecutionLimit::Timeout);
+                        break;
                     }
                 },
-                Err(err) => Err(ExecutionError::ChildRecvIO(err)),
-            })
-            .map(|msg_result: Result<_>| match msg_result {
-                Ok(msg) => match msg
-                    .serialize_to_string()
-                    .map_err(ExecutionError::JSONSerialize)
-                {
-                    Ok(json_str) => Ok(WebSocketMessage::Text(json_str)),
-                    Err(err) => Err(err),
-                },
-                Err(err) => Err(err),
-            });
+                None => stream.try_next().await?,
+            };
+            let Some(msg) = next else {
+                break;
+            };
+
+            let is_result = matches!(msg, Message::Result(_));
+            let json_str = msg
+                .serialize_to_string()
+                .map_err(ExecutionError::JSONSerialize)?;
+            output_bytes += json_str.len();
+            if limits
+                .output_limit_bytes
+                .map_or(false, |limit| output_bytes > limit)
+            {
+                exceeded_limit = Some(ExecutionLimit::OutputSize);
+                break;
+            }
+
+            ws.send(WebSocketMessage::Text(json_str))
+                .await
+                .map_err(ExecutionError::WSSendIO)?;
+            sent_result |= is_result;
+        }
+        drop(stream);
+
+        if exceeded_limit.is_some() {
+            child.kill().await.map_err(ExecutionError::ChildKill)?;
+        } else if !sent_result {
+            // The lang server exited without a result, which is what happens when it is stopped
+            // for hitting a resource limit
+            let cpu_time =
+                time::timeout(CHILD_EXIT_TIMEOUT, process::exited_child_cpu_time(&child))
+                    .await
+                    .ok()
+                    .flatten();
+            if let Ok(Ok(status)) = time::timeout(CHILD_EXIT_TIMEOUT, child.wait()).await {
+                // Wait for the last of stderr, where running out of memory gets reported
+                let _ = time::timeout(CHILD_EXIT_TIMEOUT, stderr_handle).await;
+                exceeded_limit = if out_of_memory.load(Ordering::Relaxed) {
+                    Some(ExecutionLimit::Memory)
+                } else {
+                    match status.signal() {
+                        Some(signal) if signal == process::Signal::SIGXCPU as i32 => {
+                            Some(ExecutionLimit::Cpu)
+                        }
+                        // The lang server is killed once past the limit if it ignores `SIGXCPU`,
+                        // but it may just as well have been killed for any other reason
+                        Some(signal)
+                            if signal == process::Signal::SIGKILL as i32
+                                && reached_cpu_limit(cpu_time, limits.cpu_limit) =>
+                        {
+                            Some(ExecutionLimit::Cpu)
+                        }
+                        _ => None,
+                    }
+                };
+            }
+        }
 
-        while let Some(msg) = stream.try_next().await? {
-            ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+        if let Some(limit) = exceeded_limit {
+            let message = limit_message(limit, timeout, &limits);
+            warn!(
+                execution_id = execution_id.as_str(),
+                kind = limit.failure_kind(),
+                message = message.as_str(),
+                "execution hit a limit"
+            );
+            if !sent_result {
+                Self::ws_send_limit_failure(ws, execution_id, limit, message).await?;
+            }
         }
 
         Ok(ExecutionClosing {
-            child: self.child,
-            success_marker: PhantomData,
+            child,
+            success_marker,
         })
     }
 
-    fn filter_output(output: &mut LangServerOutput, credentials: &[SensitiveString]) -> Result<()> {
-        // Note: This brings a possibility of random substrings being matched out of context,
-        // exposing that we have a secret by censoring it But trying to infer word boundary might
-        // leak the plaintext credential which is arguably worse
-        for credential in credentials {
-            if output.message.contains(credential.as_str()) {
-                output.message = output.message.replace(credential.as_str(), "[redacted]");
-            }
-        }
+    async fn ws_send_limit_failure(
+        ws: &mut WebSocket,
+        execution_id: String,
+        limit: ExecutionLimit,
+        message: String,
+    ) -> Result<()> {
+        let msg = Message::<Success>::Result(FunctionResult::Failure(FunctionResultFailure {
+            execution_id,
+            error: FunctionResultFailureError {
+                kind: FunctionResultFailureErrorKind::ExecutionLimit(limit),
+                message,
+            },
+            timestamp: crate::timestamp(),
+        }))
+        .serialize_to_string()
+        .map_err(ExecutionError::JSONSerialize)?;
 
+        time::timeout(TX_TIMEOUT_SECS, ws.send(WebSocketMessage::Text(msg)))
+            .await
+            .map_err(ExecutionError::SendTimeout)?
+            .map_err(ExecutionError::WSSendIO)?;
         Ok(())
     }
 
+    /// Scrubs known secret values from a line of output before it leaves the sandbox.
+    fn filter_output(output: &mut LangServerOutput, redactor: &Redactor) {
+        if let Cow::Owned(message) = redactor.redact_str(&output.message) {
+            output.message = message;
+        }
+        if let Some(group) = output.group.as_mut() {
+            if let Cow::Owned(redacted) = redactor.redact_str(group) {
+                *group = redacted;
+            }
+        }
+    }
+
+    /// Scrubs known secret values from the result payload, including failure messages, before
+    /// it leaves the sandbox.
     fn filter_result(
         result: &mut LangServerResult<LangServerSuccess>,
-        credentials: &[SensitiveString],
+        redactor: &Redactor,
     ) -> Result<()> {
-        let mut value = serde_json::to_value(&result).map_err(ExecutionError::JSONSerialize)?;
-        // Note: This brings a possibility of random substrings being matched out of context,
-        // exposing that we have a secret by censoring it But trying to infer word boundary might
-        // leak the plaintext credential which is arguably worse
-        for credential in credentials {
-            let mut work_queue = vec![&mut value];
-            while let Some(work) = work_queue.pop() {
-                match work {
-                    Value::Array(values) => work_queue.extend(values),
-                    Value::Object(object) => object.values_mut().for_each(|v| work_queue.push(v)),
-                    Value::String(v) if v.contains(credential.as_str()) => {
-                        *v = v.replace(credential.as_str(), "[redacted]");
-                    }
-                    Value::String(_) => {}
-                    // For now credentials can only be strings, although we should reconsider it
-                    Value::Null => {}
-                    Value::Number(_) => {}
-                    Value::Bool(_) => {}
-                }
-            }
+        if redactor.is_empty() {
+            return Ok(());
         }
+        let mut value = serde_json::to_value(&result).map_err(ExecutionError::JSONSerialize)?;
+        redactor.redact_value(&mut value);
         let mut filtered_result: LangServerResult<LangServerSuccess> =
             serde_json::from_value(value).map_err(ExecutionError::JSONDeserialize)?;
         std::mem::swap(result, &mut filtered_result);
         Ok(())
     }
+
+    /// Reports a failure caused by a connection the function wasn't allowed to make as such,
+    /// rather than as whichever error the lang server ran into.
+    fn report_blocked_egress(result: &mut LangServerResult<LangServerSuccess>) {
+        let LangServerResult::Failure(failure) = result else {
+            return;
+        };
+        let error = &mut failure.error;
+        if error.kind != FunctionResultFailureErrorKind::EgressBlocked
+            && NETWORK_ERROR_MARKERS
+                .iter()
+                .any(|marker| error.message.contains(marker))
+        {
+            error.message = format!(
+                "function execution attempted to reach the network, which its egress policy \
+                denies: {}",
+                error.message
+            );
+            error.kind = FunctionResultFailureErrorKind::EgressBlocked;
+        }
+    }
+}
+
+/// Returns true if the CPU time used by the lang server reached the limit it was given, which is
+/// set in whole seconds (see [`process::limit_cpu_time()`]).
+fn reached_cpu_limit(cpu_time: Option<Duration>, cpu_limit: Option<Duration>) -> bool {
+    match (cpu_time, cpu_limit) {
+        (Some(cpu_time), Some(cpu_limit)) => {
+            cpu_time.as_secs_f64() >= cpu_limit.as_secs().max(1) as f64
+        }
+        _ => false,
+    }
+}
+
+fn limit_message(
+    limit: ExecutionLimit,
+    timeout: Option<Duration>,
+    limits: &ExecutionLimits,
+) -> String {
+    match limit {
+        ExecutionLimit::Cpu => format!(
+            "function execution exceeded the cpu time limit of {}s",
+            limits.cpu_limit.unwrap_or_default().as_secs()
+        ),
+        ExecutionLimit::Memory => match limits.memory_limit_mib {
+            Some(memory_limit_mib) => {
+                format!("function execution exceeded the memory limit of {memory_limit_mib} MiB")
+            }
+            None => "function execution ran out of memory".to_owned(),
+        },
+        ExecutionLimit::OutputSize => format!(
+            "function execution exceeded the output limit of {} bytes",
+            limits.output_limit_bytes.unwrap_or_default()
+        ),
+        ExecutionLimit::Timeout => format!(
+            "function execution timed out after {}s",
+            timeout.unwrap_or_default().as_secs()
+        ),
+    }
 }
 
 #[derive(Debug)]
@@ -474,6 +695,6 @@ pub struct LangServerFailure {
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct LangServerFailureError {
-    kind: String,
+    kind: FunctionResultFailureErrorKind,
     message: String,
 }
@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use cyclone_core::{
-    ActionRunRequest, ComponentKind, ComponentView, ReconciliationRequest, ResolverFunctionRequest,
-    SchemaVariantDefinitionRequest, SensitiveString, ValidationRequest,
+    ActionRunRequest, ComponentKind, ComponentView, EgressPolicy, ReconciliationRequest,
+    ResolverFunctionRequest, SchemaVariantDefinitionRequest, SensitiveString, ValidationRequest,
 };
 use serde_json::Value;
 
@@ -15,6 +17,19 @@ pub trait DecryptRequest {
     fn decrypt_request(self, key: &DecryptionKey) -> Result<serde_json::Value, DecryptionKeyError>;
 }
 
+pub trait ExecutionMetadata {
+    fn execution_id(&self) -> &str;
+
+    /// Returns the timeout the request asks for, if any.
+    fn timeout(&self) -> Option<Duration>;
+
+    /// Returns the network access given to the execution, which is none unless the kind of
+    /// function being executed needs it.
+    fn egress_policy(&self) -> EgressPolicy {
+        EgressPolicy::Deny
+    }
+}
+
 impl ListSecrets for ComponentView {
     fn list_secrets(
         &self,
@@ -262,6 +277,64 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
     }
 }
 
+impl ExecutionMetadata for ResolverFunctionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionMetadata for ActionRunRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    fn egress_policy(&self) -> EgressPolicy {
+        self.egress_policy
+    }
+}
+
+impl ExecutionMetadata for ReconciliationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    fn egress_policy(&self) -> EgressPolicy {
+        self.egress_policy
+    }
+}
+
+impl ExecutionMetadata for ValidationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionMetadata for SchemaVariantDefinitionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use base64::{engine::general_purpose, Engine};
@@ -5,6 +5,7 @@ use std::{
 };
 
 use axum::routing::{IntoMakeService, Router};
+use cyclone_core::process;
 use hyper::server::{accept::Accept, conn::AddrIncoming};
 use si_settings::{CanonicalFile, CanonicalFileError};
 use telemetry::{prelude::*, TelemetryLevel};
@@ -17,8 +18,10 @@ use tokio::{
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 
 use crate::{
-    routes::routes, state::AppState, Config, DecryptionKey, DecryptionKeyError, IncomingStream,
-    UdsIncomingStream, UdsIncomingStreamError,
+    routes::routes,
+    state::{AppState, ExecutionLimits},
+    Config, DecryptionKey, DecryptionKeyError, IncomingStream, UdsIncomingStream,
+    UdsIncomingStreamError,
 };
 
 #[remain::sorted]
@@ -30,6 +33,8 @@ pub enum ServerError {
     DecryptionKey(#[from] DecryptionKeyError),
     #[error("hyper server error")]
     Hyper(#[from] hyper::Error),
+    #[error("lang server network isolation is enabled but not permitted on this host")]
+    NetworkIsolation(#[source] io::Error),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
     #[error("UDS incoming stream error")]
@@ -147,9 +152,25 @@ fn build_service(
     telemetry_level: Box<dyn TelemetryLevel>,
     decryption_key: DecryptionKey,
 ) -> Result<(IntoMakeService<Router>, oneshot::Receiver<()>)> {
+    // Fails on startup rather than on every execution denied network access
+    if config.lang_server_network_isolation() {
+        process::probe_network_isolation()
+            .map_err(|err| ServerError::NetworkIsolation(err.into()))?;
+    } else if process::has_network_interfaces().unwrap_or(true) {
+        warn!(
+            "lang server network isolation is disabled, executions denied network access only \
+            have `fetch` blocked by the lang server"
+        );
+    }
+
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
-    let state = AppState::new(config.lang_server_path(), decryption_key, telemetry_level);
+    let state = AppState::new(
+        config.lang_server_path(),
+        decryption_key,
+        telemetry_level,
+        ExecutionLimits::from_config(config),
+    );
 
     let routes = routes(config, state, shutdown_tx)
         // TODO(fnichol): customize http tracing further, using:
@@ -13,6 +13,7 @@ pub struct AppState {
     lang_server_path: LangServerPath,
     decryption_key: DecryptionKey,
     telemetry_level: TelemetryLevel,
+    execution_limits: ExecutionLimits,
 }
 
 impl AppState {
@@ -20,11 +21,13 @@ impl AppState {
         lang_server_path: impl Into<PathBuf>,
         decryption_key: crate::DecryptionKey,
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
+        execution_limits: ExecutionLimits,
     ) -> Self {
         Self {
             lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
             decryption_key: DecryptionKey(Arc::new(decryption_key)),
             telemetry_level: TelemetryLevel(Arc::new(telemetry_level)),
+            execution_limits,
         }
     }
 }
@@ -66,6 +69,38 @@ impl Deref for TelemetryLevel {
     }
 }
 
+/// The limits put on every execution and on the lang server process running it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExecutionLimits {
+    /// Used when a request doesn't carry its own timeout and as a cap when it does.
+    pub timeout: Option<Duration>,
+    pub memory_limit_mib: Option<u64>,
+    pub cpu_limit: Option<Duration>,
+    pub output_limit_bytes: Option<usize>,
+    /// Runs executions denied network access in a network namespace of their own.
+    pub network_isolation: bool,
+}
+
+impl ExecutionLimits {
+    pub fn from_config(config: &crate::Config) -> Self {
+        Self {
+            timeout: config.execution_timeout(),
+            memory_limit_mib: config.lang_server_memory_limit_mib(),
+            cpu_limit: config.lang_server_cpu_limit(),
+            output_limit_bytes: config.output_limit_bytes(),
+            network_isolation: config.lang_server_network_isolation(),
+        }
+    }
+
+    /// Returns the timeout of an execution, given the one its request asks for.
+    pub fn timeout_for(&self, requested: Option<Duration>) -> Option<Duration> {
+        match (requested, self.timeout) {
+            (Some(requested), Some(timeout)) => Some(requested.min(timeout)),
+            (requested, timeout) => requested.or(timeout),
+        }
+    }
+}
+
 pub struct WatchKeepalive {
     tx: mpsc::Sender<()>,
     timeout: Duration,
@@ -12,7 +12,7 @@ use buck2_resources::Buck2Resources;
 use dal::{
     builtins::SelectedTestBuiltinSchemas,
     job::processor::{JobQueueProcessor, NatsProcessor},
-    DalContext, JwtPublicSigningKey, ServicesContext,
+    DalContext, JwtPublicSigningKey, SecretBackends, ServicesContext,
 };
 use derive_builder::Builder;
 use jwt_simple::prelude::RS256KeyPair;
@@ -236,6 +236,7 @@ impl TestContext {
             self.encryption_key.clone(),
             self.config.pkgs_path.to_owned(),
             None,
+            SecretBackends::default(),
         )
     }
 
@@ -429,7 +430,11 @@ pub fn pinga_server(services_context: &ServicesContext) -> Result<pinga_server::
     let server = pinga_server::Server::from_services(
         config.instance_id(),
         config.concurrency(),
+        config.workspace_concurrency(),
+        config.workspace_concurrency_overrides().clone(),
+        config.durable_queue(),
         services_context.encryption_key(),
+        services_context.secret_backends().clone(),
         services_context.nats_conn().clone(),
         services_context.pg_pool().clone(),
         services_context.veritech().clone(),
@@ -3,7 +3,7 @@ use std::{env, path::Path, sync::Arc};
 use tokio::fs;
 
 use dal::{
-    pkg::export_pkg_as_bytes, DalContext, JobQueueProcessor, NatsProcessor, Schema,
+    pkg::export_pkg_as_bytes, DalContext, JobQueueProcessor, NatsProcessor, Schema, SecretBackends,
     ServicesContext, Tenancy, Workspace,
 };
 use si_data_nats::{NatsClient, NatsConfig};
@@ -46,6 +46,7 @@ async fn main() -> Result<()> {
             Some(description),
             created_by,
             variant_ids,
+            vec![],
         )
         .await?,
     )
@@ -75,6 +76,7 @@ async fn ctx() -> Result<DalContext> {
         encryption_key,
         None,
         None,
+        SecretBackends::default(),
     );
 
     Ok(DalContext::builder(services_context, false)
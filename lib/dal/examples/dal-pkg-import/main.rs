@@ -2,8 +2,8 @@ use std::{env, path::Path, sync::Arc};
 
 use buck2_resources::Buck2Resources;
 use dal::{
-    pkg::import_pkg, DalContext, JobQueueProcessor, NatsProcessor, ServicesContext, Tenancy,
-    Workspace,
+    pkg::import_pkg, DalContext, JobQueueProcessor, NatsProcessor, SecretBackends, ServicesContext,
+    Tenancy, Workspace,
 };
 use si_data_nats::{NatsClient, NatsConfig};
 use si_data_pg::{PgPool, PgPoolConfig};
@@ -47,6 +47,7 @@ async fn ctx() -> Result<DalContext> {
         encryption_key,
         None,
         None,
+        SecretBackends::default(),
     );
 
     Ok(DalContext::builder(services_context, false)
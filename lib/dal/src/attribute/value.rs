@@ -43,6 +43,7 @@ use si_data_pg::PgError;
 use std::collections::HashMap;
 use telemetry::prelude::*;
 use thiserror::Error;
+use veritech_client::FunctionResultFailureErrorKind;
 
 use crate::{
     attribute::{
@@ -124,7 +125,7 @@ pub enum AttributeValueError {
     Func(#[from] FuncError),
     #[error("function result failure: kind={kind}, message={message}, backend={backend}")]
     FuncBackendResultFailure {
-        kind: String,
+        kind: FunctionResultFailureErrorKind,
         message: String,
         backend: String,
     },
@@ -1106,32 +1107,33 @@ impl AttributeValue {
         }
 
         let func_id = attribute_prototype.func_id();
-        let (func_binding, mut func_binding_return_value) = match FuncBinding::create_and_execute(
-            ctx,
-            serde_json::to_value(func_binding_args.clone())?,
-            attribute_prototype.func_id(),
-        )
-        .instrument(debug_span!(
-            "Func execution",
-            "func.id" = %func_id,
-            ?func_binding_args,
-        ))
-        .await
-        {
-            Ok(function_return_value) => function_return_value,
-            Err(FuncBindingError::FuncBackendResultFailure {
-                kind,
-                message,
-                backend,
-            }) => {
-                return Err(AttributeValueError::FuncBackendResultFailure {
+        let (func_binding, mut func_binding_return_value) =
+            match FuncBinding::create_and_execute_memoized(
+                ctx,
+                serde_json::to_value(func_binding_args.clone())?,
+                attribute_prototype.func_id(),
+            )
+            .instrument(debug_span!(
+                "Func execution",
+                "func.id" = %func_id,
+                ?func_binding_args,
+            ))
+            .await
+            {
+                Ok(function_return_value) => function_return_value,
+                Err(FuncBindingError::FuncBackendResultFailure {
                     kind,
                     message,
                     backend,
-                })
-            }
-            Err(err) => Err(err)?,
-        };
+                }) => {
+                    return Err(AttributeValueError::FuncBackendResultFailure {
+                        kind,
+                        message,
+                        backend,
+                    })
+                }
+                Err(err) => Err(err)?,
+            };
 
         self.set_func_binding_id(ctx, *func_binding.id()).await?;
         self.set_func_binding_return_value_id(ctx, *func_binding_return_value.id())
@@ -15,6 +15,19 @@ use crate::{
 };
 use crate::{Component, ComponentError, DalContext, WsEventResult};
 
+mod conflict;
+mod diff;
+mod garbage;
+
+pub use conflict::{
+    ChangeSetConflict, ChangeSetConflictKind, ChangeSetRebase, ConflictResolution,
+    ConflictingColumn,
+};
+pub use diff::{
+    AttributeValueDiff, ChangeSetDiff, ColumnDiff, EdgeDiff, FuncDiff, SchemaVariantDefinitionDiff,
+};
+pub use garbage::ChangeSetGarbageReport;
+
 const CHANGE_SET_OPEN_LIST: &str = include_str!("queries/change_set/open_list.sql");
 const CHANGE_SET_GET_BY_PK: &str = include_str!("queries/change_set/get_by_pk.sql");
 
@@ -23,6 +36,8 @@ const CHANGE_SET_GET_BY_PK: &str = include_str!("queries/change_set/get_by_pk.sq
 pub enum ChangeSetError {
     #[error(transparent)]
     Component(#[from] ComponentError),
+    #[error("change set {0} has expired")]
+    Expired(ChangeSetPk),
     #[error(transparent)]
     HistoryEvent(#[from] HistoryEventError),
     #[error("invalid user actor pk")]
@@ -31,6 +46,8 @@ pub enum ChangeSetError {
     LabelList(#[from] LabelListError),
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("change set {0} is not open")]
+    NotOpen(ChangeSetPk),
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
@@ -65,6 +82,10 @@ pub struct ChangeSet {
     pub name: String,
     pub note: Option<String>,
     pub status: ChangeSetStatus,
+    /// When the change set is abandoned if it's still open, see
+    /// [`ChangeSet::collect_garbage`].
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -109,12 +130,20 @@ impl ChangeSet {
         Utc::now().format("%Y-%m-%d-%H:%M").to_string()
     }
 
+    /// Applies the change set to head. Only open change sets that haven't expired can be applied.
     #[instrument(skip(ctx))]
     pub async fn apply_raw(
         &mut self,
         ctx: &mut DalContext,
         run_confirmations: bool,
     ) -> ChangeSetResult<()> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk));
+        }
+        if self.is_expired() {
+            return Err(ChangeSetError::Expired(self.pk));
+        }
+
         let actor = serde_json::to_value(ctx.history_actor())?;
         let row = ctx
             .txns()
@@ -125,8 +154,9 @@ impl ChangeSet {
                 &[&self.pk, &actor, &self.tenancy],
             )
             .await?;
-        let updated_at: DateTime<Utc> = row.try_get("timestamp_updated_at")?;
-        self.timestamp.updated_at = updated_at;
+        // The change set may have been abandoned, or have expired, since it was fetched.
+        let updated_at: Option<DateTime<Utc>> = row.try_get("timestamp_updated_at")?;
+        self.timestamp.updated_at = updated_at.ok_or(ChangeSetError::NotOpen(self.pk))?;
         self.status = ChangeSetStatus::Applied;
         let _history_event = HistoryEvent::new(
             ctx,
@@ -158,6 +188,69 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Whether the change set's time to live has run out. It can't be applied, even if the
+    /// garbage collector hasn't abandoned it yet.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= Utc::now())
+    }
+
+    /// Abandons an open change set. It can no longer be applied, and its rows are purged by the
+    /// next [`ChangeSet::collect_garbage`].
+    #[instrument(skip(ctx))]
+    pub async fn abandon(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM change_set_abandon_v1($1, $2)",
+                &[&self.pk, &self.tenancy],
+            )
+            .await?;
+        let updated_at: Option<DateTime<Utc>> = row.try_get("timestamp_updated_at")?;
+        self.timestamp.updated_at = updated_at.ok_or(ChangeSetError::NotOpen(self.pk))?;
+        self.status = ChangeSetStatus::Abandoned;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.abandon",
+            "Change Set abandoned",
+            &serde_json::json![{ "pk": &self.pk }],
+        )
+        .await?;
+
+        WsEvent::change_set_canceled(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sets when the change set expires, or clears it with `None` so it stays open until it's
+    /// applied or abandoned.
+    #[instrument(skip(ctx))]
+    pub async fn set_expires_at(
+        &mut self,
+        ctx: &DalContext,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ChangeSetResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM change_set_update_expires_at_v1($1, $2, $3)",
+                &[&self.pk, &self.tenancy, &expires_at],
+            )
+            .await?;
+        let updated_at: Option<DateTime<Utc>> = row.try_get("timestamp_updated_at")?;
+        self.timestamp.updated_at = updated_at.ok_or(ChangeSetError::NotOpen(self.pk))?;
+        self.expires_at = expires_at;
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     pub async fn list_open(ctx: &DalContext) -> ChangeSetResult<LabelList<ChangeSetPk>> {
         let rows = ctx
@@ -0,0 +1,343 @@
+//! This module contains the ability to find and resolve conflicts between a [`ChangeSet`] and
+//! head.
+//!
+//! A change set takes its own copy of a row the first time it writes to it. If head writes to the
+//! same row after that, applying the change set would silently overwrite head's write. Rows the
+//! change set never wrote to can't conflict: the change set sees head's version of them as soon
+//! as head changes.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{Display, EnumString};
+use telemetry::prelude::*;
+
+use super::{ChangeSet, ChangeSetResult};
+use crate::{DalContext, HistoryEvent};
+
+const CHANGE_SET_CONFLICTS: &str = "SELECT * FROM change_set_conflicts_v1($1, $2)";
+const CHANGE_SET_CONFLICT_RESOLVE: &str =
+    "SELECT change_set_conflict_resolve_v1($1, $2, $3, $4, $5)";
+
+/// Columns that record where and when a row was written rather than what it holds.
+pub(super) const BOOKKEEPING_COLUMNS: &[&str] = &[
+    "pk",
+    "tenancy_workspace_pk",
+    "visibility_change_set_pk",
+    "visibility_deleted_at",
+    "created_at",
+    "updated_at",
+];
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeSetConflictKind {
+    /// The change set deleted the object, but head modified it.
+    DeletedInChangeSet,
+    /// Head deleted the object, but the change set modified it.
+    DeletedOnHead,
+    /// Both sides modified the object, and disagree on some of its columns.
+    Modified,
+}
+
+/// An object written to by both a [`ChangeSet`] and head since the change set started working
+/// on it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetConflict {
+    pub table_name: String,
+    pub object_id: String,
+    pub kind: ChangeSetConflictKind,
+    /// The columns the two sides disagree on.
+    pub columns: Vec<ConflictingColumn>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictingColumn {
+    pub name: String,
+    pub change_set_value: Value,
+    pub head_value: Value,
+}
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+pub enum ConflictResolution {
+    /// Keep the change set's version, overwriting head's when the change set is applied.
+    KeepChangeSet,
+    /// Drop the change set's version in favor of head's.
+    TakeHead,
+}
+
+/// The outcome of [`ChangeSet::rebase`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetRebase {
+    /// How many objects the change set now takes from head, because both sides had made the
+    /// same change to them.
+    pub refreshed: usize,
+    /// The conflicts that have to be resolved before the change set can be applied.
+    pub conflicts: Vec<ChangeSetConflict>,
+}
+
+/// A change set row whose head counterpart was written to after the change set copied it.
+#[derive(Debug, Clone)]
+struct ConflictCandidate {
+    table_name: String,
+    object_id: String,
+    change_set_object: Value,
+    head_object: Value,
+}
+
+impl ConflictCandidate {
+    /// Compares both sides, returning `None` when they agree.
+    fn into_conflict(self) -> Option<ChangeSetConflict> {
+        let deleted_in_change_set = is_deleted(&self.change_set_object);
+        let deleted_on_head = is_deleted(&self.head_object);
+        let columns = conflicting_columns(&self.change_set_object, &self.head_object);
+
+        let kind = match (deleted_in_change_set, deleted_on_head) {
+            (true, true) => return None,
+            (true, false) => ChangeSetConflictKind::DeletedInChangeSet,
+            (false, true) => ChangeSetConflictKind::DeletedOnHead,
+            (false, false) if columns.is_empty() => return None,
+            (false, false) => ChangeSetConflictKind::Modified,
+        };
+
+        Some(ChangeSetConflict {
+            table_name: self.table_name,
+            object_id: self.object_id,
+            kind,
+            columns,
+        })
+    }
+}
+
+fn is_deleted(object: &Value) -> bool {
+    !object
+        .get("visibility_deleted_at")
+        .unwrap_or(&Value::Null)
+        .is_null()
+}
+
+fn conflicting_columns(change_set_object: &Value, head_object: &Value) -> Vec<ConflictingColumn> {
+    let empty = serde_json::Map::new();
+    let change_set_columns = change_set_object.as_object().unwrap_or(&empty);
+    let head_columns = head_object.as_object().unwrap_or(&empty);
+
+    let mut names: Vec<&String> = change_set_columns
+        .keys()
+        .chain(head_columns.keys())
+        .filter(|name| !BOOKKEEPING_COLUMNS.contains(&name.as_str()))
+        .collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let change_set_value = change_set_columns.get(name).unwrap_or(&Value::Null);
+            let head_value = head_columns.get(name).unwrap_or(&Value::Null);
+            (change_set_value != head_value).then(|| ConflictingColumn {
+                name: name.clone(),
+                change_set_value: change_set_value.clone(),
+                head_value: head_value.clone(),
+            })
+        })
+        .collect()
+}
+
+impl ChangeSet {
+    /// Lists the objects that both this change set and head have written to since the change
+    /// set started working on them, and that are still unresolved. The change set shouldn't be
+    /// applied until this is empty.
+    #[instrument(skip_all)]
+    pub async fn conflicts(&self, ctx: &DalContext) -> ChangeSetResult<Vec<ChangeSetConflict>> {
+        Ok(self
+            .conflict_candidates(ctx)
+            .await?
+            .into_iter()
+            .filter_map(ConflictCandidate::into_conflict)
+            .collect())
+    }
+
+    /// Brings the change set up to date with head. Objects both sides changed in the same way
+    /// are handed back to head; the ones where they disagree are returned as conflicts, to be
+    /// settled with [`Self::resolve_conflict`].
+    #[instrument(skip_all)]
+    pub async fn rebase(&self, ctx: &DalContext) -> ChangeSetResult<ChangeSetRebase> {
+        let mut refreshed = 0;
+        let mut conflicts = Vec::new();
+        for candidate in self.conflict_candidates(ctx).await? {
+            let table_name = candidate.table_name.clone();
+            let object_id = candidate.object_id.clone();
+            match candidate.into_conflict() {
+                Some(conflict) => conflicts.push(conflict),
+                None => {
+                    self.resolve_raw(ctx, &table_name, &object_id, ConflictResolution::TakeHead)
+                        .await?;
+                    refreshed += 1;
+                }
+            }
+        }
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.rebase",
+            "Change Set rebased",
+            &serde_json::json![{
+                "pk": &self.pk,
+                "refreshed": refreshed,
+                "conflicts": conflicts.len(),
+            }],
+        )
+        .await?;
+
+        Ok(ChangeSetRebase {
+            refreshed,
+            conflicts,
+        })
+    }
+
+    /// Settles a conflict reported by [`Self::conflicts`] or [`Self::rebase`].
+    #[instrument(skip(self, ctx))]
+    pub async fn resolve_conflict(
+        &self,
+        ctx: &DalContext,
+        table_name: &str,
+        object_id: &str,
+        resolution: ConflictResolution,
+    ) -> ChangeSetResult<()> {
+        self.resolve_raw(ctx, table_name, object_id, resolution)
+            .await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.resolve_conflict",
+            "Change Set conflict resolved",
+            &serde_json::json![{
+                "pk": &self.pk,
+                "tableName": table_name,
+                "objectId": object_id,
+                "resolution": resolution,
+            }],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn resolve_raw(
+        &self,
+        ctx: &DalContext,
+        table_name: &str,
+        object_id: &str,
+        resolution: ConflictResolution,
+    ) -> ChangeSetResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                CHANGE_SET_CONFLICT_RESOLVE,
+                &[
+                    &self.pk,
+                    &self.tenancy,
+                    &table_name,
+                    &object_id,
+                    &resolution.to_string(),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn conflict_candidates(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<ConflictCandidate>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(CHANGE_SET_CONFLICTS, &[&self.pk, &self.tenancy])
+            .await?;
+
+        let mut candidates = Vec::with_capacity(rows.len());
+        for row in rows {
+            candidates.push(ConflictCandidate {
+                table_name: row.try_get("conflict_table_name")?,
+                object_id: row.try_get("conflict_object_id")?,
+                change_set_object: row.try_get("change_set_object")?,
+                head_object: row.try_get("head_object")?,
+            });
+        }
+
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn candidate(change_set_object: Value, head_object: Value) -> ConflictCandidate {
+        ConflictCandidate {
+            table_name: "schemas".to_string(),
+            object_id: "01H0000000000000000000000A".to_string(),
+            change_set_object,
+            head_object,
+        }
+    }
+
+    #[test]
+    fn matching_objects_do_not_conflict() {
+        let conflict = candidate(
+            json!({ "pk": "a", "name": "canoe", "updated_at": "later" }),
+            json!({ "pk": "b", "name": "canoe", "updated_at": "earlier" }),
+        )
+        .into_conflict();
+
+        assert_eq!(conflict, None);
+    }
+
+    #[test]
+    fn differing_columns_are_reported() {
+        let conflict = candidate(
+            json!({ "name": "canoe", "ui_hidden": false, "visibility_deleted_at": null }),
+            json!({ "name": "kayak", "ui_hidden": false, "visibility_deleted_at": null }),
+        )
+        .into_conflict()
+        .expect("objects should conflict");
+
+        assert_eq!(conflict.kind, ChangeSetConflictKind::Modified);
+        assert_eq!(
+            conflict.columns,
+            vec![ConflictingColumn {
+                name: "name".to_string(),
+                change_set_value: json!("canoe"),
+                head_value: json!("kayak"),
+            }]
+        );
+    }
+
+    #[test]
+    fn deletions_conflict_with_modifications() {
+        let conflict = candidate(
+            json!({ "name": "canoe", "visibility_deleted_at": "2023-01-01T00:00:00Z" }),
+            json!({ "name": "kayak", "visibility_deleted_at": null }),
+        )
+        .into_conflict()
+        .expect("objects should conflict");
+        assert_eq!(conflict.kind, ChangeSetConflictKind::DeletedInChangeSet);
+
+        let conflict = candidate(
+            json!({ "name": "canoe", "visibility_deleted_at": "2023-01-01T00:00:00Z" }),
+            json!({ "name": "kayak", "visibility_deleted_at": "2023-01-02T00:00:00Z" }),
+        )
+        .into_conflict();
+        assert_eq!(conflict, None);
+    }
+}
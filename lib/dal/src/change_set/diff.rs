@@ -0,0 +1,422 @@
+//! This module contains [`ChangeSetDiff`], a property-level report of everything a [`ChangeSet`]
+//! would change on head if it were applied.
+
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+
+use super::{conflict::BOOKKEEPING_COLUMNS, ChangeSet, ChangeSetPk, ChangeSetResult};
+use crate::change_status::ChangeStatus;
+use crate::prop::PropPath;
+use crate::schema::variant::definition::SchemaVariantDefinitionId;
+use crate::{
+    edge::EdgeId, AttributeValueId, ComponentId, DalContext, Edge, FuncId, PropId, Visibility,
+};
+
+const CHANGE_SET_DIFF_ATTRIBUTE_VALUES: &str =
+    include_str!("../queries/change_set/diff_attribute_values.sql");
+const CHANGE_SET_OBJECT_DIFFS: &str = "SELECT * FROM change_set_object_diffs_v1($1, $2, $3)";
+const CHANGE_SET_TOUCHED_TABLES: &str = "SELECT * FROM change_set_touched_tables_v1($1, $2)";
+
+/// Func columns covered by [`FuncDiff::old_code`] and [`FuncDiff::new_code`].
+const FUNC_CODE_COLUMNS: &[&str] = &["code_base64", "code_sha256"];
+
+/// Everything a [`ChangeSet`] changes compared to head, as returned by [`ChangeSet::diff`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetDiff {
+    pub change_set_pk: ChangeSetPk,
+    pub attribute_values: Vec<AttributeValueDiff>,
+    pub edges: Vec<EdgeDiff>,
+    pub funcs: Vec<FuncDiff>,
+    pub schema_variant_definitions: Vec<SchemaVariantDefinitionDiff>,
+    /// How many rows the change set wrote to each table, including the ones not covered above.
+    pub touched_rows: BTreeMap<String, u64>,
+}
+
+/// A change to the value of a component's attribute.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueDiff {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub prop_id: PropId,
+    /// The path of the prop, with its parts separated by `/`.
+    pub path: String,
+    /// The map key of the value, for elements of map props.
+    pub key: Option<String>,
+    pub status: ChangeStatus,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+/// An edge the change set adds or removes.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeDiff {
+    pub status: ChangeStatus,
+    pub edge: Edge,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncDiff {
+    pub func_id: FuncId,
+    pub name: String,
+    pub status: ChangeStatus,
+    pub old_code: Option<String>,
+    pub new_code: Option<String>,
+    /// Changes to everything else about the func.
+    pub columns: Vec<ColumnDiff>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaVariantDefinitionDiff {
+    pub schema_variant_definition_id: SchemaVariantDefinitionId,
+    pub name: String,
+    pub status: ChangeStatus,
+    pub columns: Vec<ColumnDiff>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDiff {
+    pub name: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+/// A row the change set wrote to, next to head's version of it.
+#[derive(Debug, Clone)]
+struct ObjectDiff<I> {
+    id: I,
+    change_set_object: Value,
+    head_object: Option<Value>,
+}
+
+impl<I> ObjectDiff<I> {
+    /// How the change set changes the object, or `None` if it ends up the same as on head.
+    fn status(&self, columns: &[ColumnDiff]) -> Option<ChangeStatus> {
+        let deleted_in_change_set = !self
+            .change_set_object
+            .get("visibility_deleted_at")
+            .unwrap_or(&Value::Null)
+            .is_null();
+        change_status(
+            deleted_in_change_set,
+            self.head_object.is_some(),
+            !columns.is_empty(),
+        )
+    }
+
+    /// The columns the change set changes, leaving out `ignored` ones.
+    fn columns(&self, ignored: &[&str]) -> Vec<ColumnDiff> {
+        let empty = serde_json::Map::new();
+        let new_columns = self.change_set_object.as_object().unwrap_or(&empty);
+        let old_columns = self
+            .head_object
+            .as_ref()
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        let mut names: Vec<&String> = new_columns
+            .keys()
+            .chain(old_columns.keys())
+            .filter(|name| {
+                !BOOKKEEPING_COLUMNS.contains(&name.as_str()) && !ignored.contains(&name.as_str())
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let new_value = new_columns.get(name).unwrap_or(&Value::Null);
+                let old_value = old_columns.get(name).unwrap_or(&Value::Null);
+                (new_value != old_value).then(|| ColumnDiff {
+                    name: name.clone(),
+                    old_value: old_value.clone(),
+                    new_value: new_value.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn change_set_str(&self, column: &str) -> Option<&str> {
+        self.change_set_object.get(column).and_then(Value::as_str)
+    }
+
+    fn head_str(&self, column: &str) -> Option<&str> {
+        self.head_object
+            .as_ref()
+            .and_then(|object| object.get(column))
+            .and_then(Value::as_str)
+    }
+}
+
+fn change_status(
+    deleted_in_change_set: bool,
+    on_head: bool,
+    changed: bool,
+) -> Option<ChangeStatus> {
+    match (deleted_in_change_set, on_head) {
+        // Created and deleted within the change set, so head never sees it.
+        (true, false) => None,
+        (true, true) => Some(ChangeStatus::Deleted),
+        (false, false) => Some(ChangeStatus::Added),
+        (false, true) if changed => Some(ChangeStatus::Modified),
+        (false, true) => None,
+    }
+}
+
+fn decode_code(code_base64: Option<&str>) -> Option<String> {
+    code_base64
+        .and_then(|code| general_purpose::STANDARD_NO_PAD.decode(code).ok())
+        .map(|code| String::from_utf8_lossy(&code).into_owned())
+}
+
+impl ChangeSet {
+    /// Compares everything this change set wrote with head.
+    #[instrument(skip_all)]
+    pub async fn diff(&self, ctx: &DalContext) -> ChangeSetResult<ChangeSetDiff> {
+        let mut touched_rows = BTreeMap::new();
+        for row in ctx
+            .txns()
+            .await?
+            .pg()
+            .query(CHANGE_SET_TOUCHED_TABLES, &[&self.pk, &self.tenancy])
+            .await?
+        {
+            let table_name: String = row.try_get("touched_table_name")?;
+            let rows: i64 = row.try_get("touched_rows")?;
+            touched_rows.insert(table_name, rows as u64);
+        }
+
+        Ok(ChangeSetDiff {
+            change_set_pk: self.pk,
+            attribute_values: self.diff_attribute_values(ctx).await?,
+            edges: self.diff_edges(ctx).await?,
+            funcs: self.diff_funcs(ctx).await?,
+            schema_variant_definitions: self.diff_schema_variant_definitions(ctx).await?,
+            touched_rows,
+        })
+    }
+
+    async fn diff_attribute_values(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<AttributeValueDiff>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                CHANGE_SET_DIFF_ATTRIBUTE_VALUES,
+                &[
+                    &self.tenancy,
+                    &self.pk,
+                    &Visibility::new(self.pk, None),
+                    &Visibility::new_head(false),
+                ],
+            )
+            .await?;
+
+        let mut diffs = Vec::new();
+        for row in rows {
+            let old_value: Option<Value> = row.try_get("head_value")?;
+            let new_value: Option<Value> = row.try_get("change_set_value")?;
+            let Some(status) = change_status(
+                row.try_get("deleted_in_change_set")?,
+                row.try_get("on_head")?,
+                old_value != new_value,
+            ) else {
+                continue;
+            };
+            let path: Option<String> = row.try_get("prop_path")?;
+
+            diffs.push(AttributeValueDiff {
+                attribute_value_id: row.try_get("attribute_value_id")?,
+                component_id: row.try_get("component_id")?,
+                prop_id: row.try_get("prop_id")?,
+                path: PropPath::from(path.unwrap_or_default()).with_replaced_sep("/"),
+                key: row.try_get("key")?,
+                status,
+                old_value,
+                new_value,
+            });
+        }
+
+        Ok(diffs)
+    }
+
+    async fn diff_edges(&self, ctx: &DalContext) -> ChangeSetResult<Vec<EdgeDiff>> {
+        let mut diffs = Vec::new();
+        for object in self.object_diffs::<EdgeId>(ctx, "edges").await? {
+            // Edges are only ever added or removed, never edited in place.
+            let Some(status) = object.status(&[]) else {
+                continue;
+            };
+            if status == ChangeStatus::Modified {
+                continue;
+            }
+            diffs.push(EdgeDiff {
+                status,
+                edge: serde_json::from_value(object.change_set_object)?,
+            });
+        }
+
+        Ok(diffs)
+    }
+
+    async fn diff_funcs(&self, ctx: &DalContext) -> ChangeSetResult<Vec<FuncDiff>> {
+        let mut diffs = Vec::new();
+        for object in self.object_diffs::<FuncId>(ctx, "funcs").await? {
+            let columns = object.columns(FUNC_CODE_COLUMNS);
+            let old_code = decode_code(object.head_str("code_base64"));
+            let new_code = decode_code(object.change_set_str("code_base64"));
+            let code_changed = object.head_object.is_some() && old_code != new_code;
+            let Some(status) = object
+                .status(&columns)
+                .or_else(|| code_changed.then_some(ChangeStatus::Modified))
+            else {
+                continue;
+            };
+
+            diffs.push(FuncDiff {
+                func_id: object.id,
+                name: object.change_set_str("name").unwrap_or_default().to_owned(),
+                status,
+                old_code,
+                new_code,
+                columns,
+            });
+        }
+
+        Ok(diffs)
+    }
+
+    async fn diff_schema_variant_definitions(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<SchemaVariantDefinitionDiff>> {
+        let mut diffs = Vec::new();
+        for object in self
+            .object_diffs::<SchemaVariantDefinitionId>(ctx, "schema_variant_definitions")
+            .await?
+        {
+            let columns = object.columns(&[]);
+            let Some(status) = object.status(&columns) else {
+                continue;
+            };
+
+            diffs.push(SchemaVariantDefinitionDiff {
+                schema_variant_definition_id: object.id,
+                name: object.change_set_str("name").unwrap_or_default().to_owned(),
+                status,
+                columns,
+            });
+        }
+
+        Ok(diffs)
+    }
+
+    async fn object_diffs<I>(
+        &self,
+        ctx: &DalContext,
+        table_name: &str,
+    ) -> ChangeSetResult<Vec<ObjectDiff<I>>>
+    where
+        I: for<'a> postgres_types::FromSql<'a>,
+    {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                CHANGE_SET_OBJECT_DIFFS,
+                &[&table_name, &self.pk, &self.tenancy],
+            )
+            .await?;
+
+        let mut objects = Vec::with_capacity(rows.len());
+        for row in rows {
+            objects.push(ObjectDiff {
+                id: row.try_get("object_id")?,
+                change_set_object: row.try_get("change_set_object")?,
+                head_object: row.try_get("head_object")?,
+            });
+        }
+
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn object(change_set_object: Value, head_object: Option<Value>) -> ObjectDiff<()> {
+        ObjectDiff {
+            id: (),
+            change_set_object,
+            head_object,
+        }
+    }
+
+    #[test]
+    fn statuses() {
+        let added = object(json!({ "name": "canoe" }), None);
+        assert_eq!(added.status(&added.columns(&[])), Some(ChangeStatus::Added));
+
+        let unchanged = object(
+            json!({ "name": "canoe", "updated_at": "later" }),
+            Some(json!({ "name": "canoe", "updated_at": "earlier" })),
+        );
+        assert_eq!(unchanged.status(&unchanged.columns(&[])), None);
+
+        let deleted = object(
+            json!({ "name": "canoe", "visibility_deleted_at": "2023-01-01T00:00:00Z" }),
+            Some(json!({ "name": "canoe", "visibility_deleted_at": null })),
+        );
+        assert_eq!(
+            deleted.status(&deleted.columns(&[])),
+            Some(ChangeStatus::Deleted)
+        );
+
+        let short_lived = object(
+            json!({ "name": "canoe", "visibility_deleted_at": "2023-01-01T00:00:00Z" }),
+            None,
+        );
+        assert_eq!(short_lived.status(&short_lived.columns(&[])), None);
+    }
+
+    #[test]
+    fn columns_leave_out_ignored_ones() {
+        let modified = object(
+            json!({ "name": "kayak", "code_base64": "bmV3", "handler": "main" }),
+            Some(json!({ "name": "canoe", "code_base64": "b2xk", "handler": "main" })),
+        );
+        let columns = modified.columns(FUNC_CODE_COLUMNS);
+
+        assert_eq!(
+            columns,
+            vec![ColumnDiff {
+                name: "name".to_string(),
+                old_value: json!("canoe"),
+                new_value: json!("kayak"),
+            }]
+        );
+        assert_eq!(modified.status(&columns), Some(ChangeStatus::Modified));
+        assert_eq!(
+            decode_code(modified.change_set_str("code_base64")),
+            Some("new".to_string())
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! This module contains the ability to reclaim the rows of change sets that will never be applied.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::{ChangeSet, ChangeSetPk, ChangeSetResult};
+use crate::{DalContext, WorkspacePk};
+
+const CHANGE_SET_EXPIRE: &str = "SELECT * FROM change_set_expire_v1()";
+const CHANGE_SET_PURGE_ABANDONED: &str = "SELECT * FROM change_set_purge_abandoned_v1()";
+
+/// What a [`ChangeSet::collect_garbage`] run did.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetGarbageReport {
+    /// The open change sets that were abandoned because they expired, with their workspaces.
+    pub expired: Vec<(ChangeSetPk, Option<WorkspacePk>)>,
+    /// How many rows were deleted, by table.
+    pub reclaimed_rows: BTreeMap<String, u64>,
+}
+
+impl ChangeSetGarbageReport {
+    pub fn total_reclaimed_rows(&self) -> u64 {
+        self.reclaimed_rows.values().sum()
+    }
+}
+
+impl ChangeSet {
+    /// Abandons the expired change sets of every workspace, then deletes the rows written by
+    /// every abandoned change set that hasn't been purged yet.
+    ///
+    /// This bypasses tenancy, so it's meant for background tasks rather than user requests.
+    #[instrument(skip_all)]
+    pub async fn collect_garbage(ctx: &DalContext) -> ChangeSetResult<ChangeSetGarbageReport> {
+        let txns = ctx.txns().await?;
+
+        let mut expired = Vec::new();
+        for row in txns.pg().query(CHANGE_SET_EXPIRE, &[]).await? {
+            expired.push((
+                row.try_get("expired_change_set_pk")?,
+                row.try_get("expired_tenancy_workspace_pk")?,
+            ));
+        }
+
+        let mut reclaimed_rows = BTreeMap::new();
+        for row in txns.pg().query(CHANGE_SET_PURGE_ABANDONED, &[]).await? {
+            let table_name: String = row.try_get("reclaimed_table_name")?;
+            let rows: i64 = row.try_get("reclaimed_rows")?;
+            reclaimed_rows.insert(table_name, rows as u64);
+        }
+
+        Ok(ChangeSetGarbageReport {
+            expired,
+            reclaimed_rows,
+        })
+    }
+}
@@ -46,6 +46,7 @@ pub mod diff;
 pub mod qualification;
 pub mod resource;
 pub mod status;
+pub mod template;
 pub mod validation;
 pub mod view;
 
@@ -0,0 +1,652 @@
+//! Component templates are groups of [`Components`](crate::Component), wired together by
+//! [`Connections`](crate::Connection), saved from a diagram so that they can be instantiated over
+//! and over (and shared through packages).
+//!
+//! Only the values set by users on the components are kept, anything computed by functions is
+//! computed again once the template is instantiated.
+
+use std::collections::{HashMap, HashSet};
+use std::num::ParseFloatError;
+
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
+use si_data_pg::PgError;
+use si_pkg::{
+    SpecError, TemplateComponentSpec, TemplateEdgeKind, TemplateEdgeSpec, TemplateSpec,
+    TemplateValueSpec,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    edge::EdgeKind,
+    func::intrinsics::IntrinsicFunc,
+    impl_standard_model,
+    job::definition::DependentValuesUpdate,
+    pk,
+    prop::PropPath,
+    socket::{SocketEdgeKind, SocketError},
+    standard_model, standard_model_accessor, AttributeContext, AttributeContextBuilderError,
+    AttributeReadContext, AttributeValue, AttributeValueError, AttributeValueId, Component,
+    ComponentError, ComponentId, Connection, DalContext, DiagramError, Edge, EdgeError,
+    ExternalProvider, ExternalProviderError, Func, FuncError, HistoryEventError, NodeError, NodeId,
+    Prop, PropError, PropId, PropKind, Schema, SchemaError, SchemaVariantId, Socket, SocketId,
+    StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError, UndoOperation,
+    Visibility,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ComponentTemplateError {
+    #[error("attribute context builder error: {0}")]
+    AttributeContextBuilder(#[from] AttributeContextBuilderError),
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("attribute value not found for context: {0:?}")]
+    AttributeValueNotFoundForContext(AttributeReadContext),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("component template has no component named {0}")]
+    ComponentNotInTemplate(String),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] DiagramError),
+    #[error("edge error: {0}")]
+    Edge(#[from] EdgeError),
+    #[error("external provider error: {0}")]
+    ExternalProvider(#[from] ExternalProviderError),
+    #[error("external provider not found for socket: {0}")]
+    ExternalProviderNotFoundForSocket(SocketId),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("history event error: {0}")]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("component template name cannot be empty")]
+    MissingName,
+    #[error("nats txn error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("no components were given to create the template from")]
+    NoComponents,
+    #[error("node error: {0}")]
+    Node(#[from] NodeError),
+    #[error("node not found for component: {0}")]
+    NodeNotFoundForComponent(ComponentId),
+    #[error("no value found for prop {0} of component {1}")]
+    NoValueFoundForComponentProp(PropId, ComponentId),
+    #[error("parent attribute value not found for attribute value: {0}")]
+    ParentAttributeValueNotFound(AttributeValueId),
+    #[error(transparent)]
+    ParseFloat(#[from] ParseFloatError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
+    #[error("schema error: {0}")]
+    Schema(#[from] SchemaError),
+    #[error("schema not found for component: {0}")]
+    SchemaNotFoundForComponent(ComponentId),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("socket error: {0}")]
+    Socket(#[from] SocketError),
+    #[error("socket {0} not found for component {1}")]
+    SocketNotFound(String, String),
+    #[error("spec error: {0}")]
+    Spec(#[from] SpecError),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ComponentTemplateResult<T> = Result<T, ComponentTemplateError>;
+
+/// The functions that set values provided by users, only values set by them are saved.
+const USER_SET_FUNCS: [IntrinsicFunc; 3] = [
+    IntrinsicFunc::SetBoolean,
+    IntrinsicFunc::SetInteger,
+    IntrinsicFunc::SetString,
+];
+
+/// Names are generated anew for every component created from a template.
+const SKIPPED_PROP_PATHS: [&[&str]; 1] = [&["root", "si", "name"]];
+
+pk!(ComponentTemplatePk);
+pk!(ComponentTemplateId);
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentTemplate {
+    pk: ComponentTemplatePk,
+    id: ComponentTemplateId,
+    name: String,
+    description: Option<String>,
+    components: Vec<TemplateComponentSpec>,
+    edges: Vec<TemplateEdgeSpec>,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    visibility: Visibility,
+}
+
+impl_standard_model! {
+    model: ComponentTemplate,
+    pk: ComponentTemplatePk,
+    id: ComponentTemplateId,
+    table_name: "component_templates",
+    history_event_label_base: "component_template",
+    history_event_message_name: "Component Template"
+}
+
+/// What instantiating a [`ComponentTemplate`] created.
+#[derive(Clone, Debug, Default)]
+pub struct ComponentTemplateInstance {
+    /// The created components, in the order of the template.
+    pub components: Vec<(ComponentId, NodeId)>,
+}
+
+impl ComponentTemplateInstance {
+    /// The operations reverting the instantiation, to be recorded as a single
+    /// [`UndoEntry`](crate::UndoEntry).
+    pub fn undo_operations(&self) -> Vec<UndoOperation> {
+        self.components
+            .iter()
+            .map(|(component_id, _)| UndoOperation::CreateComponent {
+                component_id: *component_id,
+            })
+            .collect()
+    }
+}
+
+impl ComponentTemplate {
+    #[instrument(skip_all)]
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        description: Option<String>,
+        components: Vec<TemplateComponentSpec>,
+        edges: Vec<TemplateEdgeSpec>,
+    ) -> ComponentTemplateResult<Self> {
+        let name = name.as_ref();
+        if name.trim().is_empty() {
+            return Err(ComponentTemplateError::MissingName);
+        }
+        let components_json = serde_json::to_value(&components)?;
+        let edges_json = serde_json::to_value(&edges)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM component_template_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &name,
+                    &description,
+                    &components_json,
+                    &edges_json,
+                ],
+            )
+            .await?;
+        let object = standard_model::finish_create_from_row(ctx, row).await?;
+        Ok(object)
+    }
+
+    /// Saves the given components as a new template, along with the connections between them
+    /// and the frames they are in (as long as the frame is part of the template too).
+    #[instrument(skip_all)]
+    pub async fn new_from_components(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        description: Option<String>,
+        component_ids: &[ComponentId],
+    ) -> ComponentTemplateResult<Self> {
+        if component_ids.is_empty() {
+            return Err(ComponentTemplateError::NoComponents);
+        }
+
+        // Template components are named after the components they were saved from, which are
+        // not guaranteed to be unique, so the position in the template is appended to them
+        let mut names: HashMap<ComponentId, String> = HashMap::new();
+        let mut nodes = Vec::with_capacity(component_ids.len());
+        for (index, &component_id) in component_ids.iter().enumerate() {
+            let component = Component::get_by_id(ctx, &component_id)
+                .await?
+                .ok_or(ComponentTemplateError::ComponentNotFound(component_id))?;
+            let node = component.node(ctx).await?.pop().ok_or(
+                ComponentTemplateError::NodeNotFoundForComponent(component_id),
+            )?;
+            names.insert(
+                component_id,
+                format!("{} {}", component.name(ctx).await?, index + 1),
+            );
+            nodes.push((component, node));
+        }
+
+        // Positions are kept relative to the top left corner of the selection
+        let mut positions = Vec::with_capacity(nodes.len());
+        for (_, node) in &nodes {
+            positions.push((
+                node.x().parse::<f64>()?.round() as i64,
+                node.y().parse::<f64>()?.round() as i64,
+            ));
+        }
+        let min_x = positions.iter().map(|(x, _)| *x).min().unwrap_or_default();
+        let min_y = positions.iter().map(|(_, y)| *y).min().unwrap_or_default();
+
+        let mut components = Vec::with_capacity(nodes.len());
+        for ((component, node), (x, y)) in nodes.iter().zip(positions) {
+            let schema = component.schema(ctx).await?.ok_or(
+                ComponentTemplateError::SchemaNotFoundForComponent(*component.id()),
+            )?;
+
+            let mut builder = TemplateComponentSpec::builder();
+            builder
+                .name(names[component.id()].as_str())
+                .schema(schema.name())
+                .x(x - min_x)
+                .y(y - min_y);
+            if let Some(width) = node.width() {
+                builder.width(width);
+            }
+            if let Some(height) = node.height() {
+                builder.height(height);
+            }
+            for value in user_set_values(ctx, *component.id()).await? {
+                builder.value(value);
+            }
+            components.push(builder.build()?);
+        }
+
+        let node_components: HashMap<NodeId, ComponentId> = nodes
+            .iter()
+            .map(|(component, node)| (*node.id(), *component.id()))
+            .collect();
+        let mut edges = Vec::new();
+        let mut seen_edges = HashSet::new();
+        for &component_id in component_ids {
+            for edge in Edge::list_for_component(ctx, component_id).await? {
+                if !seen_edges.insert(*edge.id()) {
+                    continue;
+                }
+                let (Some(from_component_id), Some(to_component_id)) = (
+                    node_components.get(&edge.tail_node_id()),
+                    node_components.get(&edge.head_node_id()),
+                ) else {
+                    continue;
+                };
+
+                let kind = match edge.kind() {
+                    EdgeKind::Configuration => TemplateEdgeKind::Configuration,
+                    EdgeKind::Symbolic => TemplateEdgeKind::Frame,
+                };
+                let from_socket = Socket::get_by_id(ctx, &edge.tail_socket_id())
+                    .await?
+                    .ok_or(EdgeError::SocketNotFound(edge.tail_socket_id()))?;
+                let to_socket = Socket::get_by_id(ctx, &edge.head_socket_id())
+                    .await?
+                    .ok_or(EdgeError::SocketNotFound(edge.head_socket_id()))?;
+
+                edges.push(
+                    TemplateEdgeSpec::builder()
+                        .kind(kind)
+                        .from_component(names[from_component_id].as_str())
+                        .from_socket(from_socket.name())
+                        .to_component(names[to_component_id].as_str())
+                        .to_socket(to_socket.name())
+                        .build()?,
+                );
+            }
+        }
+
+        // The connections between a frame and its children are created along with the frame
+        // connection, so they must not be saved on their own
+        let framed: HashSet<(String, String)> = edges
+            .iter()
+            .filter(|edge| edge.kind == TemplateEdgeKind::Frame)
+            .flat_map(|edge| {
+                [
+                    (edge.from_component.clone(), edge.to_component.clone()),
+                    (edge.to_component.clone(), edge.from_component.clone()),
+                ]
+            })
+            .collect();
+        edges.retain(|edge| {
+            edge.kind == TemplateEdgeKind::Frame
+                || !framed.contains(&(edge.from_component.clone(), edge.to_component.clone()))
+        });
+
+        Self::new(ctx, name, description, components, edges).await
+    }
+
+    /// Builds a template from its package representation.
+    pub async fn new_from_spec(
+        ctx: &DalContext,
+        spec: TemplateSpec,
+    ) -> ComponentTemplateResult<Self> {
+        Self::new(
+            ctx,
+            spec.name,
+            spec.description,
+            spec.components,
+            spec.edges,
+        )
+        .await
+    }
+
+    standard_model_accessor!(name, String, ComponentTemplateResult);
+    standard_model_accessor!(description, Option<String>, ComponentTemplateResult);
+
+    pub fn components(&self) -> &[TemplateComponentSpec] {
+        &self.components
+    }
+
+    pub fn edges(&self) -> &[TemplateEdgeSpec] {
+        &self.edges
+    }
+
+    pub async fn find_by_name(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+    ) -> ComponentTemplateResult<Option<Self>> {
+        Ok(Self::find_by_attr(ctx, "name", &name.as_ref()).await?.pop())
+    }
+
+    /// Returns the package representation of the template.
+    pub fn to_spec(&self) -> ComponentTemplateResult<TemplateSpec> {
+        let mut builder = TemplateSpec::builder();
+        builder
+            .name(self.name.as_str())
+            .components(self.components.clone())
+            .edges(self.edges.clone());
+        if let Some(description) = &self.description {
+            builder.description(description.as_str());
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Creates the components of the template, with the top left corner of the template at the
+    /// given position. Values are set and connections are created right away, with a single
+    /// [`DependentValuesUpdate`] enqueued for the values set and the ones flowing through
+    /// configuration connections. Children are put inside their frames with
+    /// [`Connection::connect_to_frame`], which enqueues its own updates.
+    #[instrument(skip_all)]
+    pub async fn instantiate(
+        &self,
+        ctx: &DalContext,
+        x: i64,
+        y: i64,
+    ) -> ComponentTemplateResult<ComponentTemplateInstance> {
+        let mut instance = ComponentTemplateInstance::default();
+        let mut created: HashMap<&str, (ComponentId, NodeId)> = HashMap::new();
+        let mut schema_variant_ids: HashMap<&str, SchemaVariantId> = HashMap::new();
+        let mut updated_attribute_value_ids = Vec::new();
+
+        for template_component in &self.components {
+            let schema_variant_id = match schema_variant_ids.get(template_component.schema.as_str())
+            {
+                Some(schema_variant_id) => *schema_variant_id,
+                None => {
+                    let schema_variant_id =
+                        Schema::default_schema_variant_id_for_name(ctx, &template_component.schema)
+                            .await?;
+                    schema_variant_ids
+                        .insert(template_component.schema.as_str(), schema_variant_id);
+                    schema_variant_id
+                }
+            };
+
+            let (component, mut node) =
+                Component::new(ctx, crate::generate_name(), schema_variant_id).await?;
+            node.set_geometry(
+                ctx,
+                (x + template_component.x).to_string(),
+                (y + template_component.y).to_string(),
+                template_component.width.as_deref(),
+                template_component.height.as_deref(),
+            )
+            .await?;
+
+            for value in &template_component.values {
+                updated_attribute_value_ids.push(
+                    set_value(
+                        ctx,
+                        *component.id(),
+                        schema_variant_id,
+                        &PropPath::new(&value.path),
+                        value.value.clone(),
+                    )
+                    .await?,
+                );
+            }
+
+            created.insert(
+                template_component.name.as_str(),
+                (*component.id(), *node.id()),
+            );
+            instance.components.push((*component.id(), *node.id()));
+        }
+
+        for edge in &self.edges {
+            let (from_component_id, from_node_id) =
+                *created.get(edge.from_component.as_str()).ok_or_else(|| {
+                    ComponentTemplateError::ComponentNotInTemplate(edge.from_component.clone())
+                })?;
+            let (_, to_node_id) = *created.get(edge.to_component.as_str()).ok_or_else(|| {
+                ComponentTemplateError::ComponentNotInTemplate(edge.to_component.clone())
+            })?;
+
+            match edge.kind {
+                TemplateEdgeKind::Configuration => {
+                    let from_socket = find_socket(
+                        ctx,
+                        &edge.from_socket,
+                        SocketEdgeKind::ConfigurationOutput,
+                        from_node_id,
+                        &edge.from_component,
+                    )
+                    .await?;
+                    let to_socket = find_socket(
+                        ctx,
+                        &edge.to_socket,
+                        SocketEdgeKind::ConfigurationInput,
+                        to_node_id,
+                        &edge.to_component,
+                    )
+                    .await?;
+                    Connection::new(
+                        ctx,
+                        from_node_id,
+                        *from_socket.id(),
+                        to_node_id,
+                        *to_socket.id(),
+                        EdgeKind::Configuration,
+                    )
+                    .await?;
+
+                    // The value of the output socket has to flow to the input socket, as when
+                    // connecting components on the diagram
+                    let external_provider =
+                        ExternalProvider::find_for_socket(ctx, *from_socket.id())
+                            .await?
+                            .ok_or(ComponentTemplateError::ExternalProviderNotFoundForSocket(
+                                *from_socket.id(),
+                            ))?;
+                    let read_context = AttributeReadContext {
+                        external_provider_id: Some(*external_provider.id()),
+                        component_id: Some(from_component_id),
+                        ..Default::default()
+                    };
+                    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+                        .await?
+                        .ok_or(ComponentTemplateError::AttributeValueNotFoundForContext(
+                            read_context,
+                        ))?;
+                    updated_attribute_value_ids.push(*attribute_value.id());
+                }
+                TemplateEdgeKind::Frame => {
+                    // Frame edges go from the child to its frame
+                    Connection::connect_to_frame(ctx, to_node_id, from_node_id).await?;
+                }
+            }
+        }
+
+        if !updated_attribute_value_ids.is_empty() {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                *ctx.visibility(),
+                updated_attribute_value_ids,
+            ))
+            .await?;
+        }
+
+        Ok(instance)
+    }
+}
+
+/// Lists the primitive values set by users on a component, skipping the ones inside arrays and
+/// maps, whose shape can't be expressed by a prop path.
+async fn user_set_values(
+    ctx: &DalContext,
+    component_id: ComponentId,
+) -> ComponentTemplateResult<Vec<TemplateValueSpec>> {
+    let payloads = AttributeValue::list_payload_for_read_context(
+        ctx,
+        AttributeReadContext {
+            component_id: Some(component_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await?;
+    let prop_kinds: HashMap<AttributeValueId, (PropKind, Option<AttributeValueId>)> = payloads
+        .iter()
+        .map(|payload| {
+            (
+                *payload.attribute_value.id(),
+                (*payload.prop.kind(), payload.parent_attribute_value_id),
+            )
+        })
+        .collect();
+    let user_set_func_names: Vec<&str> = USER_SET_FUNCS.iter().map(|func| func.name()).collect();
+
+    let mut values = Vec::new();
+    for payload in payloads {
+        if payload.attribute_value.context.component_id() != component_id
+            || matches!(
+                payload.prop.kind(),
+                PropKind::Array | PropKind::Map | PropKind::Object
+            )
+        {
+            continue;
+        }
+        let path = payload.prop.path().as_owned_parts();
+        if SKIPPED_PROP_PATHS.iter().any(|skipped| path == *skipped) {
+            continue;
+        }
+
+        let mut parent_id = payload.parent_attribute_value_id;
+        let mut in_collection = false;
+        while let Some((kind, grandparent_id)) = parent_id.and_then(|id| prop_kinds.get(&id)) {
+            if matches!(kind, PropKind::Array | PropKind::Map) {
+                in_collection = true;
+                break;
+            }
+            parent_id = *grandparent_id;
+        }
+        if in_collection {
+            continue;
+        }
+
+        let Some(prototype) = payload.attribute_value.attribute_prototype(ctx).await? else {
+            continue;
+        };
+        let Some(func) = Func::get_by_id(ctx, &prototype.func_id()).await? else {
+            continue;
+        };
+        if !user_set_func_names.contains(&func.name()) {
+            continue;
+        }
+        let Some(value) = payload.attribute_value.get_value(ctx).await? else {
+            continue;
+        };
+        if value.is_null() {
+            continue;
+        }
+
+        values.push(
+            TemplateValueSpec::builder()
+                .path(path)
+                .value(value)
+                .build()?,
+        );
+    }
+
+    Ok(values)
+}
+
+/// Sets the value of a prop of a newly created component without propagating it, returning the
+/// id of the updated [`AttributeValue`].
+async fn set_value(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    schema_variant_id: SchemaVariantId,
+    prop_path: &PropPath,
+    value: serde_json::Value,
+) -> ComponentTemplateResult<AttributeValueId> {
+    let prop = Prop::find_prop_by_path(ctx, schema_variant_id, prop_path).await?;
+    let attribute_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*prop.id()),
+            component_id: Some(component_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await?
+    .ok_or(ComponentTemplateError::NoValueFoundForComponentProp(
+        *prop.id(),
+        component_id,
+    ))?;
+    let parent_attribute_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await?
+        .ok_or_else(|| {
+            ComponentTemplateError::ParentAttributeValueNotFound(*attribute_value.id())
+        })?;
+
+    let attribute_context = AttributeContext::builder()
+        .set_prop_id(*prop.id())
+        .set_component_id(component_id)
+        .to_context()?;
+    let (_, attribute_value_id) =
+        AttributeValue::update_for_context_without_propagating_dependent_values(
+            ctx,
+            *attribute_value.id(),
+            Some(*parent_attribute_value.id()),
+            attribute_context,
+            Some(value),
+            None,
+        )
+        .await?;
+
+    Ok(attribute_value_id)
+}
+
+async fn find_socket(
+    ctx: &DalContext,
+    name: &str,
+    socket_edge_kind: SocketEdgeKind,
+    node_id: NodeId,
+    component_name: &str,
+) -> ComponentTemplateResult<Socket> {
+    Socket::find_by_name_for_edge_kind_and_node(ctx, name, socket_edge_kind, node_id)
+        .await?
+        .ok_or_else(|| {
+            ComponentTemplateError::SocketNotFound(name.to_owned(), component_name.to_owned())
+        })
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::str::FromStr;
+use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
@@ -43,8 +44,6 @@ pub enum ComponentViewError {
     Prop(#[from] PropError),
     #[error(transparent)]
     Secret(#[from] SecretError),
-    #[error("secret not found: {0}")]
-    SecretNotFound(SecretId),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -139,11 +138,15 @@ impl ComponentView {
             for (_key, value) in object {
                 if let Some(raw_id) = value.as_str() {
                     let id = SecretId::from_str(raw_id)?;
-                    let decrypted_secret = EncryptedSecret::get_by_id(ctx, &id)
-                        .await?
-                        .ok_or(ComponentViewError::SecretNotFound(id))?
-                        .decrypt(ctx)
-                        .await?;
+                    // A secret deleted in another change set can still be referenced here; such
+                    // a field is passed along as-is rather than failing the whole function.
+                    let Some(encrypted_secret) = EncryptedSecret::get_by_id(ctx, &id).await? else {
+                        warn!(secret_id = %id, "referenced secret not found, skipping it");
+                        continue;
+                    };
+                    let decrypted_secret = encrypted_secret.decrypt(ctx).await?;
+                    // Sealed for cyclone anew on every call, so rotating the workspace key pair
+                    // never leaves a stale payload behind.
                     let encoded = ctx
                         .encryption_key()
                         .encrypt_and_encode(serde_json::to_string(&decrypted_secret.message())?);
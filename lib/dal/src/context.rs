@@ -10,11 +10,14 @@ use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
 use veritech_client::{Client as VeritechClient, EncryptionKey};
 
 use crate::{
+    func::execution_cache::FuncExecutionCache,
     job::{
         processor::{JobQueueProcessor, JobQueueProcessorError},
         producer::{BlockingJobError, BlockingJobResult, JobProducer},
     },
-    HistoryActor, StandardModel, Tenancy, TenancyError, Visibility,
+    secret::SecretBackends,
+    HistoryActor, HistoryEventError, PendingAudit, StandardModel, Tenancy, TenancyError,
+    Visibility,
 };
 
 /// A context type which contains handles to common core service dependencies.
@@ -37,6 +40,10 @@ pub struct ServicesContext {
     pkgs_path: Option<PathBuf>,
     /// The URL of the module index
     module_index_url: Option<String>,
+    /// The backends resolving secret material at function execution time.
+    secret_backends: SecretBackends,
+    /// The results of past function executions, shared by every clone of the context.
+    func_execution_cache: FuncExecutionCache,
 }
 
 impl ServicesContext {
@@ -49,6 +56,7 @@ impl ServicesContext {
         encryption_key: Arc<EncryptionKey>,
         pkgs_path: Option<PathBuf>,
         module_index_url: Option<String>,
+        secret_backends: SecretBackends,
     ) -> Self {
         Self {
             pg_pool,
@@ -58,6 +66,8 @@ impl ServicesContext {
             encryption_key,
             pkgs_path,
             module_index_url,
+            secret_backends,
+            func_execution_cache: FuncExecutionCache::default(),
         }
     }
 
@@ -66,6 +76,7 @@ impl ServicesContext {
         DalContextBuilder {
             services_context: self,
             blocking,
+            audit: None,
         }
     }
 
@@ -93,6 +104,16 @@ impl ServicesContext {
         self.encryption_key.clone()
     }
 
+    /// Gets a reference to the secret backends.
+    pub fn secret_backends(&self) -> &SecretBackends {
+        &self.secret_backends
+    }
+
+    /// Gets a reference to the function execution cache.
+    pub fn func_execution_cache(&self) -> &FuncExecutionCache {
+        &self.func_execution_cache
+    }
+
     /// Builds and returns a new [`Connections`].
     pub async fn connections(&self) -> PgPoolResult<Connections> {
         let pg_conn = self.pg_pool.get().await?;
@@ -202,6 +223,8 @@ pub struct DalContext {
     /// This is useful to ensure child jobs of blocking jobs also block so there is no race-condition in the DAL.
     /// And also for SDF routes to block the HTTP request until the jobs get executed, so SDF tests don't race.
     blocking: bool,
+    /// The event describing the request this context serves, recorded on its first commit.
+    audit: Option<PendingAudit>,
 }
 
 impl DalContext {
@@ -211,11 +234,13 @@ impl DalContext {
         DalContextBuilder {
             services_context,
             blocking,
+            audit: None,
         }
     }
 
     /// Consumes all inner transactions and committing all changes made within them.
     pub async fn commit(&self) -> Result<(), TransactionsError> {
+        self.record_audit().await?;
         if self.blocking {
             self.blocking_commit().await?;
         } else {
@@ -237,6 +262,7 @@ impl DalContext {
     /// Consumes all inner transactions, committing all changes made within them, and
     /// blocks until all queued jobs have reported as finishing.
     pub async fn blocking_commit(&self) -> Result<(), TransactionsError> {
+        self.record_audit().await?;
         let mut guard = self.conns_state.lock().await;
 
         *guard = guard.take().blocking_commit().await?;
@@ -244,6 +270,15 @@ impl DalContext {
         Ok(())
     }
 
+    /// Records the [`PendingAudit`] this context was built with, if any, so it commits along with
+    /// the changes it describes.
+    async fn record_audit(&self) -> Result<(), TransactionsError> {
+        if let Some(audit) = &self.audit {
+            audit.record(self).await.map_err(Box::new)?;
+        }
+        Ok(())
+    }
+
     /// Rolls all inner transactions back, discarding all changes made within them.
     ///
     /// This is equivalent to the transaction's `Drop` implementations, but provides any error
@@ -433,6 +468,16 @@ impl DalContext {
         &self.services_context.encryption_key
     }
 
+    /// Gets a reference to the DAL context's secret backends.
+    pub fn secret_backends(&self) -> &SecretBackends {
+        &self.services_context.secret_backends
+    }
+
+    /// Gets a reference to the DAL context's function execution cache.
+    pub fn func_execution_cache(&self) -> &FuncExecutionCache {
+        &self.services_context.func_execution_cache
+    }
+
     /// Gets a reference to the dal context's tenancy.
     pub fn tenancy(&self) -> &Tenancy {
         &self.tenancy
@@ -531,6 +576,11 @@ impl AccessBuilder {
         }
     }
 
+    /// Gets the suitable tenancy.
+    pub fn tenancy(&self) -> &Tenancy {
+        &self.tenancy
+    }
+
     /// Builds and returns a new [`RequestContext`] using the given [`Visibility`].
     pub fn build(self, visibility: Visibility) -> RequestContext {
         RequestContext {
@@ -556,6 +606,8 @@ pub struct DalContextBuilder {
     /// This is useful to ensure child jobs of blocking jobs also block so there is no race-condition in the DAL.
     /// And also for SDF routes to block the HTTP request until the jobs get executed, so SDF tests don't race.
     blocking: bool,
+    /// The event describing the request the built contexts serve, see [`PendingAudit`].
+    audit: Option<PendingAudit>,
 }
 
 impl DalContextBuilder {
@@ -565,6 +617,7 @@ impl DalContextBuilder {
         Ok(DalContext {
             services_context: self.services_context.clone(),
             blocking: self.blocking,
+            audit: self.audit.clone(),
             conns_state: Arc::new(Mutex::new(ConnectionState::new_from_conns(conns))),
             tenancy: Tenancy::new_empty(),
             visibility: Visibility::new_head(false),
@@ -581,6 +634,7 @@ impl DalContextBuilder {
         Ok(DalContext {
             services_context: self.services_context.clone(),
             blocking: self.blocking,
+            audit: self.audit.clone(),
             conns_state: Arc::new(Mutex::new(ConnectionState::new_from_conns(conns))),
             tenancy: access_builder.tenancy,
             history_actor: access_builder.history_actor,
@@ -597,6 +651,7 @@ impl DalContextBuilder {
         Ok(DalContext {
             services_context: self.services_context.clone(),
             blocking: self.blocking,
+            audit: self.audit.clone(),
             conns_state: Arc::new(Mutex::new(ConnectionState::new_from_conns(conns))),
             tenancy: request_context.tenancy,
             visibility: request_context.visibility,
@@ -604,6 +659,11 @@ impl DalContextBuilder {
         })
     }
 
+    /// Records the given event in the first commit of the contexts built from here on.
+    pub fn set_audit(&mut self, audit: PendingAudit) {
+        self.audit = Some(audit);
+    }
+
     /// Gets a reference to the PostgreSQL connection pool.
     pub fn pg_pool(&self) -> &PgPool {
         &self.services_context.pg_pool
@@ -642,6 +702,8 @@ impl DalContextBuilder {
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum TransactionsError {
+    #[error("unable to record the audit event: {0}")]
+    Audit(#[from] Box<HistoryEventError>),
     #[error(transparent)]
     JobQueueProcessor(#[from] JobQueueProcessorError),
     #[error(transparent)]
@@ -1,12 +1,17 @@
 use serde::{Deserialize, Serialize};
 
-use crate::edge::{Edge, EdgeId, EdgeKind};
+use crate::edge::{Edge, EdgeId, EdgeKind, EdgeObjectId, VertexObjectKind};
 
 use crate::change_status::ChangeStatus;
 use crate::diagram::node::HistoryEventMetadata;
 use crate::diagram::DiagramResult;
-use crate::socket::SocketId;
-use crate::{node::NodeId, ActorView, DalContext, DiagramError, HistoryActor, StandardModel, User};
+use crate::job::definition::DependentValuesUpdate;
+use crate::socket::{SocketEdgeKind, SocketId, SocketKind};
+use crate::{
+    node::NodeId, ActorView, AttributeReadContext, AttributeValue, Component, ComponentType,
+    DalContext, DiagramError, EdgeError, ExternalProvider, HistoryActor, InternalProvider,
+    InternalProviderId, PropId, Socket, StandardModel, User,
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -71,6 +76,248 @@ impl Connection {
         }
     }
 
+    /// Puts the [`Node`](crate::Node) of a child [`Component`] inside a frame: connects their
+    /// "Frame" [`Sockets`](Socket) with an [`EdgeKind::Symbolic`] connection, then the sockets of
+    /// the child to the matching ones of the frame (see [`Self::connect_sockets_to_frame`]).
+    ///
+    /// Returns the frame connection along with the ids of all the [`Edges`](Edge) created, the
+    /// frame connection first.
+    pub async fn connect_to_frame(
+        ctx: &DalContext,
+        parent_node_id: NodeId,
+        child_node_id: NodeId,
+    ) -> DiagramResult<(Self, Vec<EdgeId>)> {
+        let from_socket = Socket::find_frame_socket_for_node(
+            ctx,
+            child_node_id,
+            SocketEdgeKind::ConfigurationOutput,
+        )
+        .await?;
+        let to_socket = Socket::find_frame_socket_for_node(
+            ctx,
+            parent_node_id,
+            SocketEdgeKind::ConfigurationInput,
+        )
+        .await?;
+
+        let connection = Self::new(
+            ctx,
+            child_node_id,
+            *from_socket.id(),
+            parent_node_id,
+            *to_socket.id(),
+            EdgeKind::Symbolic,
+        )
+        .await?;
+
+        let mut edge_ids = vec![connection.id];
+        edge_ids.extend(Self::connect_sockets_to_frame(ctx, parent_node_id, child_node_id).await?);
+        Ok((connection, edge_ids))
+    }
+
+    /// Creates all valid connections between the sockets of a frame and the ones of a child put
+    /// inside it, returning the ids of the [`Edges`](Edge) created.
+    // TODO(victor,paul) We should tidy up this function after the feature stabilizes a bit
+    pub async fn connect_sockets_to_frame(
+        ctx: &DalContext,
+        parent_node_id: NodeId,
+        child_node_id: NodeId,
+    ) -> DiagramResult<Vec<EdgeId>> {
+        let parent_component = Component::find_for_node(ctx, parent_node_id)
+            .await?
+            .ok_or(DiagramError::ComponentNotFoundForNode(parent_node_id))?;
+        let parent_sockets = Socket::list_for_component(ctx, *parent_component.id()).await?;
+
+        let child_component = Component::find_for_node(ctx, child_node_id)
+            .await?
+            .ok_or(DiagramError::ComponentNotFoundForNode(child_node_id))?;
+        let child_sockets = Socket::list_for_component(ctx, *child_component.id()).await?;
+
+        let aggregation_frame = match parent_component.get_type(ctx).await? {
+            ComponentType::AggregationFrame => true,
+            ComponentType::ConfigurationFrame => false,
+            component_type => {
+                return Err(DiagramError::InvalidComponentTypeForFrame(component_type))
+            }
+        };
+
+        let mut edge_ids = Vec::new();
+        for parent_socket in parent_sockets {
+            if parent_socket.kind() == &SocketKind::Frame {
+                continue;
+            }
+
+            if aggregation_frame {
+                match *parent_socket.edge_kind() {
+                    SocketEdgeKind::ConfigurationInput => {
+                        let provider =
+                            InternalProvider::find_explicit_for_socket(ctx, *parent_socket.id())
+                                .await?
+                                .ok_or(EdgeError::InternalProviderNotFoundForSocket(
+                                    *parent_socket.id(),
+                                ))?;
+
+                        // We don't want to connect the provider when we are not using configuration edge kind
+                        Edge::connect_internal_providers_for_components(
+                            ctx,
+                            *provider.id(),
+                            *child_component.id(),
+                            *parent_component.id(),
+                        )
+                        .await?;
+
+                        let edge = Edge::new(
+                            ctx,
+                            EdgeKind::Configuration,
+                            child_node_id,
+                            VertexObjectKind::Configuration,
+                            EdgeObjectId::from(*child_component.id()),
+                            *parent_socket.id(),
+                            parent_node_id,
+                            VertexObjectKind::Configuration,
+                            EdgeObjectId::from(*parent_component.id()),
+                            *parent_socket.id(),
+                        )
+                        .await?;
+                        edge_ids.push(*edge.id());
+
+                        let attribute_value_context = AttributeReadContext {
+                            component_id: Some(*parent_component.id()),
+                            internal_provider_id: Some(*provider.id()),
+                            ..Default::default()
+                        };
+
+                        let attribute_value =
+                            AttributeValue::find_for_context(ctx, attribute_value_context)
+                                .await?
+                                .ok_or(DiagramError::AttributeValueNotFoundForContext(
+                                    attribute_value_context,
+                                ))?;
+
+                        ctx.enqueue_job(DependentValuesUpdate::new(
+                            ctx.access_builder(),
+                            *ctx.visibility(),
+                            vec![*attribute_value.id()],
+                        ))
+                        .await?;
+                    }
+                    SocketEdgeKind::ConfigurationOutput => {
+                        let provider = ExternalProvider::find_for_socket(ctx, *parent_socket.id())
+                            .await?
+                            .ok_or(EdgeError::ExternalProviderNotFoundForSocket(
+                                *parent_socket.id(),
+                            ))?;
+
+                        Edge::connect_external_providers_for_components(
+                            ctx,
+                            *provider.id(),
+                            *parent_component.id(),
+                            *child_component.id(),
+                        )
+                        .await?;
+
+                        let edge = Edge::new(
+                            ctx,
+                            EdgeKind::Configuration,
+                            parent_node_id,
+                            VertexObjectKind::Configuration,
+                            EdgeObjectId::from(*parent_component.id()),
+                            *parent_socket.id(),
+                            child_node_id,
+                            VertexObjectKind::Configuration,
+                            EdgeObjectId::from(*child_component.id()),
+                            *parent_socket.id(),
+                        )
+                        .await?;
+                        edge_ids.push(*edge.id());
+
+                        let attribute_value_context = AttributeReadContext {
+                            component_id: Some(*child_component.id()),
+                            external_provider_id: Some(*provider.id()),
+                            ..Default::default()
+                        };
+
+                        let attribute_value =
+                            AttributeValue::find_for_context(ctx, attribute_value_context)
+                                .await?
+                                .ok_or(DiagramError::AttributeValueNotFoundForContext(
+                                    attribute_value_context,
+                                ))?;
+
+                        ctx.enqueue_job(DependentValuesUpdate::new(
+                            ctx.access_builder(),
+                            *ctx.visibility(),
+                            vec![*attribute_value.id()],
+                        ))
+                        .await?;
+                    }
+                }
+            } else if let Some(parent_provider) = parent_socket.external_provider(ctx).await? {
+                for child_socket in &child_sockets {
+                    // Skip child sockets corresponding to frames.
+                    if child_socket.kind() == &SocketKind::Frame {
+                        continue;
+                    }
+
+                    if let Some(child_provider) = child_socket.internal_provider(ctx).await? {
+                        // TODO(nick): once type definitions used for providers, we should not
+                        // match on name.
+                        if parent_provider.name() == child_provider.name() {
+                            let connection = Self::new(
+                                ctx,
+                                parent_node_id,
+                                *parent_socket.id(),
+                                child_node_id,
+                                *child_socket.id(),
+                                EdgeKind::Configuration,
+                            )
+                            .await?;
+                            edge_ids.push(connection.id);
+
+                            let attribute_read_context = AttributeReadContext {
+                                prop_id: Some(PropId::NONE),
+                                internal_provider_id: Some(InternalProviderId::NONE),
+                                external_provider_id: Some(*parent_provider.id()),
+                                component_id: Some(*parent_component.id()),
+                            };
+
+                            let attribute_value =
+                                AttributeValue::find_for_context(ctx, attribute_read_context)
+                                    .await?
+                                    .ok_or(DiagramError::AttributeValueNotFoundForContext(
+                                        attribute_read_context,
+                                    ))?;
+
+                            ctx.enqueue_job(DependentValuesUpdate::new(
+                                ctx.access_builder(),
+                                *ctx.visibility(),
+                                vec![*attribute_value.id()],
+                            ))
+                            .await?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(edge_ids)
+    }
+
+    /// Deletes an [`Edge`] created by [`Self::connect_to_frame`], taking the child out of the
+    /// frame.
+    pub async fn delete_for_frame_edge(ctx: &DalContext, edge_id: EdgeId) -> DiagramResult<()> {
+        let mut edge = Edge::get_by_id(ctx, &edge_id)
+            .await?
+            .ok_or(DiagramError::EdgeNotFound)?;
+        // Aggregation frames connect each of their sockets to itself on the child
+        if edge.head_socket_id() == edge.tail_socket_id() {
+            edge.delete_aggregation_and_propagate(ctx).await?;
+        } else {
+            edge.delete_and_propagate(ctx).await?;
+        }
+        Ok(())
+    }
+
     pub fn source(&self) -> (NodeId, SocketId) {
         (self.source.node_id, self.source.socket_id)
     }
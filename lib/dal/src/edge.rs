@@ -430,30 +430,94 @@ impl Edge {
 
         edge_argument.delete_by_id(ctx).await?;
 
-        let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
-            _ => None,
+        self.mark_deleted(ctx).await?;
+
+        let read_context = AttributeReadContext {
+            prop_id: Some(PropId::NONE),
+            internal_provider_id: Some(internal_provider_id),
+            external_provider_id: Some(ExternalProviderId::NONE),
+            component_id: Some(head_component_id),
         };
-        let _rows = ctx
-            .txns()
+
+        let mut attr_value = AttributeValue::find_for_context(ctx, read_context)
             .await?
-            .pg()
-            .query(
-                "SELECT * FROM edge_deletion_v1($1, $2, $3, $4)",
-                &[ctx.tenancy(), ctx.visibility(), self.id(), &actor_user_pk],
-            )
-            .await?;
+            .ok_or(EdgeError::AttributeValueNotFound)?;
+
+        attr_value.update_from_prototype_function(ctx).await?;
+
+        ctx.enqueue_job(DependentValuesUpdate::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            vec![*attr_value.id()],
+        ))
+        .await?;
+
+        Ok(())
+    }
 
+    /// Deletes an [`Edge`] connecting an aggregation frame to one of its children, which links
+    /// the providers of the same [`Socket`] on both ends rather than an external provider to an
+    /// internal one (see [`Self::delete_and_propagate`]).
+    pub async fn delete_aggregation_and_propagate(&mut self, ctx: &DalContext) -> EdgeResult<()> {
+        let head_component_id = *Node::get_by_id(ctx, &self.head_node_id)
+            .await?
+            .ok_or(EdgeError::NodeNotFound(self.head_node_id))?
+            .component(ctx)
+            .await?
+            .ok_or(EdgeError::ComponentNotFoundForNode(self.head_node_id))?
+            .id();
+        let tail_component_id = *Node::get_by_id(ctx, &self.tail_node_id)
+            .await?
+            .ok_or(EdgeError::NodeNotFound(self.tail_node_id))?
+            .component(ctx)
+            .await?
+            .ok_or(EdgeError::ComponentNotFoundForNode(self.tail_node_id))?
+            .id();
+        let socket = Socket::get_by_id(ctx, &self.head_socket_id)
+            .await?
+            .ok_or(EdgeError::SocketNotFound(self.head_socket_id))?;
+
+        // The provider arguments live on the head component and point at the tail one, be the
+        // frame the head (output sockets) or the tail (input sockets) of the edge
+        let (internal_provider_id, external_provider_id) =
+            match socket.internal_provider(ctx).await? {
+                Some(internal_provider) => (*internal_provider.id(), ExternalProviderId::NONE),
+                None => {
+                    let external_provider =
+                        socket.external_provider(ctx).await?.ok_or_else(|| {
+                            EdgeError::ExternalProviderNotFoundForSocket(*socket.id())
+                        })?;
+                    (InternalProviderId::NONE, *external_provider.id())
+                }
+            };
         let read_context = AttributeReadContext {
             prop_id: Some(PropId::NONE),
             internal_provider_id: Some(internal_provider_id),
-            external_provider_id: Some(ExternalProviderId::NONE),
+            external_provider_id: Some(external_provider_id),
             component_id: Some(head_component_id),
         };
 
         let mut attr_value = AttributeValue::find_for_context(ctx, read_context)
             .await?
             .ok_or(EdgeError::AttributeValueNotFound)?;
+        let attribute_prototype = attr_value
+            .attribute_prototype(ctx)
+            .await?
+            .ok_or(EdgeError::AttributePrototypeNotFound)?;
+        for mut argument in
+            AttributePrototypeArgument::list_for_attribute_prototype(ctx, *attribute_prototype.id())
+                .await?
+        {
+            if argument.head_component_id() == head_component_id
+                && argument.tail_component_id() == tail_component_id
+                && argument.internal_provider_id() == internal_provider_id
+                && argument.external_provider_id() == external_provider_id
+            {
+                argument.delete_by_id(ctx).await?;
+            }
+        }
+
+        self.mark_deleted(ctx).await?;
 
         attr_value.update_from_prototype_function(ctx).await?;
 
@@ -467,6 +531,24 @@ impl Edge {
         Ok(())
     }
 
+    /// Soft deletes the [`Edge`], recording who deleted it.
+    async fn mark_deleted(&self, ctx: &DalContext) -> EdgeResult<()> {
+        let actor_user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            _ => None,
+        };
+        let _rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM edge_deletion_v1($1, $2, $3, $4)",
+                &[ctx.tenancy(), ctx.visibility(), self.id(), &actor_user_pk],
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn restore_by_id(ctx: &DalContext, edge_id: EdgeId) -> EdgeResult<Option<Self>> {
         let ctx_with_deleted = &ctx.clone_with_delete_visibility();
 
@@ -7,6 +7,7 @@ use si_data_pg::PgError;
 use strum::IntoEnumIterator;
 use telemetry::prelude::*;
 use thiserror::Error;
+use veritech_client::EgressPolicy;
 
 use crate::func::argument::FuncArgumentError;
 use crate::{
@@ -24,6 +25,7 @@ pub mod binding;
 pub mod binding_return_value;
 pub mod description;
 pub mod execution;
+pub mod execution_cache;
 pub mod identity;
 pub mod intrinsics;
 
@@ -107,6 +109,9 @@ pub struct Func {
     handler: Option<String>,
     code_base64: Option<String>,
     code_sha256: String,
+    timeout_secs: Option<i64>,
+    memoizable: bool,
+    egress_policy: EgressPolicy,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -180,6 +185,11 @@ impl Func {
         new_func.set_builtin(ctx, self.builtin).await?;
         new_func.set_handler(ctx, self.handler()).await?;
         new_func.set_code_base64(ctx, self.code_base64()).await?;
+        new_func
+            .set_timeout_secs(ctx, self.timeout_secs().copied())
+            .await?;
+        new_func.set_memoizable(ctx, self.memoizable).await?;
+        new_func.set_egress_policy(ctx, self.egress_policy).await?;
 
         Ok(new_func)
     }
@@ -257,5 +267,18 @@ impl Func {
     );
     standard_model_accessor!(handler, Option<String>, FuncResult);
     standard_model_accessor!(code_base64, Option<String>, FuncResult);
+    standard_model_accessor!(timeout_secs, OptionBigInt<i64>, FuncResult);
+    standard_model_accessor!(memoizable, bool, FuncResult);
+    standard_model_accessor!(egress_policy, Enum(EgressPolicy), FuncResult);
     standard_model_accessor_ro!(code_sha256, String);
+
+    /// Returns the network access given to executions of the func. Only action and
+    /// reconciliation funcs may reach the network, and only if their
+    /// [`egress_policy`](Self::egress_policy) allows it.
+    pub fn execution_egress_policy(&self) -> EgressPolicy {
+        match self.backend_kind {
+            FuncBackendKind::JsAction | FuncBackendKind::JsReconciliation => self.egress_policy,
+            _ => EgressPolicy::Deny,
+        }
+    }
 }
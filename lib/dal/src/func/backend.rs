@@ -5,8 +5,8 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::mpsc;
 use veritech_client::{
-    ActionRunResultSuccess, Client as VeritechClient, FunctionResult, OutputStream,
-    ResolverFunctionResponseType,
+    ActionRunResultSuccess, Client as VeritechClient, EgressPolicy, FunctionResult,
+    FunctionResultFailureErrorKind, OutputStream, ResolverFunctionResponseType,
 };
 
 use crate::{label_list::ToLabelList, DalContext, Func, FuncId, PropKind, StandardModel};
@@ -41,7 +41,7 @@ pub enum FuncBackendError {
     InvalidArrayEntryData(serde_json::Value),
     #[error("result failure: kind={kind}, message={message}, backend={backend}")]
     ResultFailure {
-        kind: String,
+        kind: FunctionResultFailureErrorKind,
         message: String,
         backend: String,
     },
@@ -181,6 +181,10 @@ impl ToLabelList for FuncBackendKind {}
 pub struct FuncDispatchContext {
     pub veritech: VeritechClient,
     pub output_tx: mpsc::Sender<OutputStream>,
+    /// The timeout of the execution in seconds, when the func being dispatched has one.
+    pub timeout_secs: Option<u64>,
+    /// The network access given to the execution.
+    pub egress_policy: EgressPolicy,
 }
 
 impl FuncDispatchContext {
@@ -190,6 +194,8 @@ impl FuncDispatchContext {
             Self {
                 veritech: ctx.veritech().clone(),
                 output_tx,
+                timeout_secs: None,
+                egress_policy: EgressPolicy::Deny,
             },
             rx,
         )
@@ -220,11 +226,15 @@ pub trait FuncDispatch: std::fmt::Debug {
     /// This private function creates the "request" to send to veritech in a shape that it
     /// likes. The request's type is [`Self`].
     fn create(
-        context: FuncDispatchContext,
+        mut context: FuncDispatchContext,
         func: &Func,
         args: &serde_json::Value,
     ) -> FuncBackendResult<Box<Self>> {
         let args = Self::Args::deserialize(args)?;
+        context.timeout_secs = func
+            .timeout_secs()
+            .and_then(|timeout_secs| u64::try_from(*timeout_secs).ok());
+        context.egress_policy = func.execution_egress_policy();
         let code_base64 = func
             .code_base64()
             .ok_or_else(|| FuncBackendError::DispatchMissingBase64(*func.id()))?;
@@ -37,6 +37,8 @@ impl FuncDispatch for FuncBackendJsAction {
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: serde_json::to_value(args).unwrap(),
+            timeout_secs: context.timeout_secs,
+            egress_policy: context.egress_policy,
         };
 
         Box::new(Self { context, request })
@@ -49,6 +49,8 @@ impl FuncDispatch for FuncBackendJsReconciliation {
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: serde_json::to_value(args).unwrap(),
+            timeout_secs: context.timeout_secs,
+            egress_policy: context.egress_policy,
         };
 
         Box::new(Self { context, request })
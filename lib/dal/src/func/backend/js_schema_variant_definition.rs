@@ -25,6 +25,7 @@ impl FuncDispatch for FuncBackendJsSchemaVariantDefinition {
             execution_id: "villanelle".to_string(),
             handler: handler.into(),
             code_base64: code_base64.to_owned(),
+            timeout_secs: context.timeout_secs,
         };
 
         Box::new(Self { context, request })
@@ -41,6 +41,7 @@ impl FuncDispatch for FuncBackendJsValidation {
             handler: handler.into(),
             code_base64: code_base64.to_owned(),
             value: args.value,
+            timeout_secs: context.timeout_secs,
         };
 
         Box::new(Self { context, request })
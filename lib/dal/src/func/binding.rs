@@ -5,10 +5,12 @@ use si_data_pg::PgError;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::mpsc;
-use veritech_client::{OutputStream, ResolverFunctionComponent};
+use veritech_client::{
+    FunctionResultFailureErrorKind, OutputStream, Redactor, ResolverFunctionComponent,
+};
 
 use crate::func::execution::FuncExecutionPk;
-use crate::FuncError;
+use crate::func::execution_cache::{CachedFuncExecution, FuncExecutionCacheKey};
 use crate::{
     func::backend::{
         array::FuncBackendArray,
@@ -35,6 +37,7 @@ use crate::{
     Timestamp, Visibility,
 };
 use crate::{DalContext, Tenancy};
+use crate::{EncryptedSecret, FuncError, SecretError};
 
 use super::{
     binding_return_value::{FuncBindingReturnValue, FuncBindingReturnValueError},
@@ -53,7 +56,7 @@ pub enum FuncBindingError {
         "function execution result failure: kind={kind}, message={message}, backend={backend}"
     )]
     FuncBackendResultFailure {
-        kind: String,
+        kind: FunctionResultFailureErrorKind,
         message: String,
         backend: String,
     },
@@ -73,6 +76,8 @@ pub enum FuncBindingError {
     NotFound(FuncBindingId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("secret error: {0}")]
+    Secret(#[from] SecretError),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
@@ -190,6 +195,24 @@ impl FuncBinding {
         Ok((func_binding, func_binding_return_value))
     }
 
+    /// Runs [`Self::new()`] and executes, reusing the result of an identical execution when
+    /// possible. See [`Self::execute_memoized()`].
+    pub async fn create_and_execute_memoized(
+        ctx: &DalContext,
+        args: serde_json::Value,
+        func_id: FuncId,
+    ) -> FuncBindingResult<(Self, FuncBindingReturnValue)> {
+        let func = Func::get_by_id(ctx, &func_id)
+            .await?
+            .ok_or(FuncError::NotFound(func_id))?;
+        let func_binding = Self::new(ctx, args, func_id, func.backend_kind).await?;
+
+        let func_binding_return_value: FuncBindingReturnValue =
+            func_binding.execute_memoized(ctx).await?;
+
+        Ok((func_binding, func_binding_return_value))
+    }
+
     standard_model_accessor!(args, PlainJson<JsonValue>, FuncBindingResult);
     standard_model_accessor!(backend_kind, Enum(FuncBackendKind), FuncBindingResult);
     standard_model_accessor!(code_sha256, String, FuncBindingResult);
@@ -218,6 +241,77 @@ impl FuncBinding {
             .await
     }
 
+    /// Executes like [`Self::execute()`], except that a successful attribute function execution
+    /// is served from the [`FuncExecutionCache`](crate::func::execution_cache::FuncExecutionCache)
+    /// when the same code already ran with the same arguments, skipping veritech entirely.
+    ///
+    /// Funcs which are not [`memoizable`](crate::Func::memoizable), and executions referencing
+    /// secrets, always go to veritech.
+    pub async fn execute_memoized(
+        &self,
+        ctx: &DalContext,
+    ) -> FuncBindingResult<FuncBindingReturnValue> {
+        let (func, execution, context, mut rx) = self.prepare_execution(ctx).await?;
+
+        let cache_key = self.execution_cache_key(&func);
+        if let Some(cache_key) = &cache_key {
+            if let Some(cached) = ctx.func_execution_cache().get(cache_key).await {
+                debug!(func.id = %func.id(), %cache_key, "func execution cache hit");
+                return self
+                    .postprocess_execution(
+                        ctx,
+                        cached.output_stream,
+                        &func,
+                        (cached.unprocessed_value, cached.processed_value),
+                        execution,
+                    )
+                    .await;
+            }
+        }
+
+        let value = self.execute_critical_section(func.clone(), context).await?;
+
+        let mut output = Vec::new();
+        while let Some(output_stream) = rx.recv().await {
+            output.push(output_stream);
+        }
+
+        if let Some(cache_key) = cache_key {
+            ctx.func_execution_cache()
+                .insert(
+                    cache_key,
+                    CachedFuncExecution {
+                        unprocessed_value: value.0.clone(),
+                        processed_value: value.1.clone(),
+                        output_stream: output.clone(),
+                    },
+                )
+                .await;
+        }
+
+        self.postprocess_execution(ctx, output, &func, value, execution)
+            .await
+    }
+
+    /// Returns the key the execution of the binding is cached under, or `None` if it must not be
+    /// cached.
+    fn execution_cache_key(&self, func: &Func) -> Option<FuncExecutionCacheKey> {
+        if *self.backend_kind() != FuncBackendKind::JsAttribute || !func.memoizable() {
+            return None;
+        }
+        // Secret material is resolved at execution time and may change under the same id
+        if !EncryptedSecret::referenced_ids(&self.args).is_empty() {
+            return None;
+        }
+
+        Some(FuncExecutionCacheKey::new(
+            func.code_sha256(),
+            func.handler(),
+            func.backend_response_type().as_ref(),
+            &self.args,
+        ))
+    }
+
     /// Perform function execution to veritech for a given [`Func`](crate::Func) and
     /// [`FuncDispatchContext`](crate::func::backend::FuncDispatchContext).
     pub async fn execute_critical_section(
@@ -294,14 +388,33 @@ impl FuncBinding {
     pub async fn postprocess_execution(
         &self,
         ctx: &DalContext,
-        output_stream: Vec<OutputStream>,
+        mut output_stream: Vec<OutputStream>,
         func: &Func,
-        (unprocessed_value, processed_value): (
+        (mut unprocessed_value, mut processed_value): (
             Option<serde_json::Value>,
             Option<serde_json::Value>,
         ),
         mut execution: FuncExecution,
     ) -> FuncBindingResult<FuncBindingReturnValue> {
+        // Cyclone already scrubs what it knows about, but secrets can also reach a function
+        // through its arguments (e.g. a secret id resolved by the function itself), so logs and
+        // return values are scrubbed again before they are stored.
+        let redactor = self.redactor(ctx).await?;
+        if !redactor.is_empty() {
+            for output in &mut output_stream {
+                output.message = redactor.redact_str(&output.message).into_owned();
+                if let Some(group) = &mut output.group {
+                    *group = redactor.redact_str(group).into_owned();
+                }
+            }
+            for value in [&mut unprocessed_value, &mut processed_value]
+                .into_iter()
+                .flatten()
+            {
+                redactor.redact_value(value);
+            }
+        }
+
         execution.set_output_stream(ctx, output_stream).await?;
 
         let func_binding_return_value = FuncBindingReturnValue::new(
@@ -372,4 +485,28 @@ impl FuncBinding {
         let (context, rx) = FuncDispatchContext::new(ctx);
         Ok((func, execution, context, rx))
     }
+
+    /// Returns a [`Redactor`] for the secrets referenced in the arguments of the binding. Only
+    /// functions running in veritech can get their hands on the material of a secret.
+    async fn redactor(&self, ctx: &DalContext) -> FuncBindingResult<Redactor> {
+        match self.backend_kind() {
+            FuncBackendKind::JsAction
+            | FuncBackendKind::JsAttribute
+            | FuncBackendKind::JsReconciliation
+            | FuncBackendKind::JsSchemaVariantDefinition
+            | FuncBackendKind::JsValidation => {
+                Ok(EncryptedSecret::redactor_for(ctx, &self.args).await?)
+            }
+            FuncBackendKind::Array
+            | FuncBackendKind::Boolean
+            | FuncBackendKind::Identity
+            | FuncBackendKind::Diff
+            | FuncBackendKind::Integer
+            | FuncBackendKind::Map
+            | FuncBackendKind::Object
+            | FuncBackendKind::String
+            | FuncBackendKind::Unset
+            | FuncBackendKind::Validation => Ok(Redactor::default()),
+        }
+    }
 }
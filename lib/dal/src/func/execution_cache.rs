@@ -0,0 +1,170 @@
+//! A content-addressed cache of the results of [`Func`](crate::Func) executions.
+//!
+//! Attribute functions are expected to be pure: given the same code and the same arguments, they
+//! return the same value. [`FuncExecutionCache`] takes advantage of that to avoid sending an
+//! identical execution to veritech twice. Entries are keyed on everything that can change the
+//! outcome of an execution (see [`FuncExecutionCacheKey`]), so they can be shared across
+//! tenancies and change sets.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use object_tree::Hash;
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+use veritech_client::OutputStream;
+
+/// The number of executions kept by a [`FuncExecutionCache`] created with
+/// [`FuncExecutionCache::default`].
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+/// Identifies the result of a [`Func`](crate::Func) execution by its inputs.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FuncExecutionCacheKey(Hash);
+
+impl FuncExecutionCacheKey {
+    /// Computes the key of an execution of the code hashed as `code_sha256`, entering through
+    /// `handler`, with the given `args` and `response_type`.
+    ///
+    /// The arguments are canonicalized first, so the order in which the keys of an object were
+    /// inserted does not matter.
+    pub fn new(
+        code_sha256: &str,
+        handler: Option<&str>,
+        response_type: &str,
+        args: &Value,
+    ) -> Self {
+        let input = serde_json::json!({
+            "args": canonicalize(args),
+            "code_sha256": code_sha256,
+            "handler": handler,
+            "response_type": response_type,
+        });
+
+        Self(Hash::new(input.to_string().as_bytes()))
+    }
+}
+
+impl std::fmt::Display for FuncExecutionCacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The outcome of a successful execution, as it was before being post-processed.
+#[derive(Clone, Debug)]
+pub struct CachedFuncExecution {
+    pub unprocessed_value: Option<Value>,
+    pub processed_value: Option<Value>,
+    pub output_stream: Vec<OutputStream>,
+}
+
+/// A bounded, in-memory cache of [`CachedFuncExecutions`](CachedFuncExecution), shared by all the
+/// clones of a [`ServicesContext`](crate::ServicesContext).
+///
+/// Once full, the oldest entries are evicted first.
+#[derive(Clone, Debug)]
+pub struct FuncExecutionCache {
+    inner: Arc<Mutex<Entries>>,
+    capacity: usize,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    executions: HashMap<FuncExecutionCacheKey, CachedFuncExecution>,
+    insertion_order: VecDeque<FuncExecutionCacheKey>,
+}
+
+impl Default for FuncExecutionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl FuncExecutionCache {
+    /// Creates an empty cache holding up to `capacity` executions. A `capacity` of zero disables
+    /// caching altogether.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Default::default(),
+            capacity,
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    /// Returns the cached execution for `key`, counting a hit or a miss.
+    pub async fn get(&self, key: &FuncExecutionCacheKey) -> Option<CachedFuncExecution> {
+        let cached = self.inner.lock().await.executions.get(key).cloned();
+
+        match cached {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+
+        cached
+    }
+
+    /// Caches an execution under `key`, evicting the oldest entries if the cache is full.
+    pub async fn insert(&self, key: FuncExecutionCacheKey, execution: CachedFuncExecution) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.inner.lock().await;
+        if entries.executions.insert(key, execution).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+        while entries.insertion_order.len() > self.capacity {
+            if let Some(evicted) = entries.insertion_order.pop_front() {
+                entries.executions.remove(&evicted);
+            }
+        }
+    }
+
+    /// Returns the current hit and miss counters along with the number of cached executions.
+    pub async fn stats(&self) -> FuncExecutionCacheStats {
+        let entries = self.inner.lock().await.executions.len();
+
+        FuncExecutionCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries,
+        }
+    }
+}
+
+/// A point in time copy of the counters of a [`FuncExecutionCache`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FuncExecutionCacheStats {
+    /// Number of executions served from the cache.
+    pub hits: u64,
+    /// Number of executions which had to be sent to veritech.
+    pub misses: u64,
+    /// Number of executions currently cached.
+    pub entries: usize,
+}
+
+/// Returns a copy of `value` where the keys of every object are sorted.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(values.iter().map(canonicalize).collect()),
+        Value::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+            let mut canonical = Map::with_capacity(object.len());
+            for key in keys {
+                canonical.insert(key.clone(), canonicalize(&object[key]));
+            }
+            Value::Object(canonical)
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => value.clone(),
+    }
+}
@@ -1,4 +1,13 @@
-use crate::{Tenancy, TransactionsError};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{ChangeSetPk, Tenancy, TransactionsError, WorkspacePk};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::Display as StrumDisplay;
 use thiserror::Error;
@@ -9,6 +18,8 @@ use telemetry::prelude::*;
 
 use crate::{pk, DalContext, Timestamp, UserPk};
 
+const HISTORY_EVENT_LIST: &str = include_str!("queries/history_event/list.sql");
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum HistoryEventError {
@@ -58,6 +69,10 @@ pub struct HistoryEvent {
     pub actor: HistoryActor,
     pub message: String,
     pub data: serde_json::Value,
+    /// The change set the event happened in, or [`ChangeSetPk::NONE`] for head. Events recorded
+    /// before change sets were tracked have none.
+    #[serde(default, rename = "visibility_change_set_pk")]
+    pub change_set_pk: Option<ChangeSetPk>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -79,14 +94,195 @@ impl HistoryEvent {
         let row = txns
             .pg()
             .query_one(
-                "SELECT object FROM history_event_create_v1($1, $2, $3, $4, $5)",
-                &[&label.to_string(), &actor, &message, &data, ctx.tenancy()],
+                "SELECT object FROM history_event_create_v2($1, $2, $3, $4, $5, $6)",
+                &[
+                    &label.to_string(),
+                    &actor,
+                    &message,
+                    &data,
+                    ctx.tenancy(),
+                    &ctx.visibility().change_set_pk,
+                ],
             )
             .await?;
         let json: serde_json::Value = row.try_get("object")?;
-        // TODO(fnichol): determine subject(s) for publishing
-        txns.nats().publish("historyEvent", &json).await?;
+        txns.nats()
+            .publish(Self::subject(ctx.tenancy().workspace_pk(), label), &json)
+            .await?;
         let object: HistoryEvent = serde_json::from_value(json)?;
         Ok(object)
     }
+
+    /// The NATS subject events with the given label are published on. Labels are dotted, so
+    /// subscribers can narrow down on a workspace, a kind of object, or a single action, e.g.
+    /// `si.workspace_pk.<pk>.history_event.change_set.>`.
+    pub fn subject(workspace_pk: Option<WorkspacePk>, label: &str) -> String {
+        match workspace_pk {
+            Some(workspace_pk) => format!("si.workspace_pk.{workspace_pk}.history_event.{label}"),
+            None => format!("si.history_event.{label}"),
+        }
+    }
+
+    /// Lists the events of the current tenancy that match the filter, newest first.
+    #[instrument(skip_all)]
+    pub async fn list(
+        ctx: &DalContext,
+        filter: &HistoryEventFilter,
+    ) -> HistoryEventResult<Vec<HistoryEvent>> {
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+        let label_prefix = filter.label_prefix.as_deref().map(escape_like);
+        let limit = filter.limit.map(|limit| limit as i64);
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                HISTORY_EVENT_LIST,
+                &[
+                    ctx.tenancy(),
+                    &actor,
+                    &filter.label,
+                    &label_prefix,
+                    &filter.change_set_pk,
+                    &filter.since,
+                    &filter.until,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            events.push(serde_json::from_value(json)?);
+        }
+
+        Ok(events)
+    }
+
+    /// Deletes events older than the retention policy allows, across all workspaces, returning
+    /// how many were deleted.
+    #[instrument(skip_all)]
+    pub async fn purge(
+        ctx: &DalContext,
+        retention: &HistoryEventRetention,
+    ) -> HistoryEventResult<u64> {
+        let default_max_age = retention.max_age_secs.map(interval);
+        let max_ages: HashMap<String, String> = retention
+            .workspace_max_age_secs
+            .iter()
+            .map(|(workspace_pk, max_age_secs)| (workspace_pk.to_string(), interval(*max_age_secs)))
+            .collect();
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT purged FROM history_event_purge_v1($1, $2)",
+                &[&default_max_age, &serde_json::to_value(max_ages)?],
+            )
+            .await?;
+        let purged: i64 = row.try_get("purged")?;
+
+        Ok(purged as u64)
+    }
+}
+
+/// An event describing a request, recorded in the same transaction as the changes the request
+/// made: the first commit of a [`DalContext`] built with it records it (see
+/// [`DalContextBuilder::set_audit`](crate::DalContextBuilder::set_audit)). Clones share whether
+/// it has been recorded, so a request that commits several times is audited once.
+#[derive(Clone, Debug)]
+pub struct PendingAudit {
+    label: String,
+    message: String,
+    data: serde_json::Value,
+    recorded: Arc<AtomicBool>,
+}
+
+impl PendingAudit {
+    pub fn new(
+        label: impl Into<String>,
+        message: impl Into<String>,
+        data: serde_json::Value,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            message: message.into(),
+            data,
+            recorded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Records the event in the context's transactions, unless it already has been.
+    pub(crate) async fn record(&self, ctx: &DalContext) -> HistoryEventResult<()> {
+        if self.recorded.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        if let Err(err) = HistoryEvent::new(ctx, &self.label, &self.message, &self.data).await {
+            self.recorded.store(false, Ordering::SeqCst);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+/// Narrows down [`HistoryEvent::list`]. Every filter that is set has to match.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventFilter {
+    pub actor: Option<HistoryActor>,
+    pub label: Option<String>,
+    /// Matches labels starting with this, e.g. `change_set.` for everything done to change sets.
+    pub label_prefix: Option<String>,
+    pub change_set_pk: Option<ChangeSetPk>,
+    /// Only events created at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only events created before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+/// How long [`HistoryEvents`](HistoryEvent) are kept, see [`HistoryEvent::purge`].
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct HistoryEventRetention {
+    /// How long events are kept, unless their workspace says otherwise. Kept forever if unset.
+    pub max_age_secs: Option<u64>,
+    /// How long the events of specific workspaces are kept.
+    pub workspace_max_age_secs: HashMap<WorkspacePk, u64>,
+}
+
+fn interval(secs: u64) -> String {
+    format!("{secs} seconds")
+}
+
+fn escape_like(prefix: &str) -> String {
+    prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn label_prefixes_are_escaped() {
+        assert_eq!(escape_like("change_set.100%"), "change\\_set.100\\%");
+    }
+
+    #[test]
+    fn subjects_are_per_workspace() {
+        let workspace_pk = WorkspacePk::generate();
+        assert_eq!(
+            HistoryEvent::subject(Some(workspace_pk), "change_set.apply"),
+            format!("si.workspace_pk.{workspace_pk}.history_event.change_set.apply")
+        );
+        assert_eq!(
+            HistoryEvent::subject(None, "workspace.create"),
+            "si.history_event.workspace.create"
+        );
+    }
 }
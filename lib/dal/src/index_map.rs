@@ -42,6 +42,12 @@ impl IndexMap {
         self.order.retain(|x| order_set.insert(*x));
     }
 
+    /// Remove an entry from the index map, if present.
+    pub fn remove(&mut self, attribute_value_id: AttributeValueId) {
+        self.order.retain(|x| *x != attribute_value_id);
+        self.key_map.remove(&attribute_value_id);
+    }
+
     /// Returns the order of attribute resolvers for this index map as
     /// array; it does not include the keys.
     pub fn order(&self) -> &[AttributeValueId] {
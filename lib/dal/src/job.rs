@@ -3,3 +3,4 @@ pub mod definition;
 pub mod processor;
 pub mod producer;
 pub mod queue;
+pub mod retry;
@@ -9,9 +9,10 @@ use tokio::task::JoinError;
 
 use crate::{
     fix::FixError, func::binding_return_value::FuncBindingReturnValueError,
-    job::producer::BlockingJobError, job::producer::JobProducerError, status::StatusUpdaterError,
-    AccessBuilder, ActionPrototypeError, ActionPrototypeId, AttributeValueError, ComponentError,
-    ComponentId, DalContext, DalContextBuilder, FixBatchId, FixResolverError, StandardModelError,
+    job::producer::BlockingJobError, job::producer::JobPriority, job::producer::JobProducerError,
+    job::retry::RetryPolicy, status::StatusUpdaterError, AccessBuilder, ActionPrototypeError,
+    ActionPrototypeId, AttributeValueError, ComponentError, ComponentId, DalContext,
+    DalContextBuilder, FixBatchId, FixResolverError, KeyPairError, StandardModelError,
     TransactionsError, Visibility, WsEventError,
 };
 
@@ -50,6 +51,8 @@ pub enum JobConsumerError {
     Io(#[from] ::std::io::Error),
     #[error(transparent)]
     JobProducer(#[from] JobProducerError),
+    #[error(transparent)]
+    KeyPair(#[from] KeyPairError),
     #[error("missing fix execution batch for id: {0}")]
     MissingFixBatch(FixBatchId),
     #[error(transparent)]
@@ -95,6 +98,32 @@ pub struct JobInfo {
     pub access_builder: AccessBuilder,
     pub visibility: Visibility,
     pub blocking: bool,
+    /// Which attempt at running this job this is, counting from zero.
+    #[serde(default)]
+    pub attempt: u32,
+    #[serde(default)]
+    pub priority: JobPriority,
+    /// Set on retries, which must not run before their backoff has passed.
+    #[serde(default)]
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+impl JobInfo {
+    /// The next attempt at this job, which may run once `backoff` has passed.
+    pub fn next_attempt(mut self, backoff: std::time::Duration) -> Self {
+        self.attempt += 1;
+        self.not_before = chrono::Duration::from_std(backoff)
+            .ok()
+            .map(|backoff| Utc::now() + backoff);
+        self
+    }
+
+    /// How long until this job may run, if it may not run yet.
+    pub fn delay(&self) -> Option<std::time::Duration> {
+        self.not_before
+            .and_then(|not_before| (not_before - Utc::now()).to_std().ok())
+            .filter(|delay| !delay.is_zero())
+    }
 }
 
 #[async_trait]
@@ -102,6 +131,11 @@ pub trait JobConsumerMetadata: std::fmt::Debug + Sync {
     fn type_name(&self) -> String;
     fn access_builder(&self) -> AccessBuilder;
     fn visibility(&self) -> Visibility;
+
+    /// How `pinga` should retry this job when it fails. Jobs are not retried by default.
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::never()
+    }
 }
 
 #[async_trait]
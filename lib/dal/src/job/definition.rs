@@ -1,7 +1,9 @@
 mod dependent_values_update;
 mod fix;
+mod key_pair_rotation;
 mod refresh;
 
 pub use dependent_values_update::DependentValuesUpdate;
 pub use fix::{FixItem, FixesJob};
+pub use key_pair_rotation::KeyPairRotationJob;
 pub use refresh::RefreshJob;
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, collections::HashSet, convert::TryFrom};
+use std::{collections::HashMap, collections::HashSet, convert::TryFrom, time::Duration};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -12,6 +12,7 @@ use crate::{
         JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
     },
     job::producer::{JobProducer, JobProducerResult},
+    job::retry::{JobErrorKind, RetryPolicy},
     AccessBuilder, AttributeValue, AttributeValueError, AttributeValueId, AttributeValueResult,
     DalContext, StandardModel, StatusUpdater, Visibility, WsEvent,
 };
@@ -79,6 +80,13 @@ impl JobConsumerMetadata for DependentValuesUpdate {
     fn visibility(&self) -> Visibility {
         self.visibility
     }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        // Failing attribute functions are reported per value through council, so only retry
+        // when we couldn't talk to the rest of the system.
+        RetryPolicy::new(3, Duration::from_secs(1), Duration::from_secs(10))
+            .retry_on(JobErrorKind::Transport)
+    }
 }
 
 #[async_trait]
@@ -89,6 +97,8 @@ impl JobConsumer for DependentValuesUpdate {
         level = "info",
         fields(
             attribute_values = ?self.attribute_values,
+            func_execution_cache.hits = Empty,
+            func_execution_cache.misses = Empty,
         )
     )]
     async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
@@ -108,7 +118,29 @@ impl JobConsumer for DependentValuesUpdate {
         .await?;
         let pub_council = council.clone_into_pub();
 
-        match self.inner_run(ctx, &mut council, pub_council).await {
+        // Keep our lease with council alive for as long as we're running, so council only
+        // evicts us if this job actually goes away.
+        let heartbeat_council = council.clone_into_pub();
+        let heartbeat = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(council_server::client::HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(err) = heartbeat_council.heartbeat().await {
+                    warn!(error = ?err, "unable to send heartbeat to council");
+                }
+            }
+        });
+
+        let result = self.inner_run(ctx, &mut council, pub_council).await;
+        heartbeat.abort();
+
+        // The cache is shared by every job run by this process, so these are running totals
+        let cache_stats = ctx.func_execution_cache().stats().await;
+        let span = Span::current();
+        span.record("func_execution_cache.hits", cache_stats.hits);
+        span.record("func_execution_cache.misses", cache_stats.misses);
+
+        match result {
             Ok(res) => Ok(res),
             Err(e) => {
                 council.bye().await?;
@@ -268,7 +300,38 @@ impl DependentValuesUpdate {
                     // `AttributeValue::create_dependent_values` after it has already told us to do that, and after
                     // we have told it that we've finished doing so. This should never be able to happen normally,
                     // as it breaks the protocol contract we have with council.
-                    council_server::Response::OkToCreate => return Err(JobConsumerError::CouncilProtocol("Told to create values again after we've finished creating values. Multiple instances of council running?".to_string())),
+                    council_server::Response::OkToCreate { .. } => return Err(JobConsumerError::CouncilProtocol("Told to create values again after we've finished creating values. Multiple instances of council running?".to_string())),
+                    // Introspection snapshots are only ever sent to the inbox of whoever asked for
+                    // one, never to a job's reply channel.
+                    council_server::Response::Introspection { .. } => return Err(JobConsumerError::CouncilProtocol("Received an introspection snapshot on a job's reply channel".to_string())),
+                    council_server::Response::Restarted { resumed_graph: true } => {
+                        // Council picked our graph back up from its checkpoint and will hand out
+                        // the nodes that were in flight again, so there's nothing to resend.
+                        debug!(job_id = ?self.job_id(), "Council restarted and resumed our dependency graph");
+                    }
+                    council_server::Response::Restarted { resumed_graph: false } => {
+                        debug!(job_id = ?self.job_id(), "Council restarted, re-registering remaining dependency graph");
+                        // Council may have lost anything we sent while it was down, so tell it
+                        // about the nodes we're still waiting on. Dependencies on nodes that are
+                        // already done are dropped so they aren't processed again.
+                        council
+                            .register_dependency_graph(
+                                dependency_graph
+                                    .iter()
+                                    .map(|(key, value)| {
+                                        (
+                                            key.into(),
+                                            value
+                                                .iter()
+                                                .filter(|id| dependency_graph.contains_key(*id))
+                                                .map(Into::into)
+                                                .collect(),
+                                        )
+                                    })
+                                    .collect(),
+                            )
+                            .await?;
+                    }
                     council_server::Response::Shutdown => break,
                 },
                 // FIXME: reconnect
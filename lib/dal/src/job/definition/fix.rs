@@ -9,7 +9,8 @@ use crate::{
         consumer::{
             JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
         },
-        producer::{JobProducer, JobProducerResult},
+        producer::{JobPriority, JobProducer, JobProducerResult},
+        retry::RetryPolicy,
     },
     AccessBuilder, ActionKind, ActionPrototype, ActionPrototypeId, AttributeValueId, Component,
     ComponentId, DalContext, DependentValuesUpdate, Fix, FixBatch, FixBatchId, FixCompletionStatus,
@@ -85,6 +86,10 @@ impl JobProducer for FixesJob {
     fn arg(&self) -> JobProducerResult<serde_json::Value> {
         Ok(serde_json::to_value(FixesJobArgs::from(self.clone()))?)
     }
+
+    fn priority(&self) -> JobPriority {
+        JobPriority::Interactive
+    }
 }
 
 impl JobConsumerMetadata for FixesJob {
@@ -99,6 +104,11 @@ impl JobConsumerMetadata for FixesJob {
     fn visibility(&self) -> Visibility {
         self.visibility
     }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        // Fixes run actions against real resources, which are not safe to run a second time.
+        RetryPolicy::never()
+    }
 }
 
 #[async_trait]
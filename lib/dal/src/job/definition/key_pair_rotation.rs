@@ -0,0 +1,97 @@
+use std::{convert::TryFrom, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        producer::{JobPriority, JobProducer, JobProducerResult},
+        retry::{JobErrorKind, RetryPolicy},
+    },
+    AccessBuilder, DalContext, KeyPair, Visibility,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct KeyPairRotationJobArgs {}
+
+/// Re-seals the secrets of a workspace for the rotation started with
+/// [`KeyPair::start_rotation`], see [`KeyPair::rotate`].
+#[derive(Clone, Debug, Serialize)]
+pub struct KeyPairRotationJob {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl KeyPairRotationJob {
+    pub fn new(access_builder: AccessBuilder, visibility: Visibility) -> Box<Self> {
+        Box::new(Self {
+            access_builder,
+            visibility,
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for KeyPairRotationJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(KeyPairRotationJobArgs {})?)
+    }
+
+    fn priority(&self) -> JobPriority {
+        JobPriority::Background
+    }
+}
+
+impl JobConsumerMetadata for KeyPairRotationJob {
+    fn type_name(&self) -> String {
+        "KeyPairRotationJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        // A rotation resumes where it left off, so running it again is always safe.
+        RetryPolicy::new(5, Duration::from_secs(5), Duration::from_secs(300))
+            .retry_on(JobErrorKind::Database)
+            .retry_on(JobErrorKind::Transport)
+    }
+}
+
+#[async_trait]
+impl JobConsumer for KeyPairRotationJob {
+    #[instrument(name = "key_pair_rotation_job.run", skip_all, level = "info")]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        let rotation = KeyPair::rotate(ctx).await?;
+        info!(
+            rotation_pk = %rotation.pk(),
+            reencrypted_secrets = rotation.reencrypted_secrets(),
+            failed_secrets = rotation.failed_secrets(),
+            "key pair rotation finished"
+        );
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for KeyPairRotationJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
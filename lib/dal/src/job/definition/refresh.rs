@@ -1,4 +1,4 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, time::Duration};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -9,7 +9,8 @@ use crate::{
         consumer::{
             JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
         },
-        producer::{JobProducer, JobProducerResult},
+        producer::{JobPriority, JobProducer, JobProducerResult},
+        retry::{JobErrorKind, RetryPolicy},
     },
     AccessBuilder, ActionKind, Component, ComponentId, DalContext, StandardModel, Visibility,
     WsEvent,
@@ -33,6 +34,8 @@ pub struct RefreshJob {
     component_ids: Vec<ComponentId>,
     access_builder: AccessBuilder,
     visibility: Visibility,
+    #[serde(skip)]
+    priority: JobPriority,
     job: Option<JobInfo>,
 }
 
@@ -46,15 +49,26 @@ impl RefreshJob {
             component_ids,
             access_builder,
             visibility,
+            priority: JobPriority::Interactive,
             job: None,
         })
     }
+
+    /// Refreshes nobody is waiting on, such as the periodic ones, can run at a lower priority.
+    pub fn with_priority(mut self: Box<Self>, priority: JobPriority) -> Box<Self> {
+        self.priority = priority;
+        self
+    }
 }
 
 impl JobProducer for RefreshJob {
     fn arg(&self) -> JobProducerResult<serde_json::Value> {
         Ok(serde_json::to_value(RefreshJobArgs::from(self.clone()))?)
     }
+
+    fn priority(&self) -> JobPriority {
+        self.priority
+    }
 }
 
 impl JobConsumerMetadata for RefreshJob {
@@ -69,6 +83,13 @@ impl JobConsumerMetadata for RefreshJob {
     fn visibility(&self) -> Visibility {
         self.visibility
     }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        // Flaky veritech or cloud provider calls are worth another go.
+        RetryPolicy::new(3, Duration::from_secs(5), Duration::from_secs(60))
+            .retry_on(JobErrorKind::Execution)
+            .retry_on(JobErrorKind::Transport)
+    }
 }
 
 #[async_trait]
@@ -114,6 +135,7 @@ impl TryFrom<JobInfo> for RefreshJob {
             component_ids: args.component_ids,
             access_builder: job.access_builder,
             visibility: job.visibility,
+            priority: job.priority,
             job: Some(job),
         })
     }
@@ -3,11 +3,16 @@ use dyn_clone::DynClone;
 use thiserror::Error;
 
 use crate::{
-    job::producer::{BlockingJobError, BlockingJobResult, JobProducer, JobProducerError},
+    job::{
+        consumer::JobInfo,
+        producer::{BlockingJobError, BlockingJobResult, JobProducer, JobProducerError},
+    },
     DalContext,
 };
 
+pub mod jetstream_processor;
 mod nats_processor;
+pub use jetstream_processor::JetStreamProcessor;
 pub use nats_processor::NatsProcessor;
 
 #[remain::sorted]
@@ -35,6 +40,8 @@ pub trait JobQueueProcessor: std::fmt::Debug + DynClone {
     ) -> BlockingJobResult;
     async fn process_queue(&self) -> JobQueueProcessorResult<()>;
     async fn blocking_process_queue(&self) -> JobQueueProcessorResult<()>;
+    /// Puts a job that has already been enqueued once back on the queue, as is.
+    async fn requeue_job(&self, job_info: JobInfo) -> JobQueueProcessorResult<()>;
 }
 
 dyn_clone::clone_trait_object!(JobQueueProcessor);
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use si_data_nats::{HeaderMap, JetStream, NatsClient};
+use telemetry::prelude::*;
+use tokio::{sync::OnceCell, task::JoinSet};
+
+use crate::{
+    job::{
+        consumer::JobInfo,
+        producer::{BlockingJobError, BlockingJobResult, JobProducer, JobProducerError},
+        queue::JobQueue,
+    },
+    DalContext,
+};
+
+use super::{JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult};
+
+const JETSTREAM_JOB_SUBJECT: &str = "pinga-jobs-durable";
+const JETSTREAM_JOB_STREAM: &str = "PINGA_JOBS";
+
+/// JetStream messages carry their ack subject in the reply field, so the mailbox a blocking job
+/// should report back to travels in this header instead.
+pub const REPLY_MAILBOX_HEADER: &str = "Si-Reply-Mailbox";
+
+/// A [`JobQueueProcessor`] that persists jobs in a JetStream work queue, so a job is kept until
+/// a `pinga` instance acknowledges it has finished running it.
+#[derive(Clone, Debug)]
+pub struct JetStreamProcessor {
+    client: NatsClient,
+    jetstream: JetStream,
+    queue: JobQueue,
+    pinga_subject: String,
+    stream_name: String,
+    stream_ready: Arc<OnceCell<()>>,
+}
+
+impl JetStreamProcessor {
+    pub fn new(client: NatsClient) -> Self {
+        let prefix = client.metadata().subject_prefix();
+        let pinga_subject = Self::subject(prefix);
+        let stream_name = Self::stream_name(prefix);
+
+        Self {
+            jetstream: client.jetstream(),
+            client,
+            queue: JobQueue::new(),
+            pinga_subject,
+            stream_name,
+            stream_ready: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// The subject jobs are published on.
+    pub fn subject(prefix: Option<&str>) -> String {
+        match prefix {
+            Some(prefix) => format!("{prefix}.{JETSTREAM_JOB_SUBJECT}"),
+            None => JETSTREAM_JOB_SUBJECT.to_owned(),
+        }
+    }
+
+    /// The name of the stream backing the job queue. Stream names are global to a NATS account,
+    /// so the subject prefix is folded into it.
+    pub fn stream_name(prefix: Option<&str>) -> String {
+        prefixed_stream_name(prefix, JETSTREAM_JOB_STREAM)
+    }
+
+    async fn ensure_stream(&self) -> JobQueueProcessorResult<()> {
+        self.stream_ready
+            .get_or_try_init(|| async {
+                self.jetstream
+                    .get_or_create_work_queue(&self.stream_name, vec![self.pinga_subject.clone()])
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn push_all_jobs(&self) -> JobQueueProcessorResult<()> {
+        self.ensure_stream().await?;
+
+        while let Some(element) = self.queue.fetch_job().await {
+            let job_info = JobInfo::new(element)?;
+
+            if let Err(err) = self
+                .jetstream
+                .publish(&self.pinga_subject, serde_json::to_vec(&job_info)?)
+                .await
+            {
+                error!("JetStream job push failed, some jobs will be dropped");
+                return Err(JobQueueProcessorError::Transport(Box::new(err)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Folds a subject prefix into a stream name, keeping only the characters stream names allow.
+pub(crate) fn prefixed_stream_name(prefix: Option<&str>, name: &str) -> String {
+    match prefix {
+        Some(prefix) => format!(
+            "{}_{name}",
+            prefix
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+                .to_uppercase()
+        ),
+        None => name.to_owned(),
+    }
+}
+
+#[async_trait]
+impl JobQueueProcessor for JetStreamProcessor {
+    async fn enqueue_job(&self, job: Box<dyn JobProducer + Send + Sync>, _ctx: &DalContext) {
+        self.queue.enqueue_job(job).await
+    }
+
+    async fn block_on_job(&self, job: Box<dyn JobProducer + Send + Sync>) -> BlockingJobResult {
+        let job_info = JobInfo::new_blocking(job)
+            .map_err(|e: JobProducerError| BlockingJobError::JobProducer(e.to_string()))?;
+
+        self.ensure_stream()
+            .await
+            .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+
+        let job_reply_inbox = self.client.new_inbox();
+        let mut reply_subscriber = self
+            .client
+            .subscribe(&job_reply_inbox)
+            .await
+            .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(REPLY_MAILBOX_HEADER, job_reply_inbox.as_str());
+        self.jetstream
+            .publish_with_headers(
+                &self.pinga_subject,
+                headers,
+                serde_json::to_vec(&job_info)
+                    .map_err(|e| BlockingJobError::Serde(e.to_string()))?,
+            )
+            .await
+            .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+
+        match reply_subscriber.next().await {
+            Some(message) => serde_json::from_slice::<BlockingJobResult>(message.payload())
+                .map_err(|e| BlockingJobError::Serde(e.to_string()))?,
+            None => Err(BlockingJobError::Nats(
+                "Subscriber or connection no longer valid".to_string(),
+            )),
+        }
+    }
+
+    async fn block_on_jobs(
+        &self,
+        jobs: Vec<Box<dyn JobProducer + Send + Sync>>,
+    ) -> BlockingJobResult {
+        let mut dispatched_jobs = JoinSet::new();
+
+        // Fan out, dispatching all queued jobs to pinga over JetStream.
+        for job in jobs {
+            let job_processor = self.clone();
+            dispatched_jobs.spawn(async move { job_processor.block_on_job(job).await });
+        }
+
+        let mut results = Vec::new();
+        // Wait for all queued jobs to finish (regardless of success), before exiting.
+        loop {
+            match dispatched_jobs.join_next().await {
+                // All jobs done.
+                None => break,
+                Some(Ok(Ok(_))) => { /* Nothing to do. Job succeeded. */ }
+                Some(Ok(Err(job_error))) => {
+                    results.push(job_error);
+                }
+                Some(Err(join_err)) => {
+                    results.push(BlockingJobError::JobExecution(join_err.to_string()));
+                }
+            }
+        }
+
+        if !results.is_empty() {
+            Err(BlockingJobError::JobExecution(
+                results
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn process_queue(&self) -> JobQueueProcessorResult<()> {
+        let processor = self.clone();
+        tokio::spawn(async move {
+            if let Err(err) = processor.push_all_jobs().await {
+                error!("Unable to push jobs to JetStream: {err}");
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn blocking_process_queue(&self) -> JobQueueProcessorResult<()> {
+        self.block_on_jobs(self.queue.drain().await).await?;
+
+        Ok(())
+    }
+
+    async fn requeue_job(&self, job_info: JobInfo) -> JobQueueProcessorResult<()> {
+        self.ensure_stream().await?;
+
+        self.jetstream
+            .publish(&self.pinga_subject, serde_json::to_vec(&job_info)?)
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))
+    }
+}
@@ -150,4 +150,11 @@ impl JobQueueProcessor for NatsProcessor {
 
         Ok(())
     }
+
+    async fn requeue_job(&self, job_info: JobInfo) -> JobQueueProcessorResult<()> {
+        self.client
+            .publish(&self.pinga_subject, serde_json::to_vec(&job_info)?)
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))
+    }
 }
@@ -16,8 +16,27 @@ pub enum JobProducerError {
 
 pub type JobProducerResult<T> = Result<T, JobProducerError>;
 
+/// How urgently `pinga` should run a job. Higher classes are always scheduled ahead of lower
+/// ones; within a class, `pinga` takes turns between workspaces.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum JobPriority {
+    /// Housekeeping nobody is waiting on.
+    Background,
+    #[default]
+    Normal,
+    /// Work a user is actively waiting on, such as running fixes or refreshing resources.
+    Interactive,
+}
+
 pub trait JobProducer: std::fmt::Debug + Send + JobConsumerMetadata {
     fn arg(&self) -> JobProducerResult<serde_json::Value>;
+
+    fn priority(&self) -> JobPriority {
+        JobPriority::Normal
+    }
 }
 
 pub type BlockingJobResult = Result<(), BlockingJobError>;
@@ -49,6 +68,9 @@ impl JobInfo {
             access_builder: job_producer.access_builder(),
             visibility: job_producer.visibility(),
             blocking: false,
+            attempt: 0,
+            priority: job_producer.priority(),
+            not_before: None,
         })
     }
 
@@ -63,6 +85,9 @@ impl JobInfo {
             access_builder: job_producer.access_builder(),
             visibility: job_producer.visibility(),
             blocking: true,
+            attempt: 0,
+            priority: job_producer.priority(),
+            not_before: None,
         })
     }
 }
@@ -0,0 +1,197 @@
+//! Retry policies for jobs executed by `pinga`, and the dead letters published for jobs that
+//! exhausted them.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_nats::{NatsClient, NatsError, WorkQueue};
+
+use crate::job::{
+    consumer::{JobConsumerError, JobInfo},
+    processor::{
+        jetstream_processor::prefixed_stream_name, JobQueueProcessor, JobQueueProcessorResult,
+    },
+};
+
+const NATS_DEAD_LETTER_QUEUE: &str = "pinga-jobs-dead-letter";
+const JETSTREAM_DEAD_LETTER_STREAM: &str = "PINGA_JOBS_DEAD_LETTER";
+
+/// Broad classes of [`JobConsumerError`] that a [`RetryPolicy`] can choose to retry.
+#[remain::sorted]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobErrorKind {
+    /// Talking to the database failed.
+    Database,
+    /// Executing a function (through veritech) failed.
+    Execution,
+    /// Anything that retrying will not fix, such as bad arguments or missing objects.
+    Permanent,
+    /// Talking to NATS, or to a sibling task, failed.
+    Transport,
+}
+
+impl JobConsumerError {
+    pub fn kind(&self) -> JobErrorKind {
+        match self {
+            Self::KeyPair(_) | Self::PgPool(_) | Self::Transactions(_) => JobErrorKind::Database,
+            Self::ActionPrototype(_)
+            | Self::AttributeValue(_)
+            | Self::Component(_)
+            | Self::Fix(_)
+            | Self::FixResolver(_)
+            | Self::FuncBindingReturnValue(_) => JobErrorKind::Execution,
+            Self::BlockingJob(_)
+            | Self::Council(_)
+            | Self::Io(_)
+            | Self::Nats(_)
+            | Self::NatsUnavailable
+            | Self::TokioTask(_) => JobErrorKind::Transport,
+            _ => JobErrorKind::Permanent,
+        }
+    }
+}
+
+/// How many times, how often, and for which kinds of errors a job is retried before it is given
+/// up on and sent to the dead letter queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry. Each following retry doubles it.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between two attempts.
+    pub max_backoff: Duration,
+    pub retryable: Vec<JobErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+impl RetryPolicy {
+    /// Run the job once, failures go straight to the dead letter queue.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            retryable: Vec::new(),
+        }
+    }
+
+    pub fn new(max_attempts: u32, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff,
+            max_backoff,
+            retryable: Vec::new(),
+        }
+    }
+
+    pub fn retry_on(mut self, kind: JobErrorKind) -> Self {
+        self.retryable.push(kind);
+        self
+    }
+
+    /// Whether a job that failed with `err` on attempt number `attempt` (counting from zero)
+    /// should run again.
+    pub fn should_retry(&self, err: &JobConsumerError, attempt: u32) -> bool {
+        attempt + 1 < self.max_attempts && self.retryable.contains(&err.kind())
+    }
+
+    /// How long to wait before running attempt number `attempt + 1`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// A job that exhausted its [`RetryPolicy`], along with the error of its last attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub job: JobInfo,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    pub fn new(job: JobInfo, error: impl Into<String>) -> Self {
+        Self {
+            job,
+            error: error.into(),
+            failed_at: Utc::now(),
+        }
+    }
+
+    pub fn subject(prefix: Option<&str>) -> String {
+        match prefix {
+            Some(prefix) => format!("{prefix}.{NATS_DEAD_LETTER_QUEUE}"),
+            None => NATS_DEAD_LETTER_QUEUE.to_owned(),
+        }
+    }
+
+    /// The name of the stream dead letters are kept in until they are replayed.
+    pub fn stream_name(prefix: Option<&str>) -> String {
+        prefixed_stream_name(prefix, JETSTREAM_DEAD_LETTER_STREAM)
+    }
+
+    /// Ensures the stream dead letters are kept in exists.
+    pub async fn queue(nats: &NatsClient) -> Result<WorkQueue, NatsError> {
+        let prefix = nats.metadata().subject_prefix();
+        nats.jetstream()
+            .get_or_create_work_queue(Self::stream_name(prefix), vec![Self::subject(prefix)])
+            .await
+    }
+
+    /// Keeps the dead letter on the dead letter queue until it is replayed.
+    pub async fn publish(&self, nats: &NatsClient) -> Result<(), NatsError> {
+        Self::queue(nats).await?;
+
+        let subject = Self::subject(nats.metadata().subject_prefix());
+        let message = serde_json::to_vec(self).map_err(NatsError::Serialize)?;
+        nats.jetstream().publish(subject, message).await
+    }
+
+    /// Puts the job back on the job queue with a fresh set of attempts.
+    pub async fn replay(
+        self,
+        job_processor: &(dyn JobQueueProcessor + Send + Sync),
+    ) -> JobQueueProcessorResult<()> {
+        let mut job = self.job;
+        job.attempt = 0;
+        job.not_before = None;
+        // Whoever was blocking on the original job has long since been told it failed.
+        job.blocking = false;
+        job_processor.requeue_job(job).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::new(5, Duration::from_secs(2), Duration::from_secs(10));
+        assert_eq!(policy.backoff(0), Duration::from_secs(2));
+        assert_eq!(policy.backoff(1), Duration::from_secs(4));
+        assert_eq!(policy.backoff(2), Duration::from_secs(8));
+        assert_eq!(policy.backoff(3), Duration::from_secs(10));
+        assert_eq!(policy.backoff(40), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn only_retries_retryable_kinds_within_max_attempts() {
+        let policy = RetryPolicy::new(2, Duration::from_secs(1), Duration::from_secs(1))
+            .retry_on(JobErrorKind::Transport);
+
+        assert!(policy.should_retry(&JobConsumerError::NatsUnavailable, 0));
+        assert!(!policy.should_retry(&JobConsumerError::NatsUnavailable, 1));
+        assert!(!policy.should_retry(&JobConsumerError::CouncilProtocol("nope".to_string()), 0));
+        assert!(!RetryPolicy::never().should_retry(&JobConsumerError::NatsUnavailable, 0));
+    }
+}
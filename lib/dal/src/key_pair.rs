@@ -1,8 +1,12 @@
 use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
-use sodiumoxide::crypto::box_::{self, PublicKey as BoxPublicKey, SecretKey as BoxSecretKey};
+use sodiumoxide::crypto::{
+    box_::{self, PublicKey as BoxPublicKey, SecretKey as BoxSecretKey},
+    sealedbox,
+};
 use telemetry::prelude::*;
 use thiserror::Error;
 
@@ -13,6 +17,9 @@ use crate::{
 
 mod key_pair_box_public_key_serde;
 mod key_pair_box_secret_key_serde;
+mod rotation;
+
+pub use rotation::{KeyPairRotation, KeyPairRotationPk};
 
 const PUBLIC_KEY_GET_CURRENT: &str = include_str!("./queries/public_key_get_current.sql");
 const KEY_PAIR_GET_BY_PK: &str = include_str!("queries/key_pair_get_by_pk.sql");
@@ -28,6 +35,8 @@ pub enum KeyPairError {
     Nats(#[from] NatsError),
     #[error("no current key pair found when one was expected")]
     NoCurrentKeyPair,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("error serializing/deserializing json: {0}")]
@@ -52,6 +61,8 @@ pub struct KeyPair {
     #[serde(with = "key_pair_box_secret_key_serde")]
     secret_key: BoxSecretKey,
     created_lamport_clock: u64,
+    #[serde(default)]
+    retired_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -125,6 +136,19 @@ impl KeyPair {
     standard_model_accessor_ro!(public_key, BoxPublicKey);
     standard_model_accessor_ro!(secret_key, BoxSecretKey);
     standard_model_accessor_ro!(created_lamport_clock, u64);
+    standard_model_accessor_ro!(retired_at, Option<DateTime<Utc>>);
+
+    /// Whether this key pair was retired by a rotation and must not be used to seal new secrets.
+    pub fn is_retired(&self) -> bool {
+        self.retired_at.is_some()
+    }
+
+    /// Opens a payload sealed with this key pair and seals it again with the `target` key pair.
+    /// Returns `None` if the payload was not sealed with this key pair.
+    pub fn reseal(&self, crypted: &[u8], target: &KeyPair) -> Option<Vec<u8>> {
+        let message = sealedbox::open(crypted, &self.public_key, &self.secret_key).ok()?;
+        Some(sealedbox::seal(&message, &target.public_key))
+    }
 
     pub async fn workspace(&self, ctx: &DalContext) -> KeyPairResult<Workspace> {
         Workspace::get_by_pk(ctx, &self.workspace_pk)
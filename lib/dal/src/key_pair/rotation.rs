@@ -0,0 +1,286 @@
+//! Rotation of the [`KeyPair`](super::KeyPair) that a workspace's secrets are sealed with.
+//!
+//! A rotation generates a fresh key pair, re-seals every [`EncryptedSecret`](crate::EncryptedSecret)
+//! row of the workspace (in every change set) with it and finally retires the key pairs that are
+//! no longer in use. Progress is committed in batches and the in-flight rotation is recorded in
+//! the database, so a rotation interrupted by a crash picks up where it left off the next time
+//! [`KeyPair::rotate`](super::KeyPair::rotate) is called. It runs in the background as a
+//! [`KeyPairRotationJob`](crate::job::definition::KeyPairRotationJob), which is retried on
+//! failure.
+//!
+//! A row that can't be re-sealed (e.g. its payload is corrupt) is recorded as a failure and
+//! skipped, rather than stopping the rotation; the key pair it is sealed with stays in service.
+//!
+//! The cyclone [`EncryptionKey`](veritech_client::EncryptionKey) is out of scope: nothing is
+//! stored sealed with it, as secrets are decrypted and sealed for cyclone anew on every function
+//! execution, so replacing it is a matter of deploying a new key to sdf, pinga and cyclone.
+
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::{KeyPair, KeyPairError, KeyPairPk, KeyPairResult};
+use crate::{pk, secret::SecretPk, DalContext, HistoryEvent, WorkspacePk};
+
+const ROTATION_GET_UNFINISHED: &str =
+    include_str!("../queries/key_pair/rotation_get_unfinished.sql");
+const ROTATION_PENDING_SECRETS: &str =
+    include_str!("../queries/key_pair/rotation_pending_secrets.sql");
+
+/// How many secrets are re-sealed per committed batch.
+const RESEAL_BATCH_SIZE: i64 = 100;
+
+pk!(KeyPairRotationPk);
+
+/// The persisted state of a (possibly unfinished) key pair rotation.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyPairRotation {
+    pk: KeyPairRotationPk,
+    workspace_pk: WorkspacePk,
+    new_key_pair_pk: KeyPairPk,
+    reencrypted_secrets: u64,
+    failed_secrets: u64,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl KeyPairRotation {
+    pub fn pk(&self) -> KeyPairRotationPk {
+        self.pk
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    /// The key pair every secret of the workspace is sealed with once the rotation finishes.
+    pub fn new_key_pair_pk(&self) -> KeyPairPk {
+        self.new_key_pair_pk
+    }
+
+    /// How many encrypted secret rows have been re-sealed so far.
+    pub fn reencrypted_secrets(&self) -> u64 {
+        self.reencrypted_secrets
+    }
+
+    /// How many encrypted secret rows could not be re-sealed, and were left as they were.
+    pub fn failed_secrets(&self) -> u64 {
+        self.failed_secrets
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.finished_at
+    }
+
+    /// Returns the rotation currently in flight for the workspace of the [`DalContext`], if any.
+    pub async fn get_unfinished(ctx: &DalContext) -> KeyPairResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(ROTATION_GET_UNFINISHED, &[&ctx.tenancy().workspace_pk()])
+            .await?;
+        match maybe_row {
+            Some(row) => {
+                let json: serde_json::Value = row.try_get("object")?;
+                Ok(Some(serde_json::from_value(json)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn start(ctx: &DalContext) -> KeyPairResult<Self> {
+        let new_key_pair = KeyPair::new(ctx, "default").await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM key_pair_rotation_start_v1($1, $2)",
+                &[&ctx.tenancy().workspace_pk(), &new_key_pair.pk()],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        let rotation: Self = serde_json::from_value(json)?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "key_pair.rotation.start".to_owned(),
+            "Key Pair rotation started".to_owned(),
+            &serde_json::json![{
+                "rotation_pk": rotation.pk,
+                "workspace_pk": rotation.workspace_pk,
+                "new_key_pair_pk": rotation.new_key_pair_pk,
+            }],
+        )
+        .await?;
+
+        Ok(rotation)
+    }
+
+    async fn finish(self, ctx: &DalContext) -> KeyPairResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM key_pair_rotation_finish_v1($1)",
+                &[&self.pk],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        let rotation: Self = serde_json::from_value(json)?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "key_pair.rotation.finish".to_owned(),
+            "Key Pair rotation finished".to_owned(),
+            &serde_json::json![{
+                "rotation_pk": rotation.pk,
+                "workspace_pk": rotation.workspace_pk,
+                "new_key_pair_pk": rotation.new_key_pair_pk,
+                "reencrypted_secrets": rotation.reencrypted_secrets,
+                "failed_secrets": rotation.failed_secrets,
+            }],
+        )
+        .await?;
+
+        Ok(rotation)
+    }
+
+    async fn record_failure(
+        &self,
+        ctx: &DalContext,
+        secret_pk: SecretPk,
+        reason: impl AsRef<str>,
+    ) -> KeyPairResult<()> {
+        let reason = reason.as_ref();
+        warn!(rotation_pk = %self.pk, %secret_pk, %reason, "unable to re-seal secret");
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "SELECT key_pair_rotation_record_failure_v1($1, $2, $3)",
+                &[&self.pk, &secret_pk, &reason],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl KeyPair {
+    /// Starts rotating the key pair of the workspace in the [`DalContext`], or returns the
+    /// rotation already in flight. The re-sealing itself is done by [`Self::rotate`].
+    pub async fn start_rotation(ctx: &DalContext) -> KeyPairResult<KeyPairRotation> {
+        ctx.tenancy()
+            .workspace_pk()
+            .ok_or(KeyPairError::NoWorkspaceInTenancy)?;
+
+        match KeyPairRotation::get_unfinished(ctx).await? {
+            Some(rotation) => {
+                info!(rotation_pk = %rotation.pk, "resuming key pair rotation");
+                Ok(rotation)
+            }
+            None => KeyPairRotation::start(ctx).await,
+        }
+    }
+
+    /// Rotates the key pair of the workspace in the [`DalContext`]: a new key pair is generated,
+    /// every encrypted secret of the workspace is re-sealed with it and the key pairs left
+    /// unused are retired.
+    ///
+    /// If an earlier rotation was interrupted, it is resumed instead of starting a new one.
+    ///
+    /// **Note:** this commits the [`DalContext`] after every batch of re-sealed secrets.
+    #[instrument(skip_all)]
+    pub async fn rotate(ctx: &DalContext) -> KeyPairResult<KeyPairRotation> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(KeyPairError::NoWorkspaceInTenancy)?;
+
+        let rotation = Self::start_rotation(ctx).await?;
+        ctx.commit().await?;
+
+        let new_key_pair = Self::get_by_pk(ctx, rotation.new_key_pair_pk).await?;
+        let mut old_key_pairs: HashMap<KeyPairPk, Self> = HashMap::new();
+
+        loop {
+            let rows = ctx
+                .txns()
+                .await?
+                .pg()
+                .query(
+                    ROTATION_PENDING_SECRETS,
+                    &[
+                        &workspace_pk,
+                        &new_key_pair.pk,
+                        &rotation.pk,
+                        &RESEAL_BATCH_SIZE,
+                    ],
+                )
+                .await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            for row in rows {
+                let secret_pk: SecretPk = row.try_get("pk")?;
+                let crypted: String = row.try_get("crypted")?;
+                let old_key_pair_pk: KeyPairPk = row.try_get("key_pair_pk")?;
+
+                if !old_key_pairs.contains_key(&old_key_pair_pk) {
+                    let old_key_pair = Self::get_by_pk(ctx, old_key_pair_pk).await?;
+                    old_key_pairs.insert(old_key_pair_pk, old_key_pair);
+                }
+                let old_key_pair = &old_key_pairs[&old_key_pair_pk];
+
+                let crypted = match general_purpose::STANDARD_NO_PAD.decode(crypted) {
+                    Ok(crypted) => crypted,
+                    Err(err) => {
+                        rotation
+                            .record_failure(ctx, secret_pk, err.to_string())
+                            .await?;
+                        continue;
+                    }
+                };
+                let Some(resealed) = old_key_pair.reseal(&crypted, &new_key_pair) else {
+                    rotation
+                        .record_failure(ctx, secret_pk, "payload could not be opened")
+                        .await?;
+                    continue;
+                };
+
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .query_one(
+                        "SELECT resealed FROM encrypted_secret_reseal_v1($1, $2, $3, $4, $5)",
+                        &[
+                            &rotation.pk,
+                            &secret_pk,
+                            &old_key_pair_pk,
+                            &new_key_pair.pk,
+                            &general_purpose::STANDARD_NO_PAD.encode(resealed),
+                        ],
+                    )
+                    .await?;
+            }
+
+            ctx.commit().await?;
+        }
+
+        let rotation = rotation.finish(ctx).await?;
+        ctx.commit().await?;
+
+        Ok(rotation)
+    }
+}
@@ -60,6 +60,7 @@ pub mod status;
 pub mod tasks;
 pub mod tenancy;
 pub mod timestamp;
+pub mod undo;
 pub mod user;
 pub mod validation;
 pub mod visibility;
@@ -89,11 +90,20 @@ pub use attribute::{
     },
 };
 pub use builtins::{BuiltinsError, BuiltinsResult};
-pub use change_set::{ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus};
+pub use change_set::{
+    ChangeSet, ChangeSetConflict, ChangeSetConflictKind, ChangeSetDiff, ChangeSetError,
+    ChangeSetGarbageReport, ChangeSetPk, ChangeSetRebase, ChangeSetStatus, ConflictResolution,
+    ConflictingColumn,
+};
 pub use code_view::{CodeLanguage, CodeView};
 pub use component::{
-    resource::ResourceView, status::ComponentStatus, status::HistoryActorTimestamp, Component,
-    ComponentError, ComponentId, ComponentView, ComponentViewProperties,
+    resource::ResourceView,
+    status::ComponentStatus,
+    status::HistoryActorTimestamp,
+    template::{
+        ComponentTemplate, ComponentTemplateError, ComponentTemplateId, ComponentTemplateInstance,
+    },
+    Component, ComponentError, ComponentId, ComponentView, ComponentViewProperties,
 };
 pub use context::{
     AccessBuilder, Connections, DalContext, DalContextBuilder, RequestContext, ServicesContext,
@@ -116,13 +126,16 @@ pub use func::{
     binding::{FuncBinding, FuncBindingError, FuncBindingId},
     Func, FuncError, FuncId, FuncResult,
 };
-pub use history_event::{HistoryActor, HistoryEvent, HistoryEventError};
+pub use history_event::{
+    HistoryActor, HistoryEvent, HistoryEventError, HistoryEventFilter, HistoryEventRetention,
+    PendingAudit,
+};
 pub use index_map::IndexMap;
 pub use job::definition::DependentValuesUpdate;
-pub use job::processor::{JobQueueProcessor, NatsProcessor};
+pub use job::processor::{JetStreamProcessor, JobQueueProcessor, NatsProcessor};
 pub use job_failure::{JobFailure, JobFailureError, JobFailureResult};
 pub use jwt_key::JwtPublicSigningKey;
-pub use key_pair::{KeyPair, KeyPairError, KeyPairResult, PublicKey};
+pub use key_pair::{KeyPair, KeyPairError, KeyPairResult, KeyPairRotation, PublicKey};
 pub use label_list::{LabelEntry, LabelList, LabelListError};
 pub use node::NodeId;
 pub use node::{Node, NodeError, NodeKind};
@@ -148,8 +161,10 @@ pub use schema::variant::root_prop::RootPropChild;
 pub use schema::variant::SchemaVariantError;
 pub use schema::{Schema, SchemaError, SchemaId, SchemaPk, SchemaVariant, SchemaVariantId};
 pub use secret::{
-    DecryptedSecret, EncryptedSecret, Secret, SecretAlgorithm, SecretError, SecretId, SecretKind,
-    SecretObjectType, SecretPk, SecretResult, SecretVersion,
+    DecryptedSecret, EncryptedSecret, FileSecretBackend, PostgresSecretBackend, Secret,
+    SecretAlgorithm, SecretBackend, SecretBackendKind, SecretBackends, SecretBackendsConfig,
+    SecretError, SecretId, SecretKind, SecretKindDefinition, SecretKindDefinitionId,
+    SecretKindField, SecretObjectType, SecretPk, SecretResult, SecretUsage, SecretVersion,
 };
 pub use socket::{Socket, SocketArity, SocketId};
 pub use standard_model::{StandardModel, StandardModelError, StandardModelResult};
@@ -158,6 +173,10 @@ pub use status::{
 };
 pub use tenancy::{Tenancy, TenancyError};
 pub use timestamp::{Timestamp, TimestampError};
+pub use undo::{
+    AttributeValueSource, NodePosition, UndoEntry, UndoEntryPk, UndoError, UndoOperation,
+    UndoResult,
+};
 pub use user::{User, UserClaim, UserError, UserPk, UserResult};
 pub use validation::prototype::{
     context::ValidationPrototypeContext, ValidationPrototype, ValidationPrototypeError,
@@ -303,6 +322,7 @@ pub async fn migrate_builtins(
         Arc::new(*encryption_key),
         Some(pkgs_path),
         Some(module_index_url),
+        SecretBackends::default(),
     );
     let dal_context = services_context.into_builder(true);
     let mut ctx = dal_context.build_default().await?;
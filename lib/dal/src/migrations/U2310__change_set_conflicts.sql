@@ -0,0 +1,90 @@
+CREATE TABLE change_set_conflict_resolutions
+(
+    pk                   ident primary key default ident_create_v1(),
+    change_set_pk        ident                    NOT NULL,
+    tenancy_workspace_pk ident,
+    table_name           text                     NOT NULL,
+    object_id            text                     NOT NULL,
+    resolution           text                     NOT NULL,
+    resolved_at          timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON change_set_conflict_resolutions (change_set_pk, table_name, object_id);
+
+-- Every row of a change set whose head counterpart was written to after the change set took its
+-- copy of it, and which hasn't been resolved since. Whether the two actually disagree is left to
+-- the caller, which has an easier time comparing the objects column by column.
+CREATE OR REPLACE FUNCTION change_set_conflicts_v1(this_change_set_pk ident,
+                                                   this_tenancy jsonb)
+    RETURNS TABLE
+            (
+                conflict_table_name   text,
+                conflict_object_id    text,
+                change_set_object     jsonb,
+                head_object           jsonb
+            )
+AS
+$$
+DECLARE
+    standard_model standard_models%ROWTYPE;
+BEGIN
+    FOR standard_model IN SELECT * FROM standard_models
+        LOOP
+            RETURN QUERY EXECUTE format('SELECT %1$L::text, '
+                                        '       change_set_row.id::text, '
+                                        '       to_jsonb(change_set_row.*), '
+                                        '       to_jsonb(head_row.*) '
+                                        'FROM %1$I AS change_set_row '
+                                        'JOIN %1$I AS head_row '
+                                        '  ON head_row.id = change_set_row.id '
+                                        ' AND head_row.tenancy_workspace_pk IS NOT DISTINCT FROM change_set_row.tenancy_workspace_pk '
+                                        ' AND head_row.visibility_change_set_pk = ident_nil_v1() '
+                                        'WHERE change_set_row.visibility_change_set_pk = %2$L '
+                                        '  AND in_tenancy_v1(%3$L, change_set_row.tenancy_workspace_pk) '
+                                        '  AND head_row.updated_at > change_set_row.created_at '
+                                        '  AND NOT EXISTS ( '
+                                        '      SELECT 1 '
+                                        '      FROM change_set_conflict_resolutions AS resolution '
+                                        '      WHERE resolution.change_set_pk = %2$L '
+                                        '        AND resolution.table_name = %1$L '
+                                        '        AND resolution.object_id = change_set_row.id::text '
+                                        '        AND resolution.resolved_at >= head_row.updated_at '
+                                        '  ) '
+                                        'ORDER BY change_set_row.id',
+                                        standard_model.table_name, this_change_set_pk, this_tenancy);
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL STABLE;
+
+-- Resolves a conflict either by dropping the change set's row, so the change set sees head's
+-- version again ('TakeHead'), or by keeping it over head's version ('KeepChangeSet').
+CREATE OR REPLACE FUNCTION change_set_conflict_resolve_v1(this_change_set_pk ident,
+                                                          this_tenancy jsonb,
+                                                          this_table_name text,
+                                                          this_object_id text,
+                                                          this_resolution text)
+    RETURNS VOID
+AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    IF NOT EXISTS (SELECT 1 FROM standard_models WHERE table_name = this_table_name) THEN
+        RAISE EXCEPTION 'change_set_conflict_resolve_v1: % is not a standard model table', this_table_name;
+    END IF;
+
+    IF this_resolution = 'TakeHead' THEN
+        EXECUTE format('DELETE FROM %1$I '
+                       'WHERE id = %2$L '
+                       '  AND visibility_change_set_pk = %3$L '
+                       '  AND in_tenancy_v1(%4$L, tenancy_workspace_pk)',
+                       this_table_name, this_object_id, this_change_set_pk, this_tenancy);
+    ELSIF this_resolution != 'KeepChangeSet' THEN
+        RAISE EXCEPTION 'change_set_conflict_resolve_v1: unknown resolution %', this_resolution;
+    END IF;
+
+    INSERT INTO change_set_conflict_resolutions (change_set_pk, tenancy_workspace_pk, table_name, object_id, resolution)
+    VALUES (this_change_set_pk, this_tenancy_record.tenancy_workspace_pk, this_table_name, this_object_id, this_resolution);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,105 @@
+ALTER TABLE change_sets
+    ADD COLUMN expires_at timestamp with time zone,
+    ADD COLUMN purged_at  timestamp with time zone;
+
+CREATE OR REPLACE FUNCTION change_set_abandon_v1(this_change_set_pk ident,
+                                                 this_tenancy jsonb,
+                                                 OUT timestamp_updated_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET status     = 'Abandoned',
+        updated_at = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND status = 'Open'
+      AND in_tenancy_v1(this_tenancy, change_sets.tenancy_workspace_pk)
+    RETURNING updated_at INTO timestamp_updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_update_expires_at_v1(this_change_set_pk ident,
+                                                           this_tenancy jsonb,
+                                                           this_expires_at timestamp with time zone,
+                                                           OUT timestamp_updated_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET expires_at = this_expires_at,
+        updated_at = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND status = 'Open'
+      AND in_tenancy_v1(this_tenancy, change_sets.tenancy_workspace_pk)
+    RETURNING updated_at INTO timestamp_updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Abandons every open change set, in any workspace, whose time to live has run out.
+CREATE OR REPLACE FUNCTION change_set_expire_v1()
+    RETURNS TABLE
+            (
+                expired_change_set_pk        ident,
+                expired_tenancy_workspace_pk ident
+            )
+AS
+$$
+BEGIN
+    RETURN QUERY UPDATE change_sets
+        SET status     = 'Abandoned',
+            updated_at = clock_timestamp()
+        WHERE status = 'Open'
+          AND expires_at <= clock_timestamp()
+        RETURNING pk, tenancy_workspace_pk;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Deletes the rows abandoned change sets wrote to every standard model table, returning how many
+-- rows were deleted from each table that had any. Change sets are only purged once.
+CREATE OR REPLACE FUNCTION change_set_purge_abandoned_v1()
+    RETURNS TABLE
+            (
+                reclaimed_table_name text,
+                reclaimed_rows       bigint
+            )
+AS
+$$
+DECLARE
+    standard_model           standard_models%ROWTYPE;
+    abandoned_change_set_pks ident[];
+    deleted_rows             bigint;
+BEGIN
+    SELECT array_agg(pk)
+    INTO abandoned_change_set_pks
+    FROM change_sets
+    WHERE status = 'Abandoned'
+      AND purged_at IS NULL;
+
+    IF abandoned_change_set_pks IS NULL THEN
+        RETURN;
+    END IF;
+
+    FOR standard_model IN SELECT * FROM standard_models
+        LOOP
+            EXECUTE format('DELETE FROM %1$I WHERE visibility_change_set_pk = ANY($1)',
+                           standard_model.table_name)
+                USING abandoned_change_set_pks;
+            GET DIAGNOSTICS deleted_rows = ROW_COUNT;
+            IF deleted_rows > 0 THEN
+                reclaimed_table_name := standard_model.table_name;
+                reclaimed_rows := deleted_rows;
+                RETURN NEXT;
+            END IF;
+        END LOOP;
+
+    DELETE FROM change_set_conflict_resolutions WHERE change_set_pk = ANY (abandoned_change_set_pks);
+    GET DIAGNOSTICS deleted_rows = ROW_COUNT;
+    IF deleted_rows > 0 THEN
+        reclaimed_table_name := 'change_set_conflict_resolutions';
+        reclaimed_rows := deleted_rows;
+        RETURN NEXT;
+    END IF;
+
+    UPDATE change_sets
+    SET purged_at = clock_timestamp()
+    WHERE pk = ANY (abandoned_change_set_pks);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
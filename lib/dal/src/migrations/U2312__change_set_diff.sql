@@ -0,0 +1,63 @@
+-- Every row a change set wrote to the given standard model table, next to head's version of it if
+-- head has one.
+CREATE OR REPLACE FUNCTION change_set_object_diffs_v1(this_table_name text,
+                                                      this_change_set_pk ident,
+                                                      this_tenancy jsonb)
+    RETURNS TABLE
+            (
+                object_id         ident,
+                change_set_object jsonb,
+                head_object       jsonb
+            )
+AS
+$$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM standard_models WHERE table_name = this_table_name) THEN
+        RAISE EXCEPTION 'change_set_object_diffs_v1: % is not a standard model table', this_table_name;
+    END IF;
+
+    RETURN QUERY EXECUTE format('SELECT change_set_row.id, '
+                                '       to_jsonb(change_set_row.*), '
+                                '       to_jsonb(head_row.*) '
+                                'FROM %1$I AS change_set_row '
+                                'LEFT JOIN %1$I AS head_row '
+                                '  ON head_row.id = change_set_row.id '
+                                ' AND head_row.tenancy_workspace_pk IS NOT DISTINCT FROM change_set_row.tenancy_workspace_pk '
+                                ' AND head_row.visibility_change_set_pk = ident_nil_v1() '
+                                ' AND head_row.visibility_deleted_at IS NULL '
+                                'WHERE change_set_row.visibility_change_set_pk = %2$L '
+                                '  AND in_tenancy_v1(%3$L, change_set_row.tenancy_workspace_pk) '
+                                'ORDER BY change_set_row.id',
+                                this_table_name, this_change_set_pk, this_tenancy);
+END;
+$$ LANGUAGE PLPGSQL STABLE;
+
+-- How many rows a change set wrote to each standard model table it touched.
+CREATE OR REPLACE FUNCTION change_set_touched_tables_v1(this_change_set_pk ident,
+                                                        this_tenancy jsonb)
+    RETURNS TABLE
+            (
+                touched_table_name text,
+                touched_rows       bigint
+            )
+AS
+$$
+DECLARE
+    standard_model standard_models%ROWTYPE;
+    row_count      bigint;
+BEGIN
+    FOR standard_model IN SELECT * FROM standard_models
+        LOOP
+            EXECUTE format('SELECT count(*) FROM %1$I '
+                           'WHERE visibility_change_set_pk = %2$L '
+                           '  AND in_tenancy_v1(%3$L, tenancy_workspace_pk)',
+                           standard_model.table_name, this_change_set_pk, this_tenancy)
+                INTO row_count;
+            IF row_count > 0 THEN
+                touched_table_name := standard_model.table_name;
+                touched_rows := row_count;
+                RETURN NEXT;
+            END IF;
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL STABLE;
@@ -0,0 +1,44 @@
+ALTER TABLE history_events
+    ADD COLUMN visibility_change_set_pk ident;
+
+CREATE INDEX history_events_tenancy_created_at ON history_events (tenancy_workspace_pk, created_at);
+CREATE INDEX history_events_label ON history_events (label text_pattern_ops);
+CREATE INDEX history_events_actor ON history_events USING GIN (actor jsonb_path_ops);
+
+CREATE OR REPLACE FUNCTION history_event_create_v2(this_label text,
+                                                   this_actor jsonb,
+                                                   this_message text,
+                                                   this_data jsonb,
+                                                   this_tenancy jsonb,
+                                                   this_change_set_pk ident,
+                                                   OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        history_events%ROWTYPE;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+    INSERT INTO history_events (label, actor, message, data, tenancy_workspace_pk, visibility_change_set_pk)
+    VALUES (this_label, this_actor, this_message, this_data, this_tenancy_record.tenancy_workspace_pk,
+            this_change_set_pk)
+    RETURNING * INTO this_new_row;
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Deletes history events that are older than their workspace's maximum age. `this_max_ages` maps
+-- workspace pks to intervals, and `this_default_max_age` applies to every other workspace. Events
+-- without a maximum age are kept.
+CREATE OR REPLACE FUNCTION history_event_purge_v1(this_default_max_age text,
+                                                  this_max_ages jsonb,
+                                                  OUT purged bigint) AS
+$$
+BEGIN
+    DELETE
+    FROM history_events
+    WHERE created_at < clock_timestamp() -
+                       COALESCE((this_max_ages ->> tenancy_workspace_pk)::interval,
+                                this_default_max_age::interval);
+    GET DIAGNOSTICS purged = ROW_COUNT;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,86 @@
+ALTER TABLE key_pairs
+    ADD COLUMN retired_at timestamp with time zone;
+
+CREATE TABLE key_pair_rotations
+(
+    pk                  ident primary key default ident_create_v1(),
+    workspace_pk        ident                    NOT NULL,
+    new_key_pair_pk     ident                    NOT NULL,
+    reencrypted_secrets bigint                   NOT NULL DEFAULT 0,
+    started_at          timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    finished_at         timestamp with time zone
+);
+CREATE UNIQUE INDEX ON key_pair_rotations (pk);
+-- Only one rotation may be in flight per workspace at a time
+CREATE UNIQUE INDEX ON key_pair_rotations (workspace_pk) WHERE finished_at IS NULL;
+
+CREATE OR REPLACE FUNCTION key_pair_rotation_start_v1(
+    this_workspace_pk ident,
+    this_new_key_pair_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row key_pair_rotations%ROWTYPE;
+BEGIN
+    INSERT INTO key_pair_rotations (workspace_pk, new_key_pair_pk)
+    VALUES (this_workspace_pk, this_new_key_pair_pk)
+    RETURNING * INTO this_new_row;
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Swaps the crypted payload of a single encrypted secret row, in any change set, over to a new
+-- key pair. The swap only happens if the row is still sealed with the key pair the caller
+-- decrypted it with, so a resumed rotation never seals a payload twice. The row's "updated_at" is
+-- left alone on purpose: re-encryption is not a user change and must not surface as a change set
+-- conflict.
+CREATE OR REPLACE FUNCTION encrypted_secret_reseal_v1(
+    this_rotation_pk ident,
+    this_pk ident,
+    this_old_key_pair_pk ident,
+    this_new_key_pair_pk ident,
+    this_crypted text,
+    OUT resealed bool) AS
+$$
+BEGIN
+    UPDATE encrypted_secrets
+    SET crypted     = this_crypted,
+        key_pair_pk = this_new_key_pair_pk
+    WHERE pk = this_pk
+      AND key_pair_pk = this_old_key_pair_pk;
+    resealed := FOUND;
+
+    IF resealed THEN
+        UPDATE key_pair_rotations
+        SET reencrypted_secrets = reencrypted_secrets + 1
+        WHERE pk = this_rotation_pk;
+    END IF;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Retires every other key pair of the workspace that no encrypted secret is sealed with anymore
+-- and marks the rotation as finished.
+CREATE OR REPLACE FUNCTION key_pair_rotation_finish_v1(
+    this_rotation_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_rotation key_pair_rotations%ROWTYPE;
+BEGIN
+    SELECT * INTO this_rotation FROM key_pair_rotations WHERE pk = this_rotation_pk;
+
+    UPDATE key_pairs
+    SET retired_at = clock_timestamp(),
+        updated_at = clock_timestamp()
+    WHERE workspace_pk = this_rotation.workspace_pk
+      AND pk != this_rotation.new_key_pair_pk
+      AND retired_at IS NULL
+      AND NOT EXISTS(SELECT 1 FROM encrypted_secrets WHERE encrypted_secrets.key_pair_pk = key_pairs.pk);
+
+    UPDATE key_pair_rotations
+    SET finished_at = clock_timestamp()
+    WHERE pk = this_rotation_pk
+    RETURNING * INTO this_rotation;
+    object := row_to_json(this_rotation);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,92 @@
+-- Secrets held by an external backend only store a reference to their material in SI
+ALTER TABLE encrypted_secrets
+    ADD COLUMN backend           text NOT NULL DEFAULT 'postgres',
+    ADD COLUMN backend_reference text;
+
+CREATE OR REPLACE FUNCTION encrypted_secret_create_v2(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_name text,
+    this_object_type text,
+    this_kind text,
+    this_crypted text,
+    this_version text,
+    this_algorithm text,
+    this_key_pair_pk ident,
+    this_backend text,
+    this_backend_reference text,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           encrypted_secrets%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO encrypted_secrets (tenancy_workspace_pk,
+                                   visibility_change_set_pk,
+                                   name,
+                                   object_type,
+                                   kind,
+                                   crypted,
+                                   version,
+                                   algorithm,
+                                   key_pair_pk,
+                                   backend,
+                                   backend_reference)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_name,
+            this_object_type,
+            this_kind,
+            this_crypted,
+            this_version,
+            this_algorithm,
+            this_key_pair_pk,
+            this_backend,
+            this_backend_reference)
+    RETURNING * INTO this_new_row;
+
+    -- Purge the returning record of sensitive data to avoid accidentally
+    -- deserializing these fields in application code
+    this_new_row.crypted = null;
+    this_new_row.version = null;
+    this_new_row.algorithm = null;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Secrets held by an external backend have no crypted payload to re-seal during a key pair
+-- rotation, so they are simply moved over to the new key pair when it finishes.
+CREATE OR REPLACE FUNCTION key_pair_rotation_finish_v1(
+    this_rotation_pk ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_rotation key_pair_rotations%ROWTYPE;
+BEGIN
+    SELECT * INTO this_rotation FROM key_pair_rotations WHERE pk = this_rotation_pk;
+
+    UPDATE encrypted_secrets
+    SET key_pair_pk = this_rotation.new_key_pair_pk
+    WHERE tenancy_workspace_pk = this_rotation.workspace_pk
+      AND backend != 'postgres';
+
+    UPDATE key_pairs
+    SET retired_at = clock_timestamp(),
+        updated_at = clock_timestamp()
+    WHERE workspace_pk = this_rotation.workspace_pk
+      AND pk != this_rotation.new_key_pair_pk
+      AND retired_at IS NULL
+      AND NOT EXISTS(SELECT 1 FROM encrypted_secrets WHERE encrypted_secrets.key_pair_pk = key_pairs.pk);
+
+    UPDATE key_pair_rotations
+    SET finished_at = clock_timestamp()
+    WHERE pk = this_rotation_pk
+    RETURNING * INTO this_rotation;
+    object := row_to_json(this_rotation);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
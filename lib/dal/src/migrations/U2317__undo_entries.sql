@@ -0,0 +1,148 @@
+CREATE TABLE undo_entries
+(
+    pk            ident primary key default ident_create_v1(),
+    change_set_pk ident                    NOT NULL,
+    -- NULL for edits made by the system rather than by a user
+    user_pk       ident,
+    operations    jsonb                    NOT NULL,
+    undone        bool                     NOT NULL DEFAULT false,
+    created_at    timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at    timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE UNIQUE INDEX ON undo_entries (pk);
+CREATE INDEX ON undo_entries (change_set_pk, user_pk, undone, updated_at);
+
+-- Records a new entry on top of the undo stack of a user in a change set. Recording a new edit
+-- clears the redo stack and only the most recent "this_depth" entries are kept.
+CREATE OR REPLACE FUNCTION undo_entry_record_v1(
+    this_change_set_pk ident,
+    this_user_pk ident,
+    this_operations jsonb,
+    this_depth bigint,
+    OUT object json) AS
+$$
+DECLARE
+    this_new_row undo_entries%ROWTYPE;
+BEGIN
+    DELETE
+    FROM undo_entries
+    WHERE change_set_pk = this_change_set_pk
+      AND user_pk IS NOT DISTINCT FROM this_user_pk
+      AND undone;
+
+    INSERT INTO undo_entries (change_set_pk, user_pk, operations)
+    VALUES (this_change_set_pk, this_user_pk, this_operations)
+    RETURNING * INTO this_new_row;
+
+    DELETE
+    FROM undo_entries
+    WHERE pk IN (SELECT pk
+                 FROM undo_entries
+                 WHERE change_set_pk = this_change_set_pk
+                   AND user_pk IS NOT DISTINCT FROM this_user_pk
+                 ORDER BY updated_at DESC, pk DESC
+                 OFFSET this_depth);
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Returns, and locks, the top entry of the undo stack (when "this_undone" is false) or of the redo
+-- stack (when it is true) of a user in a change set.
+CREATE OR REPLACE FUNCTION undo_entry_top_v1(
+    this_change_set_pk ident,
+    this_user_pk ident,
+    this_undone bool,
+    OUT object json) AS
+$$
+BEGIN
+    SELECT row_to_json(undo_entries.*)
+    INTO object
+    FROM undo_entries
+    WHERE change_set_pk = this_change_set_pk
+      AND user_pk IS NOT DISTINCT FROM this_user_pk
+      AND undone = this_undone
+    ORDER BY updated_at DESC, pk DESC
+    LIMIT 1
+    FOR UPDATE;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Moves an entry over to the other stack, keeping the operations as they were replayed.
+CREATE OR REPLACE FUNCTION undo_entry_set_undone_v1(
+    this_pk ident,
+    this_undone bool,
+    this_operations jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row undo_entries%ROWTYPE;
+BEGIN
+    UPDATE undo_entries
+    SET undone     = this_undone,
+        operations = this_operations,
+        updated_at = clock_timestamp()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Undo stacks of abandoned change sets are reclaimed along with their rows
+CREATE OR REPLACE FUNCTION change_set_purge_abandoned_v1()
+    RETURNS TABLE
+            (
+                reclaimed_table_name text,
+                reclaimed_rows       bigint
+            )
+AS
+$$
+DECLARE
+    standard_model           standard_models%ROWTYPE;
+    abandoned_change_set_pks ident[];
+    deleted_rows             bigint;
+BEGIN
+    SELECT array_agg(pk)
+    INTO abandoned_change_set_pks
+    FROM change_sets
+    WHERE status = 'Abandoned'
+      AND purged_at IS NULL;
+
+    IF abandoned_change_set_pks IS NULL THEN
+        RETURN;
+    END IF;
+
+    FOR standard_model IN SELECT * FROM standard_models
+        LOOP
+            EXECUTE format('DELETE FROM %1$I WHERE visibility_change_set_pk = ANY($1)',
+                           standard_model.table_name)
+                USING abandoned_change_set_pks;
+            GET DIAGNOSTICS deleted_rows = ROW_COUNT;
+            IF deleted_rows > 0 THEN
+                reclaimed_table_name := standard_model.table_name;
+                reclaimed_rows := deleted_rows;
+                RETURN NEXT;
+            END IF;
+        END LOOP;
+
+    DELETE FROM change_set_conflict_resolutions WHERE change_set_pk = ANY (abandoned_change_set_pks);
+    GET DIAGNOSTICS deleted_rows = ROW_COUNT;
+    IF deleted_rows > 0 THEN
+        reclaimed_table_name := 'change_set_conflict_resolutions';
+        reclaimed_rows := deleted_rows;
+        RETURN NEXT;
+    END IF;
+
+    DELETE FROM undo_entries WHERE change_set_pk = ANY (abandoned_change_set_pks);
+    GET DIAGNOSTICS deleted_rows = ROW_COUNT;
+    IF deleted_rows > 0 THEN
+        reclaimed_table_name := 'undo_entries';
+        reclaimed_rows := deleted_rows;
+        RETURN NEXT;
+    END IF;
+
+    UPDATE change_sets
+    SET purged_at = clock_timestamp()
+    WHERE pk = ANY (abandoned_change_set_pks);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,59 @@
+CREATE TABLE component_templates
+(
+    pk                          ident primary key                 default ident_create_v1(),
+    id                          ident                    not null default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    visibility_change_set_pk    ident                    NOT NULL DEFAULT ident_nil_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+
+    name                        text                     NOT NULL,
+    description                 text,
+    components                  jsonb                    NOT NULL DEFAULT '[]'::jsonb,
+    edges                       jsonb                    NOT NULL DEFAULT '[]'::jsonb
+);
+
+CREATE UNIQUE INDEX unique_component_templates
+    ON component_templates (name,
+                            tenancy_workspace_pk,
+                            visibility_change_set_pk);
+
+SELECT standard_model_table_constraints_v1('component_templates');
+INSERT INTO standard_models (table_name, table_type, history_event_label_base, history_event_message_name)
+VALUES ('component_templates', 'model', 'component_template', 'Component Template');
+
+CREATE OR REPLACE FUNCTION component_template_create_v1(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_name text,
+    this_description text,
+    this_components jsonb,
+    this_edges jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           component_templates%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO component_templates (tenancy_workspace_pk,
+                                     visibility_change_set_pk,
+                                     name,
+                                     description,
+                                     components,
+                                     edges)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_name,
+            this_description,
+            this_components,
+            this_edges)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
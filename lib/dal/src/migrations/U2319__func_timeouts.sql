@@ -0,0 +1,3 @@
+-- Seconds an execution of the func may run for, capped by the timeout cyclone is configured with.
+-- NULL leaves the execution to the cyclone timeout alone.
+ALTER TABLE funcs ADD COLUMN timeout_secs bigint;
@@ -0,0 +1,3 @@
+-- Whether the results of the func can be reused for an execution with the same code and arguments.
+-- Funcs reading anything besides their arguments (e.g. the clock or the network) must opt out.
+ALTER TABLE funcs ADD COLUMN memoizable bool NOT NULL DEFAULT true;
@@ -0,0 +1,3 @@
+-- Network access given to executions of the func. Only action and reconciliation funcs are ever
+-- allowed to reach the network; every other kind of func runs without it, whatever this says.
+ALTER TABLE funcs ADD COLUMN egress_policy text NOT NULL DEFAULT 'allow';
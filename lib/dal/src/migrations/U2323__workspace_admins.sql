@@ -0,0 +1,28 @@
+ALTER TABLE user_belongs_to_workspaces
+    ADD COLUMN is_admin boolean NOT NULL DEFAULT false;
+
+CREATE OR REPLACE FUNCTION user_grant_workspace_admin_v1(
+    this_user_pk ident,
+    this_workspace_pk ident
+    ) RETURNS void AS
+$$
+BEGIN
+    INSERT INTO user_belongs_to_workspaces (user_pk, workspace_pk, is_admin)
+        VALUES (this_user_pk, this_workspace_pk, true)
+        ON CONFLICT (user_pk, workspace_pk) DO UPDATE SET is_admin   = true,
+                                                          updated_at = clock_timestamp();
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION user_is_workspace_admin_v1(
+    this_user_pk ident,
+    this_workspace_pk ident
+    ) RETURNS bool AS
+$$
+SELECT EXISTS(SELECT 1
+              FROM user_belongs_to_workspaces
+              WHERE user_pk = this_user_pk
+                AND workspace_pk = this_workspace_pk
+                AND visibility_deleted_at IS NULL
+                AND is_admin)
+$$ LANGUAGE SQL STABLE;
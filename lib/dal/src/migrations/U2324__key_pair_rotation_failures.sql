@@ -0,0 +1,32 @@
+ALTER TABLE key_pair_rotations
+    ADD COLUMN failed_secrets bigint NOT NULL DEFAULT 0;
+
+-- Encrypted secret rows a rotation could not re-seal. They are skipped for the rest of the
+-- rotation, keep their key pair from being retired, and are tried again by the next rotation.
+CREATE TABLE key_pair_rotation_failures
+(
+    rotation_pk ident                    NOT NULL,
+    secret_pk   ident                    NOT NULL,
+    reason      text                     NOT NULL,
+    failed_at   timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (rotation_pk, secret_pk)
+);
+
+CREATE OR REPLACE FUNCTION key_pair_rotation_record_failure_v1(
+    this_rotation_pk ident,
+    this_secret_pk ident,
+    this_reason text
+    ) RETURNS void AS
+$$
+BEGIN
+    INSERT INTO key_pair_rotation_failures (rotation_pk, secret_pk, reason)
+    VALUES (this_rotation_pk, this_secret_pk, this_reason)
+    ON CONFLICT DO NOTHING;
+
+    IF FOUND THEN
+        UPDATE key_pair_rotations
+        SET failed_secrets = failed_secrets + 1
+        WHERE pk = this_rotation_pk;
+    END IF;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,17 @@
+-- Rewrites an id in the operations of every undo entry of a change set, for when redoing an edit
+-- creates a new object in place of the one undone. Ids are ULIDs, so the quoted id can't match
+-- anything else. The stacks are ordered by "updated_at", which is left as it is.
+CREATE OR REPLACE FUNCTION undo_entry_replace_id_v1(
+    this_change_set_pk ident,
+    this_old_id text,
+    this_new_id text) RETURNS VOID AS
+$$
+BEGIN
+    UPDATE undo_entries
+    SET operations = replace(operations::text,
+                             '"' || this_old_id || '"',
+                             '"' || this_new_id || '"')::jsonb
+    WHERE change_set_pk = this_change_set_pk
+      AND operations::text LIKE '%"' || this_old_id || '"%';
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
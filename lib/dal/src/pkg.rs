@@ -8,7 +8,10 @@ pub use export::export_pkg_as_bytes;
 pub use export::get_component_type;
 pub use import::{import_pkg, import_pkg_from_pkg, ImportOptions};
 
-use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
+use si_pkg::{
+    FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecEgressPolicy, SiPkgError, SpecError,
+};
+use veritech_client::EgressPolicy;
 
 use crate::schema::variant::definition::SchemaVariantDefinitionId;
 use crate::{
@@ -22,10 +25,11 @@ use crate::{
     socket::SocketError,
     ActionPrototypeError, AttributeContextBuilderError, AttributePrototypeArgumentError,
     AttributePrototypeArgumentId, AttributePrototypeError, AttributePrototypeId,
-    AttributeReadContext, AttributeValueError, ExternalProviderError, ExternalProviderId,
-    FuncBackendKind, FuncBackendResponseType, FuncError, FuncId, InternalProviderError,
-    InternalProviderId, PropError, PropId, PropKind, SchemaError, SchemaId, SchemaVariantError,
-    SchemaVariantId, StandardModelError, ValidationPrototypeError,
+    AttributeReadContext, AttributeValueError, ComponentTemplateError, ComponentTemplateId,
+    ExternalProviderError, ExternalProviderId, FuncBackendKind, FuncBackendResponseType, FuncError,
+    FuncId, InternalProviderError, InternalProviderId, PropError, PropId, PropKind, SchemaError,
+    SchemaId, SchemaVariantError, SchemaVariantId, SecretError, StandardModelError,
+    ValidationPrototypeError,
 };
 
 #[remain::sorted]
@@ -57,6 +61,10 @@ pub enum PkgError {
     ),
     #[error(transparent)]
     AttributeValue(#[from] AttributeValueError),
+    #[error(transparent)]
+    ComponentTemplate(#[from] ComponentTemplateError),
+    #[error("component template {0} not found")]
+    ComponentTemplateNotFound(ComponentTemplateId),
     #[error("map item prop {0} has both custom key prototypes and custom prop only prototype")]
     ConflictingMapKeyPrototypes(PropId),
     #[error("Cannot find Socket for explicit InternalProvider {0}")]
@@ -135,6 +143,8 @@ pub enum PkgError {
     SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
     #[error("schema variant not found: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
     #[error("json serialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -252,3 +262,21 @@ impl From<FuncSpecBackendResponseType> for FuncBackendResponseType {
         }
     }
 }
+
+impl From<EgressPolicy> for FuncSpecEgressPolicy {
+    fn from(value: EgressPolicy) -> Self {
+        match value {
+            EgressPolicy::Allow => Self::Allow,
+            EgressPolicy::Deny => Self::Deny,
+        }
+    }
+}
+
+impl From<FuncSpecEgressPolicy> for EgressPolicy {
+    fn from(value: FuncSpecEgressPolicy) -> Self {
+        match value {
+            FuncSpecEgressPolicy::Allow => Self::Allow,
+            FuncSpecEgressPolicy::Deny => Self::Deny,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{hash_map::Entry, HashMap};
 use strum::IntoEnumIterator;
 use telemetry::prelude::*;
+use veritech_client::EgressPolicy;
 
 use si_pkg::{
     ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, FuncArgumentSpec,
@@ -18,11 +19,11 @@ use crate::{
     socket::SocketKind,
     validation::Validation,
     ActionPrototype, ActionPrototypeContext, AttributeContextBuilder, AttributePrototype,
-    AttributePrototypeArgument, AttributeReadContext, AttributeValue, ComponentType, DalContext,
-    ExternalProvider, ExternalProviderId, Func, FuncDescription, FuncId, InternalProvider,
-    InternalProviderId, LeafInputLocation, LeafKind, Prop, PropId, PropKind, Schema, SchemaVariant,
-    SchemaVariantError, SchemaVariantId, Socket, StandardModel, StandardModelError,
-    ValidationPrototype,
+    AttributePrototypeArgument, AttributeReadContext, AttributeValue, ComponentTemplate,
+    ComponentTemplateId, ComponentType, DalContext, ExternalProvider, ExternalProviderId, Func,
+    FuncDescription, FuncId, InternalProvider, InternalProviderId, LeafInputLocation, LeafKind,
+    Prop, PropId, PropKind, Schema, SchemaVariant, SchemaVariantError, SchemaVariantId, Socket,
+    StandardModel, StandardModelError, ValidationPrototype,
 };
 
 use super::{PkgError, PkgResult};
@@ -36,9 +37,19 @@ pub async fn export_pkg_as_bytes(
     description: Option<impl Into<String>>,
     created_by: impl Into<String>,
     variant_ids: Vec<SchemaVariantId>,
+    template_ids: Vec<ComponentTemplateId>,
 ) -> PkgResult<Vec<u8>> {
     info!("Building module package");
-    let pkg = build_pkg(ctx, name, version, description, created_by, variant_ids).await?;
+    let pkg = build_pkg(
+        ctx,
+        name,
+        version,
+        description,
+        created_by,
+        variant_ids,
+        template_ids,
+    )
+    .await?;
     info!("Exporting as bytes");
 
     Ok(pkg.write_to_bytes()?)
@@ -51,6 +62,7 @@ async fn build_pkg(
     description: Option<impl Into<String>>,
     created_by: impl Into<String>,
     variant_ids: Vec<SchemaVariantId>,
+    template_ids: Vec<ComponentTemplateId>,
 ) -> PkgResult<SiPkg> {
     let mut pkg_spec_builder = PkgSpec::builder();
     pkg_spec_builder
@@ -89,6 +101,13 @@ async fn build_pkg(
         pkg_spec_builder.schema(schema_spec);
     }
 
+    for template_id in template_ids {
+        let template = ComponentTemplate::get_by_id(ctx, &template_id)
+            .await?
+            .ok_or(PkgError::ComponentTemplateNotFound(template_id))?;
+        pkg_spec_builder.template(template.to_spec()?);
+    }
+
     let spec = pkg_spec_builder.build()?;
 
     let pkg = SiPkg::load_from_spec(spec)?;
@@ -120,6 +139,18 @@ fn build_func_spec(func: &Func, args: &[FuncArgument]) -> PkgResult<FuncSpec> {
     func_spec_builder.backend_kind(*func.backend_kind());
 
     func_spec_builder.hidden(func.hidden());
+    func_spec_builder.memoizable(func.memoizable());
+    // Only written when not the default, like the other optional func settings
+    if *func.egress_policy() != EgressPolicy::default() {
+        func_spec_builder.egress_policy(*func.egress_policy());
+    }
+
+    if let Some(timeout_secs) = func
+        .timeout_secs()
+        .and_then(|timeout_secs| u64::try_from(*timeout_secs).ok())
+    {
+        func_spec_builder.timeout_secs(timeout_secs);
+    }
 
     for arg in args {
         func_spec_builder.argument(
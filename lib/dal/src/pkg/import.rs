@@ -1,11 +1,13 @@
 use std::path::Path;
 use telemetry::prelude::*;
 use tokio::sync::Mutex;
+use veritech_client::EgressPolicy;
 
 use si_pkg::{
     FuncUniqueId, SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView,
     SiPkgError, SiPkgFunc, SiPkgFuncDescription, SiPkgLeafFunction, SiPkgProp, SiPkgSchema,
-    SiPkgSchemaVariant, SiPkgSocket, SiPkgValidation, SocketSpecKind,
+    SiPkgSchemaVariant, SiPkgSecretKind, SiPkgSocket, SiPkgValidation, SocketSpecKind,
+    TemplateSpec,
 };
 
 use crate::{
@@ -24,10 +26,11 @@ use crate::{
     },
     validation::{create_validation, Validation, ValidationKind},
     ActionPrototype, ActionPrototypeContext, AttributeContextBuilder, AttributePrototypeArgument,
-    AttributeReadContext, AttributeValue, AttributeValueError, DalContext, ExternalProvider,
-    ExternalProviderId, Func, FuncArgument, FuncDescription, FuncDescriptionContents, FuncError,
-    FuncId, InternalProvider, Prop, PropId, PropKind, Schema, SchemaId, SchemaVariant,
-    SchemaVariantError, SchemaVariantId, StandardModel,
+    AttributeReadContext, AttributeValue, AttributeValueError, ComponentTemplate, DalContext,
+    ExternalProvider, ExternalProviderId, Func, FuncArgument, FuncDescription,
+    FuncDescriptionContents, FuncError, FuncId, InternalProvider, Prop, PropId, PropKind, Schema,
+    SchemaId, SchemaVariant, SchemaVariantError, SchemaVariantId, SecretKindDefinition,
+    SecretKindField, StandardModel,
 };
 
 use super::{PkgError, PkgResult};
@@ -106,6 +109,35 @@ pub async fn import_pkg_from_pkg(
         funcs_by_unique_id.insert(unique_id, func);
     }
 
+    for secret_kind_spec in pkg.secret_kinds()? {
+        info!(
+            "installing secret kind '{}' from {}",
+            secret_kind_spec.name(),
+            file_name
+        );
+        import_secret_kind(ctx, secret_kind_spec).await?;
+    }
+
+    for template_spec in pkg.templates()? {
+        if ComponentTemplate::find_by_name(ctx, template_spec.name())
+            .await?
+            .is_some()
+        {
+            info!(
+                "skipping component template '{}' from {}, one with the same name exists",
+                template_spec.name(),
+                file_name
+            );
+            continue;
+        }
+        info!(
+            "installing component template '{}' from {}",
+            template_spec.name(),
+            file_name
+        );
+        ComponentTemplate::new_from_spec(ctx, TemplateSpec::try_from(template_spec)?).await?;
+    }
+
     let mut installed_schema_variant_ids = vec![];
 
     for schema_spec in pkg.schemas()? {
@@ -133,6 +165,43 @@ pub async fn import_pkg_from_pkg(
     Ok((installed_pkg_id, installed_schema_variant_ids))
 }
 
+/// Declares the custom secret kind of the package, or updates the fields of an existing
+/// declaration of the same name.
+async fn import_secret_kind(
+    ctx: &DalContext,
+    secret_kind_spec: SiPkgSecretKind<'_>,
+) -> PkgResult<SecretKindDefinition> {
+    let fields: Vec<SecretKindField> = secret_kind_spec
+        .fields()
+        .iter()
+        .map(|field| SecretKindField {
+            name: field.name.to_owned(),
+            required: field.required,
+        })
+        .collect();
+
+    let definition = match SecretKindDefinition::find_by_name(ctx, secret_kind_spec.name()).await? {
+        Some(mut definition) => {
+            definition
+                .set_description(ctx, secret_kind_spec.description().map(ToOwned::to_owned))
+                .await?;
+            definition.set_fields(ctx, fields).await?;
+            definition
+        }
+        None => {
+            SecretKindDefinition::new(
+                ctx,
+                secret_kind_spec.name(),
+                secret_kind_spec.description().map(ToOwned::to_owned),
+                fields,
+            )
+            .await?
+        }
+    };
+
+    Ok(definition)
+}
+
 pub async fn import_pkg(ctx: &DalContext, pkg_file_path: impl AsRef<Path>) -> PkgResult<SiPkg> {
     let pkg_file_path_str = pkg_file_path.as_ref().to_string_lossy().to_string();
 
@@ -182,6 +251,22 @@ async fn create_func(
             func.set_hidden(ctx, func.hidden()).await?;
             func.set_link(ctx, func_spec.link().map(|l| l.to_string()))
                 .await?;
+            func.set_timeout_secs(
+                ctx,
+                func_spec
+                    .timeout_secs()
+                    .and_then(|timeout_secs| i64::try_from(timeout_secs).ok()),
+            )
+            .await?;
+            func.set_memoizable(ctx, func_spec.memoizable()).await?;
+            func.set_egress_policy(
+                ctx,
+                func_spec
+                    .egress_policy()
+                    .map(EgressPolicy::from)
+                    .unwrap_or_default(),
+            )
+            .await?;
 
             // If the func exists above with the matching hash, we assume the arguments are correct
             // and only create the arguments if we're creating the function
@@ -7,8 +7,9 @@ use si_data_pg::PgError;
 use thiserror::Error;
 
 use crate::{
-    pk, schema::variant::SchemaVariantError, AttributeValueError, AttributeValueId, ComponentError,
-    PropId, SchemaVariantId, StandardModelError, TransactionsError, ValidationResolverError,
+    pk, schema::variant::SchemaVariantError, AttributeContextBuilderError, AttributeValueError,
+    AttributeValueId, ComponentError, ComponentId, PropError, PropId, SchemaVariantId,
+    StandardModelError, TransactionsError, ValidationResolverError,
 };
 
 pub mod schema;
@@ -18,6 +19,8 @@ pub mod values;
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum PropertyEditorError {
+    #[error("attribute context builder error: {0}")]
+    AttributeContextBuilder(#[from] AttributeContextBuilderError),
     #[error("attribute value error: {0}")]
     AttributeValue(#[from] AttributeValueError),
     #[error("invalid AttributeReadContext: {0}")]
@@ -26,10 +29,16 @@ pub enum PropertyEditorError {
     Component(#[from] ComponentError),
     #[error("component not found")]
     ComponentNotFound,
+    #[error("no value found for prop {0} on component {1}")]
+    NoValueFoundForComponentProp(PropId, ComponentId),
     #[error("no value(s) found for property editor prop id: {0}")]
     NoValuesFoundForPropertyEditorProp(PropertyEditorPropId),
+    #[error("parent attribute value not found for attribute value: {0}")]
+    ParentAttributeValueNotFound(AttributeValueId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
     #[error("prop not found for id: {0}")]
     PropNotFound(PropId),
     #[error("root prop not found for schema variant")]
@@ -5,11 +5,14 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::job::definition::DependentValuesUpdate;
+use crate::prop::PropPath;
 use crate::property_editor::{PropertyEditorError, PropertyEditorResult};
 use crate::property_editor::{PropertyEditorPropId, PropertyEditorValueId};
 use crate::{
-    AttributeReadContext, AttributeValue, AttributeValueId, Component, ComponentId, DalContext,
-    Prop, PropId, StandardModel,
+    AttributeContext, AttributeReadContext, AttributeValue, AttributeValueId, AttributeValueSource,
+    Component, ComponentId, DalContext, Prop, PropId, SchemaVariantId, StandardModel,
+    UndoOperation,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -99,6 +102,97 @@ impl PropertyEditorValues {
             Err(PropertyEditorError::RootPropNotFound)
         }
     }
+
+    /// Sets the value of the [`Prop`] found at the given path on every one of the given
+    /// [`Components`](crate::Component), e.g. the region of every EC2 instance in a change set.
+    ///
+    /// Rather than one job per edit, a single
+    /// [`DependentValuesUpdate`](crate::job::definition::DependentValuesUpdate) is enqueued for all
+    /// the [`AttributeValues`](crate::AttributeValue) that were touched. The updates all happen in
+    /// the transactions of the [`DalContext`], so either all of them are committed or none are.
+    ///
+    /// Returns the [`UndoOperations`](crate::UndoOperation) reverting the updates.
+    pub async fn update_for_components(
+        ctx: &DalContext,
+        prop_path: &PropPath,
+        component_ids: &[ComponentId],
+        value: Option<Value>,
+    ) -> PropertyEditorResult<Vec<UndoOperation>> {
+        // The components are usually all of the same schema variant, so the prop only needs to
+        // be looked up once
+        let mut props: HashMap<SchemaVariantId, PropId> = HashMap::new();
+        let mut updated_attribute_value_ids = Vec::with_capacity(component_ids.len());
+        let mut operations = Vec::with_capacity(component_ids.len());
+
+        for &component_id in component_ids {
+            let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+            let prop_id = match props.get(&schema_variant_id) {
+                Some(prop_id) => *prop_id,
+                None => {
+                    let prop = Prop::find_prop_by_path(ctx, schema_variant_id, prop_path).await?;
+                    props.insert(schema_variant_id, *prop.id());
+                    *prop.id()
+                }
+            };
+
+            let attribute_value = AttributeValue::find_for_context(
+                ctx,
+                AttributeReadContext {
+                    prop_id: Some(prop_id),
+                    component_id: Some(component_id),
+                    ..AttributeReadContext::default()
+                },
+            )
+            .await?
+            .ok_or(PropertyEditorError::NoValueFoundForComponentProp(
+                prop_id,
+                component_id,
+            ))?;
+            let parent_attribute_value = attribute_value
+                .parent_attribute_value(ctx)
+                .await?
+                .ok_or_else(|| {
+                    PropertyEditorError::ParentAttributeValueNotFound(*attribute_value.id())
+                })?;
+
+            let before = AttributeValueSource::of(ctx, &attribute_value).await?;
+
+            let attribute_context = AttributeContext::builder()
+                .set_prop_id(prop_id)
+                .set_component_id(component_id)
+                .to_context()?;
+            let (_, updated_attribute_value_id) =
+                AttributeValue::update_for_context_without_propagating_dependent_values(
+                    ctx,
+                    *attribute_value.id(),
+                    Some(*parent_attribute_value.id()),
+                    attribute_context,
+                    value.clone(),
+                    None,
+                )
+                .await?;
+            updated_attribute_value_ids.push(updated_attribute_value_id);
+            operations.push(UndoOperation::SetAttributeValue {
+                attribute_value_id: updated_attribute_value_id,
+                parent_attribute_value_id: Some(*parent_attribute_value.id()),
+                context: attribute_context,
+                key: None,
+                before,
+                after: value.clone(),
+            });
+        }
+
+        if !updated_attribute_value_ids.is_empty() {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                *ctx.visibility(),
+                updated_attribute_value_ids,
+            ))
+            .await?;
+        }
+
+        Ok(operations)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
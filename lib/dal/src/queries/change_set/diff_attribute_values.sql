@@ -0,0 +1,34 @@
+SELECT change_set_av.id                                        AS attribute_value_id,
+       change_set_av.attribute_context_component_id            AS component_id,
+       change_set_av.attribute_context_prop_id                 AS prop_id,
+       props.path                                              AS prop_path,
+       change_set_av.key                                       AS key,
+       change_set_av.visibility_deleted_at IS NOT NULL         AS deleted_in_change_set,
+       head_av.id IS NOT NULL                                  AS on_head,
+       change_set_fbrv.value                                   AS change_set_value,
+       head_fbrv.value                                         AS head_value
+FROM attribute_values AS change_set_av
+         LEFT JOIN attribute_values AS head_av
+                   ON head_av.id = change_set_av.id
+                       AND head_av.tenancy_workspace_pk IS NOT DISTINCT FROM change_set_av.tenancy_workspace_pk
+                       AND head_av.visibility_change_set_pk = ident_nil_v1()
+                       AND head_av.visibility_deleted_at IS NULL
+         LEFT JOIN func_binding_return_values_v1($1, $3) AS change_set_fbrv
+                   ON change_set_fbrv.id = change_set_av.func_binding_return_value_id
+         LEFT JOIN func_binding_return_values_v1($1, $4) AS head_fbrv
+                   ON head_fbrv.id = head_av.func_binding_return_value_id
+         LEFT JOIN props_v1($1, $3) AS props
+                   ON props.id = change_set_av.attribute_context_prop_id
+
+-- Compare only to the change set
+WHERE change_set_av.visibility_change_set_pk = $2
+
+  -- Only values of components, not the schema variant defaults
+  AND change_set_av.attribute_context_component_id != ident_nil_v1()
+
+  -- Scope the tenancy one last time
+  AND in_tenancy_v1($1, change_set_av.tenancy_workspace_pk)
+
+ORDER BY change_set_av.attribute_context_component_id,
+         props.path,
+         change_set_av.key
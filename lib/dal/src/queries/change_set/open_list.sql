@@ -4,4 +4,5 @@ SELECT
 FROM change_sets
 WHERE
     status = 'Open'
+    AND (expires_at IS NULL OR expires_at > CLOCK_TIMESTAMP())
     AND in_tenancy_v1($1, change_sets.tenancy_workspace_pk)
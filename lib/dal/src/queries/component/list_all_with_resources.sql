@@ -0,0 +1,37 @@
+-- Every component on head, across all workspaces, whose resource has a payload. Components that
+-- were deleted but still need their resource destroyed are included.
+SELECT DISTINCT ON (components.id) components.id,
+                                   row_to_json(components.*) AS object
+FROM components
+WHERE is_visible_v1($1, components.visibility_change_set_pk, components.visibility_deleted_at)
+  AND (components.visibility_deleted_at IS NULL OR components.needs_destroy)
+  AND EXISTS(
+        SELECT 1
+        FROM attribute_values
+                 INNER JOIN func_binding_return_values
+                            ON func_binding_return_values.id = attribute_values.func_binding_return_value_id
+                                AND is_visible_v1($1, func_binding_return_values.visibility_change_set_pk,
+                                                  func_binding_return_values.visibility_deleted_at)
+                 INNER JOIN internal_providers
+                            ON internal_providers.id = attribute_values.attribute_context_internal_provider_id
+                                AND is_visible_v1($1, internal_providers.visibility_change_set_pk,
+                                                  internal_providers.visibility_deleted_at)
+                 INNER JOIN props
+                            ON props.id = internal_providers.prop_id
+                                AND props.name = 'resource'
+                                AND is_visible_v1($1, props.visibility_change_set_pk,
+                                                  props.visibility_deleted_at)
+                 INNER JOIN prop_belongs_to_prop
+                            ON prop_belongs_to_prop.object_id = props.id
+                                AND is_visible_v1($1, prop_belongs_to_prop.visibility_change_set_pk,
+                                                  prop_belongs_to_prop.visibility_deleted_at)
+                 INNER JOIN schema_variants
+                            ON schema_variants.root_prop_id = prop_belongs_to_prop.belongs_to_id
+                                AND is_visible_v1($1, schema_variants.visibility_change_set_pk,
+                                                  schema_variants.visibility_deleted_at)
+        WHERE attribute_values.attribute_context_component_id = components.id
+          AND is_visible_v1($1, attribute_values.visibility_change_set_pk,
+                            attribute_values.visibility_deleted_at)
+          AND COALESCE(jsonb_typeof(func_binding_return_values.value -> 'payload'), 'null') != 'null'
+    )
+ORDER BY components.id
@@ -0,0 +1,11 @@
+SELECT row_to_json(history_events.*) AS object
+FROM history_events
+WHERE in_tenancy_v1($1, history_events.tenancy_workspace_pk)
+  AND ($2::jsonb IS NULL OR history_events.actor @> $2::jsonb)
+  AND ($3::text IS NULL OR history_events.label = $3::text)
+  AND ($4::text IS NULL OR history_events.label LIKE $4::text || '%')
+  AND ($5::ident IS NULL OR history_events.visibility_change_set_pk = $5::ident)
+  AND ($6::timestamp with time zone IS NULL OR history_events.created_at >= $6::timestamp with time zone)
+  AND ($7::timestamp with time zone IS NULL OR history_events.created_at < $7::timestamp with time zone)
+ORDER BY history_events.created_at DESC
+LIMIT $8::bigint
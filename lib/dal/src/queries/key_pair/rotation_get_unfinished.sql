@@ -0,0 +1,4 @@
+SELECT row_to_json(key_pair_rotations.*) AS object
+FROM key_pair_rotations
+WHERE key_pair_rotations.workspace_pk = $1
+  AND key_pair_rotations.finished_at IS NULL
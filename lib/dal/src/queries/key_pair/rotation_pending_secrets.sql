@@ -0,0 +1,15 @@
+SELECT encrypted_secrets.pk,
+       encrypted_secrets.crypted,
+       encrypted_secrets.key_pair_pk
+FROM encrypted_secrets
+WHERE encrypted_secrets.tenancy_workspace_pk = $1
+  AND encrypted_secrets.key_pair_pk != $2
+  -- Only the Postgres backend holds crypted payloads
+  AND encrypted_secrets.backend = 'postgres'
+  -- Rows this rotation already failed to re-seal are not tried again
+  AND NOT EXISTS(SELECT 1
+                 FROM key_pair_rotation_failures
+                 WHERE key_pair_rotation_failures.rotation_pk = $3
+                   AND key_pair_rotation_failures.secret_pk = encrypted_secrets.pk)
+ORDER BY encrypted_secrets.pk
+LIMIT $4
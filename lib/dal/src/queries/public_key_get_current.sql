@@ -1,5 +1,6 @@
 SELECT row_to_json(key_pairs.*) as object
 FROM key_pairs as key_pairs
 WHERE key_pairs.workspace_pk = $1
+  AND key_pairs.retired_at IS NULL
 ORDER BY key_pairs.created_lamport_clock DESC
 LIMIT 1;
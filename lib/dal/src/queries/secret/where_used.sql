@@ -0,0 +1,22 @@
+SELECT DISTINCT ON (components.id, props.path, av.key) components.id AS component_id,
+                                                       av.id         AS attribute_value_id,
+                                                       props.path    AS prop_path,
+                                                       av.key        AS key
+FROM attribute_values_v1($1, $2) AS av
+         JOIN func_binding_return_values_v1($1, $2) AS fbrv
+              ON fbrv.id = av.func_binding_return_value_id
+         JOIN components_v1($1, $2) AS components
+              ON components.id = av.attribute_context_component_id
+         JOIN props_v1($1, $2) AS props
+              ON props.id = av.attribute_context_prop_id
+
+-- Secret select widgets store the id of the secret as a plain string value
+WHERE fbrv.value = to_jsonb($3::ident::text)
+  AND (props.widget_kind = 'secretSelect'
+    OR props.path LIKE 'root' || chr(11) || 'secrets' || chr(11) || '%'
+    OR props.path LIKE 'root' || chr(11) || 'domain' || chr(11) || '%')
+
+ORDER BY components.id,
+         props.path,
+         av.key,
+         av.attribute_context_component_id DESC
@@ -4,4 +4,5 @@ FROM sockets_v1($1, $2) as sockets
               ON sockets.id = socket_to_schema_variant.left_object_id
          JOIN component_belongs_to_schema_variant_v1($1, $2) as component_belongs_to_schema_variant
               ON component_belongs_to_schema_variant.belongs_to_id = socket_to_schema_variant.right_object_id
-                  AND component_belongs_to_schema_variant.object_id = $3;
\ No newline at end of file
+                  AND component_belongs_to_schema_variant.object_id = $3
+ORDER BY sockets.id;
\ No newline at end of file
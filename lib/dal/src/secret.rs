@@ -1,5 +1,5 @@
 use crate::{Tenancy, TransactionsError};
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
@@ -12,43 +12,85 @@ use sodiumoxide::crypto::{
 use strum::{AsRefStr, Display, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
-use veritech_client::SensitiveContainer;
+use veritech_client::{Redactor, SensitiveContainer};
 
 use crate::{
+    component::ComponentKind,
     impl_standard_model,
     key_pair::KeyPairPk,
     pk,
+    prop::PropPath,
     standard_model::{self, TypeHint},
-    standard_model_accessor, standard_model_accessor_ro, DalContext, HistoryEvent,
-    HistoryEventError, KeyPair, KeyPairError, StandardModel, StandardModelError, Timestamp,
-    Visibility,
+    standard_model_accessor, standard_model_accessor_ro, AttributeValue, AttributeValueError,
+    AttributeValueId, ComponentId, DalContext, HistoryEvent, HistoryEventError, KeyPair,
+    KeyPairError, StandardModel, StandardModelError, Timestamp, Visibility,
 };
 
 /// Error type for Secrets.
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SecretError {
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] Box<AttributeValueError>),
+    #[error("attribute value not found: {0}")]
+    AttributeValueNotFound(AttributeValueId),
+    #[error("error reading secret from backend: {0}")]
+    BackendIo(#[source] std::io::Error),
+    #[error("secret backend not configured: {0}")]
+    BackendNotConfigured(SecretBackendKind),
     #[error("error when decrypting crypted secret")]
     DecryptionFailed,
     #[error("error deserializing message: {0}")]
     DeserializeMessage(#[source] serde_json::Error),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("secret {0} is still used by {1} attribute value(s)")]
+    InUse(SecretId, usize),
+    #[error("invalid secret backend reference: {0}")]
+    InvalidBackendReference(String),
+    #[error("invalid secret kind: {0}")]
+    InvalidKind(String),
+    #[error("invalid message for secret kind {0}: {1}")]
+    InvalidMessage(SecretKind, String),
     #[error("key pair error: {0}")]
     KeyPair(#[from] KeyPairError),
     #[error("key pair not found for secret")]
     KeyPairNotFound,
+    #[error("secret {0} has no backend reference")]
+    MissingBackendReference(SecretId),
+    #[error("secret {0} does not belong to a workspace")]
+    NoWorkspace(SecretId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("secret kind not found: {0}")]
+    SecretKindNotFound(String),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
+    #[error("operation not supported for secrets held by the {0} backend")]
+    UnsupportedForBackend(SecretBackendKind),
 }
 
 /// Result type for Secrets.
 pub type SecretResult<T> = Result<T, SecretError>;
 
+mod backend;
+mod kind;
+
+pub use backend::{
+    FileSecretBackend, PostgresSecretBackend, SecretBackend, SecretBackendKind, SecretBackends,
+    SecretBackendsConfig,
+};
+pub use kind::{
+    SecretKind, SecretKindDefinition, SecretKindDefinitionId, SecretKindDefinitionPk,
+    SecretKindField,
+};
+
+const WHERE_USED: &str = include_str!("queries/secret/where_used.sql");
+
 pk!(SecretPk);
 pk!(SecretId);
 
@@ -121,6 +163,100 @@ impl Secret {
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Lists every component attribute value that references this secret, either through a
+    /// secret select widget or as a value under "/root/secrets" or "/root/domain".
+    pub async fn where_used(&self, ctx: &DalContext) -> SecretResult<Vec<SecretUsage>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WHERE_USED, &[ctx.tenancy(), ctx.visibility(), &self.id])
+            .await?;
+
+        let mut usages = Vec::with_capacity(rows.len());
+        for row in rows {
+            let prop_path: String = row.try_get("prop_path")?;
+            usages.push(SecretUsage {
+                component_id: row.try_get("component_id")?,
+                attribute_value_id: row.try_get("attribute_value_id")?,
+                prop_path: PropPath::new([prop_path]).with_replaced_sep("/"),
+                key: row.try_get("key")?,
+            });
+        }
+        Ok(usages)
+    }
+
+    /// Deletes the secret. While components still reference it, deletion is refused with
+    /// [`SecretError::InUse`] unless `force` is set, in which case the references are unset.
+    pub async fn delete(&mut self, ctx: &DalContext, force: bool) -> SecretResult<()> {
+        let usages = self.where_used(ctx).await?;
+        if !usages.is_empty() && !force {
+            return Err(SecretError::InUse(self.id, usages.len()));
+        }
+        for usage in &usages {
+            unset_usage(ctx, usage).await?;
+        }
+
+        // Delete from the underlying `encrypted_secrets` table rather than the `secrets` view
+        let deleted_at = standard_model::delete_by_id(ctx, "encrypted_secrets", self.id).await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["deleted"]),
+            Self::history_event_message("deleted"),
+            &serde_json::json!({
+                "pk": self.pk,
+                "id": self.id,
+                "forced": force,
+                "usages": usages.len(),
+                "visibility": ctx.visibility(),
+            }),
+        )
+        .await?;
+        self.visibility.deleted_at = Some(deleted_at);
+        self.timestamp.updated_at = deleted_at;
+
+        Ok(())
+    }
+}
+
+/// Clears the value of an attribute value referencing a secret that is being deleted, so it
+/// doesn't dangle.
+async fn unset_usage(ctx: &DalContext, usage: &SecretUsage) -> SecretResult<()> {
+    let attribute_value = AttributeValue::get_by_id(ctx, &usage.attribute_value_id)
+        .await?
+        .ok_or(SecretError::AttributeValueNotFound(
+            usage.attribute_value_id,
+        ))?;
+    let parent_attribute_value_id = attribute_value
+        .parent_attribute_value(ctx)
+        .await
+        .map_err(Box::new)?
+        .map(|parent| *parent.id());
+
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        parent_attribute_value_id,
+        attribute_value.context,
+        None,
+        attribute_value.key.clone(),
+    )
+    .await
+    .map_err(Box::new)?;
+    Ok(())
+}
+
+/// A component attribute value referencing a [`Secret`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretUsage {
+    pub component_id: ComponentId,
+    pub attribute_value_id: AttributeValueId,
+    /// The path of the prop holding the reference, separated by "/".
+    pub prop_path: String,
+    /// The map key of the attribute value, if it is an entry of a map.
+    pub key: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -138,7 +274,7 @@ impl From<Secret> for SecretView {
             id: *secret.id(),
             name: secret.name().to_owned(),
             object_type: *secret.object_type(),
-            kind: *secret.kind(),
+            kind: secret.kind().clone(),
         }
     }
 }
@@ -162,6 +298,10 @@ pub struct EncryptedSecret {
     crypted: Vec<u8>,
     version: SecretVersion,
     algorithm: SecretAlgorithm,
+    #[serde(default)]
+    backend: SecretBackendKind,
+    #[serde(default)]
+    backend_reference: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -180,6 +320,7 @@ impl fmt::Debug for EncryptedSecret {
             .field("kind", &self.kind)
             .field("version", &self.version)
             .field("algorithm", &self.algorithm)
+            .field("backend", &self.backend)
             .field("tenancy", &self.tenancy)
             .field("timestamp", &self.timestamp)
             .field("visibility", &self.visibility)
@@ -210,6 +351,8 @@ impl EncryptedSecret {
         algorithm: SecretAlgorithm,
     ) -> SecretResult<Secret> {
         let name = name.as_ref();
+        let (crypted, key_pair_pk) = seal_with_live_key_pair(ctx, crypted, key_pair_pk).await?;
+        validate_sealed_message(ctx, &kind, version, algorithm, &crypted, key_pair_pk).await?;
 
         let row = ctx
             .txns()
@@ -222,8 +365,8 @@ impl EncryptedSecret {
                     ctx.visibility(),
                     &name,
                     &object_type.as_ref(),
-                    &kind.as_ref(),
-                    &encode_crypted(crypted),
+                    &kind.to_string(),
+                    &encode_crypted(&crypted),
                     &version.as_ref(),
                     &algorithm.as_ref(),
                     &key_pair_pk,
@@ -235,41 +378,214 @@ impl EncryptedSecret {
         Ok(object)
     }
 
+    /// Creates a new secret whose material is held by an external [`SecretBackend`]. Only the
+    /// `reference` is stored in SI; it is resolved by the backend at function execution time.
+    pub async fn new_external(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        object_type: SecretObjectType,
+        kind: SecretKind,
+        backend: SecretBackendKind,
+        reference: impl AsRef<str>,
+    ) -> SecretResult<Secret> {
+        if backend == SecretBackendKind::Postgres {
+            return Err(SecretError::UnsupportedForBackend(backend));
+        }
+        ctx.secret_backends()
+            .get(backend)
+            .ok_or(SecretError::BackendNotConfigured(backend))?
+            .validate_reference(reference.as_ref())?;
+        let name = name.as_ref();
+        // External secrets are not sealed, but are still tied to the current key pair so that
+        // the "key_pair_pk" column keeps pointing at a live pair
+        let key_pair = KeyPair::get_current(ctx).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM encrypted_secret_create_v2($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &name,
+                    &object_type.as_ref(),
+                    &kind.to_string(),
+                    &"",
+                    &SecretVersion::default().as_ref(),
+                    &SecretAlgorithm::default().as_ref(),
+                    &key_pair.pk(),
+                    &backend.as_ref(),
+                    &reference.as_ref(),
+                ],
+            )
+            .await?;
+        let object: Secret = standard_model::finish_create_from_row(ctx, row).await?;
+
+        Ok(object)
+    }
+
     standard_model_accessor!(name, String, SecretResult);
 
+    /// Replaces the encrypted payload, e.g. when the credential it holds has been rotated.
+    pub async fn set_crypted(
+        &mut self,
+        ctx: &DalContext,
+        crypted: &[u8],
+        key_pair_pk: KeyPairPk,
+    ) -> SecretResult<()> {
+        if self.backend != SecretBackendKind::Postgres {
+            return Err(SecretError::UnsupportedForBackend(self.backend));
+        }
+        let (crypted, key_pair_pk) = seal_with_live_key_pair(ctx, crypted, key_pair_pk).await?;
+        validate_sealed_message(
+            ctx,
+            &self.kind,
+            self.version,
+            self.algorithm,
+            &crypted,
+            key_pair_pk,
+        )
+        .await?;
+
+        standard_model::update(
+            ctx,
+            Self::table_name(),
+            "crypted",
+            self.id(),
+            &encode_crypted(&crypted),
+            TypeHint::Text,
+        )
+        .await?;
+        let updated_at = standard_model::update(
+            ctx,
+            Self::table_name(),
+            "key_pair_pk",
+            self.id(),
+            &key_pair_pk,
+            TypeHint::Ident,
+        )
+        .await?;
+        // Never record the payload itself
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["updated"]),
+            Self::history_event_message("updated"),
+            &serde_json::json!({"pk": self.pk, "field": "crypted", "key_pair_pk": key_pair_pk}),
+        )
+        .await?;
+        self.timestamp.updated_at = updated_at;
+        self.crypted = crypted;
+        self.key_pair_pk = key_pair_pk;
+
+        Ok(())
+    }
+
     // Once created, these object fields are to be considered immutable
     standard_model_accessor_ro!(object_type, SecretObjectType);
     standard_model_accessor_ro!(kind, SecretKind);
     standard_model_accessor_ro!(version, SecretVersion);
     standard_model_accessor_ro!(algorithm, SecretAlgorithm);
 
-    /// Decrypts the encrypted secret with its associated [`KeyPair`] and returns a
+    standard_model_accessor_ro!(backend, SecretBackendKind);
+    standard_model_accessor_ro!(backend_reference, Option<String>);
+
+    /// Resolves the secret's material through the [`SecretBackend`] holding it and returns a
     /// [`DecryptedSecret`].
+    ///
+    /// This is meant to be called as late as possible, right before handing the secret to
+    /// veritech, so that external backends are consulted for the material at execution time.
     pub async fn decrypt(self, ctx: &DalContext) -> SecretResult<DecryptedSecret> {
-        let key_pair = self.key_pair(ctx).await?;
-        self.into_decrypted(key_pair.public_key(), key_pair.secret_key())
+        let backend = ctx
+            .secret_backends()
+            .get(self.backend)
+            .ok_or(SecretError::BackendNotConfigured(self.backend))?;
+        let message = backend.resolve(ctx, &self).await?;
+        // The material of external secrets is never seen before it is resolved
+        if self.backend != SecretBackendKind::Postgres {
+            self.kind.validate_message(ctx, &message).await?;
+        }
+
+        Ok(DecryptedSecret {
+            name: self.name,
+            object_type: self.object_type,
+            secret_kind: self.kind,
+            message,
+        })
     }
 
-    fn into_decrypted(self, pkey: &PublicKey, skey: &SecretKey) -> SecretResult<DecryptedSecret> {
-        // Explicitly match on (version, algorithm) tuple to ensure that any new
-        // versions/algorithms will trigger a compilation failure
-        match (self.version, self.algorithm) {
-            (SecretVersion::V1, SecretAlgorithm::Sealedbox) => Ok(DecryptedSecret {
-                name: self.name,
-                object_type: self.object_type,
-                secret_kind: self.kind,
-                message: serde_json::from_slice(
-                    &sealedbox::open(&self.crypted, pkey, skey)
-                        .map_err(|_| SecretError::DecryptionFailed)?,
-                )
-                .map_err(SecretError::DeserializeMessage)?,
-            }),
-        }
+    /// Opens the crypted payload stored with the secret.
+    fn open_message(&self, pkey: &PublicKey, skey: &SecretKey) -> SecretResult<Value> {
+        open_crypted(self.version, self.algorithm, &self.crypted, pkey, skey)
     }
 
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Returns the ids of the secrets referenced by a value, such as the arguments of a function
+    /// execution, without touching the database.
+    ///
+    /// Only values typed as secrets count: the properties of views of
+    /// [`credential`](crate::ComponentKind::Credential) components, which is what gets decrypted
+    /// before a function runs. Any other string that happens to parse as an id is left alone.
+    pub fn referenced_ids(value: &Value) -> Vec<SecretId> {
+        let mut ids = Vec::new();
+
+        let mut work_queue = vec![(value, false)];
+        while let Some((work, is_secret)) = work_queue.pop() {
+            match work {
+                Value::Array(values) => {
+                    work_queue.extend(values.iter().map(|value| (value, is_secret)))
+                }
+                Value::Object(object) => {
+                    let is_credential_view = object.get("kind").and_then(Value::as_str)
+                        == Some(ComponentKind::Credential.as_ref());
+                    match object.get("properties") {
+                        Some(properties) if is_credential_view => {
+                            work_queue.push((properties, true))
+                        }
+                        _ => work_queue.extend(object.values().map(|value| (value, is_secret))),
+                    }
+                }
+                Value::String(raw_id) if is_secret => {
+                    if let Ok(id) = SecretId::from_str(raw_id) {
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                }
+                Value::String(_) | Value::Null | Value::Bool(_) | Value::Number(_) => {}
+            }
+        }
+
+        ids
+    }
+
+    /// Builds a [`Redactor`] for the decrypted messages of every secret referenced by the value
+    /// (see [`Self::referenced_ids`]).
+    ///
+    /// Secrets whose material can't be resolved are skipped: a function can't have been handed
+    /// what SI itself can't read.
+    pub async fn redactor_for(ctx: &DalContext, value: &Value) -> SecretResult<Redactor> {
+        let mut redactor = Redactor::default();
+
+        for id in Self::referenced_ids(value) {
+            let encrypted_secret = match Self::get_by_id(ctx, &id).await? {
+                Some(encrypted_secret) => encrypted_secret,
+                None => continue,
+            };
+            match encrypted_secret.decrypt(ctx).await {
+                Ok(decrypted) => redactor.insert_message(&decrypted.message),
+                Err(err) => {
+                    debug!(secret_id = %id, error = ?err, "skipping unresolvable secret");
+                }
+            }
+        }
+
+        Ok(redactor)
+    }
 }
 
 /// A secret that has been decrypted.
@@ -305,7 +621,7 @@ impl DecryptedSecret {
 
     /// Gets the decrypted secret's kind.
     pub fn kind(&self) -> SecretKind {
-        self.secret_kind
+        self.secret_kind.clone()
     }
 }
 
@@ -367,22 +683,65 @@ pub enum SecretObjectType {
     Credential,
 }
 
-/// The kind of a secret.
-#[remain::sorted]
-#[derive(
-    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
-)]
-#[serde(rename_all = "camelCase")]
-#[strum(serialize_all = "camelCase")]
-pub enum SecretKind {
-    /// An AWS access key
-    AwsAccessKey,
-    /// An Azure service principal
-    AzureServicePrincipal,
-    /// A Docker Hub credential
-    DockerHub,
-    /// A Helm repository credential
-    HelmRepo,
+/// Clients may still hold the public key of a key pair that a rotation has since retired, so
+/// payloads sealed with a retired key pair are re-sealed with the current one instead of being
+/// stored as-is.
+async fn seal_with_live_key_pair(
+    ctx: &DalContext,
+    crypted: &[u8],
+    key_pair_pk: KeyPairPk,
+) -> SecretResult<(Vec<u8>, KeyPairPk)> {
+    let key_pair = KeyPair::get_by_pk(ctx, key_pair_pk).await?;
+    if !key_pair.is_retired() {
+        return Ok((crypted.to_vec(), key_pair_pk));
+    }
+
+    let current = KeyPair::get_current(ctx).await?;
+    let resealed = key_pair
+        .reseal(crypted, &current)
+        .ok_or(SecretError::DecryptionFailed)?;
+    Ok((resealed, current.pk()))
+}
+
+fn open_crypted(
+    version: SecretVersion,
+    algorithm: SecretAlgorithm,
+    crypted: &[u8],
+    pkey: &PublicKey,
+    skey: &SecretKey,
+) -> SecretResult<Value> {
+    // Explicitly match on (version, algorithm) tuple to ensure that any new
+    // versions/algorithms will trigger a compilation failure
+    match (version, algorithm) {
+        (SecretVersion::V1, SecretAlgorithm::Sealedbox) => serde_json::from_slice(
+            &sealedbox::open(crypted, pkey, skey).map_err(|_| SecretError::DecryptionFailed)?,
+        )
+        .map_err(SecretError::DeserializeMessage),
+    }
+}
+
+/// Opens a payload about to be stored and checks it fits the secret's [`SecretKind`]. Payloads of
+/// kinds without message validation are stored as-is, without being opened.
+async fn validate_sealed_message(
+    ctx: &DalContext,
+    kind: &SecretKind,
+    version: SecretVersion,
+    algorithm: SecretAlgorithm,
+    crypted: &[u8],
+    key_pair_pk: KeyPairPk,
+) -> SecretResult<()> {
+    if !kind.validates_message() {
+        return Ok(());
+    }
+    let key_pair = KeyPair::get_by_pk(ctx, key_pair_pk).await?;
+    let message = open_crypted(
+        version,
+        algorithm,
+        crypted,
+        key_pair.public_key(),
+        key_pair.secret_key(),
+    )?;
+    kind.validate_message(ctx, &message).await
 }
 
 fn encode_crypted(crypted: &[u8]) -> String {
@@ -445,6 +804,8 @@ mod tests {
                 crypted,
                 version: Default::default(),
                 algorithm: Default::default(),
+                backend: Default::default(),
+                backend_reference: None,
                 tenancy: Tenancy::new(wid),
                 timestamp: Timestamp::now(),
                 visibility: Visibility::new_head(false),
@@ -462,7 +823,7 @@ mod tests {
         }
 
         #[test]
-        fn into_decrypted() {
+        fn open_message() {
             sodiumoxide::init().expect("crypto failed to init");
             let (pkey, skey) = box_::gen_keypair();
 
@@ -477,14 +838,38 @@ mod tests {
                 crypted,
                 WorkspacePk::NONE,
             );
-            let decrypted = encrypted
-                .into_decrypted(&pkey, &skey)
-                .expect("could not decrypt secret");
-
-            assert_eq!("the-cadillac-three", decrypted.name);
-            assert_eq!(SecretObjectType::Credential, decrypted.object_type);
-            assert_eq!(SecretKind::DockerHub, decrypted.secret_kind);
-            assert_eq!(message, decrypted.message);
+            let opened = encrypted
+                .open_message(&pkey, &skey)
+                .expect("could not open secret");
+
+            assert_eq!(message, opened);
+        }
+
+        #[test]
+        fn referenced_ids_only_in_credential_views() {
+            let secret_id = SecretId::generate();
+            let other_id = SecretId::generate();
+
+            let args = serde_json::json!({
+                "component": {
+                    "data": {
+                        "kind": "standard",
+                        "properties": { "domain": { "name": other_id.to_string() } },
+                    },
+                    "parents": [
+                        {
+                            "kind": "credential",
+                            "properties": {
+                                "secret": secret_id.to_string(),
+                                "again": [secret_id.to_string()],
+                            },
+                        },
+                    ],
+                },
+                "ulid": other_id.to_string(),
+            });
+
+            assert_eq!(vec![secret_id], EncryptedSecret::referenced_ids(&args));
         }
     }
 
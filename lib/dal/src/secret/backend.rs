@@ -0,0 +1,313 @@
+//! Backends resolving the material of an [`EncryptedSecret`] at function execution time.
+//!
+//! The built-in [`PostgresSecretBackend`] opens the payload stored alongside the secret with the
+//! workspace [`KeyPair`]. Other backends only keep a reference in SI and look the material up
+//! elsewhere, e.g. the [`FileSecretBackend`] which reads it from a local directory (such as a
+//! mounted volume populated by a Vault agent), with a subdirectory per workspace.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{AsRefStr, Display, EnumString};
+
+use super::{EncryptedSecret, SecretError, SecretResult};
+use crate::{DalContext, KeyPair, WorkspacePk};
+
+/// Where the material of a secret is held.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SecretBackendKind {
+    /// A file in a local directory, holding the JSON message of the secret
+    File,
+    /// The crypted payload stored in Postgres, sealed with the workspace key pair
+    Postgres,
+}
+
+impl Default for SecretBackendKind {
+    fn default() -> Self {
+        Self::Postgres
+    }
+}
+
+/// Resolves the message of an [`EncryptedSecret`] right before it is handed to veritech.
+#[async_trait]
+pub trait SecretBackend: fmt::Debug + Send + Sync {
+    /// The kind of secrets this backend resolves.
+    fn kind(&self) -> SecretBackendKind;
+
+    /// Returns the decrypted message of the secret.
+    async fn resolve(&self, ctx: &DalContext, secret: &EncryptedSecret) -> SecretResult<Value>;
+
+    /// Checks that a reference is one this backend could resolve, before it gets stored.
+    fn validate_reference(&self, _reference: &str) -> SecretResult<()> {
+        Ok(())
+    }
+}
+
+/// The built-in backend: opens the payload stored in Postgres with the secret's [`KeyPair`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PostgresSecretBackend;
+
+#[async_trait]
+impl SecretBackend for PostgresSecretBackend {
+    fn kind(&self) -> SecretBackendKind {
+        SecretBackendKind::Postgres
+    }
+
+    async fn resolve(&self, ctx: &DalContext, secret: &EncryptedSecret) -> SecretResult<Value> {
+        let key_pair = KeyPair::get_by_pk(ctx, secret.key_pair_pk).await?;
+        secret.open_message(key_pair.public_key(), key_pair.secret_key())
+    }
+}
+
+/// Resolves secret references as paths of JSON files, relative to the directory of the secret's
+/// workspace under the root: `<root>/<workspace_pk>/<reference>`. A workspace can't reach the
+/// files of another one, neither through its references nor through symlinks.
+#[derive(Clone, Debug)]
+pub struct FileSecretBackend {
+    root: PathBuf,
+}
+
+impl FileSecretBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The directory the references of the workspace are resolved in.
+    fn workspace_root(&self, workspace_pk: WorkspacePk) -> PathBuf {
+        self.root.join(workspace_pk.to_string())
+    }
+
+    /// Normalizes the reference into a relative path, refusing references that are absolute or
+    /// climb out of the directory they are resolved in.
+    fn normalize(reference: &str) -> SecretResult<PathBuf> {
+        let mut normalized = PathBuf::new();
+        for component in Path::new(reference).components() {
+            match component {
+                Component::Normal(part) => normalized.push(part),
+                Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(SecretError::InvalidBackendReference(reference.to_owned()));
+                }
+            }
+        }
+        if normalized.as_os_str().is_empty() {
+            return Err(SecretError::InvalidBackendReference(reference.to_owned()));
+        }
+        Ok(normalized)
+    }
+
+    /// Returns the path the reference of the workspace points to.
+    fn path_for(&self, workspace_pk: WorkspacePk, reference: &str) -> SecretResult<PathBuf> {
+        Ok(self
+            .workspace_root(workspace_pk)
+            .join(Self::normalize(reference)?))
+    }
+
+    /// Reads the message the reference of the workspace points to, refusing files that turn out
+    /// to be outside of the workspace's directory once symlinks are resolved.
+    async fn read_message(
+        &self,
+        workspace_pk: WorkspacePk,
+        reference: &str,
+    ) -> SecretResult<Value> {
+        let path = self.path_for(workspace_pk, reference)?;
+        let workspace_root = tokio::fs::canonicalize(self.workspace_root(workspace_pk))
+            .await
+            .map_err(SecretError::BackendIo)?;
+        let path = tokio::fs::canonicalize(path)
+            .await
+            .map_err(SecretError::BackendIo)?;
+        if !path.starts_with(&workspace_root) {
+            return Err(SecretError::InvalidBackendReference(reference.to_owned()));
+        }
+
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(SecretError::BackendIo)?;
+        serde_json::from_slice(&bytes).map_err(SecretError::DeserializeMessage)
+    }
+}
+
+#[async_trait]
+impl SecretBackend for FileSecretBackend {
+    fn kind(&self) -> SecretBackendKind {
+        SecretBackendKind::File
+    }
+
+    fn validate_reference(&self, reference: &str) -> SecretResult<()> {
+        Self::normalize(reference).map(|_| ())
+    }
+
+    async fn resolve(&self, _ctx: &DalContext, secret: &EncryptedSecret) -> SecretResult<Value> {
+        let reference = secret
+            .backend_reference
+            .as_deref()
+            .ok_or(SecretError::MissingBackendReference(secret.id))?;
+        let workspace_pk = secret
+            .tenancy
+            .workspace_pk()
+            .ok_or(SecretError::NoWorkspace(secret.id))?;
+        self.read_message(workspace_pk, reference).await
+    }
+}
+
+/// Configuration of the secret backends available next to the built-in Postgres one.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SecretBackendsConfig {
+    /// The directory the file backend resolves references in, holding a directory per workspace
+    /// named after its pk. The file backend is only available when this is set.
+    #[serde(default)]
+    pub file_root: Option<PathBuf>,
+}
+
+/// The registry of [`SecretBackends`](SecretBackend), by kind.
+#[derive(Clone, Debug)]
+pub struct SecretBackends {
+    backends: HashMap<SecretBackendKind, Arc<dyn SecretBackend>>,
+}
+
+impl Default for SecretBackends {
+    fn default() -> Self {
+        let mut backends = Self {
+            backends: HashMap::new(),
+        };
+        backends.register(PostgresSecretBackend);
+        backends
+    }
+}
+
+impl SecretBackends {
+    pub fn from_config(config: &SecretBackendsConfig) -> Self {
+        let mut backends = Self::default();
+        if let Some(file_root) = &config.file_root {
+            backends.register(FileSecretBackend::new(file_root));
+        }
+        backends
+    }
+
+    /// Registers a backend, replacing any backend previously registered for the same kind.
+    pub fn register(&mut self, backend: impl SecretBackend + 'static) {
+        self.backends.insert(backend.kind(), Arc::new(backend));
+    }
+
+    pub fn get(&self, kind: SecretBackendKind) -> Option<Arc<dyn SecretBackend>> {
+        self.backends.get(&kind).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_backend_refuses_escaping_references() {
+        let backend = FileSecretBackend::new("/run/secrets");
+        let workspace_pk = WorkspacePk::generate();
+
+        for reference in ["aws/prod.json", "./aws/prod.json", "aws/./prod.json"] {
+            assert_eq!(
+                PathBuf::from(format!("/run/secrets/{workspace_pk}/aws/prod.json")),
+                backend
+                    .path_for(workspace_pk, reference)
+                    .expect("valid reference"),
+            );
+        }
+        for reference in [
+            "",
+            ".",
+            "../etc/passwd",
+            "/etc/passwd",
+            "aws/../../etc/passwd",
+            "aws/../prod.json",
+        ] {
+            assert!(
+                matches!(
+                    backend.path_for(workspace_pk, reference),
+                    Err(SecretError::InvalidBackendReference(_))
+                ),
+                "reference {reference:?} should be refused"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn file_backend_resolves_workspace_files() {
+        let root = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace_pk = WorkspacePk::generate();
+        let message = serde_json::json!({"username": "The Cadillac Three"});
+        std::fs::create_dir_all(root.path().join(workspace_pk.to_string()).join("aws"))
+            .expect("failed to create workspace dir");
+        std::fs::write(
+            root.path()
+                .join(workspace_pk.to_string())
+                .join("aws/prod.json"),
+            serde_json::to_vec(&message).expect("failed to serialize message"),
+        )
+        .expect("failed to write secret file");
+
+        let backend = FileSecretBackend::new(root.path());
+        assert_eq!(
+            message,
+            backend
+                .read_message(workspace_pk, "aws/prod.json")
+                .await
+                .expect("failed to read message")
+        );
+    }
+
+    #[tokio::test]
+    async fn file_backend_refuses_cross_workspace_references() {
+        let root = tempfile::tempdir().expect("failed to create temp dir");
+        let owner = WorkspacePk::generate();
+        let other = WorkspacePk::generate();
+        let owner_file = root.path().join(owner.to_string()).join("prod.json");
+        let other_root = root.path().join(other.to_string());
+        std::fs::create_dir_all(root.path().join(owner.to_string()))
+            .expect("failed to create workspace dir");
+        std::fs::create_dir_all(&other_root).expect("failed to create workspace dir");
+        std::fs::write(&owner_file, br#"{"password": "Slow Rollin"}"#)
+            .expect("failed to write secret file");
+        std::os::unix::fs::symlink(&owner_file, other_root.join("link.json"))
+            .expect("failed to create symlink");
+
+        let backend = FileSecretBackend::new(root.path());
+        // The same reference resolves within the other workspace's own directory
+        assert!(matches!(
+            backend.read_message(other, "prod.json").await,
+            Err(SecretError::BackendIo(_))
+        ));
+        for reference in [format!("../{owner}/prod.json"), "link.json".to_owned()] {
+            assert!(
+                matches!(
+                    backend.read_message(other, &reference).await,
+                    Err(SecretError::InvalidBackendReference(_))
+                ),
+                "reference {reference:?} should be refused"
+            );
+        }
+    }
+
+    #[test]
+    fn backends_from_config() {
+        let backends = SecretBackends::from_config(&SecretBackendsConfig::default());
+        assert!(backends.get(SecretBackendKind::Postgres).is_some());
+        assert!(backends.get(SecretBackendKind::File).is_none());
+
+        let backends = SecretBackends::from_config(&SecretBackendsConfig {
+            file_root: Some("/run/secrets".into()),
+        });
+        assert!(backends.get(SecretBackendKind::File).is_some());
+    }
+}
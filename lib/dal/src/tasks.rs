@@ -2,9 +2,20 @@
 //! SI binaries that are dependent on the [`dal`](crate).
 
 // This modules should remain private! Add "pub use" statements to use their contents.
+mod change_set_garbage_collector;
+mod history_event_purger;
 mod resource_scheduler;
 mod status_receiver;
 
-pub use resource_scheduler::{ResourceScheduler, ResourceSchedulerError};
+pub use change_set_garbage_collector::{
+    ChangeSetGarbageCollector, ChangeSetGarbageCollectorConfig, ChangeSetGarbageCollectorError,
+};
+pub use history_event_purger::{
+    HistoryEventPurger, HistoryEventPurgerConfig, HistoryEventPurgerError,
+};
+pub use resource_scheduler::{
+    ComponentRefreshStatus, ResourceScheduler, ResourceSchedulerConfig, ResourceSchedulerError,
+    ResourceSchedulerHandle, ResourceSchedulerStatus,
+};
 pub use status_receiver::client::StatusReceiverClient;
 pub use status_receiver::{StatusReceiver, StatusReceiverError, StatusReceiverRequest};
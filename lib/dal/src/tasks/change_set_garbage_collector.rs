@@ -0,0 +1,126 @@
+//! This module contains [`ChangeSetGarbageCollector`], which is a "long-running" task that
+//! abandons expired change sets and reclaims the rows of abandoned ones.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{
+    AccessBuilder, ChangeSet, ChangeSetError, ChangeSetGarbageReport, HistoryActor,
+    ServicesContext, Tenancy, TransactionsError, WsEvent, WsEventError,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ChangeSetGarbageCollectorError {
+    #[error(transparent)]
+    ChangeSet(#[from] ChangeSetError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type ChangeSetGarbageCollectorResult<T> = Result<T, ChangeSetGarbageCollectorError>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ChangeSetGarbageCollectorConfig {
+    /// How often expired change sets are abandoned and abandoned ones purged.
+    pub interval_secs: u64,
+}
+
+impl Default for ChangeSetGarbageCollectorConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+        }
+    }
+}
+
+/// The change set garbage collector periodically runs [`ChangeSet::collect_garbage`] across all
+/// workspaces, letting the expired change sets' workspaces know they were abandoned.
+#[derive(Debug, Clone)]
+pub struct ChangeSetGarbageCollector {
+    services_context: ServicesContext,
+    config: ChangeSetGarbageCollectorConfig,
+}
+
+impl ChangeSetGarbageCollector {
+    pub fn new(
+        services_context: ServicesContext,
+        config: ChangeSetGarbageCollectorConfig,
+    ) -> ChangeSetGarbageCollector {
+        ChangeSetGarbageCollector {
+            services_context,
+            config,
+        }
+    }
+
+    /// Starts the garbage collector, consuming itself.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Change Set Garbage Collector received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Change Set Garbage Collector stopped");
+        });
+    }
+
+    #[instrument(name = "change_set_garbage_collector.run", skip_all, level = "debug")]
+    pub async fn run(&self) -> ChangeSetGarbageCollectorResult<ChangeSetGarbageReport> {
+        let builder = self.services_context.clone().into_builder(false);
+
+        let ctx = builder.build_default().await?;
+        let report = ChangeSet::collect_garbage(&ctx).await?;
+        ctx.commit().await?;
+
+        for (change_set_pk, workspace_pk) in &report.expired {
+            let tenancy = match workspace_pk {
+                Some(workspace_pk) => Tenancy::new(*workspace_pk),
+                None => Tenancy::new_empty(),
+            };
+            let ctx = builder
+                .build_head(AccessBuilder::new(tenancy, HistoryActor::SystemInit))
+                .await?;
+            WsEvent::change_set_canceled(&ctx, *change_set_pk)
+                .await?
+                .publish_on_commit(&ctx)
+                .await?;
+            ctx.commit().await?;
+        }
+
+        info!(
+            expired = report.expired.len(),
+            reclaimed_rows = report.total_reclaimed_rows(),
+            "collected change set garbage"
+        );
+        for (table_name, rows) in &report.reclaimed_rows {
+            debug!(%table_name, rows, "reclaimed change set rows");
+        }
+
+        Ok(report)
+    }
+
+    /// The internal task spawned by `start`.
+    #[instrument(
+        name = "change_set_garbage_collector.start_task",
+        skip_all,
+        level = "debug"
+    )]
+    async fn start_task(&self) {
+        let mut interval = time::interval(Duration::from_secs(self.config.interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+}
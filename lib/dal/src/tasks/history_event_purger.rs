@@ -0,0 +1,107 @@
+//! This module contains [`HistoryEventPurger`], which is a "long-running" task that deletes
+//! [`HistoryEvents`](crate::HistoryEvent) once their retention period is over.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{
+    HistoryEvent, HistoryEventError, HistoryEventRetention, ServicesContext, TransactionsError,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum HistoryEventPurgerError {
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type HistoryEventPurgerResult<T> = Result<T, HistoryEventPurgerError>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct HistoryEventPurgerConfig {
+    /// How often events past their retention period are deleted.
+    pub interval_secs: u64,
+    #[serde(flatten)]
+    pub retention: HistoryEventRetention,
+}
+
+impl Default for HistoryEventPurgerConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            retention: Default::default(),
+        }
+    }
+}
+
+/// The history event purger periodically runs [`HistoryEvent::purge`] with the configured
+/// [`HistoryEventRetention`]. Without any retention configured, it does nothing.
+#[derive(Debug, Clone)]
+pub struct HistoryEventPurger {
+    services_context: ServicesContext,
+    config: HistoryEventPurgerConfig,
+}
+
+impl HistoryEventPurger {
+    pub fn new(
+        services_context: ServicesContext,
+        config: HistoryEventPurgerConfig,
+    ) -> HistoryEventPurger {
+        HistoryEventPurger {
+            services_context,
+            config,
+        }
+    }
+
+    /// Starts the purger, consuming itself.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        if self.config.retention == HistoryEventRetention::default() {
+            debug!("no history event retention configured, not starting the purger");
+            return;
+        }
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("History Event Purger received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("History Event Purger stopped");
+        });
+    }
+
+    #[instrument(name = "history_event_purger.run", skip_all, level = "debug")]
+    pub async fn run(&self) -> HistoryEventPurgerResult<u64> {
+        let ctx = self
+            .services_context
+            .clone()
+            .into_builder(false)
+            .build_default()
+            .await?;
+        let purged = HistoryEvent::purge(&ctx, &self.config.retention).await?;
+        ctx.commit().await?;
+
+        info!(purged, "purged history events past their retention period");
+        Ok(purged)
+    }
+
+    /// The internal task spawned by `start`.
+    #[instrument(name = "history_event_purger.start_task", skip_all, level = "debug")]
+    async fn start_task(&self) {
+        let mut interval = time::interval(Duration::from_secs(self.config.interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+}
@@ -1,19 +1,40 @@
 //! This module contains [`ResourceScheduler`], which is a "long-running" tasks that performs
 //! [`resource`](crate::component::resource) syncing on a cadence.
 
-use std::time::Duration;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::{PgError, PgPoolError};
 use telemetry::prelude::*;
 use thiserror::Error;
-use tokio::{sync::broadcast, time};
+use tokio::{
+    sync::{broadcast, Mutex},
+    time,
+};
+use veritech_client::ResourceStatus;
 
-use crate::{standard_model, Component, ServicesContext, StandardModelError, TransactionsError};
+use crate::{
+    job::{definition::RefreshJob, producer::JobPriority},
+    standard_model, AccessBuilder, Component, ComponentError, ComponentId, DalContextBuilder,
+    HistoryActor, SchemaVariantId, ServicesContext, StandardModel, StandardModelError, Tenancy,
+    TransactionsError, Visibility, WorkspacePk,
+};
+
+const LIST_ALL_WITH_RESOURCES: &str =
+    include_str!("../queries/component/list_all_with_resources.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ResourceSchedulerError {
+    #[error(transparent)]
+    Component(#[from] ComponentError),
     #[error(transparent)]
     Nats(#[from] NatsError),
     #[error(transparent)]
@@ -30,25 +51,148 @@ pub enum ResourceSchedulerError {
 
 pub type ResourceSchedulerResult<T> = Result<T, ResourceSchedulerError>;
 
-/// The resource scheduler handles looking up all the components, and scheduling
-/// their resources to refresh. Eventually, it should become smart enough to parallelize,
-/// it might be extracted to a fully separate service, etc etc. For now,
-/// it is the dumbest thing that could possibly work - no more often than every 30
-/// seconds, it will ask a resource to refresh
+/// How often, and how hard, the [`ResourceScheduler`] refreshes resources.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ResourceSchedulerConfig {
+    /// How often the scheduler looks for resources that are due for a refresh. Values below `1`
+    /// are treated as `1`.
+    pub tick_interval_secs: u64,
+    /// How long a resource goes between refreshes, unless its schema variant says otherwise.
+    pub refresh_interval_secs: u64,
+    /// Refresh intervals for the components of specific schema variants.
+    pub schema_variant_refresh_interval_secs: HashMap<SchemaVariantId, u64>,
+    /// Up to this fraction of the interval is randomly added to or taken from each refresh, so
+    /// resources created together don't stay in lockstep.
+    pub jitter: f64,
+    /// How many components are refreshed by a single [`RefreshJob`].
+    pub batch_size: usize,
+    /// Upper bound on how long a resource whose refresh keeps failing is left alone.
+    pub max_backoff_secs: u64,
+}
+
+impl Default for ResourceSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            tick_interval_secs: 30,
+            refresh_interval_secs: 300,
+            schema_variant_refresh_interval_secs: HashMap::new(),
+            jitter: 0.1,
+            batch_size: 25,
+            max_backoff_secs: 3600,
+        }
+    }
+}
+
+impl ResourceSchedulerConfig {
+    fn tick_interval(&self) -> Duration {
+        Duration::from_secs(self.tick_interval_secs.max(1))
+    }
+
+    fn refresh_interval(&self, schema_variant_id: SchemaVariantId) -> Duration {
+        Duration::from_secs(
+            self.schema_variant_refresh_interval_secs
+                .get(&schema_variant_id)
+                .copied()
+                .unwrap_or(self.refresh_interval_secs),
+        )
+    }
+
+    /// How long to wait before the next refresh of a component of `schema_variant_id` that has
+    /// failed `consecutive_failures` times in a row. The interval doubles with every failure.
+    fn next_delay(
+        &self,
+        schema_variant_id: SchemaVariantId,
+        consecutive_failures: u32,
+    ) -> Duration {
+        let interval = self.refresh_interval(schema_variant_id);
+        let delay = if consecutive_failures == 0 {
+            interval
+        } else {
+            interval
+                .saturating_mul(2_u32.saturating_pow(consecutive_failures))
+                .min(Duration::from_secs(self.max_backoff_secs).max(interval))
+        };
+
+        jittered(delay, self.jitter)
+    }
+}
+
+fn jittered(delay: Duration, jitter: f64) -> Duration {
+    let jitter = jitter.clamp(0.0, 1.0);
+    if jitter == 0.0 {
+        return delay;
+    }
+    let factor = rand::thread_rng().gen_range((1.0 - jitter)..=(1.0 + jitter));
+    delay.mul_f64(factor)
+}
+
+/// A snapshot of what the [`ResourceScheduler`] is doing, see [`ResourceSchedulerHandle::status`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSchedulerStatus {
+    /// When the scheduler last looked for resources to refresh.
+    pub last_tick_at: Option<DateTime<Utc>>,
+    /// The error the last look ended with, if it failed.
+    pub last_error: Option<String>,
+    pub components: Vec<ComponentRefreshStatus>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentRefreshStatus {
+    pub component_id: ComponentId,
+    pub workspace_pk: Option<WorkspacePk>,
+    pub schema_variant_id: SchemaVariantId,
+    /// Whether a [`RefreshJob`] for the component is currently running.
+    pub in_flight: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    pub consecutive_failures: u32,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    last_tick_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    components: HashMap<ComponentId, ComponentRefreshStatus>,
+}
+
+/// The resource scheduler looks up every component with a resource on head, across all
+/// workspaces, and enqueues [`RefreshJobs`](RefreshJob) for the ones that are due, batched by
+/// workspace. Each component is refreshed on its schema variant's interval (with some jitter),
+/// and components whose refreshes keep failing are backed off.
 #[derive(Debug, Clone)]
 pub struct ResourceScheduler {
     services_context: ServicesContext,
+    config: Arc<ResourceSchedulerConfig>,
+    state: Arc<Mutex<SchedulerState>>,
 }
 
 impl ResourceScheduler {
     pub fn new(services_context: ServicesContext) -> ResourceScheduler {
-        ResourceScheduler { services_context }
+        Self::new_with_config(services_context, ResourceSchedulerConfig::default())
+    }
+
+    pub fn new_with_config(
+        services_context: ServicesContext,
+        config: ResourceSchedulerConfig,
+    ) -> ResourceScheduler {
+        ResourceScheduler {
+            services_context,
+            config: Arc::new(config),
+            state: Default::default(),
+        }
     }
 
-    /// Starts the scheduler. It returns the join handle to the spawned scheduler, and
-    /// consumes itself. The caller should check for errors and restart the scheduler if
-    /// it ever returns an error.
-    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+    /// Starts the scheduler, consuming itself. The returned handle reports on what it is doing.
+    pub fn start(
+        self,
+        mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) -> ResourceSchedulerHandle {
+        let handle = ResourceSchedulerHandle {
+            state: self.state.clone(),
+        };
         tokio::spawn(async move {
             tokio::select! {
                 _ = shutdown_broadcast_rx.recv() => {
@@ -58,46 +202,215 @@ impl ResourceScheduler {
             }
             info!("Resource Refreshing stopped");
         });
+
+        handle
     }
 
     #[instrument(name = "resource_scheduler.run", skip_all, level = "debug")]
-    async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
-        /*
+    async fn run(&self) -> ResourceSchedulerResult<()> {
         let components = self.components().await?;
-        info!("Refresh {} resources", components.len());
+        let now = Utc::now();
+
+        // Forget about components that were deleted or lost their resource, and find the ones we
+        // haven't seen before.
+        let new_components: Vec<&Component> = {
+            let mut state = self.state.lock().await;
+            state.last_tick_at = Some(now);
+
+            let current: HashSet<ComponentId> =
+                components.iter().map(|component| *component.id()).collect();
+            state.components.retain(|id, _| current.contains(id));
 
-        for component in components {
-            // First we're building a ctx with no tenancy at head, then updating it with a
-            // workspace head request context
+            components
+                .iter()
+                .filter(|component| !state.components.contains_key(component.id()))
+                .collect()
+        };
+        let new_components = self.track(new_components).await?;
 
-            let builder = self.services_context.clone().into_builder(false);
-            let mut ctx = builder.build_default().await?;
+        let due = {
+            let mut state = self.state.lock().await;
+
+            // Spread the first refresh of newly seen components over their interval, so a
+            // restart doesn't refresh everything at once.
+            for component in new_components {
+                let first_run = self
+                    .config
+                    .refresh_interval(component.schema_variant_id)
+                    .mul_f64(rand::thread_rng().gen_range(0.0..=1.0));
+                state.components.insert(
+                    component.component_id,
+                    ComponentRefreshStatus {
+                        next_run_at: now + to_chrono(first_run),
+                        ..component
+                    },
+                );
+            }
 
-            ctx.update_tenancy(*component.tenancy());
+            let mut due: HashMap<Option<WorkspacePk>, Vec<ComponentId>> = HashMap::new();
+            for component in state.components.values_mut() {
+                if !component.in_flight && component.next_run_at <= now {
+                    component.in_flight = true;
+                    due.entry(component.workspace_pk)
+                        .or_default()
+                        .push(component.component_id);
+                }
+            }
+            due
+        };
 
-            component.act(&ctx, "refresh").await?;
-            ctx.commit().await?;
+        for (workspace_pk, component_ids) in due {
+            for batch in component_ids.chunks(self.config.batch_size.max(1)) {
+                info!(
+                    ?workspace_pk,
+                    count = batch.len(),
+                    "refreshing resources of components"
+                );
+                let scheduler = self.clone();
+                let batch = batch.to_vec();
+                tokio::spawn(async move { scheduler.refresh(workspace_pk, batch).await });
+            }
         }
-        */
+
         Ok(())
     }
 
-    /// The internal task spawned by `start`. No more frequently than every 30
-    /// seconds, it will iterate over all the components on head in the database and
-    /// schedule them to refresh.
+    /// Looks up what the scheduler needs to know about components it hasn't seen before, with
+    /// one read-only context per workspace. Components whose lookup fails are left out, so
+    /// they're retried on the next tick instead of holding up everything else.
+    async fn track(
+        &self,
+        components: Vec<&Component>,
+    ) -> ResourceSchedulerResult<Vec<ComponentRefreshStatus>> {
+        if components.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut by_workspace: HashMap<Option<WorkspacePk>, (Tenancy, Vec<ComponentId>)> =
+            HashMap::new();
+        for component in &components {
+            by_workspace
+                .entry(component.tenancy().workspace_pk())
+                .or_insert_with(|| (*component.tenancy(), Vec::new()))
+                .1
+                .push(*component.id());
+        }
+
+        let builder = self.services_context.clone().into_builder(false);
+        let mut tracked = Vec::with_capacity(components.len());
+        for (tenancy, component_ids) in by_workspace.into_values() {
+            let mut ctx = builder
+                .build_head(AccessBuilder::new(tenancy, HistoryActor::SystemInit))
+                .await?;
+            ctx.update_with_deleted_visibility();
+
+            let mut schema_variant_ids = Vec::with_capacity(component_ids.len());
+            for component_id in component_ids {
+                match Component::schema_variant_id(&ctx, component_id).await {
+                    Ok(schema_variant_id) => {
+                        schema_variant_ids.push((component_id, schema_variant_id))
+                    }
+                    Err(err) => {
+                        warn!(
+                            error = ?err,
+                            ?component_id,
+                            "unable to look up schema variant of component, skipping it"
+                        );
+                    }
+                }
+            }
+            // Nothing was written, so hand the connection back without committing.
+            ctx.rollback().await?;
+
+            for (component_id, schema_variant_id) in schema_variant_ids {
+                tracked.push(ComponentRefreshStatus {
+                    component_id,
+                    workspace_pk: tenancy.workspace_pk(),
+                    schema_variant_id,
+                    in_flight: false,
+                    next_run_at: Utc::now(),
+                    last_refreshed_at: None,
+                    consecutive_failures: 0,
+                });
+            }
+        }
+
+        Ok(tracked)
+    }
+
+    /// Refreshes a batch of components of a single workspace, and schedules their next refresh
+    /// based on how it went.
+    #[instrument(name = "resource_scheduler.refresh", skip_all, level = "debug")]
+    async fn refresh(&self, workspace_pk: Option<WorkspacePk>, component_ids: Vec<ComponentId>) {
+        let tenancy = match workspace_pk {
+            Some(workspace_pk) => Tenancy::new(workspace_pk),
+            None => Tenancy::new_empty(),
+        };
+        let access_builder = AccessBuilder::new(tenancy, HistoryActor::SystemInit);
+
+        let job = RefreshJob::new(
+            access_builder,
+            Visibility::new_head(false),
+            component_ids.clone(),
+        )
+        .with_priority(JobPriority::Background);
+        let failed: HashSet<ComponentId> = match self
+            .services_context
+            .job_processor()
+            .block_on_job(job)
+            .await
+        {
+            Ok(()) => {
+                let builder = self.services_context.clone().into_builder(false);
+                match failed_refreshes(&builder, access_builder, &component_ids).await {
+                    Ok(failed) => failed,
+                    Err(err) => {
+                        warn!(error = ?err, "unable to check refreshed resources");
+                        HashSet::new()
+                    }
+                }
+            }
+            Err(err) => {
+                warn!(error = ?err, "refresh job failed");
+                component_ids.iter().copied().collect()
+            }
+        };
+
+        let now = Utc::now();
+        let mut state = self.state.lock().await;
+        for component_id in component_ids {
+            if let Some(component) = state.components.get_mut(&component_id) {
+                component.in_flight = false;
+                if failed.contains(&component_id) {
+                    component.consecutive_failures += 1;
+                } else {
+                    component.consecutive_failures = 0;
+                    component.last_refreshed_at = Some(now);
+                }
+                let delay = self
+                    .config
+                    .next_delay(component.schema_variant_id, component.consecutive_failures);
+                component.next_run_at = now + to_chrono(delay);
+            }
+        }
+    }
+
+    /// The internal task spawned by `start`. Every tick, it will look for components on head
+    /// that are due for a refresh and schedule them.
     #[instrument(name = "resource_scheduler.start_task", skip_all, level = "debug")]
     async fn start_task(&self) {
-        let mut interval = time::interval(Duration::from_secs(300));
+        let mut interval = time::interval(self.config.tick_interval());
         loop {
             interval.tick().await;
-            match self.run().await {
-                Ok(()) => {}
-                Err(err) => error!("{err}"),
+            let result = self.run().await;
+            if let Err(err) = &result {
+                error!("{err}");
             }
+            self.state.lock().await.last_error = result.err().map(|err| err.to_string());
         }
     }
 
-    /// Gets a list of all the resources in the database.
+    /// Gets a list of all the components with a resource in the database.
     #[instrument(skip_all, level = "debug")]
     pub async fn components(&self) -> ResourceSchedulerResult<Vec<Component>> {
         let builder = self.services_context.clone().into_builder(false);
@@ -109,14 +422,7 @@ impl ResourceScheduler {
             .txns()
             .await?
             .pg()
-            .query(
-                "SELECT DISTINCT ON (id) id, row_to_json(components.*) as object
-                 FROM components
-                 WHERE is_visible_v1($1, visibility_change_set_pk, visibility_deleted_at)
-                       AND (visibility_deleted_at IS NULL OR needs_destroy)
-                 ORDER BY id",
-                &[ctx.visibility()],
-            )
+            .query(LIST_ALL_WITH_RESOURCES, &[ctx.visibility()])
             .await?;
         let components: Vec<Component> = standard_model::objects_from_rows(rows)?;
 
@@ -124,3 +430,125 @@ impl ResourceScheduler {
         Ok(components)
     }
 }
+
+/// Reports on a started [`ResourceScheduler`].
+#[derive(Debug, Clone)]
+pub struct ResourceSchedulerHandle {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl ResourceSchedulerHandle {
+    /// Reports which components the scheduler knows about and when they will next be refreshed.
+    pub async fn status(&self) -> ResourceSchedulerStatus {
+        let state = self.state.lock().await;
+        let mut components: Vec<ComponentRefreshStatus> =
+            state.components.values().cloned().collect();
+        components.sort_by_key(|component| component.next_run_at);
+
+        ResourceSchedulerStatus {
+            last_tick_at: state.last_tick_at,
+            last_error: state.last_error.clone(),
+            components,
+        }
+    }
+}
+
+/// The components whose resource reported an error after being refreshed.
+async fn failed_refreshes(
+    builder: &DalContextBuilder,
+    access_builder: AccessBuilder,
+    component_ids: &[ComponentId],
+) -> ResourceSchedulerResult<HashSet<ComponentId>> {
+    let mut ctx = builder.build_head(access_builder).await?;
+    ctx.update_with_deleted_visibility();
+
+    let mut failed = HashSet::new();
+    let mut lookup_error = None;
+    for component_id in component_ids {
+        match Component::resource_by_id(&ctx, *component_id).await {
+            Ok(resource) => {
+                if resource.status == ResourceStatus::Error {
+                    failed.insert(*component_id);
+                }
+            }
+            Err(err) => {
+                lookup_error = Some(err);
+                break;
+            }
+        }
+    }
+    // Nothing was written, so hand the connection back without committing.
+    ctx.rollback().await?;
+    if let Some(err) = lookup_error {
+        return Err(err.into());
+    }
+
+    Ok(failed)
+}
+
+fn to_chrono(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or_else(|_| chrono::Duration::max_value())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn next_delay_backs_off_up_to_max() {
+        let schema_variant_id = SchemaVariantId::generate();
+        let config = ResourceSchedulerConfig {
+            refresh_interval_secs: 60,
+            jitter: 0.0,
+            max_backoff_secs: 600,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.next_delay(schema_variant_id, 0),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            config.next_delay(schema_variant_id, 1),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            config.next_delay(schema_variant_id, 3),
+            Duration::from_secs(480)
+        );
+        assert_eq!(
+            config.next_delay(schema_variant_id, 30),
+            Duration::from_secs(600)
+        );
+    }
+
+    #[test]
+    fn tick_interval_is_at_least_a_second() {
+        let config = ResourceSchedulerConfig {
+            tick_interval_secs: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(config.tick_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn schema_variant_interval_overrides_default_and_jitter_stays_in_bounds() {
+        let schema_variant_id = SchemaVariantId::generate();
+        let config = ResourceSchedulerConfig {
+            refresh_interval_secs: 60,
+            schema_variant_refresh_interval_secs: HashMap::from([(schema_variant_id, 100)]),
+            jitter: 0.2,
+            ..Default::default()
+        };
+
+        for _ in 0..100 {
+            let delay = config.next_delay(schema_variant_id, 0);
+            assert!(delay >= Duration::from_secs(80) && delay <= Duration::from_secs(120));
+        }
+        assert_eq!(
+            config.refresh_interval(SchemaVariantId::generate()),
+            Duration::from_secs(60)
+        );
+    }
+}
@@ -0,0 +1,690 @@
+//! This module contains the undo and redo stacks of the edits made in a
+//! [`ChangeSet`](crate::ChangeSet).
+//!
+//! Every user gets their own stacks in every change set. Each [`UndoEntry`] records the
+//! [`UndoOperations`](UndoOperation) of one edit (e.g. deleting a few
+//! [`Components`](crate::Component) at once), which are replayed backwards to undo the edit and
+//! forwards to redo it. Replaying goes through the same dal functions as the original edit, so
+//! [`DependentValuesUpdates`](crate::job::definition::DependentValuesUpdate) are enqueued the
+//! same way.
+//!
+//! Redoing some edits (e.g. creating a [`Connection`]) makes new objects rather than restoring
+//! the ones undone, in which case the ids recorded by every entry of the change set are rewritten
+//! to the new ones.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    attribute::value::AttributeValueResult,
+    edge::{EdgeId, EdgeKind},
+    func::{binding::FuncBindingId, binding_return_value::FuncBindingReturnValueId},
+    job::definition::DependentValuesUpdate,
+    pk, AttributeContext, AttributePrototype, AttributePrototypeError, AttributePrototypeId,
+    AttributeReadContext, AttributeValue, AttributeValueError, AttributeValueId, ChangeSetPk,
+    Component, ComponentError, ComponentId, ComponentType, Connection, DalContext, DiagramError,
+    ExternalProvider, ExternalProviderError, FuncId, HistoryActor, Node, NodeError, NodeId,
+    SocketId, StandardModel, StandardModelError, TransactionsError, UserPk,
+};
+
+/// How many edits a user can undo in a change set.
+const UNDO_STACK_DEPTH: i64 = 100;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum UndoError {
+    #[error("attribute prototype error: {0}")]
+    AttributePrototype(#[from] AttributePrototypeError),
+    #[error("attribute prototype not found: {0}")]
+    AttributePrototypeNotFound(AttributePrototypeId),
+    #[error("attribute prototype not found for attribute value: {0}")]
+    AttributePrototypeNotFoundForValue(AttributeValueId),
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("attribute value not found: {0}")]
+    AttributeValueNotFound(AttributeValueId),
+    #[error("attribute value not found for context: {0:?}")]
+    AttributeValueNotFoundForContext(AttributeReadContext),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("component not found for node: {0}")]
+    ComponentNotFoundForNode(NodeId),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] DiagramError),
+    #[error("external provider error: {0}")]
+    ExternalProvider(#[from] ExternalProviderError),
+    #[error("external provider not found for socket: {0}")]
+    ExternalProviderNotFoundForSocket(SocketId),
+    #[error("edits made on head cannot be undone")]
+    Head,
+    #[error("node error: {0}")]
+    Node(#[from] NodeError),
+    #[error("node not found: {0}")]
+    NodeNotFound(NodeId),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type UndoResult<T> = Result<T, UndoError>;
+
+pk!(UndoEntryPk);
+
+/// What an [`AttributeValue`] is computed from: the [`AttributePrototype`] it belongs to and the
+/// func binding it was last computed with.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueSource {
+    attribute_prototype_id: AttributePrototypeId,
+    func_id: FuncId,
+    func_binding_id: FuncBindingId,
+    func_binding_return_value_id: FuncBindingReturnValueId,
+    sealed_proxy: bool,
+}
+
+impl AttributeValueSource {
+    /// Captures the source of an [`AttributeValue`] before it is set.
+    pub async fn of(
+        ctx: &DalContext,
+        attribute_value: &AttributeValue,
+    ) -> AttributeValueResult<Self> {
+        let attribute_prototype = attribute_value.attribute_prototype(ctx).await?.ok_or(
+            AttributeValueError::AttributePrototypeNotFound(
+                *attribute_value.id(),
+                *ctx.visibility(),
+            ),
+        )?;
+        Ok(Self {
+            attribute_prototype_id: *attribute_prototype.id(),
+            func_id: attribute_prototype.func_id(),
+            func_binding_id: attribute_value.func_binding_id(),
+            func_binding_return_value_id: attribute_value.func_binding_return_value_id(),
+            sealed_proxy: attribute_value.sealed_proxy(),
+        })
+    }
+
+    /// Puts the source back on an [`AttributeValue`].
+    ///
+    /// Setting a value either points the prototype of the [`AttributeValue`] to a "si:set*"
+    /// func, or gives the value a new prototype if the one it had is for a less specific
+    /// [`AttributeContext`]. The func is restored in the former case and the new prototype is
+    /// removed in the latter.
+    async fn restore(
+        &self,
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+    ) -> UndoResult<()> {
+        let mut attribute_value = AttributeValue::get_by_id(ctx, &attribute_value_id)
+            .await?
+            .ok_or(UndoError::AttributeValueNotFound(attribute_value_id))?;
+        let mut attribute_prototype =
+            AttributePrototype::get_by_id(ctx, &self.attribute_prototype_id)
+                .await?
+                .ok_or(UndoError::AttributePrototypeNotFound(
+                    self.attribute_prototype_id,
+                ))?;
+        if attribute_prototype.func_id() != self.func_id {
+            attribute_prototype.set_func_id(ctx, self.func_id).await?;
+        }
+
+        let edited_prototype = attribute_value.attribute_prototype(ctx).await?;
+        attribute_value
+            .set_attribute_prototype(ctx, &self.attribute_prototype_id)
+            .await?;
+        if let Some(edited_prototype) = edited_prototype {
+            if *edited_prototype.id() != self.attribute_prototype_id {
+                AttributePrototype::remove(ctx, edited_prototype.id(), false).await?;
+            }
+        }
+
+        attribute_value
+            .set_func_binding_id(ctx, self.func_binding_id)
+            .await?;
+        attribute_value
+            .set_func_binding_return_value_id(ctx, self.func_binding_return_value_id)
+            .await?;
+        attribute_value
+            .set_sealed_proxy(ctx, self.sealed_proxy)
+            .await?;
+
+        ctx.enqueue_job(DependentValuesUpdate::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            vec![attribute_value_id],
+        ))
+        .await?;
+        Ok(())
+    }
+}
+
+/// Where a [`Node`] sits on the diagram, along with its size if it is a frame.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodePosition {
+    pub x: String,
+    pub y: String,
+    pub width: Option<String>,
+    pub height: Option<String>,
+}
+
+impl NodePosition {
+    pub fn of(node: &Node) -> Self {
+        Self {
+            x: node.x().to_owned(),
+            y: node.y().to_owned(),
+            width: node.width().map(ToOwned::to_owned),
+            height: node.height().map(ToOwned::to_owned),
+        }
+    }
+}
+
+/// A single edit, along with what is needed to revert it.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum UndoOperation {
+    /// A child [`Component`] was put inside a frame (see [`Connection::connect_to_frame()`]).
+    /// Undoing deletes every edge created, frame connection first, redoing connects the child
+    /// anew.
+    #[serde(rename_all = "camelCase")]
+    ConnectComponentToFrame {
+        parent_node_id: NodeId,
+        child_node_id: NodeId,
+        edge_ids: Vec<EdgeId>,
+    },
+    /// A [`Component`] was created. Undoing deletes it, redoing restores it.
+    #[serde(rename_all = "camelCase")]
+    CreateComponent { component_id: ComponentId },
+    /// A [`Connection`] was created. Undoing deletes it, redoing creates an identical one.
+    #[serde(rename_all = "camelCase")]
+    CreateConnection {
+        edge_id: EdgeId,
+        from_node_id: NodeId,
+        from_socket_id: SocketId,
+        to_node_id: NodeId,
+        to_socket_id: SocketId,
+    },
+    /// A [`Component`] was deleted. Undoing restores it, redoing deletes it again.
+    #[serde(rename_all = "camelCase")]
+    DeleteComponent { component_id: ComponentId },
+    /// A [`Connection`] was deleted. Undoing restores it, redoing deletes it again.
+    #[serde(rename_all = "camelCase")]
+    DeleteConnection { edge_id: EdgeId },
+    /// An element was inserted in an array or a map. Undoing removes it, redoing inserts an
+    /// identical one.
+    #[serde(rename_all = "camelCase")]
+    InsertAttributeValue {
+        attribute_value_id: AttributeValueId,
+        parent_attribute_value_id: AttributeValueId,
+        context: AttributeContext,
+        value: Option<Value>,
+        key: Option<String>,
+    },
+    /// A deleted [`Component`] was restored. Undoing deletes it again, redoing restores it.
+    #[serde(rename_all = "camelCase")]
+    RestoreComponent { component_id: ComponentId },
+    /// A deleted [`Connection`] was restored. Undoing deletes it again, redoing restores it.
+    #[serde(rename_all = "camelCase")]
+    RestoreConnection { edge_id: EdgeId },
+    /// The value of an [`AttributeValue`] was set. Undoing puts back what the value was computed
+    /// from, be it a function or a value set by a user, redoing sets it again.
+    #[serde(rename_all = "camelCase")]
+    SetAttributeValue {
+        attribute_value_id: AttributeValueId,
+        parent_attribute_value_id: Option<AttributeValueId>,
+        context: AttributeContext,
+        key: Option<String>,
+        before: AttributeValueSource,
+        after: Option<Value>,
+    },
+    /// The [`ComponentType`] of a [`Component`] was set.
+    #[serde(rename_all = "camelCase")]
+    SetComponentType {
+        component_id: ComponentId,
+        before: ComponentType,
+        after: ComponentType,
+    },
+    /// A [`Node`] was moved or resized.
+    #[serde(rename_all = "camelCase")]
+    SetNodePosition {
+        node_id: NodeId,
+        before: NodePosition,
+        after: NodePosition,
+    },
+}
+
+impl UndoOperation {
+    /// Reverts the edit.
+    async fn undo(&mut self, ctx: &DalContext) -> UndoResult<()> {
+        match self {
+            Self::ConnectComponentToFrame { edge_ids, .. } => {
+                for edge_id in edge_ids.iter() {
+                    Connection::delete_for_frame_edge(ctx, *edge_id).await?;
+                }
+                Ok(())
+            }
+            Self::CreateComponent { component_id } | Self::RestoreComponent { component_id } => {
+                delete_component(ctx, *component_id).await
+            }
+            Self::CreateConnection { edge_id, .. } | Self::RestoreConnection { edge_id } => {
+                Ok(Connection::delete_for_edge(ctx, *edge_id).await?)
+            }
+            Self::DeleteComponent { component_id } => {
+                Component::restore_and_propagate(ctx, *component_id).await?;
+                Ok(())
+            }
+            Self::DeleteConnection { edge_id } => {
+                Ok(Connection::restore_for_edge(ctx, *edge_id).await?)
+            }
+            Self::InsertAttributeValue {
+                attribute_value_id,
+                parent_attribute_value_id,
+                ..
+            } => remove_element(ctx, *attribute_value_id, *parent_attribute_value_id).await,
+            Self::SetAttributeValue {
+                attribute_value_id,
+                before,
+                ..
+            } => before.restore(ctx, *attribute_value_id).await,
+            Self::SetComponentType {
+                component_id,
+                before,
+                ..
+            } => set_component_type(ctx, *component_id, *before).await,
+            Self::SetNodePosition {
+                node_id, before, ..
+            } => set_node_position(ctx, *node_id, before).await,
+        }
+    }
+
+    /// Makes the edit again, after it has been undone. Returns the `(old, new)` ids of what was
+    /// made anew rather than restored.
+    async fn redo(&mut self, ctx: &DalContext) -> UndoResult<Vec<(String, String)>> {
+        match self {
+            Self::ConnectComponentToFrame {
+                parent_node_id,
+                child_node_id,
+                edge_ids,
+            } => {
+                // Sockets are listed by id, so they are connected in the same order every time
+                // and the new edges line up with the old ones
+                let (_, new_edge_ids) =
+                    Connection::connect_to_frame(ctx, *parent_node_id, *child_node_id).await?;
+                let replaced_ids = edge_ids
+                    .iter()
+                    .zip(&new_edge_ids)
+                    .map(|(old, new)| (old.to_string(), new.to_string()))
+                    .collect();
+                *edge_ids = new_edge_ids;
+                Ok(replaced_ids)
+            }
+            Self::CreateComponent { component_id } | Self::RestoreComponent { component_id } => {
+                Component::restore_and_propagate(ctx, *component_id).await?;
+                Ok(vec![])
+            }
+            Self::CreateConnection {
+                edge_id,
+                from_node_id,
+                from_socket_id,
+                to_node_id,
+                to_socket_id,
+            } => {
+                // Restoring an edge created in this change set would hard delete it (see
+                // `Edge::restore_by_id()`), so a new one takes its place
+                let connection = Connection::new(
+                    ctx,
+                    *from_node_id,
+                    *from_socket_id,
+                    *to_node_id,
+                    *to_socket_id,
+                    EdgeKind::Configuration,
+                )
+                .await?;
+                let replaced_id = (edge_id.to_string(), connection.id.to_string());
+                *edge_id = connection.id;
+
+                let from_component = Component::find_for_node(ctx, *from_node_id)
+                    .await?
+                    .ok_or(UndoError::ComponentNotFoundForNode(*from_node_id))?;
+                let external_provider = ExternalProvider::find_for_socket(ctx, *from_socket_id)
+                    .await?
+                    .ok_or(UndoError::ExternalProviderNotFoundForSocket(
+                        *from_socket_id,
+                    ))?;
+                let read_context = AttributeReadContext {
+                    external_provider_id: Some(*external_provider.id()),
+                    component_id: Some(*from_component.id()),
+                    ..Default::default()
+                };
+                let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+                    .await?
+                    .ok_or(UndoError::AttributeValueNotFoundForContext(read_context))?;
+                ctx.enqueue_job(DependentValuesUpdate::new(
+                    ctx.access_builder(),
+                    *ctx.visibility(),
+                    vec![*attribute_value.id()],
+                ))
+                .await?;
+                Ok(vec![replaced_id])
+            }
+            Self::DeleteComponent { component_id } => {
+                delete_component(ctx, *component_id).await?;
+                Ok(vec![])
+            }
+            Self::DeleteConnection { edge_id } => {
+                Connection::delete_for_edge(ctx, *edge_id).await?;
+                Ok(vec![])
+            }
+            Self::InsertAttributeValue {
+                attribute_value_id,
+                parent_attribute_value_id,
+                context,
+                value,
+                key,
+            } => {
+                let new_attribute_value_id = AttributeValue::insert_for_context(
+                    ctx,
+                    *context,
+                    *parent_attribute_value_id,
+                    value.clone(),
+                    key.clone(),
+                )
+                .await?;
+                let replaced_id = (
+                    attribute_value_id.to_string(),
+                    new_attribute_value_id.to_string(),
+                );
+                *attribute_value_id = new_attribute_value_id;
+                Ok(vec![replaced_id])
+            }
+            Self::RestoreConnection { edge_id } => {
+                Connection::restore_for_edge(ctx, *edge_id).await?;
+                Ok(vec![])
+            }
+            Self::SetAttributeValue {
+                attribute_value_id,
+                parent_attribute_value_id,
+                context,
+                key,
+                before,
+                after,
+            } => {
+                // What the value is computed from may have changed since the edit was undone
+                let attribute_value = AttributeValue::get_by_id(ctx, attribute_value_id)
+                    .await?
+                    .ok_or(UndoError::AttributeValueNotFound(*attribute_value_id))?;
+                *before = AttributeValueSource::of(ctx, &attribute_value).await?;
+
+                let (_, updated_attribute_value_id) = AttributeValue::update_for_context(
+                    ctx,
+                    *attribute_value_id,
+                    *parent_attribute_value_id,
+                    *context,
+                    after.clone(),
+                    key.clone(),
+                )
+                .await?;
+                *attribute_value_id = updated_attribute_value_id;
+                Ok(vec![])
+            }
+            Self::SetComponentType {
+                component_id,
+                after,
+                ..
+            } => {
+                set_component_type(ctx, *component_id, *after).await?;
+                Ok(vec![])
+            }
+            Self::SetNodePosition { node_id, after, .. } => {
+                set_node_position(ctx, *node_id, after).await?;
+                Ok(vec![])
+            }
+        }
+    }
+}
+
+async fn delete_component(ctx: &DalContext, component_id: ComponentId) -> UndoResult<()> {
+    let mut component = Component::get_by_id(ctx, &component_id)
+        .await?
+        .ok_or(UndoError::ComponentNotFound(component_id))?;
+    component.delete_and_propagate(ctx).await?;
+    Ok(())
+}
+
+/// Removes an element inserted in an array or a map, along with its own children.
+async fn remove_element(
+    ctx: &DalContext,
+    attribute_value_id: AttributeValueId,
+    parent_attribute_value_id: AttributeValueId,
+) -> UndoResult<()> {
+    let attribute_value = AttributeValue::get_by_id(ctx, &attribute_value_id)
+        .await?
+        .ok_or(UndoError::AttributeValueNotFound(attribute_value_id))?;
+    let attribute_prototype = attribute_value.attribute_prototype(ctx).await?.ok_or(
+        UndoError::AttributePrototypeNotFoundForValue(attribute_value_id),
+    )?;
+    AttributePrototype::remove(ctx, attribute_prototype.id(), false).await?;
+
+    let mut parent_attribute_value = AttributeValue::get_by_id(ctx, &parent_attribute_value_id)
+        .await?
+        .ok_or(UndoError::AttributeValueNotFound(parent_attribute_value_id))?;
+    let mut index_map = parent_attribute_value.index_map.clone();
+    if let Some(index_map) = index_map.as_mut() {
+        index_map.remove(attribute_value_id);
+    }
+    parent_attribute_value.set_index_map(ctx, index_map).await?;
+
+    ctx.enqueue_job(DependentValuesUpdate::new(
+        ctx.access_builder(),
+        *ctx.visibility(),
+        vec![parent_attribute_value_id],
+    ))
+    .await?;
+    Ok(())
+}
+
+async fn set_component_type(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    component_type: ComponentType,
+) -> UndoResult<()> {
+    let component = Component::get_by_id(ctx, &component_id)
+        .await?
+        .ok_or(UndoError::ComponentNotFound(component_id))?;
+    component.set_type(ctx, component_type).await?;
+    Ok(())
+}
+
+async fn set_node_position(
+    ctx: &DalContext,
+    node_id: NodeId,
+    position: &NodePosition,
+) -> UndoResult<()> {
+    // Nodes of deleted components can be moved around too
+    let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+    let mut node = Node::get_by_id(ctx_with_deleted, &node_id)
+        .await?
+        .ok_or(UndoError::NodeNotFound(node_id))?;
+    let ctx = if node.visibility().deleted_at.is_some() {
+        ctx_with_deleted
+    } else {
+        ctx
+    };
+    node.set_geometry(
+        ctx,
+        &position.x,
+        &position.y,
+        position.width.as_deref(),
+        position.height.as_deref(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// One edit on the undo (or redo) stack of a user in a [`ChangeSet`](crate::ChangeSet).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct UndoEntry {
+    pk: UndoEntryPk,
+    change_set_pk: ChangeSetPk,
+    user_pk: Option<UserPk>,
+    operations: Vec<UndoOperation>,
+    undone: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl UndoEntry {
+    pub fn pk(&self) -> UndoEntryPk {
+        self.pk
+    }
+
+    pub fn change_set_pk(&self) -> ChangeSetPk {
+        self.change_set_pk
+    }
+
+    pub fn user_pk(&self) -> Option<UserPk> {
+        self.user_pk
+    }
+
+    pub fn operations(&self) -> &[UndoOperation] {
+        &self.operations
+    }
+
+    /// Whether the entry sits on the redo stack.
+    pub fn undone(&self) -> bool {
+        self.undone
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Records an edit made with the [`DalContext`] on top of the undo stack of its user, which
+    /// clears their redo stack. Nothing is recorded for an edit without operations.
+    #[instrument(skip_all)]
+    pub async fn record(
+        ctx: &DalContext,
+        operations: Vec<UndoOperation>,
+    ) -> UndoResult<Option<Self>> {
+        if operations.is_empty() {
+            return Ok(None);
+        }
+        let change_set_pk = Self::change_set_pk_for(ctx)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM undo_entry_record_v1($1, $2, $3, $4)",
+                &[
+                    &change_set_pk,
+                    &Self::user_pk_for(ctx),
+                    &serde_json::to_value(&operations)?,
+                    &UNDO_STACK_DEPTH,
+                ],
+            )
+            .await?;
+        let json: Value = row.try_get("object")?;
+        Ok(Some(serde_json::from_value(json)?))
+    }
+
+    /// Reverts the most recent edit on the undo stack of the user of the [`DalContext`] and moves
+    /// it over to their redo stack. Returns [`None`] if there is nothing to undo.
+    #[instrument(skip_all)]
+    pub async fn undo(ctx: &DalContext) -> UndoResult<Option<Self>> {
+        let mut entry = match Self::top(ctx, false).await? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        for operation in entry.operations.iter_mut().rev() {
+            operation.undo(ctx).await?;
+        }
+        Ok(Some(entry.set_undone(ctx, true).await?))
+    }
+
+    /// Makes the most recently undone edit of the user of the [`DalContext`] again and moves it
+    /// back to their undo stack. Returns [`None`] if there is nothing to redo.
+    #[instrument(skip_all)]
+    pub async fn redo(ctx: &DalContext) -> UndoResult<Option<Self>> {
+        let mut entry = match Self::top(ctx, true).await? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let mut replaced_ids = Vec::new();
+        for operation in entry.operations.iter_mut() {
+            replaced_ids.extend(operation.redo(ctx).await?);
+        }
+        // The edits made after this one still refer to what was made anew by its old ids
+        for (old_id, new_id) in replaced_ids {
+            let _rows = ctx
+                .txns()
+                .await?
+                .pg()
+                .query(
+                    "SELECT undo_entry_replace_id_v1($1, $2, $3)",
+                    &[&entry.change_set_pk, &old_id, &new_id],
+                )
+                .await?;
+        }
+        Ok(Some(entry.set_undone(ctx, false).await?))
+    }
+
+    /// Returns the top entry of the undo stack, or of the redo stack if `undone` is true.
+    async fn top(ctx: &DalContext, undone: bool) -> UndoResult<Option<Self>> {
+        let change_set_pk = Self::change_set_pk_for(ctx)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM undo_entry_top_v1($1, $2, $3)",
+                &[&change_set_pk, &Self::user_pk_for(ctx), &undone],
+            )
+            .await?;
+        let maybe_json: Option<Value> = row.try_get("object")?;
+        match maybe_json {
+            Some(json) => Ok(Some(serde_json::from_value(json)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set_undone(self, ctx: &DalContext, undone: bool) -> UndoResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM undo_entry_set_undone_v1($1, $2, $3)",
+                &[&self.pk, &undone, &serde_json::to_value(&self.operations)?],
+            )
+            .await?;
+        let json: Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    fn change_set_pk_for(ctx: &DalContext) -> UndoResult<ChangeSetPk> {
+        if ctx.visibility().is_head() {
+            return Err(UndoError::Head);
+        }
+        Ok(ctx.visibility().change_set_pk)
+    }
+
+    fn user_pk_for(ctx: &DalContext) -> Option<UserPk> {
+        match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        }
+    }
+}
@@ -137,6 +137,40 @@ impl User {
             .await?;
         Ok(())
     }
+
+    /// Makes this user an admin of the workspace, associating them with it if need be.
+    pub async fn grant_workspace_admin(
+        &self,
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "SELECT user_grant_workspace_admin_v1($1, $2)",
+                &[&self.pk, &workspace_pk],
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_workspace_admin(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<bool> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT user_is_workspace_admin_v1($1, $2) AS is_admin",
+                &[&user_pk, &workspace_pk],
+            )
+            .await?;
+        Ok(row.try_get("is_admin")?)
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
@@ -125,6 +125,7 @@ impl Workspace {
             None::<&str>,
         )
         .await?;
+        user.grant_workspace_admin(ctx, *workspace.pk()).await?;
         ctx.update_history_actor(HistoryActor::User(user.pk()));
 
         ctx.import_builtins().await?;
@@ -1,5 +1,13 @@
-use dal::{ChangeSet, ChangeSetStatus, DalContext, Visibility};
-use dal_test::{helpers::create_change_set, test, DalContextHeadMutRef, DalContextHeadRef};
+use dal::change_status::ChangeStatus;
+use dal::component::ComponentKind;
+use dal::{
+    ChangeSet, ChangeSetConflictKind, ChangeSetError, ChangeSetStatus, ConflictResolution,
+    DalContext, Func, FuncBackendKind, FuncBackendResponseType, Schema, StandardModel, Visibility,
+};
+use dal_test::{
+    helpers::{create_change_set, create_visibility_for_change_set},
+    test, DalContextHeadMutRef, DalContextHeadRef,
+};
 
 #[test]
 async fn new(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
@@ -75,6 +83,38 @@ async fn list_open(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
     );
 }
 
+#[test]
+async fn only_open_change_sets_apply(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let mut abandoned = create_change_set(ctx).await;
+    abandoned.abandon(ctx).await.expect("cannot abandon");
+    assert!(matches!(
+        abandoned.apply(ctx).await,
+        Err(ChangeSetError::NotOpen(pk)) if pk == abandoned.pk
+    ));
+
+    let mut expired = create_change_set(ctx).await;
+    expired
+        .set_expires_at(ctx, Some(chrono::Utc::now()))
+        .await
+        .expect("cannot set expiry");
+    assert!(matches!(
+        expired.apply(ctx).await,
+        Err(ChangeSetError::Expired(pk)) if pk == expired.pk
+    ));
+
+    // A copy fetched before the change set was abandoned is refused by the database.
+    let mut change_set = create_change_set(ctx).await;
+    let mut stale = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set")
+        .expect("change set not found");
+    change_set.abandon(ctx).await.expect("cannot abandon");
+    assert!(matches!(
+        stale.apply(ctx).await,
+        Err(ChangeSetError::NotOpen(pk)) if pk == change_set.pk
+    ));
+}
+
 #[test]
 async fn get_by_pk(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
     let change_set = create_change_set(ctx).await;
@@ -84,3 +124,154 @@ async fn get_by_pk(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
         .expect("change set pk should exist");
     assert_eq!(&change_set, &result);
 }
+
+#[test]
+async fn conflicts_with_head(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut head_schema = Schema::new(ctx, "mastodon", &ComponentKind::Standard)
+        .await
+        .expect("cannot create schema");
+
+    let change_set = create_change_set(ctx).await;
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(create_visibility_for_change_set(&change_set));
+    let mut change_set_schema = Schema::get_by_id(&change_set_ctx, head_schema.id())
+        .await
+        .expect("cannot get schema")
+        .expect("schema not found");
+    change_set_schema
+        .set_name(&change_set_ctx, "gojira")
+        .await
+        .expect("cannot set name in change set");
+    assert!(change_set
+        .conflicts(ctx)
+        .await
+        .expect("cannot list conflicts")
+        .is_empty());
+
+    head_schema
+        .set_name(ctx, "baroness")
+        .await
+        .expect("cannot set name on head");
+    let conflicts = change_set
+        .conflicts(ctx)
+        .await
+        .expect("cannot list conflicts");
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].table_name, "schemas");
+    assert_eq!(conflicts[0].object_id, head_schema.id().to_string());
+    assert_eq!(conflicts[0].kind, ChangeSetConflictKind::Modified);
+
+    change_set
+        .resolve_conflict(
+            ctx,
+            &conflicts[0].table_name,
+            &conflicts[0].object_id,
+            ConflictResolution::TakeHead,
+        )
+        .await
+        .expect("cannot resolve conflict");
+    assert!(change_set
+        .conflicts(ctx)
+        .await
+        .expect("cannot list conflicts")
+        .is_empty());
+
+    let schema = Schema::get_by_id(&change_set_ctx, head_schema.id())
+        .await
+        .expect("cannot get schema")
+        .expect("schema not found");
+    assert_eq!(schema.name(), "baroness");
+}
+
+#[test]
+async fn abandon_and_collect_garbage(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(create_visibility_for_change_set(&change_set));
+    let schema = Schema::new(&change_set_ctx, "mastodon", &ComponentKind::Standard)
+        .await
+        .expect("cannot create schema");
+
+    change_set.abandon(ctx).await.expect("cannot abandon");
+    assert_eq!(change_set.status, ChangeSetStatus::Abandoned);
+    assert!(change_set.abandon(ctx).await.is_err());
+    assert!(ChangeSet::list_open(ctx)
+        .await
+        .expect("cannot list open change sets")
+        .iter()
+        .all(|item| item.value != change_set.pk));
+
+    let report = ChangeSet::collect_garbage(ctx)
+        .await
+        .expect("cannot collect garbage");
+    assert!(report.reclaimed_rows.get("schemas").copied().unwrap_or(0) >= 1);
+    assert!(Schema::get_by_id(&change_set_ctx, schema.id())
+        .await
+        .expect("cannot get schema")
+        .is_none());
+}
+
+#[test]
+async fn expired_change_sets_are_abandoned(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+    change_set
+        .set_expires_at(ctx, Some(chrono::Utc::now()))
+        .await
+        .expect("cannot set expiry");
+
+    let report = ChangeSet::collect_garbage(ctx)
+        .await
+        .expect("cannot collect garbage");
+    assert!(report
+        .expired
+        .iter()
+        .any(|(change_set_pk, _)| *change_set_pk == change_set.pk));
+
+    let change_set = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set")
+        .expect("change set not found");
+    assert_eq!(change_set.status, ChangeSetStatus::Abandoned);
+}
+
+#[test]
+async fn diff_func_code(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut head_func = Func::new(
+        ctx,
+        "test:diffFuncCode",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create func");
+    head_func
+        .set_code_plaintext(ctx, Some("function main() { return 'canoe'; }"))
+        .await
+        .expect("cannot set code on head");
+
+    let change_set = create_change_set(ctx).await;
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(create_visibility_for_change_set(&change_set));
+    let mut func = Func::get_by_id(&change_set_ctx, head_func.id())
+        .await
+        .expect("cannot get func")
+        .expect("func not found");
+    func.set_code_plaintext(&change_set_ctx, Some("function main() { return 'kayak'; }"))
+        .await
+        .expect("cannot set code in change set");
+
+    let diff = change_set.diff(ctx).await.expect("cannot diff change set");
+    assert_eq!(diff.funcs.len(), 1);
+    assert_eq!(diff.funcs[0].func_id, *head_func.id());
+    assert_eq!(diff.funcs[0].status, ChangeStatus::Modified);
+    assert_eq!(
+        diff.funcs[0].old_code.as_deref(),
+        Some("function main() { return 'canoe'; }")
+    );
+    assert_eq!(
+        diff.funcs[0].new_code.as_deref(),
+        Some("function main() { return 'kayak'; }")
+    );
+    assert!(diff.funcs[0].columns.is_empty());
+    assert_eq!(diff.touched_rows.get("funcs"), Some(&1));
+}
@@ -23,6 +23,7 @@ mod code;
 mod confirmation;
 mod qualification;
 mod resource;
+mod template;
 mod validation;
 mod view;
 
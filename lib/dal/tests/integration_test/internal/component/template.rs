@@ -0,0 +1,177 @@
+use dal::edge::EdgeKind;
+use dal::{
+    prop::PropPath, property_editor::values::PropertyEditorValues, socket::SocketEdgeKind,
+    AttributeReadContext, AttributeValue, Component, ComponentTemplate, Connection, DalContext,
+    Edge, Node, Prop, Socket, StandardModel,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use si_pkg::TemplateEdgeKind;
+
+#[test]
+async fn save_and_instantiate_template(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+
+    for (node_id, x, y) in [
+        (fallout_bag.node_id, "100", "200"),
+        (starfield_bag.node_id, "600", "250"),
+    ] {
+        Node::get_by_id(ctx, &node_id)
+            .await
+            .expect("could not find node")
+            .expect("node not found")
+            .set_geometry(ctx, x, y, Some("500"), Some("500"))
+            .await
+            .expect("cannot set node geometry");
+    }
+
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        *output_socket.id(),
+        starfield_bag.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    PropertyEditorValues::update_for_components(
+        ctx,
+        &PropPath::new(["root", "domain", "special"]),
+        &[fallout_bag.component_id],
+        Some(serde_json::json!["foo"]),
+    )
+    .await
+    .expect("could not update value");
+    let freestar_path = PropPath::new(["root", "domain", "freestar"]);
+    PropertyEditorValues::update_for_components(
+        ctx,
+        &freestar_path,
+        &[starfield_bag.component_id],
+        Some(serde_json::json!["collective"]),
+    )
+    .await
+    .expect("could not update value");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let template = ComponentTemplate::new_from_components(
+        ctx,
+        "bethesda games",
+        None,
+        &[fallout_bag.component_id, starfield_bag.component_id],
+    )
+    .await
+    .expect("could not save template");
+
+    assert_eq!(2, template.components().len());
+    let starfield = template
+        .components()
+        .iter()
+        .find(|component| component.schema == "starfield")
+        .expect("starfield not in template");
+    assert_eq!((500, 50), (starfield.x, starfield.y));
+    assert_eq!(1, starfield.values.len());
+    assert_eq!(
+        vec!["root", "domain", "freestar"],
+        starfield.values[0].path.clone()
+    );
+    assert_eq!(serde_json::json!["collective"], starfield.values[0].value);
+    assert_eq!(1, template.edges().len());
+    assert_eq!(TemplateEdgeKind::Configuration, template.edges()[0].kind);
+
+    let instance = template
+        .instantiate(ctx, 1000, 1000)
+        .await
+        .expect("could not instantiate template");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    assert_eq!(2, instance.components.len());
+    let (new_starfield_id, new_starfield_node_id) = instance.components[1];
+    assert_ne!(starfield_bag.component_id, new_starfield_id);
+    let new_starfield_node = Node::get_by_id(ctx, &new_starfield_node_id)
+        .await
+        .expect("could not find node")
+        .expect("node not found");
+    assert_eq!(
+        ("1500", "1050"),
+        (new_starfield_node.x(), new_starfield_node.y())
+    );
+
+    let schema_variant_id = Component::schema_variant_id(ctx, new_starfield_id)
+        .await
+        .expect("could not get schema variant id");
+    let freestar_prop = Prop::find_prop_by_path(ctx, schema_variant_id, &freestar_path)
+        .await
+        .expect("could not find prop");
+    let value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*freestar_prop.id()),
+            component_id: Some(new_starfield_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("could not perform find for context")
+    .expect("attribute value not found")
+    .get_value(ctx)
+    .await
+    .expect("could not get value");
+    assert_eq!(Some(serde_json::json!["collective"]), value);
+
+    // The value of the fallout flows through the connection
+    let attributes_prop = Prop::find_prop_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "attributes"]),
+    )
+    .await
+    .expect("could not find prop");
+    let connected_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*attributes_prop.id()),
+            component_id: Some(new_starfield_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("could not perform find for context")
+    .expect("attribute value not found")
+    .get_value(ctx)
+    .await
+    .expect("could not get value");
+    assert_eq!(Some(serde_json::json!["foo"]), connected_value);
+
+    let edges = Edge::list_for_component(ctx, new_starfield_id)
+        .await
+        .expect("could not list edges");
+    assert_eq!(1, edges.len());
+    assert_eq!(instance.components[0].1, edges[0].tail_node_id());
+}
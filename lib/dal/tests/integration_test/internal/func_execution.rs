@@ -3,13 +3,14 @@ use dal::{
         backend::string::FuncBackendStringArgs,
         execution::{FuncExecution, FuncExecutionState},
     },
-    DalContext, StandardModel,
+    DalContext, EncryptedSecret, Func, FuncBackendKind, FuncBackendResponseType, FuncBinding,
+    SecretAlgorithm, SecretKind, SecretObjectType, SecretVersion, StandardModel, WorkspaceSignup,
 };
 use dal_test::{
     test,
-    test_harness::{create_func, create_func_binding},
+    test_harness::{create_func, create_func_binding, encrypt_message, generate_fake_name},
 };
-use veritech_client::OutputStream;
+use veritech_client::{OutputStream, REDACTED};
 
 #[test]
 async fn new(ctx: &DalContext) {
@@ -100,6 +101,146 @@ async fn process_return_value(ctx: &DalContext) {
     );
 }
 
+#[test]
+async fn postprocess_execution_redacts_secrets(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let token = format!("token-{}", generate_fake_name());
+    let secret = EncryptedSecret::new(
+        ctx,
+        generate_fake_name(),
+        SecretObjectType::Credential,
+        SecretKind::ApiToken,
+        &encrypt_message(
+            ctx,
+            nw.key_pair.pk(),
+            &serde_json::json!({ "token": token }),
+        )
+        .await,
+        nw.key_pair.pk(),
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await
+    .expect("cannot create secret");
+
+    let func = Func::new(
+        ctx,
+        generate_fake_name(),
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create func");
+    let func_binding = create_func_binding(
+        ctx,
+        serde_json::json!({ "secret": secret.id().to_string() }),
+        *func.id(),
+        *func.backend_kind(),
+    )
+    .await;
+    let execution = FuncExecution::new(ctx, &func, &func_binding)
+        .await
+        .expect("cannot create a new func execution");
+
+    let leaked = serde_json::json!(format!("Bearer {token}"));
+    let func_binding_return_value = func_binding
+        .postprocess_execution(
+            ctx,
+            vec![OutputStream {
+                stream: "stdout".to_string(),
+                execution_id: "foo".to_string(),
+                level: "info".to_string(),
+                group: None,
+                message: format!("calling the api with {token}"),
+                timestamp: 1865,
+            }],
+            &func,
+            (Some(leaked.clone()), Some(leaked)),
+            execution,
+        )
+        .await
+        .expect("cannot postprocess execution");
+
+    let redacted = serde_json::json!(format!("Bearer {REDACTED}"));
+    assert_eq!(func_binding_return_value.value(), Some(&redacted));
+    assert_eq!(
+        func_binding_return_value.unprocessed_value(),
+        Some(&redacted)
+    );
+
+    let output_stream = func_binding_return_value
+        .get_output_stream(ctx)
+        .await
+        .expect("cannot get output stream")
+        .expect("has an output stream");
+    assert_eq!(
+        vec![format!("calling the api with {REDACTED}")],
+        output_stream
+            .iter()
+            .map(|output| output.message.clone())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+async fn execute_memoized_reuses_identical_executions(ctx: &DalContext) {
+    let mut func = Func::new(
+        ctx,
+        generate_fake_name(),
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create func");
+    func.set_code_plaintext(
+        ctx,
+        Some("function greet(input) { return `${input.greeting}, ${input.name}`; }"),
+    )
+    .await
+    .expect("cannot set code");
+    func.set_handler(ctx, Some("greet"))
+        .await
+        .expect("cannot set handler");
+
+    let before = ctx.func_execution_cache().stats().await;
+    let (_, first) = FuncBinding::create_and_execute_memoized(
+        ctx,
+        serde_json::json!({ "greeting": "hail", "name": "odin" }),
+        *func.id(),
+    )
+    .await
+    .expect("cannot execute func");
+    // The same arguments, in a different order, hit the cache
+    let (_, second) = FuncBinding::create_and_execute_memoized(
+        ctx,
+        serde_json::json!({ "name": "odin", "greeting": "hail" }),
+        *func.id(),
+    )
+    .await
+    .expect("cannot execute func");
+    let after = ctx.func_execution_cache().stats().await;
+
+    assert_eq!(Some(&serde_json::json!("hail, odin")), first.value());
+    assert_eq!(first.value(), second.value());
+    assert_eq!(before.hits + 1, after.hits);
+    assert_eq!(before.misses + 1, after.misses);
+
+    // Funcs which are not memoizable always run
+    func.set_memoizable(ctx, false)
+        .await
+        .expect("cannot opt out of memoization");
+    FuncBinding::create_and_execute_memoized(
+        ctx,
+        serde_json::json!({ "greeting": "hail", "name": "odin" }),
+        *func.id(),
+    )
+    .await
+    .expect("cannot execute func");
+    let not_memoized = ctx.func_execution_cache().stats().await;
+
+    assert_eq!(after.hits, not_memoized.hits);
+    assert_eq!(after.misses, not_memoized.misses);
+}
+
 // FIXME(nick,fletcher): re-add test once upsert is added.
 // #[test]
 // async fn execution_upserts_return_value() {
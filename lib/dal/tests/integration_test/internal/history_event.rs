@@ -1,4 +1,7 @@
-use dal::{DalContext, HistoryActor, HistoryEvent};
+use dal::{
+    AccessBuilder, DalContext, DalContextBuilder, HistoryActor, HistoryEvent, HistoryEventFilter,
+    PendingAudit, Tenancy, WorkspaceSignup,
+};
 use dal_test::test;
 
 #[test]
@@ -17,3 +20,99 @@ async fn new(ctx: &DalContext) {
     assert_eq!(&history_event.data, &serde_json::json!({}));
     assert_eq!(&history_event.tenancy, ctx.tenancy());
 }
+
+#[test]
+async fn list(ctx: &DalContext) {
+    for label in [
+        "audit.canoe.create",
+        "audit.canoe.update",
+        "audit.kayak.create",
+    ] {
+        HistoryEvent::new(ctx, label, "boats", &serde_json::json!({}))
+            .await
+            .expect("cannot create a new history event");
+    }
+
+    let events = HistoryEvent::list(
+        ctx,
+        &HistoryEventFilter {
+            label_prefix: Some("audit.canoe.".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.label.as_str())
+            .collect::<Vec<_>>(),
+        vec!["audit.canoe.update", "audit.canoe.create"]
+    );
+    assert!(events
+        .iter()
+        .all(|event| event.change_set_pk == Some(ctx.visibility().change_set_pk)));
+
+    let events = HistoryEvent::list(
+        ctx,
+        &HistoryEventFilter {
+            label: Some("audit.kayak.create".to_string()),
+            actor: Some(HistoryActor::SystemInit),
+            limit: Some(1),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+async fn pending_audit_commits_once_with_the_changes(
+    builder: &DalContextBuilder,
+    nw: &WorkspaceSignup,
+) {
+    let access_builder = AccessBuilder::new(
+        Tenancy::new(*nw.workspace.pk()),
+        HistoryActor::User(nw.user.pk()),
+    );
+
+    let mut audited = builder.clone();
+    audited.set_audit(PendingAudit::new(
+        "sdf.canoe.paddle",
+        "POST /api/canoe/paddle",
+        serde_json::json!({ "canoeId": "1" }),
+    ));
+    let ctx = audited
+        .build_head(access_builder)
+        .await
+        .expect("cannot build ctx");
+    ctx.commit().await.expect("cannot commit");
+    ctx.commit().await.expect("cannot commit again");
+
+    let mut rolled_back = builder.clone();
+    rolled_back.set_audit(PendingAudit::new(
+        "sdf.canoe.capsize",
+        "POST /api/canoe/capsize",
+        serde_json::json!({}),
+    ));
+    let ctx = rolled_back
+        .build_head(access_builder)
+        .await
+        .expect("cannot build ctx");
+    ctx.rollback().await.expect("cannot roll back");
+
+    let events = HistoryEvent::list(
+        &ctx,
+        &HistoryEventFilter {
+            label_prefix: Some("sdf.canoe.".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].label, "sdf.canoe.paddle");
+    assert_eq!(events[0].actor, HistoryActor::User(nw.user.pk()));
+    assert_eq!(events[0].data, serde_json::json!({ "canoeId": "1" }));
+}
@@ -1,7 +1,10 @@
-use dal::{key_pair::PublicKey, DalContext, KeyPair, Tenancy};
+use dal::{
+    key_pair::PublicKey, DalContext, EncryptedSecret, KeyPair, KeyPairRotation, StandardModel,
+    Tenancy, WorkspaceSignup,
+};
 use dal_test::{
     test,
-    test_harness::{create_key_pair, create_workspace},
+    test_harness::{create_key_pair, create_secret_with_message, create_workspace},
 };
 
 #[test]
@@ -44,3 +47,100 @@ async fn public_key_get_current(ctx: &mut DalContext) {
     assert_eq!(second_key_pair.pk(), *pk.pk());
     assert_eq!(second_key_pair.public_key(), pk.public_key());
 }
+
+#[test]
+async fn rotate(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let message = serde_json::json!({"song": "Bar Round Here"});
+    let secret = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+
+    let rotation = KeyPair::rotate(ctx).await.expect("cannot rotate key pair");
+    assert!(rotation.finished_at().is_some());
+    assert_eq!(rotation.reencrypted_secrets(), 1);
+
+    let old_key_pair = KeyPair::get_by_pk(ctx, nw.key_pair.pk())
+        .await
+        .expect("cannot get old key pair");
+    assert!(old_key_pair.is_retired());
+
+    let current = PublicKey::get_current(ctx)
+        .await
+        .expect("cannot get public key");
+    assert_eq!(*current.pk(), rotation.new_key_pair_pk());
+
+    let encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret");
+    let key_pair = encrypted_secret
+        .key_pair(ctx)
+        .await
+        .expect("failed to fetch key pair");
+    assert_eq!(key_pair.pk(), rotation.new_key_pair_pk());
+
+    let decrypted = encrypted_secret
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt encrypted secret");
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serialize decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+
+    // Payloads sealed with the retired key pair are re-sealed with the current one on create
+    let late_secret = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+    let late_key_pair = EncryptedSecret::get_by_id(ctx, late_secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .key_pair(ctx)
+        .await
+        .expect("failed to fetch key pair");
+    assert_eq!(late_key_pair.pk(), rotation.new_key_pair_pk());
+
+    // Nothing is left to do for a second rotation to resume
+    assert!(KeyPairRotation::get_unfinished(ctx)
+        .await
+        .expect("cannot get unfinished rotation")
+        .is_none());
+}
+
+#[test]
+async fn rotate_skips_secrets_that_cannot_be_resealed(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let message = serde_json::json!({"song": "Bar Round Here"});
+    let secret = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+    let corrupt = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+    ctx.txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .execute(
+            "UPDATE encrypted_secrets SET crypted = 'bm90IHNlYWxlZA' WHERE pk = $1",
+            &[corrupt.pk()],
+        )
+        .await
+        .expect("cannot corrupt secret");
+
+    let rotation = KeyPair::rotate(ctx).await.expect("cannot rotate key pair");
+    assert!(rotation.finished_at().is_some());
+    assert_eq!(rotation.reencrypted_secrets(), 1);
+    assert_eq!(rotation.failed_secrets(), 1);
+
+    let resealed_key_pair = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .key_pair(ctx)
+        .await
+        .expect("failed to fetch key pair");
+    assert_eq!(resealed_key_pair.pk(), rotation.new_key_pair_pk());
+
+    // The corrupt row is left alone, and its key pair stays in service for it
+    let corrupt_key_pair = EncryptedSecret::get_by_id(ctx, corrupt.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .key_pair(ctx)
+        .await
+        .expect("failed to fetch key pair");
+    assert_eq!(corrupt_key_pair.pk(), nw.key_pair.pk());
+    assert!(!corrupt_key_pair.is_retired());
+}
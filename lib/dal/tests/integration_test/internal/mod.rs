@@ -22,6 +22,7 @@ mod socket;
 mod standard_model;
 mod status_update;
 mod tenancy;
+mod undo;
 mod user;
 mod validation_prototype;
 mod validation_resolver;
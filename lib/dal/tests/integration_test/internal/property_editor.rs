@@ -1,9 +1,11 @@
 use dal::func::argument::FuncArgumentKind;
 use dal::{
     generate_name,
+    prop::PropPath,
     property_editor::{schema::PropertyEditorSchema, values::PropertyEditorValues},
-    DalContext, Func, FuncArgument, FuncBackendKind, FuncBackendResponseType, LeafInput,
-    LeafInputLocation, LeafKind, Prop, PropKind, SchemaVariant, StandardModel,
+    AttributeReadContext, AttributeValue, DalContext, Func, FuncArgument, FuncBackendKind,
+    FuncBackendResponseType, LeafInput, LeafInputLocation, LeafKind, Prop, PropKind, SchemaVariant,
+    StandardModel,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::test;
@@ -154,3 +156,62 @@ async fn property_editor_value(ctx: &DalContext) {
     assert_eq!(found_name.replace('"', ""), name);
     assert_eq!(si_name_value, domain_name_value);
 }
+
+#[test]
+async fn property_editor_update_for_components(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let mut component_bags = Vec::new();
+    for _ in 0..3 {
+        component_bags.push(
+            bagger
+                .create_component(ctx, &generate_name(), "starfield")
+                .await,
+        );
+    }
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let freestar_path = PropPath::new(["root", "domain", "freestar"]);
+    let component_ids: Vec<_> = component_bags
+        .iter()
+        .map(|component_bag| component_bag.component_id)
+        .collect();
+    let undo_operations = PropertyEditorValues::update_for_components(
+        ctx,
+        &freestar_path,
+        &component_ids,
+        Some(serde_json::json!["hello from the other side"]),
+    )
+    .await
+    .expect("could not bulk update values");
+    assert_eq!(component_ids.len(), undo_operations.len());
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let freestar_prop =
+        Prop::find_prop_by_path(ctx, component_bags[0].schema_variant_id, &freestar_path)
+            .await
+            .expect("could not find prop");
+    for component_bag in &component_bags {
+        let attribute_value = AttributeValue::find_for_context(
+            ctx,
+            AttributeReadContext {
+                prop_id: Some(*freestar_prop.id()),
+                ..component_bag.base_attribute_read_context
+            },
+        )
+        .await
+        .expect("could not perform find for context")
+        .expect("attribute value not found");
+        assert_eq!(
+            Some(serde_json::json!["hello from the other side"]),
+            attribute_value
+                .get_value(ctx)
+                .await
+                .expect("could not get value")
+        );
+    }
+}
@@ -1,10 +1,15 @@
 use dal::{
-    DalContext, EncryptedSecret, Secret, SecretAlgorithm, SecretKind, SecretObjectType,
-    SecretVersion, StandardModel, WorkspaceSignup,
+    AttributeContext, AttributeReadContext, AttributeValue, Component, DalContext, EncryptedSecret,
+    Prop, PropKind, Secret, SecretAlgorithm, SecretBackendKind, SecretError, SecretKind,
+    SecretKindDefinition, SecretKindField, SecretObjectType, SecretVersion, StandardModel,
+    WorkspaceSignup,
 };
 use dal_test::{
     test,
-    test_harness::{create_secret, generate_fake_name},
+    test_harness::{
+        create_schema, create_schema_variant_with_root, create_secret, encrypt_message,
+        generate_fake_name,
+    },
 };
 
 #[test]
@@ -117,3 +122,257 @@ async fn encrypt_decrypt_round_trip(ctx: &DalContext, nw: &WorkspaceSignup) {
         serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
     assert_eq!(decrypted_value["message"], message);
 }
+
+#[test]
+async fn encrypted_secret_set_crypted(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    let message = serde_json::json!({"song": "Clumsy"});
+    let mut encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret");
+    encrypted_secret
+        .set_crypted(
+            ctx,
+            &encrypt_message(ctx, nw.key_pair.pk(), &message).await,
+            nw.key_pair.pk(),
+        )
+        .await
+        .expect("failed to set crypted");
+
+    let decrypted = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt encrypted secret");
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serialize decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+}
+
+#[test]
+async fn secret_delete_unused(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    assert!(secret
+        .where_used(ctx)
+        .await
+        .expect("failed to list secret usages")
+        .is_empty());
+    secret
+        .delete(ctx, false)
+        .await
+        .expect("failed to delete unused secret");
+
+    assert!(Secret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to get secret")
+        .is_none());
+}
+
+#[test]
+async fn new_external_requires_configured_backend(ctx: &DalContext) {
+    let result = EncryptedSecret::new_external(
+        ctx,
+        generate_fake_name(),
+        SecretObjectType::Credential,
+        SecretKind::DockerHub,
+        SecretBackendKind::File,
+        "docker/hub.json",
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(SecretError::BackendNotConfigured(SecretBackendKind::File))
+    ));
+}
+
+#[test]
+async fn new_encrypted_secret_validates_message(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let result = EncryptedSecret::new(
+        ctx,
+        generate_fake_name(),
+        SecretObjectType::Credential,
+        SecretKind::ApiToken,
+        &encrypt_message(ctx, nw.key_pair.pk(), &serde_json::json!({"tokn": "abc"})).await,
+        nw.key_pair.pk(),
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(SecretError::InvalidMessage(SecretKind::ApiToken, _))
+    ));
+
+    let secret = EncryptedSecret::new(
+        ctx,
+        generate_fake_name(),
+        SecretObjectType::Credential,
+        SecretKind::ApiToken,
+        &encrypt_message(ctx, nw.key_pair.pk(), &serde_json::json!({"token": "abc"})).await,
+        nw.key_pair.pk(),
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await
+    .expect("failed to create secret");
+    assert_eq!(secret.kind(), &SecretKind::ApiToken);
+}
+
+#[test]
+async fn new_encrypted_secret_of_custom_kind(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let kind = SecretKind::Custom("digitalOceanToken".to_owned());
+    let message = serde_json::json!({"token": "abc"});
+
+    let result = EncryptedSecret::new(
+        ctx,
+        generate_fake_name(),
+        SecretObjectType::Credential,
+        kind.clone(),
+        &encrypt_message(ctx, nw.key_pair.pk(), &message).await,
+        nw.key_pair.pk(),
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await;
+    assert!(matches!(result, Err(SecretError::SecretKindNotFound(_))));
+
+    SecretKindDefinition::new(
+        ctx,
+        "digitalOceanToken",
+        None,
+        vec![SecretKindField {
+            name: "token".to_owned(),
+            required: true,
+        }],
+    )
+    .await
+    .expect("failed to create secret kind definition");
+
+    let secret = EncryptedSecret::new(
+        ctx,
+        generate_fake_name(),
+        SecretObjectType::Credential,
+        kind.clone(),
+        &encrypt_message(ctx, nw.key_pair.pk(), &message).await,
+        nw.key_pair.pk(),
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await
+    .expect("failed to create secret");
+    assert_eq!(secret.kind(), &kind);
+}
+
+#[test]
+async fn secret_delete_used(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    let head_ctx = ctx.clone_with_head();
+    let mut schema = create_schema(&head_ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(&head_ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(&head_ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let token_prop = Prop::new(
+        &head_ctx,
+        "token",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(&head_ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+    head_ctx
+        .blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (component, _) = Component::new(ctx, "vault", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+    let domain_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(root.domain_prop_id),
+            component_id: Some(*component.id()),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("could not fetch domain AttributeValue")
+    .expect("could not find domain AttributeValue");
+    let token_context = AttributeContext::builder()
+        .set_prop_id(*token_prop.id())
+        .set_component_id(*component.id())
+        .to_context()
+        .expect("cannot create token AttributeContext");
+    let token_value = AttributeValue::find_for_context(ctx, token_context.into())
+        .await
+        .expect("could not fetch token AttributeValue")
+        .expect("could not find token AttributeValue");
+    let (_, token_value_id) = AttributeValue::update_for_context(
+        ctx,
+        *token_value.id(),
+        Some(*domain_value.id()),
+        token_context,
+        Some(serde_json::json!(secret.id().to_string())),
+        None,
+    )
+    .await
+    .expect("could not set token value");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let usages = secret
+        .where_used(ctx)
+        .await
+        .expect("failed to list secret usages");
+    assert_eq!(usages.len(), 1);
+    assert_eq!(usages[0].component_id, *component.id());
+    assert_eq!(usages[0].attribute_value_id, token_value_id);
+    assert_eq!(usages[0].prop_path, "root/domain/token");
+
+    let result = secret.delete(ctx, false).await;
+    assert!(
+        matches!(result, Err(SecretError::InUse(id, 1)) if id == *secret.id()),
+        "unexpected result: {result:?}"
+    );
+    assert!(Secret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to get secret")
+        .is_some());
+
+    // Forcing it unsets the references, so nothing is left pointing at the deleted secret
+    secret
+        .delete(ctx, true)
+        .await
+        .expect("failed to force delete used secret");
+    assert!(Secret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to get secret")
+        .is_none());
+    let token_value = AttributeValue::find_for_context(ctx, token_context.into())
+        .await
+        .expect("could not fetch token AttributeValue")
+        .expect("could not find token AttributeValue");
+    assert_eq!(
+        token_value
+            .get_value(ctx)
+            .await
+            .expect("could not get token value"),
+        None
+    );
+}
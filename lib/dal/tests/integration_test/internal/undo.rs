@@ -0,0 +1,603 @@
+use std::collections::HashSet;
+
+use dal::{
+    edge::{EdgeId, EdgeKind},
+    generate_name,
+    prop::PropPath,
+    property_editor::values::PropertyEditorValues,
+    socket::SocketEdgeKind,
+    AttributeContext, AttributeReadContext, AttributeValue, Component, ComponentId, ComponentType,
+    ComponentView, Connection, DalContext, Edge, NodeId, Prop, PropKind, Socket, StandardModel,
+    UndoEntry, UndoOperation,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::test;
+use dal_test::test_harness::{create_schema, create_schema_variant_with_root};
+use pretty_assertions_sorted::assert_eq;
+
+async fn freestar_value(
+    ctx: &DalContext,
+    read_context: AttributeReadContext,
+) -> Option<serde_json::Value> {
+    AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("could not perform find for context")
+        .expect("attribute value not found")
+        .get_value(ctx)
+        .await
+        .expect("could not get value")
+}
+
+async fn edge_ids(ctx: &DalContext, component_id: ComponentId) -> HashSet<EdgeId> {
+    Edge::list_for_component(ctx, component_id)
+        .await
+        .expect("could not list edges")
+        .iter()
+        .map(|edge| *edge.id())
+        .collect()
+}
+
+async fn socket(
+    ctx: &DalContext,
+    name: &str,
+    edge_kind: SocketEdgeKind,
+    node_id: NodeId,
+) -> Socket {
+    Socket::find_by_name_for_edge_kind_and_node(ctx, name, edge_kind, node_id)
+        .await
+        .expect("could not perform socket find")
+        .expect("could not find socket")
+}
+
+#[test]
+async fn undo_and_redo_component_creation(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger
+        .create_component(ctx, &generate_name(), "starfield")
+        .await;
+    UndoEntry::record(
+        ctx,
+        vec![UndoOperation::CreateComponent {
+            component_id: component_bag.component_id,
+        }],
+    )
+    .await
+    .expect("could not record undo entry")
+    .expect("no undo entry recorded");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let entry = UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    assert!(entry.undone());
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert!(Component::get_by_id(ctx, &component_bag.component_id)
+        .await
+        .expect("could not get component")
+        .is_none());
+
+    // The redo stack now holds the entry, and the undo stack is empty
+    assert!(UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .is_none());
+
+    let entry = UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    assert!(!entry.undone());
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert!(Component::get_by_id(ctx, &component_bag.component_id)
+        .await
+        .expect("could not get component")
+        .is_some());
+    assert!(UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .is_none());
+}
+
+#[test]
+async fn undo_and_redo_bulk_update(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger
+        .create_component(ctx, &generate_name(), "starfield")
+        .await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let freestar_path = PropPath::new(["root", "domain", "freestar"]);
+    let freestar_prop =
+        Prop::find_prop_by_path(ctx, component_bag.schema_variant_id, &freestar_path)
+            .await
+            .expect("could not find prop");
+    let read_context = AttributeReadContext {
+        prop_id: Some(*freestar_prop.id()),
+        ..component_bag.base_attribute_read_context
+    };
+    let before = freestar_value(ctx, read_context).await;
+
+    let operations = PropertyEditorValues::update_for_components(
+        ctx,
+        &freestar_path,
+        &[component_bag.component_id],
+        Some(serde_json::json!["the other side"]),
+    )
+    .await
+    .expect("could not bulk update values");
+    UndoEntry::record(ctx, operations)
+        .await
+        .expect("could not record undo entry");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        Some(serde_json::json!["the other side"]),
+        freestar_value(ctx, read_context).await
+    );
+
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(before, freestar_value(ctx, read_context).await);
+
+    UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        Some(serde_json::json!["the other side"]),
+        freestar_value(ctx, read_context).await
+    );
+}
+
+#[test]
+async fn undo_and_redo_component_type(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger
+        .create_component(ctx, &generate_name(), "starfield")
+        .await;
+    let component = Component::get_by_id(ctx, &component_bag.component_id)
+        .await
+        .expect("could not get component")
+        .expect("component not found");
+    let before = component
+        .get_type(ctx)
+        .await
+        .expect("could not get component type");
+
+    component
+        .set_type(ctx, ComponentType::ConfigurationFrame)
+        .await
+        .expect("could not set component type");
+    UndoEntry::record(
+        ctx,
+        vec![UndoOperation::SetComponentType {
+            component_id: component_bag.component_id,
+            before,
+            after: ComponentType::ConfigurationFrame,
+        }],
+    )
+    .await
+    .expect("could not record undo entry");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        before,
+        component
+            .get_type(ctx)
+            .await
+            .expect("could not get component type")
+    );
+
+    UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        ComponentType::ConfigurationFrame,
+        component
+            .get_type(ctx)
+            .await
+            .expect("could not get component type")
+    );
+}
+
+#[test]
+async fn redo_connection_creation_replaces_edge_id(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+    let from_socket_id = *socket(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .id();
+    let to_socket_id = *socket(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .id();
+
+    let connection = Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        from_socket_id,
+        starfield_bag.node_id,
+        to_socket_id,
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+    UndoEntry::record(
+        ctx,
+        vec![UndoOperation::CreateConnection {
+            edge_id: connection.id,
+            from_node_id: fallout_bag.node_id,
+            from_socket_id,
+            to_node_id: starfield_bag.node_id,
+            to_socket_id,
+        }],
+    )
+    .await
+    .expect("could not record undo entry");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    Connection::delete_for_edge(ctx, connection.id)
+        .await
+        .expect("could not delete connection");
+    UndoEntry::record(
+        ctx,
+        vec![UndoOperation::DeleteConnection {
+            edge_id: connection.id,
+        }],
+    )
+    .await
+    .expect("could not record undo entry");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert!(edge_ids(ctx, starfield_bag.component_id).await.is_empty());
+
+    // Undo the deletion, then the creation
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        HashSet::from([connection.id]),
+        edge_ids(ctx, starfield_bag.component_id).await
+    );
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert!(edge_ids(ctx, starfield_bag.component_id).await.is_empty());
+
+    // Redoing the creation makes a new edge, which the deletion is rewritten to
+    let entry = UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let new_edge_id = match entry.operations() {
+        [UndoOperation::CreateConnection { edge_id, .. }] => *edge_id,
+        operations => panic!("unexpected operations: {operations:?}"),
+    };
+    assert_ne!(connection.id, new_edge_id);
+    assert_eq!(
+        HashSet::from([new_edge_id]),
+        edge_ids(ctx, starfield_bag.component_id).await
+    );
+
+    let entry = UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        &[UndoOperation::DeleteConnection {
+            edge_id: new_edge_id
+        }],
+        entry.operations()
+    );
+    assert!(edge_ids(ctx, starfield_bag.component_id).await.is_empty());
+}
+
+#[test]
+async fn undo_and_redo_frame_connection(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "frame", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "child", "starfield").await;
+    fallout_bag
+        .component(ctx)
+        .await
+        .set_type(ctx, ComponentType::ConfigurationFrame)
+        .await
+        .expect("could not set component type");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (_, edge_ids_before) =
+        Connection::connect_to_frame(ctx, fallout_bag.node_id, starfield_bag.node_id)
+            .await
+            .expect("could not connect component to frame");
+    // The frame connection, then "bethesda" and "fallout" from the frame to the child
+    assert_eq!(3, edge_ids_before.len());
+    UndoEntry::record(
+        ctx,
+        vec![UndoOperation::ConnectComponentToFrame {
+            parent_node_id: fallout_bag.node_id,
+            child_node_id: starfield_bag.node_id,
+            edge_ids: edge_ids_before.clone(),
+        }],
+    )
+    .await
+    .expect("could not record undo entry");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        HashSet::from_iter(edge_ids_before.iter().copied()),
+        edge_ids(ctx, starfield_bag.component_id).await
+    );
+
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert!(edge_ids(ctx, starfield_bag.component_id).await.is_empty());
+
+    let entry = UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let edge_ids_after = match entry.operations() {
+        [UndoOperation::ConnectComponentToFrame { edge_ids, .. }] => edge_ids.clone(),
+        operations => panic!("unexpected operations: {operations:?}"),
+    };
+    assert_eq!(
+        HashSet::from_iter(edge_ids_after.iter().copied()),
+        edge_ids(ctx, starfield_bag.component_id).await
+    );
+
+    // Every new edge took the place of the old one between the same sockets
+    let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+    for (before, after) in edge_ids_before.iter().zip(&edge_ids_after) {
+        assert_ne!(before, after);
+        let before = Edge::get_by_id(ctx_with_deleted, before)
+            .await
+            .expect("could not get edge")
+            .expect("edge not found");
+        let after = Edge::get_by_id(ctx, after)
+            .await
+            .expect("could not get edge")
+            .expect("edge not found");
+        assert_eq!(before.kind(), after.kind());
+        assert_eq!(
+            (before.tail_socket_id(), before.head_socket_id()),
+            (after.tail_socket_id(), after.head_socket_id())
+        );
+    }
+
+    // Undoing again deletes the new edges
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert!(edge_ids(ctx, starfield_bag.component_id).await.is_empty());
+}
+
+#[test]
+async fn undo_and_redo_array_element_insertion(ctx: &DalContext) {
+    let (schema_variant_id, domain_prop_id, array_prop_id, element_prop_id) = {
+        let head_ctx = &ctx.clone_with_head();
+        let mut schema = create_schema(head_ctx).await;
+        let (mut schema_variant, root) =
+            create_schema_variant_with_root(head_ctx, *schema.id()).await;
+        schema
+            .set_default_schema_variant_id(head_ctx, Some(*schema_variant.id()))
+            .await
+            .expect("cannot set default schema variant");
+        let array_prop = Prop::new(
+            head_ctx,
+            "sammy_hagar",
+            PropKind::Array,
+            None,
+            *schema_variant.id(),
+            Some(root.domain_prop_id),
+        )
+        .await
+        .expect("could not create prop");
+        let element_prop = Prop::new(
+            head_ctx,
+            "album",
+            PropKind::String,
+            None,
+            *schema_variant.id(),
+            Some(*array_prop.id()),
+        )
+        .await
+        .expect("could not create prop");
+        schema_variant
+            .finalize(head_ctx, None)
+            .await
+            .expect("cannot finalize SchemaVariant");
+        head_ctx
+            .blocking_commit()
+            .await
+            .expect("could not commit & run jobs");
+        (
+            *schema_variant.id(),
+            root.domain_prop_id,
+            *array_prop.id(),
+            *element_prop.id(),
+        )
+    };
+
+    let (component, _) = Component::new(ctx, "sammy", schema_variant_id)
+        .await
+        .expect("could not create component");
+    let mut base_context = AttributeContext::builder();
+    base_context.set_component_id(*component.id());
+    let domain_value = AttributeValue::find_for_context(
+        ctx,
+        base_context
+            .clone()
+            .set_prop_id(domain_prop_id)
+            .to_context()
+            .expect("could not create domain AttributeContext")
+            .into(),
+    )
+    .await
+    .expect("could not retrieve domain AttributeValue")
+    .expect("could not find domain AttributeValue");
+    let array_context = base_context
+        .clone()
+        .set_prop_id(array_prop_id)
+        .to_context()
+        .expect("could not create array AttributeContext");
+    let unset_array_value = AttributeValue::find_for_context(ctx, array_context.into())
+        .await
+        .expect("could not retrieve array AttributeValue")
+        .expect("could not find array AttributeValue");
+    let (_, array_value_id) = AttributeValue::update_for_context(
+        ctx,
+        *unset_array_value.id(),
+        Some(*domain_value.id()),
+        array_context,
+        Some(serde_json::json![[]]),
+        None,
+    )
+    .await
+    .expect("could not update array AttributeValue");
+
+    let element_context = base_context
+        .clone()
+        .set_prop_id(element_prop_id)
+        .to_context()
+        .expect("could not create element AttributeContext");
+    let element_value_id = AttributeValue::insert_for_context(
+        ctx,
+        element_context,
+        array_value_id,
+        Some(serde_json::json!["standing_hampton"]),
+        None,
+    )
+    .await
+    .expect("could not insert element AttributeValue");
+    UndoEntry::record(
+        ctx,
+        vec![UndoOperation::InsertAttributeValue {
+            attribute_value_id: element_value_id,
+            parent_attribute_value_id: array_value_id,
+            context: element_context,
+            value: Some(serde_json::json!["standing_hampton"]),
+            key: None,
+        }],
+    )
+    .await
+    .expect("could not record undo entry");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let array_property =
+        |component_view: ComponentView| component_view.properties["domain"]["sammy_hagar"].clone();
+    let component_view = ComponentView::new(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        serde_json::json![["standing_hampton"]],
+        array_property(component_view)
+    );
+
+    UndoEntry::undo(ctx)
+        .await
+        .expect("could not undo")
+        .expect("nothing to undo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let component_view = ComponentView::new(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(serde_json::json![[]], array_property(component_view));
+
+    let entry = UndoEntry::redo(ctx)
+        .await
+        .expect("could not redo")
+        .expect("nothing to redo");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    match entry.operations() {
+        [UndoOperation::InsertAttributeValue {
+            attribute_value_id, ..
+        }] => assert_ne!(element_value_id, *attribute_value_id),
+        operations => panic!("unexpected operations: {operations:?}"),
+    }
+    let component_view = ComponentView::new(ctx, *component.id())
+        .await
+        .expect("cannot get component view");
+    assert_eq!(
+        serde_json::json![["standing_hampton"]],
+        array_property(component_view)
+    );
+}
@@ -39,3 +39,36 @@ async fn authorize(ctx: &DalContext, nw: &WorkspaceSignup) {
     );
     */
 }
+
+#[test]
+async fn workspace_admin(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let workspace_pk = *nw.workspace.pk();
+    assert!(User::is_workspace_admin(ctx, nw.user.pk(), workspace_pk)
+        .await
+        .expect("cannot check workspace admin"));
+
+    let member = User::new(
+        ctx,
+        UserPk::generate(),
+        "funky",
+        "bobotclown@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("cannot create user");
+    member
+        .associate_workspace(ctx, workspace_pk)
+        .await
+        .expect("cannot associate workspace");
+    assert!(!User::is_workspace_admin(ctx, member.pk(), workspace_pk)
+        .await
+        .expect("cannot check workspace admin"));
+
+    member
+        .grant_workspace_admin(ctx, workspace_pk)
+        .await
+        .expect("cannot grant workspace admin");
+    assert!(User::is_workspace_admin(ctx, member.pk(), workspace_pk)
+        .await
+        .expect("cannot check workspace admin"));
+}
@@ -309,6 +309,22 @@ pub trait Instance {
     /// # Ok::<(), TerminationError>(())
     /// ```
     async fn terminate(mut self) -> result::Result<(), Self::Error>;
+
+    /// Clears any state left behind by prior requests, so that the instance can safely serve the
+    /// next one.
+    ///
+    /// The default implementation has nothing to clear.
+    async fn scrub(&mut self) -> result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Returns `true` once the instance has served all the requests it was spawned for, meaning
+    /// it should be retired rather than recycled.
+    ///
+    /// The default implementation never runs out of requests.
+    fn is_exhausted(&self) -> bool {
+        false
+    }
 }
 
 // async fn spawn<B, E, I, S>(builder: &B) -> Result<impl Instance<Error = E>, E>
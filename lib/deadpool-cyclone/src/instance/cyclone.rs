@@ -1,5 +1,7 @@
 //! Cyclone implementations of [`Instance`][`super::Instance`].
 
+use std::{env, ffi::OsString, io, path::Path};
+
 pub use local_http::{
     LocalHttpInstance, LocalHttpInstanceError, LocalHttpInstanceSpec, LocalHttpInstanceSpecBuilder,
     LocalHttpSocketStrategy,
@@ -8,6 +10,85 @@ pub use local_uds::{
     LocalUdsInstance, LocalUdsInstanceError, LocalUdsInstanceSpec, LocalUdsInstanceSpecBuilder,
     LocalUdsSocketStrategy,
 };
+pub use sandboxed_uds::{
+    SandboxRuntime, SandboxedUdsInstance, SandboxedUdsInstanceError, SandboxedUdsInstanceSpec,
+    SandboxedUdsInstanceSpecBuilder,
+};
 
 mod local_http;
 mod local_uds;
+mod sandboxed_uds;
+
+/// Name of the environment variable pointing a spawned Cyclone server, and the lang server
+/// processes it runs, at the instance's scratch directory.
+const SCRATCH_DIR_ENV: &str = "TMPDIR";
+
+/// Name of the directory in an instance's scratch directory used as the home directory of a
+/// spawned Cyclone server, so that files functions write under `$HOME` are scrubbed as well.
+const HOME_DIR_NAME: &str = "home";
+
+/// Environment variables passed on from our own environment to a spawned Cyclone server.
+///
+/// A Cyclone server starts with an otherwise empty environment so that credentials and
+/// configuration meant for us never reach the functions it runs. Proxy settings (in either
+/// case) and CA certificate locations are kept so that functions reach the network the same way
+/// we do.
+const PASSTHROUGH_ENV: &[&str] = &[
+    "ALL_PROXY",
+    "HTTPS_PROXY",
+    "HTTP_PROXY",
+    "LANG",
+    "LC_ALL",
+    "NO_PROXY",
+    "PATH",
+    "SI_CYCLONE_LOG",
+    "SI_CYCLONE_LOG_SPAN_EVENTS",
+    "SI_LOG",
+    "SI_LOG_SPAN_EVENTS",
+    "SSL_CERT_DIR",
+    "SSL_CERT_FILE",
+    "TZ",
+    "all_proxy",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+];
+
+/// Prefix of the environment variables configuring telemetry export, which are passed on to a
+/// spawned Cyclone server.
+const PASSTHROUGH_ENV_PREFIX: &str = "OTEL_";
+
+/// Returns the complete environment of a spawned Cyclone server using the given scratch
+/// directory.
+fn cyclone_env(scratch_dir: &Path) -> Vec<(OsString, OsString)> {
+    let mut vars: Vec<(OsString, OsString)> = env::vars_os()
+        .filter(|(key, _)| {
+            key.to_str().map_or(false, |key| {
+                PASSTHROUGH_ENV.contains(&key) || key.starts_with(PASSTHROUGH_ENV_PREFIX)
+            })
+        })
+        .collect();
+    vars.push(("HOME".into(), scratch_dir.join(HOME_DIR_NAME).into()));
+    vars.push((SCRATCH_DIR_ENV.into(), scratch_dir.into()));
+    vars
+}
+
+/// Creates the directories a spawned Cyclone server expects in its scratch directory.
+async fn prepare_scratch_dir(path: &Path) -> io::Result<()> {
+    tokio::fs::create_dir_all(path.join(HOME_DIR_NAME)).await
+}
+
+/// Removes everything left in an instance's scratch directory, including its home directory,
+/// and prepares it for the next execution.
+async fn scrub_scratch_dir(path: &Path) -> io::Result<()> {
+    let mut entries = tokio::fs::read_dir(path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_type().await?.is_dir() {
+            tokio::fs::remove_dir_all(entry.path()).await?;
+        } else {
+            tokio::fs::remove_file(entry.path()).await?;
+        }
+    }
+
+    prepare_scratch_dir(path).await
+}
@@ -1,5 +1,6 @@
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::Path,
     result,
     time::Duration,
 };
@@ -19,6 +20,7 @@ use cyclone_core::{
 use derive_builder::Builder;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
 use thiserror::Error;
 use tokio::{
     io::{self, AsyncRead, AsyncWrite},
@@ -29,6 +31,7 @@ use tokio::{
 };
 use tracing::{debug, trace, warn};
 
+use super::{cyclone_env, prepare_scratch_dir, scrub_scratch_dir};
 use crate::instance::{Instance, Spec, SpecBuilder};
 
 /// Error type for [`LocalHttpInstance`].
@@ -50,6 +53,12 @@ pub enum LocalHttpInstanceError {
     /// Instance has exhausted its predefined request count.
     #[error("no remaining requests, cyclone server is considered unhealthy")]
     NoRemainingRequests,
+    /// Failed to create the scratch directory of an instance.
+    #[error("failed to create scratch directory")]
+    ScratchDir(#[source] io::Error),
+    /// Failed to clear the scratch directory of an instance.
+    #[error("failed to clear scratch directory")]
+    Scrub(#[source] io::Error),
     /// Error when binding local socket.
     #[error("error when binding local socket")]
     SocketBind(#[source] io::Error),
@@ -72,6 +81,9 @@ type Result<T> = result::Result<T, LocalHttpInstanceError>;
 /// A local Cyclone [`Instance`], managed as a spawned child process, communicating over HTTP.
 #[derive(Debug)]
 pub struct LocalHttpInstance {
+    // Handed to the Cyclone server as its temp directory and cleared in between requests, the
+    // directory is deleted when `LocalHttpInstance` is dropped.
+    scratch_dir: TempDir,
     client: HttpClient,
     limit_requests: Option<u32>,
     child: Child,
@@ -100,6 +112,16 @@ impl Instance for LocalHttpInstance {
 
         Ok(())
     }
+
+    async fn scrub(&mut self) -> result::Result<(), Self::Error> {
+        scrub_scratch_dir(self.scratch_dir.path())
+            .await
+            .map_err(LocalHttpInstanceError::Scrub)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        !self.has_remaining_requests()
+    }
 }
 
 #[async_trait]
@@ -278,6 +300,27 @@ pub struct LocalHttpInstanceSpec {
     #[builder(setter(into), default = "Some(1)")]
     limit_requests: Option<u32>,
 
+    /// Sets the execution timeout for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    execution_timeout: Option<Duration>,
+
+    /// Sets the lang server memory limit, in MiB, for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    lang_server_memory_limit_mib: Option<u64>,
+
+    /// Sets the lang server CPU time limit for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    lang_server_cpu_limit: Option<Duration>,
+
+    /// Sets the execution output limit, in bytes, for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    output_limit_bytes: Option<usize>,
+
+    /// Runs lang servers denied network access in their own network namespace for a spawned
+    /// Cyclone server, which needs permission to create user namespaces.
+    #[builder(default = "false")]
+    lang_server_network_isolation: bool,
+
     /// Enables the `ping` execution endpoint for a spawned Cyclone server.
     #[builder(private, setter(name = "_ping"), default = "false")]
     ping: bool,
@@ -298,7 +341,11 @@ impl Spec for LocalHttpInstanceSpec {
 
     async fn spawn(&self) -> result::Result<Self::Instance, Self::Error> {
         let socket_addr = socket_addr_from(&self.socket_strategy).await?;
-        let mut cmd = self.build_command(&socket_addr);
+        let scratch_dir = tempfile::tempdir().map_err(Self::Error::ScratchDir)?;
+        prepare_scratch_dir(scratch_dir.path())
+            .await
+            .map_err(Self::Error::ScratchDir)?;
+        let mut cmd = self.build_command(&socket_addr, scratch_dir.path());
 
         debug!("spawning child process; cmd={:?}", &cmd);
         let child = cmd.spawn().map_err(Self::Error::ChildSpawn)?;
@@ -336,6 +383,7 @@ impl Spec for LocalHttpInstanceSpec {
         tokio::spawn(watch_task(watch_progress, watch_shutdown_rx));
 
         Ok(Self::Instance {
+            scratch_dir,
             client,
             limit_requests: self.limit_requests,
             child,
@@ -345,9 +393,11 @@ impl Spec for LocalHttpInstanceSpec {
 }
 
 impl LocalHttpInstanceSpec {
-    fn build_command(&self, socket: &SocketAddr) -> Command {
+    fn build_command(&self, socket: &SocketAddr, scratch_dir: &Path) -> Command {
         let mut cmd = Command::new(&self.cyclone_cmd_path);
-        cmd.arg("--bind-addr")
+        cmd.env_clear()
+            .envs(cyclone_env(scratch_dir))
+            .arg("--bind-addr")
             .arg(socket.to_string())
             .arg("--decryption-key")
             .arg(&self.cyclone_decryption_key_path)
@@ -361,6 +411,25 @@ impl LocalHttpInstanceSpec {
             cmd.arg("--watch-timeout")
                 .arg(timeout.as_secs().to_string());
         }
+        if let Some(timeout) = self.execution_timeout {
+            cmd.arg("--execution-timeout")
+                .arg(timeout.as_secs().to_string());
+        }
+        if let Some(memory_limit_mib) = self.lang_server_memory_limit_mib {
+            cmd.arg("--lang-server-memory-limit")
+                .arg(memory_limit_mib.to_string());
+        }
+        if let Some(cpu_limit) = self.lang_server_cpu_limit {
+            cmd.arg("--lang-server-cpu-limit")
+                .arg(cpu_limit.as_secs().to_string());
+        }
+        if let Some(output_limit_bytes) = self.output_limit_bytes {
+            cmd.arg("--output-limit-bytes")
+                .arg(output_limit_bytes.to_string());
+        }
+        if self.lang_server_network_isolation {
+            cmd.arg("--enable-lang-server-network-isolation");
+        }
         if self.ping {
             cmd.arg("--enable-ping");
         }
@@ -20,7 +20,7 @@ use cyclone_core::{
 use derive_builder::Builder;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tempfile::{NamedTempFile, TempPath};
+use tempfile::{NamedTempFile, TempDir, TempPath};
 use thiserror::Error;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
@@ -30,6 +30,7 @@ use tokio::{
 };
 use tracing::{debug, trace, warn};
 
+use super::{cyclone_env, prepare_scratch_dir, scrub_scratch_dir};
 use crate::instance::{Instance, Spec, SpecBuilder};
 
 /// Error type for [`LocalUdsInstance`].
@@ -51,6 +52,12 @@ pub enum LocalUdsInstanceError {
     /// Instance has exhausted its predefined request count.
     #[error("no remaining requests, cyclone server is considered unhealthy")]
     NoRemainingRequests,
+    /// Failed to create the scratch directory of an instance.
+    #[error("failed to create scratch directory")]
+    ScratchDir(#[source] io::Error),
+    /// Failed to clear the scratch directory of an instance.
+    #[error("failed to clear scratch directory")]
+    Scrub(#[source] io::Error),
     /// Failed to create socket from temporary file.
     #[error("failed to create temp socket")]
     TempSocket(#[source] io::Error),
@@ -78,6 +85,9 @@ pub struct LocalUdsInstance {
     // guard](https://rust-unofficial.github.io/patterns/patterns/behavioural/RAII.html), that is,
     // when `LocalUdsInstance` is dropped, the temp file is marked for deletion.
     _temp_path: Option<TempPath>,
+    // Handed to the Cyclone server as its temp directory and cleared in between requests, the
+    // directory is deleted when `LocalUdsInstance` is dropped.
+    scratch_dir: TempDir,
     client: UdsClient,
     limit_requests: Option<u32>,
     child: Child,
@@ -106,6 +116,16 @@ impl Instance for LocalUdsInstance {
 
         Ok(())
     }
+
+    async fn scrub(&mut self) -> result::Result<(), Self::Error> {
+        scrub_scratch_dir(self.scratch_dir.path())
+            .await
+            .map_err(LocalUdsInstanceError::Scrub)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        !self.has_remaining_requests()
+    }
 }
 
 #[async_trait]
@@ -291,6 +311,27 @@ pub struct LocalUdsInstanceSpec {
     #[builder(setter(into), default = "Some(1)")]
     limit_requests: Option<u32>,
 
+    /// Sets the execution timeout for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    execution_timeout: Option<Duration>,
+
+    /// Sets the lang server memory limit, in MiB, for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    lang_server_memory_limit_mib: Option<u64>,
+
+    /// Sets the lang server CPU time limit for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    lang_server_cpu_limit: Option<Duration>,
+
+    /// Sets the execution output limit, in bytes, for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    output_limit_bytes: Option<usize>,
+
+    /// Runs lang servers denied network access in their own network namespace for a spawned
+    /// Cyclone server, which needs permission to create user namespaces.
+    #[builder(default = "false")]
+    lang_server_network_isolation: bool,
+
     /// Enables the `ping` execution endpoint for a spawned Cyclone server.
     #[builder(private, setter(name = "_ping"), default = "false")]
     ping: bool,
@@ -311,7 +352,11 @@ impl Spec for LocalUdsInstanceSpec {
 
     async fn spawn(&self) -> result::Result<Self::Instance, Self::Error> {
         let (temp_path, socket) = temp_path_and_socket_from(&self.socket_strategy)?;
-        let mut cmd = self.build_command(&socket);
+        let scratch_dir = tempfile::tempdir().map_err(Self::Error::ScratchDir)?;
+        prepare_scratch_dir(scratch_dir.path())
+            .await
+            .map_err(Self::Error::ScratchDir)?;
+        let mut cmd = self.build_command(&socket, scratch_dir.path());
 
         debug!("spawning child process; cmd={:?}", &cmd);
         let child = cmd.spawn().map_err(Self::Error::ChildSpawn)?;
@@ -350,6 +395,7 @@ impl Spec for LocalUdsInstanceSpec {
 
         Ok(Self::Instance {
             _temp_path: temp_path,
+            scratch_dir,
             client,
             limit_requests: self.limit_requests,
             child,
@@ -359,9 +405,11 @@ impl Spec for LocalUdsInstanceSpec {
 }
 
 impl LocalUdsInstanceSpec {
-    fn build_command(&self, socket: &Path) -> Command {
+    fn build_command(&self, socket: &Path, scratch_dir: &Path) -> Command {
         let mut cmd = Command::new(&self.cyclone_cmd_path);
-        cmd.arg("--bind-uds")
+        cmd.env_clear()
+            .envs(cyclone_env(scratch_dir))
+            .arg("--bind-uds")
             .arg(socket)
             .arg("--decryption-key")
             .arg(&self.cyclone_decryption_key_path)
@@ -375,6 +423,25 @@ impl LocalUdsInstanceSpec {
             cmd.arg("--watch-timeout")
                 .arg(timeout.as_secs().to_string());
         }
+        if let Some(timeout) = self.execution_timeout {
+            cmd.arg("--execution-timeout")
+                .arg(timeout.as_secs().to_string());
+        }
+        if let Some(memory_limit_mib) = self.lang_server_memory_limit_mib {
+            cmd.arg("--lang-server-memory-limit")
+                .arg(memory_limit_mib.to_string());
+        }
+        if let Some(cpu_limit) = self.lang_server_cpu_limit {
+            cmd.arg("--lang-server-cpu-limit")
+                .arg(cpu_limit.as_secs().to_string());
+        }
+        if let Some(output_limit_bytes) = self.output_limit_bytes {
+            cmd.arg("--output-limit-bytes")
+                .arg(output_limit_bytes.to_string());
+        }
+        if self.lang_server_network_isolation {
+            cmd.arg("--enable-lang-server-network-isolation");
+        }
         if self.ping {
             cmd.arg("--enable-ping");
         }
@@ -486,7 +553,7 @@ fn temp_path_and_socket_from(
     }
 }
 
-async fn watch_task<Strm>(
+pub(super) async fn watch_task<Strm>(
     mut watch_progress: WatchStarted<Strm>,
     mut shutdown_rx: oneshot::Receiver<()>,
 ) where
@@ -0,0 +1,939 @@
+use std::{
+    ffi::OsString,
+    io,
+    os::unix::io::RawFd,
+    path::{Path, PathBuf},
+    result,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use cyclone_client::{
+    Client, ClientError, CycloneClient, Execution, LivenessStatus, PingExecution, ReadinessStatus,
+    UdsClient, UnixStream, Watch, WatchError,
+};
+use cyclone_core::{
+    process::{self, ShutdownError},
+    ActionRunRequest, ActionRunResultSuccess, CanonicalCommand, ReconciliationRequest,
+    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, ValidationRequest,
+    ValidationResultSuccess,
+};
+use derive_builder::Builder;
+use futures::StreamExt;
+use nix::{
+    fcntl::{self, OFlag},
+    sys::stat::Mode,
+    unistd,
+};
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use thiserror::Error;
+use tokio::{
+    process::{Child, Command},
+    sync::oneshot,
+    time,
+};
+use tracing::{debug, trace, warn};
+
+use super::{cyclone_env, local_uds::watch_task, prepare_scratch_dir, scrub_scratch_dir};
+use crate::instance::{Instance, Spec, SpecBuilder};
+
+/// Name of the Unix domain socket a spawned Cyclone server binds to, in its instance directory.
+const SOCKET_FILE_NAME: &str = "cyclone.sock";
+/// Name of the scratch directory of a spawned Cyclone server, in its instance directory.
+const SCRATCH_DIR_NAME: &str = "scratch";
+/// Host paths holding the system's programs and libraries, mounted read-only into a
+/// [`SandboxRuntime::Bubblewrap`] sandbox when present.
+const SYSTEM_PATHS: &[&str] = &["/bin", "/lib", "/lib64", "/usr"];
+/// Host files from `/etc` needed to resolve names, verify TLS certificates and look up users,
+/// mounted read-only into a [`SandboxRuntime::Bubblewrap`] sandbox when present.
+const ETC_PATHS: &[&str] = &[
+    "/etc/ca-certificates",
+    "/etc/group",
+    "/etc/hosts",
+    "/etc/ld.so.cache",
+    "/etc/localtime",
+    "/etc/nsswitch.conf",
+    "/etc/passwd",
+    "/etc/pki",
+    "/etc/resolv.conf",
+    "/etc/ssl",
+];
+
+/// Error type for [`SandboxedUdsInstance`].
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SandboxedUdsInstanceError {
+    /// Spec builder error.
+    #[error(transparent)]
+    Builder(#[from] SandboxedUdsInstanceSpecBuilderError),
+    /// Error when waiting for child process to shutdown.
+    #[error(transparent)]
+    ChildShutdown(#[from] ShutdownError),
+    /// Failed to spawn a child process.
+    #[error("failed to spawn sandboxed cyclone child process")]
+    ChildSpawn(#[source] io::Error),
+    /// Cyclone client error.
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// Failed to create the directory shared with the sandbox of an instance.
+    #[error("failed to create instance directory")]
+    InstanceDir(#[source] io::Error),
+    /// Instance has exhausted its predefined request count.
+    #[error("no remaining requests, cyclone server is considered unhealthy")]
+    NoRemainingRequests,
+    /// Failed to clear the scratch directory of an instance.
+    #[error("failed to clear scratch directory")]
+    Scrub(#[source] io::Error),
+    /// Failed to open the seccomp profile handed to the sandbox.
+    #[error("failed to open seccomp profile")]
+    SeccompProfile(#[source] nix::Error),
+    /// Cyclone client `watch` endpoint error.
+    #[error(transparent)]
+    Watch(#[from] WatchError),
+    /// Cyclone client `watch` session ended earlier than expected.
+    #[error("server closed watch session before expected")]
+    WatchClosed,
+    /// Cyclone client initial `watch` session connection with retries timed out.
+    #[error("timeout while retrying to start a client watch session")]
+    WatchInitTimeout,
+    /// Cyclone client `watch` session shut down earlier than expected.
+    #[error("watch session is shut down, cyclone server is considered unhealthy")]
+    WatchShutDown,
+}
+
+type Result<T> = result::Result<T, SandboxedUdsInstanceError>;
+
+/// A Cyclone [`Instance`], managed as a child process isolated in a sandbox, communicating over a
+/// Unix domain socket ("Uds").
+///
+/// The sandbox only sees the host's system paths and the programs it runs, read-only, has no
+/// network unless its [`Spec`] allows it, and can only write to the instance's own directory,
+/// which holds the socket and a scratch directory cleared in between requests.
+#[derive(Debug)]
+pub struct SandboxedUdsInstance {
+    // Shared with the sandbox and deleted when `SandboxedUdsInstance` is dropped.
+    instance_dir: TempDir,
+    client: UdsClient,
+    limit_requests: Option<u32>,
+    child: Child,
+    watch_shutdown_tx: oneshot::Sender<()>,
+}
+
+#[async_trait]
+impl Instance for SandboxedUdsInstance {
+    type SpecBuilder = SandboxedUdsInstanceSpecBuilder;
+    type Error = SandboxedUdsInstanceError;
+
+    async fn terminate(mut self) -> result::Result<(), Self::Error> {
+        if !self.watch_shutdown_tx.is_closed() && self.watch_shutdown_tx.send(()).is_err() {
+            debug!("sent watch shutdown but receiver was already closed");
+        }
+        process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None).await?;
+
+        Ok(())
+    }
+
+    async fn ensure_healthy(&mut self) -> result::Result<(), Self::Error> {
+        self.ensure_healthy_client().await?;
+        match self.client.readiness().await? {
+            ReadinessStatus::Ready => {}
+        }
+
+        Ok(())
+    }
+
+    async fn scrub(&mut self) -> result::Result<(), Self::Error> {
+        scrub_scratch_dir(&self.instance_dir.path().join(SCRATCH_DIR_NAME))
+            .await
+            .map_err(SandboxedUdsInstanceError::Scrub)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        !self.has_remaining_requests()
+    }
+}
+
+#[async_trait]
+impl CycloneClient<UnixStream> for SandboxedUdsInstance {
+    async fn watch(&mut self) -> result::Result<Watch<UnixStream>, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.watch().await
+    }
+
+    async fn liveness(&mut self) -> result::Result<LivenessStatus, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.liveness().await
+    }
+
+    async fn readiness(&mut self) -> result::Result<ReadinessStatus, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.readiness().await
+    }
+
+    async fn execute_ping(&mut self) -> result::Result<PingExecution<UnixStream>, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_ping().await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_resolver(
+        &mut self,
+        request: ResolverFunctionRequest,
+    ) -> result::Result<
+        Execution<UnixStream, ResolverFunctionRequest, ResolverFunctionResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_resolver(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_validation(
+        &mut self,
+        request: ValidationRequest,
+    ) -> result::Result<
+        Execution<UnixStream, ValidationRequest, ValidationResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_validation(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_action_run(
+        &mut self,
+        request: ActionRunRequest,
+    ) -> result::Result<Execution<UnixStream, ActionRunRequest, ActionRunResultSuccess>, ClientError>
+    {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_action_run(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_reconciliation(
+        &mut self,
+        request: ReconciliationRequest,
+    ) -> result::Result<
+        Execution<UnixStream, ReconciliationRequest, ReconciliationResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_reconciliation(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_schema_variant_definition(
+        &mut self,
+        request: SchemaVariantDefinitionRequest,
+    ) -> result::Result<
+        Execution<UnixStream, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_schema_variant_definition(request).await;
+        self.count_request();
+
+        result
+    }
+}
+
+impl SandboxedUdsInstance {
+    async fn ensure_healthy_client(&mut self) -> Result<()> {
+        if !self.is_watch_shutdown_open() {
+            return Err(SandboxedUdsInstanceError::WatchShutDown);
+        }
+        if !self.has_remaining_requests() {
+            return Err(SandboxedUdsInstanceError::NoRemainingRequests);
+        }
+
+        Ok(())
+    }
+
+    fn has_remaining_requests(&self) -> bool {
+        match self.limit_requests {
+            Some(remaining) if remaining == 0 => false,
+            Some(_) | None => true,
+        }
+    }
+
+    fn is_watch_shutdown_open(&self) -> bool {
+        !self.watch_shutdown_tx.is_closed()
+    }
+
+    fn count_request(&mut self) {
+        if let Some(limit_requests) = self.limit_requests.as_mut() {
+            *limit_requests = limit_requests.saturating_sub(1);
+        }
+    }
+}
+
+/// The [`Spec`] for [`SandboxedUdsInstance`]
+#[derive(Builder, Clone, Debug, Eq, PartialEq)]
+#[builder(build_fn(validate = "Self::validate"))]
+pub struct SandboxedUdsInstanceSpec {
+    /// Sandboxing runtime used to isolate a spawned Cyclone server.
+    #[builder(default)]
+    runtime: SandboxRuntime,
+
+    /// Canonical path to the sandboxing runtime program, i.e. `bwrap` or `podman`.
+    #[builder(try_setter, setter(into))]
+    runtime_cmd_path: CanonicalCommand,
+
+    /// Container image holding the `cyclone` and language server programs, required by the
+    /// [`SandboxRuntime::Podman`] runtime.
+    #[builder(setter(into, strip_option), default)]
+    image: Option<String>,
+
+    /// Path to the `cyclone` program, as seen from inside the sandbox.
+    #[builder(setter(into))]
+    cyclone_cmd_path: PathBuf,
+
+    /// Canonical path to Cyclone's secret key file, mounted read-only into the sandbox.
+    #[builder(setter(into))]
+    cyclone_decryption_key_path: String,
+
+    /// Path to the language server program, as seen from inside the sandbox.
+    #[builder(setter(into))]
+    lang_server_cmd_path: PathBuf,
+
+    /// Host paths holding what the `cyclone` and language server programs load at runtime,
+    /// mounted read-only into a [`SandboxRuntime::Bubblewrap`] sandbox along with the directories
+    /// of the two programs.
+    #[builder(setter(each(name = "install_path", into)), default)]
+    install_paths: Vec<PathBuf>,
+
+    /// Parent directory of the directories shared with each sandbox, defaulting to the system's
+    /// temp directory.
+    #[builder(setter(into, strip_option), default)]
+    runtime_dir: Option<PathBuf>,
+
+    /// Allows a spawned Cyclone server to reach the network.
+    #[builder(default = "false")]
+    network: bool,
+
+    /// Path to a seccomp profile applied to a spawned Cyclone server, in the format expected by
+    /// the runtime: compiled BPF for [`SandboxRuntime::Bubblewrap`] and JSON for
+    /// [`SandboxRuntime::Podman`].
+    #[builder(setter(into, strip_option), default)]
+    seccomp_profile_path: Option<PathBuf>,
+
+    /// Sets the watch timeout value for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    watch_timeout: Option<Duration>,
+
+    /// Sets the limit requests strategy for a spawned Cyclone server.
+    #[builder(setter(into), default = "Some(1)")]
+    limit_requests: Option<u32>,
+
+    /// Sets the execution timeout for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    execution_timeout: Option<Duration>,
+
+    /// Sets the lang server memory limit, in MiB, for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    lang_server_memory_limit_mib: Option<u64>,
+
+    /// Sets the lang server CPU time limit for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    lang_server_cpu_limit: Option<Duration>,
+
+    /// Sets the execution output limit, in bytes, for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    output_limit_bytes: Option<usize>,
+
+    /// Runs lang servers denied network access in their own network namespace for a spawned
+    /// Cyclone server. Only applies when the sandbox itself allows `network`,
+    /// as there is otherwise nothing to isolate executions from.
+    #[builder(default = "false")]
+    lang_server_network_isolation: bool,
+
+    /// Enables the `ping` execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_ping"), default = "false")]
+    ping: bool,
+
+    /// Enables the `resolver` execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_resolver"), default = "false")]
+    resolver: bool,
+
+    /// Enables the `action` execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_action"), default = "false")]
+    action: bool,
+}
+
+#[async_trait]
+impl Spec for SandboxedUdsInstanceSpec {
+    type Instance = SandboxedUdsInstance;
+    type Error = SandboxedUdsInstanceError;
+
+    async fn spawn(&self) -> result::Result<Self::Instance, Self::Error> {
+        let instance_dir = match &self.runtime_dir {
+            Some(runtime_dir) => tempfile::tempdir_in(runtime_dir),
+            None => tempfile::tempdir(),
+        }
+        .map_err(Self::Error::InstanceDir)?;
+        prepare_scratch_dir(&instance_dir.path().join(SCRATCH_DIR_NAME))
+            .await
+            .map_err(Self::Error::InstanceDir)?;
+        let socket = instance_dir.path().join(SOCKET_FILE_NAME);
+
+        // bwrap reads the seccomp profile from an inherited file descriptor, which is closed on
+        // our side as soon as the child has been spawned
+        let seccomp_fd = match (self.runtime, &self.seccomp_profile_path) {
+            (SandboxRuntime::Bubblewrap, Some(path)) => Some(
+                fcntl::open(path, OFlag::O_RDONLY, Mode::empty())
+                    .map_err(Self::Error::SeccompProfile)?,
+            ),
+            (SandboxRuntime::Bubblewrap, None) | (SandboxRuntime::Podman, _) => None,
+        };
+        let mut cmd = self.build_command(instance_dir.path(), &socket, seccomp_fd);
+
+        debug!("spawning sandboxed child process; cmd={:?}", &cmd);
+        let spawned = cmd.spawn();
+        if let Some(seccomp_fd) = seccomp_fd {
+            if let Err(err) = unistd::close(seccomp_fd) {
+                warn!(error = ?err, "failed to close seccomp profile");
+            }
+        }
+        let child = spawned.map_err(Self::Error::ChildSpawn)?;
+
+        let mut client = Client::uds(socket)?;
+
+        // Establish the client watch session. Starting a sandbox takes longer than starting a
+        // plain process, so we will retry for a longer period before giving up and assuming that
+        // the server instance has failed.
+        let watch = {
+            let mut retries = 120;
+            loop {
+                trace!("calling client.watch()");
+                if let Ok(watch) = client.watch().await {
+                    trace!("client watch session established");
+                    break watch;
+                }
+                if retries < 1 {
+                    return Err(Self::Error::WatchInitTimeout);
+                }
+                retries -= 1;
+                time::sleep(Duration::from_millis(64)).await;
+            }
+        };
+
+        let mut watch_progress = watch.start().await?;
+        // Establish that we have received our first watch ping, which should happen immediately
+        // after establishing a watch session
+        watch_progress
+            .next()
+            .await
+            .ok_or(Self::Error::WatchClosed)??;
+
+        let (watch_shutdown_tx, watch_shutdown_rx) = oneshot::channel();
+        // Spawn a task to keep the watch session open until we shut it down
+        tokio::spawn(watch_task(watch_progress, watch_shutdown_rx));
+
+        Ok(Self::Instance {
+            instance_dir,
+            client,
+            limit_requests: self.limit_requests,
+            child,
+            watch_shutdown_tx,
+        })
+    }
+}
+
+impl SandboxedUdsInstanceSpec {
+    fn build_command(
+        &self,
+        instance_dir: &Path,
+        socket: &Path,
+        seccomp_fd: Option<RawFd>,
+    ) -> Command {
+        let scratch_dir = instance_dir.join(SCRATCH_DIR_NAME);
+        let mut cmd = Command::new(&self.runtime_cmd_path);
+
+        match self.runtime {
+            SandboxRuntime::Bubblewrap => {
+                cmd.arg("--die-with-parent")
+                    .arg("--new-session")
+                    .arg("--unshare-all");
+                if self.network {
+                    cmd.arg("--share-net");
+                }
+                for path in SYSTEM_PATHS.iter().chain(ETC_PATHS) {
+                    cmd.arg("--ro-bind-try").arg(path).arg(path);
+                }
+                for path in self.bwrap_install_paths() {
+                    cmd.arg("--ro-bind").arg(path).arg(path);
+                }
+                cmd.arg("--dev").arg("/dev");
+                cmd.arg("--proc").arg("/proc");
+                // Hides the directories of the other instances before sharing our own
+                if let Some(parent) = instance_dir.parent() {
+                    cmd.arg("--tmpfs").arg(parent);
+                }
+                cmd.arg("--bind").arg(instance_dir).arg(instance_dir);
+                cmd.arg("--ro-bind")
+                    .arg(&self.cyclone_decryption_key_path)
+                    .arg(&self.cyclone_decryption_key_path);
+                cmd.arg("--clearenv");
+                for (key, value) in cyclone_env(&scratch_dir) {
+                    cmd.arg("--setenv").arg(key).arg(value);
+                }
+                if let Some(seccomp_fd) = seccomp_fd {
+                    cmd.arg("--seccomp").arg(seccomp_fd.to_string());
+                }
+                cmd.arg("--").arg(&self.cyclone_cmd_path);
+            }
+            SandboxRuntime::Podman => {
+                cmd.arg("run")
+                    .arg("--rm")
+                    .arg("--read-only")
+                    .arg("--cap-drop=all")
+                    .arg("--security-opt=no-new-privileges")
+                    // Keeps the socket created in the container owned by the user running us
+                    .arg("--userns=keep-id");
+                if !self.network {
+                    cmd.arg("--network=none");
+                }
+                if let Some(seccomp_profile_path) = &self.seccomp_profile_path {
+                    cmd.arg("--security-opt")
+                        .arg(format!("seccomp={}", seccomp_profile_path.display()));
+                }
+                cmd.arg("--volume")
+                    .arg(format!("{0}:{0}:rw", instance_dir.display()));
+                cmd.arg("--volume")
+                    .arg(format!("{0}:{0}:ro", self.cyclone_decryption_key_path));
+                // The image brings its own `PATH`, which is kept
+                for (key, value) in cyclone_env(&scratch_dir)
+                    .into_iter()
+                    .filter(|(key, _)| key != "PATH")
+                {
+                    let mut var = key;
+                    var.push("=");
+                    var.push(value);
+                    cmd.arg("--env").arg(var);
+                }
+                // Presence of the image is checked when the spec is built
+                if let Some(image) = &self.image {
+                    cmd.arg(image);
+                }
+                cmd.arg(&self.cyclone_cmd_path);
+            }
+        }
+
+        cmd.args(self.cyclone_args(socket));
+        cmd
+    }
+
+    fn bwrap_install_paths(&self) -> Vec<&Path> {
+        let cmd_dirs = [&self.cyclone_cmd_path, &self.lang_server_cmd_path]
+            .into_iter()
+            .filter_map(|cmd_path| cmd_path.parent());
+        let install_paths = self.install_paths.iter().map(PathBuf::as_path);
+
+        let mut paths = Vec::new();
+        for path in cmd_dirs.chain(install_paths) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    fn cyclone_args(&self, socket: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "--bind-uds".into(),
+            socket.into(),
+            "--decryption-key".into(),
+            self.cyclone_decryption_key_path.as_str().into(),
+            "--lang-server".into(),
+            self.lang_server_cmd_path.as_os_str().into(),
+            "--enable-watch".into(),
+        ];
+        if let Some(limit_requests) = self.limit_requests {
+            args.extend(["--limit-requests".into(), limit_requests.to_string().into()]);
+        }
+        if let Some(timeout) = self.watch_timeout {
+            args.extend([
+                "--watch-timeout".into(),
+                timeout.as_secs().to_string().into(),
+            ]);
+        }
+        if let Some(timeout) = self.execution_timeout {
+            args.extend([
+                "--execution-timeout".into(),
+                timeout.as_secs().to_string().into(),
+            ]);
+        }
+        if let Some(memory_limit_mib) = self.lang_server_memory_limit_mib {
+            args.extend([
+                "--lang-server-memory-limit".into(),
+                memory_limit_mib.to_string().into(),
+            ]);
+        }
+        if let Some(cpu_limit) = self.lang_server_cpu_limit {
+            args.extend([
+                "--lang-server-cpu-limit".into(),
+                cpu_limit.as_secs().to_string().into(),
+            ]);
+        }
+        if let Some(output_limit_bytes) = self.output_limit_bytes {
+            args.extend([
+                "--output-limit-bytes".into(),
+                output_limit_bytes.to_string().into(),
+            ]);
+        }
+        if self.network && self.lang_server_network_isolation {
+            // Without network access in the sandbox, there is nothing left to isolate executions
+            // from and nested namespaces may not be permitted by the runtime, so the flag is only
+            // passed on when the sandbox shares the network
+            args.push("--enable-lang-server-network-isolation".into());
+        }
+        if self.ping {
+            args.push("--enable-ping".into());
+        }
+        if self.resolver {
+            args.push("--enable-resolver".into());
+        }
+        if self.action {
+            args.push("--enable-action-run".into());
+        }
+
+        args
+    }
+}
+
+impl SpecBuilder for SandboxedUdsInstanceSpecBuilder {
+    type Spec = SandboxedUdsInstanceSpec;
+    type Error = SandboxedUdsInstanceError;
+
+    fn build(&self) -> result::Result<Self::Spec, Self::Error> {
+        self.build().map_err(Into::into)
+    }
+}
+
+impl SandboxedUdsInstanceSpecBuilder {
+    /// Sets the limit requests strategy to `1` for a spawned Cyclone server.
+    pub fn oneshot(&mut self) -> &mut Self {
+        self.limit_requests(Some(1))
+    }
+
+    /// Enables the `ping` execution endpoint for a spawned Cyclone server.
+    pub fn ping(&mut self) -> &mut Self {
+        self._ping(true)
+    }
+
+    /// Enables the `resolver` execution endpoint for a spawned Cyclone server.
+    pub fn resolver(&mut self) -> &mut Self {
+        self._resolver(true)
+    }
+
+    /// Enables the `action` execution endpoint for a spawned Cyclone server.
+    pub fn action(&mut self) -> &mut Self {
+        self._action(true)
+    }
+
+    /// Enables all available endpoints for a spawned Cyclone server
+    pub fn all_endpoints(&mut self) -> &mut Self {
+        self.action().resolver()
+    }
+
+    fn validate(&self) -> result::Result<(), String> {
+        match (self.runtime.unwrap_or_default(), &self.image) {
+            (SandboxRuntime::Podman, None | Some(None)) => {
+                Err("an image is required by the podman runtime".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Sandboxing runtime isolating a spawned [`SandboxedUdsInstance`].
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SandboxRuntime {
+    /// Runs Cyclone in a set of fresh Linux namespaces with
+    /// [bubblewrap](https://github.com/containers/bubblewrap).
+    Bubblewrap,
+    /// Runs Cyclone in a rootless container with [podman](https://podman.io), using the
+    /// configured image.
+    Podman,
+}
+
+impl Default for SandboxRuntime {
+    fn default() -> Self {
+        Self::Bubblewrap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CYCLONE_CMD_PATH: &str = "/opt/cyclone/bin/cyclone";
+    const DECRYPTION_KEY_PATH: &str = "/run/cyclone/decryption.key";
+    const LANG_SERVER_CMD_PATH: &str = "/opt/lang-js/bin/lang-js";
+    const INSTANCE_DIR: &str = "/tmp/sandboxes/instance";
+
+    fn spec_builder(runtime: SandboxRuntime) -> SandboxedUdsInstanceSpecBuilder {
+        let mut builder = SandboxedUdsInstance::spec();
+        builder
+            .runtime(runtime)
+            // The runtime isn't run when only building its arguments, so any program will do
+            .try_runtime_cmd_path("sh")
+            .expect("failed to find sh")
+            .cyclone_cmd_path(CYCLONE_CMD_PATH)
+            .cyclone_decryption_key_path(DECRYPTION_KEY_PATH)
+            .lang_server_cmd_path(LANG_SERVER_CMD_PATH);
+        builder
+    }
+
+    fn args(spec: &SandboxedUdsInstanceSpec, seccomp_fd: Option<RawFd>) -> Vec<String> {
+        let instance_dir = Path::new(INSTANCE_DIR);
+        spec.build_command(
+            instance_dir,
+            &instance_dir.join(SOCKET_FILE_NAME),
+            seccomp_fd,
+        )
+        .as_std()
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+    }
+
+    fn contains(args: &[String], expected: &[&str]) -> bool {
+        args.windows(expected.len())
+            .any(|window| window == expected)
+    }
+
+    #[test]
+    fn bwrap_network() {
+        let spec = spec_builder(SandboxRuntime::Bubblewrap)
+            .build()
+            .expect("failed to build spec");
+        let args = args(&spec, None);
+        assert!(contains(&args, &["--unshare-all"]));
+        assert!(!contains(&args, &["--share-net"]));
+
+        let spec = spec_builder(SandboxRuntime::Bubblewrap)
+            .network(true)
+            .build()
+            .expect("failed to build spec");
+        let args = args(&spec, None);
+        assert!(contains(&args, &["--unshare-all", "--share-net"]));
+        assert!(!contains(
+            &args,
+            &["--enable-lang-server-network-isolation"]
+        ));
+    }
+
+    #[test]
+    fn lang_server_network_isolation() {
+        let spec = spec_builder(SandboxRuntime::Bubblewrap)
+            .lang_server_network_isolation(true)
+            .build()
+            .expect("failed to build spec");
+        assert!(!contains(
+            &args(&spec, None),
+            &["--enable-lang-server-network-isolation"]
+        ));
+
+        let spec = spec_builder(SandboxRuntime::Bubblewrap)
+            .network(true)
+            .lang_server_network_isolation(true)
+            .build()
+            .expect("failed to build spec");
+        assert!(contains(
+            &args(&spec, None),
+            &["--enable-lang-server-network-isolation"]
+        ));
+    }
+
+    #[test]
+    fn bwrap_seccomp_fd() {
+        let spec = spec_builder(SandboxRuntime::Bubblewrap)
+            .build()
+            .expect("failed to build spec");
+        assert!(!contains(&args(&spec, None), &["--seccomp"]));
+        assert!(contains(&args(&spec, Some(7)), &["--seccomp", "7"]));
+    }
+
+    #[test]
+    fn bwrap_mounts() {
+        let spec = spec_builder(SandboxRuntime::Bubblewrap)
+            .install_path("/nix/store")
+            .build()
+            .expect("failed to build spec");
+        let args = args(&spec, None);
+
+        assert!(!contains(&args, &["--ro-bind", "/", "/"]));
+        assert!(contains(&args, &["--ro-bind-try", "/usr", "/usr"]));
+        assert!(contains(&args, &["--ro-bind-try", "/lib", "/lib"]));
+        assert!(contains(
+            &args,
+            &["--ro-bind-try", "/etc/resolv.conf", "/etc/resolv.conf"]
+        ));
+        assert!(!contains(&args, &["--ro-bind-try", "/etc", "/etc"]));
+        assert!(contains(
+            &args,
+            &["--ro-bind", "/opt/cyclone/bin", "/opt/cyclone/bin"]
+        ));
+        assert!(contains(
+            &args,
+            &["--ro-bind", "/opt/lang-js/bin", "/opt/lang-js/bin"]
+        ));
+        assert!(contains(&args, &["--ro-bind", "/nix/store", "/nix/store"]));
+        assert!(contains(
+            &args,
+            &["--ro-bind", DECRYPTION_KEY_PATH, DECRYPTION_KEY_PATH]
+        ));
+        assert!(contains(&args, &["--tmpfs", "/tmp/sandboxes"]));
+        assert!(contains(&args, &["--bind", INSTANCE_DIR, INSTANCE_DIR]));
+
+        assert!(contains(&args, &["--clearenv"]));
+        assert!(contains(
+            &args,
+            &["--setenv", "TMPDIR", "/tmp/sandboxes/instance/scratch"]
+        ));
+        assert!(contains(
+            &args,
+            &["--setenv", "HOME", "/tmp/sandboxes/instance/scratch/home"]
+        ));
+        assert!(contains(
+            &args,
+            &[
+                "--",
+                CYCLONE_CMD_PATH,
+                "--bind-uds",
+                "/tmp/sandboxes/instance/cyclone.sock"
+            ]
+        ));
+    }
+
+    #[test]
+    fn podman_args() {
+        let spec = spec_builder(SandboxRuntime::Podman)
+            .image("si/cyclone:latest")
+            .seccomp_profile_path("/etc/si/seccomp.json")
+            .build()
+            .expect("failed to build spec");
+        let args = args(&spec, None);
+        assert!(contains(&args, &["--network=none"]));
+        assert!(contains(
+            &args,
+            &["--security-opt", "seccomp=/etc/si/seccomp.json"]
+        ));
+        assert!(contains(
+            &args,
+            &[
+                "--volume",
+                "/tmp/sandboxes/instance:/tmp/sandboxes/instance:rw"
+            ]
+        ));
+        assert!(contains(
+            &args,
+            &["--env", "HOME=/tmp/sandboxes/instance/scratch/home"]
+        ));
+        assert!(!args.iter().any(|arg| arg.starts_with("PATH=")));
+        assert!(contains(&args, &["si/cyclone:latest", CYCLONE_CMD_PATH]));
+
+        let spec = spec_builder(SandboxRuntime::Podman)
+            .image("si/cyclone:latest")
+            .network(true)
+            .build()
+            .expect("failed to build spec");
+        assert!(!contains(&args(&spec, None), &["--network=none"]));
+    }
+
+    #[test]
+    fn podman_requires_image() {
+        spec_builder(SandboxRuntime::Podman)
+            .build()
+            .expect_err("podman spec without an image should not build");
+        spec_builder(SandboxRuntime::Podman)
+            .image("si/cyclone:latest")
+            .build()
+            .expect("failed to build spec");
+        spec_builder(SandboxRuntime::Bubblewrap)
+            .build()
+            .expect("failed to build spec");
+    }
+
+    #[tokio::test]
+    async fn spawns_in_bwrap_sandbox() {
+        if CanonicalCommand::try_from("bwrap").is_err() {
+            eprintln!("skipping test, bwrap is not installed");
+            return;
+        }
+
+        let mut config_file = veritech_server::ConfigFile::default_local_uds();
+        veritech_server::detect_and_configure_development(&mut config_file)
+            .expect("failed to determine test configuration");
+
+        let mut builder = SandboxedUdsInstance::spec();
+        builder
+            .try_runtime_cmd_path("bwrap")
+            .expect("failed to find bwrap")
+            .cyclone_cmd_path(config_file.cyclone.cyclone_cmd_path())
+            .cyclone_decryption_key_path(config_file.cyclone.cyclone_decryption_key_path())
+            .lang_server_cmd_path(config_file.cyclone.lang_server_cmd_path())
+            .ping();
+        // Development builds may link against libraries in the Nix store
+        if Path::new("/nix/store").is_dir() {
+            builder.install_path("/nix/store");
+        }
+        let spec = builder.build().expect("failed to build spec");
+
+        let mut instance = spec.spawn().await.expect("failed to spawn instance");
+        let status = instance
+            .liveness()
+            .await
+            .expect("failed to run liveness check");
+        assert_eq!(status, LivenessStatus::Ok);
+        instance
+            .execute_ping()
+            .await
+            .expect("failed execute ping")
+            .start()
+            .await
+            .expect("failed to start protocol");
+        instance.terminate().await.expect("failed to terminate");
+    }
+}
@@ -15,25 +15,38 @@
     clippy::module_name_repetitions
 )]
 
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
 use async_trait::async_trait;
 use deadpool::managed;
 use thiserror::Error;
+use tokio::time::Instant;
+use tracing::{debug, trace, warn};
 
 pub use self::instance::{Instance, Spec};
+pub use self::metrics::{ManagerMetrics, ManagerMetricsSnapshot};
 
 pub use cyclone_client::{
-    ClientError, CycloneClient, EncryptionKey, EncryptionKeyError, ExecutionError,
+    ClientError, CycloneClient, EncryptionKey, EncryptionKeyError, ExecutionError, UnixStream,
 };
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, ComponentView, FunctionResult, FunctionResultFailure,
-    FunctionResultFailureError, OutputStream, ProgressMessage, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
-    ResourceStatus, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
-    ValidationRequest, ValidationResultSuccess,
+    FunctionResultFailureError, FunctionResultFailureErrorKind, OutputStream, ProgressMessage,
+    ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
 };
 
 /// [`Instance`] implementations.
 pub mod instance;
+mod metrics;
 
 /// Type alias for using [`managed::Pool`] with Cyclone.
 pub type Pool<S> = managed::Pool<Manager<S>>;
@@ -70,33 +83,231 @@ pub enum ManagerError<T> {
 }
 
 /// [`Manager`] for creating and recycling generic [`Instance`]s.
-#[derive(Debug)]
-pub struct Manager<S> {
-    spec: S,
+///
+/// A manager can keep a number of spawned and ready instances on the side ("warm" instances) so
+/// that creating an instance for the pool doesn't wait on a process to boot. Warm instances are
+/// replaced in the background as they are handed out.
+pub struct Manager<S: Spec> {
+    spec: Arc<S>,
+    warm_instances: usize,
+    warm: Arc<Mutex<VecDeque<S::Instance>>>,
+    warming: Arc<AtomicUsize>,
+    shutting_down: Arc<AtomicBool>,
+    metrics: Arc<ManagerMetrics>,
 }
 
-impl<S> Manager<S> {
-    /// Creates a new [`Manager`] from the given instance specification.
+impl<S: Spec> Manager<S> {
+    /// Creates a new [`Manager`] from the given instance specification, which spawns instances
+    /// on demand.
     pub fn new(spec: S) -> Self {
-        Self { spec }
+        Self::with_warm_instances(spec, 0)
+    }
+
+    /// Creates a new [`Manager`] from the given instance specification, which keeps the given
+    /// number of spawned instances ready to be handed out.
+    ///
+    /// Instances are spawned in the background, starting with a call to [`Manager::warm_up`] or
+    /// with the first instance created for the pool.
+    pub fn with_warm_instances(spec: S, warm_instances: usize) -> Self {
+        Self {
+            spec: Arc::new(spec),
+            warm_instances,
+            warm: Default::default(),
+            warming: Default::default(),
+            shutting_down: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns the metrics of the instances spawned and recycled by this manager.
+    pub fn metrics(&self) -> Arc<ManagerMetrics> {
+        self.metrics.clone()
+    }
+}
+
+impl<S, I, E> Manager<S>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<Error = E> + Send + 'static,
+    E: fmt::Debug + Send + 'static,
+{
+    /// Starts spawning warm instances in the background, up to the configured number.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn warm_up(&self) {
+        if self.shutting_down.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // The lock is held while counting so that concurrent callers don't overshoot the number
+        // of warm instances
+        let warm = match self.warm.lock() {
+            Ok(warm) => warm,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let pending = warm.len() + self.warming.load(Ordering::Relaxed);
+        for _ in pending..self.warm_instances {
+            self.warming.fetch_add(1, Ordering::Relaxed);
+
+            let spec = self.spec.clone();
+            let warm = self.warm.clone();
+            let warming = self.warming.clone();
+            let shutting_down = self.shutting_down.clone();
+            let metrics = self.metrics.clone();
+            tokio::spawn(async move {
+                let result = spawn_instance(&spec, &metrics).await;
+                warming.fetch_sub(1, Ordering::Relaxed);
+                match result {
+                    Ok(instance) => {
+                        let mut warm = match warm.lock() {
+                            Ok(warm) => warm,
+                            Err(poisoned) => poisoned.into_inner(),
+                        };
+                        // The flag is checked under the lock so that an instance finishing its
+                        // spawn during a shutdown is either drained or terminated here
+                        if shutting_down.load(Ordering::Relaxed) {
+                            drop(warm);
+                            if let Err(err) = instance.terminate().await {
+                                warn!(error = ?err, "failed to terminate warm instance");
+                            }
+                            return;
+                        }
+                        warm.push_back(instance);
+                        metrics.set_warm(warm.len());
+                        trace!(warm = warm.len(), "warm instance ready");
+                    }
+                    Err(err) => warn!(error = ?err, "failed to spawn warm instance"),
+                }
+            });
+        }
+    }
+
+    /// Stops spawning warm instances and terminates the ones which are ready.
+    ///
+    /// Instances handed out to the pool are not affected. Warm instances still being spawned are
+    /// terminated as soon as they are ready.
+    pub async fn shutdown(&self) {
+        let drained: Vec<I> = {
+            let mut warm = match self.warm.lock() {
+                Ok(warm) => warm,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            self.shutting_down.store(true, Ordering::Relaxed);
+            let drained = warm.drain(..).collect();
+            self.metrics.set_warm(0);
+            drained
+        };
+
+        debug!(count = drained.len(), "terminating warm instances");
+        for instance in drained {
+            if let Err(err) = instance.terminate().await {
+                warn!(error = ?err, "failed to terminate warm instance");
+            }
+        }
+    }
+
+    fn take_warm(&self) -> Option<I> {
+        let mut warm = match self.warm.lock() {
+            Ok(warm) => warm,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let instance = warm.pop_front();
+        self.metrics.set_warm(warm.len());
+        instance
+    }
+}
+
+impl<S> fmt::Debug for Manager<S>
+where
+    S: Spec + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Manager")
+            .field("spec", &self.spec)
+            .field("warm_instances", &self.warm_instances)
+            .field("metrics", &self.metrics)
+            .finish_non_exhaustive()
     }
 }
 
 #[async_trait]
 impl<B, S, I, E> managed::Manager for Manager<S>
 where
-    S: Spec<Error = E, Instance = I> + Send + Sync,
-    I: Instance<SpecBuilder = B, Error = E> + Send,
+    S: Spec<Error = E, Instance = I> + Send + Sync + 'static,
+    I: Instance<SpecBuilder = B, Error = E> + Send + 'static,
+    E: fmt::Debug + Send + 'static,
 {
     type Type = I;
     type Error = E;
 
     async fn create(&self) -> Result<Self::Type, Self::Error> {
-        self.spec.spawn().await
+        // Warm instances may have gone stale while waiting, for example if their watch session
+        // timed out, so they are checked before being handed out
+        while let Some(mut instance) = self.take_warm() {
+            match instance.ensure_healthy().await {
+                Ok(()) => {
+                    self.metrics.record_warm_hit();
+                    self.warm_up();
+                    return Ok(instance);
+                }
+                Err(err) => {
+                    debug!(error = ?err, "discarding unhealthy warm instance");
+                    tokio::spawn(async move {
+                        if let Err(err) = instance.terminate().await {
+                            warn!(error = ?err, "failed to terminate unhealthy warm instance");
+                        }
+                    });
+                }
+            }
+        }
+
+        if self.warm_instances > 0 {
+            self.metrics.record_warm_miss();
+        }
+        let instance = spawn_instance(&self.spec, &self.metrics).await;
+        self.warm_up();
+        instance
     }
 
     async fn recycle(&self, obj: &mut Self::Type) -> managed::RecycleResult<Self::Error> {
-        obj.ensure_healthy().await.map_err(Into::into)
+        if obj.is_exhausted() {
+            self.metrics.record_retirement();
+            return Err(managed::RecycleError::StaticMessage(
+                "instance has no remaining requests",
+            ));
+        }
+
+        let recycled = match obj.ensure_healthy().await {
+            Ok(()) => obj.scrub().await,
+            Err(err) => Err(err),
+        };
+        match recycled {
+            Ok(()) => {
+                self.metrics.record_recycle();
+                Ok(())
+            }
+            Err(err) => {
+                self.metrics.record_recycle_failure();
+                Err(err.into())
+            }
+        }
+    }
+}
+
+async fn spawn_instance<S, I, E>(spec: &S, metrics: &ManagerMetrics) -> Result<I, E>
+where
+    S: Spec<Error = E, Instance = I>,
+{
+    let started_at = Instant::now();
+    match spec.spawn().await {
+        Ok(instance) => {
+            metrics.record_spawn(started_at.elapsed());
+            Ok(instance)
+        }
+        Err(err) => {
+            metrics.record_spawn_failure();
+            Err(err)
+        }
     }
 }
 
@@ -155,4 +366,123 @@ mod tests {
 
         instance.terminate().await.expect("failed to terminate");
     }
+
+    #[derive(Debug, Default)]
+    struct CountingSpecBuilder;
+
+    impl instance::SpecBuilder for CountingSpecBuilder {
+        type Spec = CountingSpec;
+        type Error = std::convert::Infallible;
+
+        fn build(&self) -> Result<Self::Spec, Self::Error> {
+            Ok(CountingSpec::default())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingSpec {
+        spawned: AtomicUsize,
+        terminated: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Spec for CountingSpec {
+        type Instance = CountingInstance;
+        type Error = std::convert::Infallible;
+
+        async fn spawn(&self) -> Result<Self::Instance, Self::Error> {
+            Ok(CountingInstance {
+                id: self.spawned.fetch_add(1, Ordering::Relaxed),
+                remaining_requests: 1,
+                terminated: self.terminated.clone(),
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct CountingInstance {
+        id: usize,
+        remaining_requests: u32,
+        terminated: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Instance for CountingInstance {
+        type SpecBuilder = CountingSpecBuilder;
+        type Error = std::convert::Infallible;
+
+        async fn ensure_healthy(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        async fn terminate(mut self) -> Result<(), Self::Error> {
+            self.terminated.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn is_exhausted(&self) -> bool {
+            self.remaining_requests == 0
+        }
+    }
+
+    #[tokio::test]
+    async fn warm_instances() {
+        let manager = Manager::with_warm_instances(CountingSpec::default(), 2);
+        manager.warm_up();
+        let metrics = manager.metrics();
+        while metrics.snapshot().warm < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // Warm instances are handed out first and replaced in the background
+        let mut instance = managed::Manager::create(&manager)
+            .await
+            .expect("failed to create instance");
+        assert_eq!(0, instance.id);
+        while metrics.snapshot().warm < 2 {
+            tokio::task::yield_now().await;
+        }
+        let snapshot = metrics.snapshot();
+        assert_eq!(3, snapshot.spawned);
+        assert_eq!(1, snapshot.warm_hits);
+        assert_eq!(0, snapshot.warm_misses);
+
+        // Instances get recycled until they have served all of their requests
+        managed::Manager::recycle(&manager, &mut instance)
+            .await
+            .expect("failed to recycle instance");
+        instance.remaining_requests = 0;
+        managed::Manager::recycle(&manager, &mut instance)
+            .await
+            .expect_err("exhausted instance should not be recycled");
+        let snapshot = metrics.snapshot();
+        assert_eq!(1, snapshot.recycled);
+        assert_eq!(1, snapshot.retired);
+        assert_eq!(0, snapshot.recycle_failures);
+    }
+
+    #[tokio::test]
+    async fn shutdown_terminates_warm_instances() {
+        let spec = CountingSpec::default();
+        let terminated = spec.terminated.clone();
+        let manager = Manager::with_warm_instances(spec, 2);
+        manager.warm_up();
+        let metrics = manager.metrics();
+        while metrics.snapshot().warm < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        manager.shutdown().await;
+        assert_eq!(2, terminated.load(Ordering::Relaxed));
+        assert_eq!(0, metrics.snapshot().warm);
+
+        // No more warm instances are spawned once shut down
+        let instance = managed::Manager::create(&manager)
+            .await
+            .expect("failed to create instance");
+        assert_eq!(2, instance.id);
+        tokio::task::yield_now().await;
+        assert_eq!(3, metrics.snapshot().spawned);
+        assert_eq!(0, metrics.snapshot().warm);
+    }
 }
@@ -0,0 +1,112 @@
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Metrics about the [`Instance`](crate::Instance)s spawned and recycled by a
+/// [`Manager`](crate::Manager).
+///
+/// The counters are updated by the manager as it goes, and a consistent enough view of them can
+/// be taken at any time with [`ManagerMetrics::snapshot`].
+#[derive(Debug, Default)]
+pub struct ManagerMetrics {
+    warm: AtomicUsize,
+    warm_hits: AtomicU64,
+    warm_misses: AtomicU64,
+    spawned: AtomicU64,
+    spawn_failures: AtomicU64,
+    spawn_latency_total_micros: AtomicU64,
+    spawn_latency_last_micros: AtomicU64,
+    recycled: AtomicU64,
+    recycle_failures: AtomicU64,
+    retired: AtomicU64,
+}
+
+impl ManagerMetrics {
+    /// Returns the current value of every metric.
+    pub fn snapshot(&self) -> ManagerMetricsSnapshot {
+        let spawned = self.spawned.load(Ordering::Relaxed);
+        let spawn_latency_total_micros = self.spawn_latency_total_micros.load(Ordering::Relaxed);
+
+        ManagerMetricsSnapshot {
+            warm: self.warm.load(Ordering::Relaxed),
+            warm_hits: self.warm_hits.load(Ordering::Relaxed),
+            warm_misses: self.warm_misses.load(Ordering::Relaxed),
+            spawned,
+            spawn_failures: self.spawn_failures.load(Ordering::Relaxed),
+            last_spawn_latency: Duration::from_micros(
+                self.spawn_latency_last_micros.load(Ordering::Relaxed),
+            ),
+            mean_spawn_latency: Duration::from_micros(
+                spawn_latency_total_micros
+                    .checked_div(spawned)
+                    .unwrap_or_default(),
+            ),
+            recycled: self.recycled.load(Ordering::Relaxed),
+            recycle_failures: self.recycle_failures.load(Ordering::Relaxed),
+            retired: self.retired.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn set_warm(&self, warm: usize) {
+        self.warm.store(warm, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_warm_hit(&self) {
+        self.warm_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_warm_miss(&self) {
+        self.warm_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_spawn(&self, latency: Duration) {
+        let latency_micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.spawned.fetch_add(1, Ordering::Relaxed);
+        self.spawn_latency_total_micros
+            .fetch_add(latency_micros, Ordering::Relaxed);
+        self.spawn_latency_last_micros
+            .store(latency_micros, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_spawn_failure(&self) {
+        self.spawn_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_recycle(&self) {
+        self.recycled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_recycle_failure(&self) {
+        self.recycle_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_retirement(&self) {
+        self.retired.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A point in time copy of [`ManagerMetrics`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ManagerMetricsSnapshot {
+    /// Number of spawned instances ready to be handed out.
+    pub warm: usize,
+    /// Number of instances handed out from the warm instances.
+    pub warm_hits: u64,
+    /// Number of instances spawned on demand as no warm instance was ready.
+    pub warm_misses: u64,
+    /// Number of instances successfully spawned.
+    pub spawned: u64,
+    /// Number of instances which failed to spawn.
+    pub spawn_failures: u64,
+    /// Time taken to spawn the latest instance.
+    pub last_spawn_latency: Duration,
+    /// Mean time taken to spawn an instance.
+    pub mean_spawn_latency: Duration,
+    /// Number of instances successfully recycled in between requests.
+    pub recycled: u64,
+    /// Number of instances which were unhealthy or failed to be scrubbed when recycled.
+    pub recycle_failures: u64,
+    /// Number of instances retired after serving all the requests they were spawned for.
+    pub retired: u64,
+}
@@ -1,4 +1,4 @@
-use std::{env, path::Path};
+use std::{collections::HashMap, env, path::Path};
 
 use buck2_resources::Buck2Resources;
 use derive_builder::Builder;
@@ -10,6 +10,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 pub use dal::CycloneKeyPair;
+use dal::{SecretBackendsConfig, WorkspacePk};
 pub use si_settings::{StandardConfig, StandardConfigFile};
 use ulid::Ulid;
 
@@ -51,6 +52,18 @@ pub struct Config {
 
     #[builder(default = "random_instance_id()")]
     instance_id: String,
+
+    #[builder(default)]
+    durable_queue: bool,
+
+    #[builder(default)]
+    workspace_concurrency: Option<usize>,
+
+    #[builder(default)]
+    workspace_concurrency_overrides: HashMap<WorkspacePk, usize>,
+
+    #[builder(default)]
+    secret_backends: SecretBackendsConfig,
 }
 
 impl StandardConfig for Config {
@@ -90,6 +103,27 @@ impl Config {
     pub fn instance_id(&self) -> &str {
         self.instance_id.as_ref()
     }
+
+    /// Whether jobs are also consumed from, and enqueued onto, the durable JetStream job queue.
+    pub fn durable_queue(&self) -> bool {
+        self.durable_queue
+    }
+
+    /// Gets the config's limit on how many jobs of a single workspace can run at once, if any.
+    pub fn workspace_concurrency(&self) -> Option<usize> {
+        self.workspace_concurrency
+    }
+
+    /// Gets a reference to the config's per-workspace limits, which take precedence over
+    /// [`workspace_concurrency`](Self::workspace_concurrency).
+    pub fn workspace_concurrency_overrides(&self) -> &HashMap<WorkspacePk, usize> {
+        &self.workspace_concurrency_overrides
+    }
+
+    /// Gets a reference to the config's secret backends config.
+    pub fn secret_backends(&self) -> &SecretBackendsConfig {
+        &self.secret_backends
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -104,6 +138,14 @@ pub struct ConfigFile {
     concurrency_limit: usize,
     #[serde(default = "random_instance_id")]
     instance_id: String,
+    #[serde(default)]
+    durable_queue: bool,
+    #[serde(default)]
+    workspace_concurrency_limit: Option<usize>,
+    #[serde(default)]
+    workspace_concurrency_limit_overrides: HashMap<WorkspacePk, usize>,
+    #[serde(default)]
+    secret_backends: SecretBackendsConfig,
 }
 
 impl Default for ConfigFile {
@@ -114,6 +156,10 @@ impl Default for ConfigFile {
             cyclone_encryption_key_path: default_cyclone_encryption_key_path(),
             concurrency_limit: default_concurrency_limit(),
             instance_id: random_instance_id(),
+            durable_queue: false,
+            workspace_concurrency_limit: None,
+            workspace_concurrency_limit_overrides: HashMap::new(),
+            secret_backends: Default::default(),
         }
     }
 }
@@ -134,6 +180,10 @@ impl TryFrom<ConfigFile> for Config {
         config.cyclone_encryption_key_path(value.cyclone_encryption_key_path.try_into()?);
         config.concurrency(value.concurrency_limit);
         config.instance_id(value.instance_id);
+        config.durable_queue(value.durable_queue);
+        config.workspace_concurrency(value.workspace_concurrency_limit);
+        config.workspace_concurrency_overrides(value.workspace_concurrency_limit_overrides);
+        config.secret_backends(value.secret_backends);
         config.build().map_err(Into::into)
     }
 }
@@ -1,4 +1,5 @@
 mod config;
+mod scheduler;
 pub mod server;
 
 pub use crate::{
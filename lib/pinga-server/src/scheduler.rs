@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use dal::{job::producer::JobPriority, WorkspacePk};
+
+/// Decides which pending job `pinga` runs next.
+///
+/// Jobs of a higher [`JobPriority`] always go first. Within a priority, workspaces take turns,
+/// so a workspace that enqueues hundreds of jobs only delays everyone else by one job at a time.
+/// A workspace that already has its cap of jobs running is skipped until one of them finishes.
+///
+/// Jobs that another job is blocked on are the exception: they go ahead of everything else and
+/// are never held back by a cap, since the job waiting on them already holds a slot and would
+/// otherwise wait on them forever.
+#[derive(Debug)]
+pub struct JobScheduler<T> {
+    workspace_limit: Option<usize>,
+    workspace_limits: HashMap<WorkspacePk, usize>,
+    running: HashMap<Option<WorkspacePk>, usize>,
+    blocking: VecDeque<(Option<WorkspacePk>, T)>,
+    pending: BTreeMap<JobPriority, RoundRobin<T>>,
+    len: usize,
+}
+
+impl<T> JobScheduler<T> {
+    pub fn new(
+        workspace_limit: Option<usize>,
+        workspace_limits: HashMap<WorkspacePk, usize>,
+    ) -> Self {
+        Self {
+            workspace_limit,
+            workspace_limits,
+            running: HashMap::new(),
+            blocking: VecDeque::new(),
+            pending: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the jobs waiting to run.
+    pub fn pending(&self) -> impl Iterator<Item = &T> {
+        self.blocking.iter().map(|(_, job)| job).chain(
+            self.pending
+                .values()
+                .flat_map(|queue| queue.jobs.values().flatten()),
+        )
+    }
+
+    pub fn push(&mut self, workspace_pk: Option<WorkspacePk>, priority: JobPriority, job: T) {
+        self.pending
+            .entry(priority)
+            .or_insert_with(RoundRobin::new)
+            .push(workspace_pk, job);
+        self.len += 1;
+    }
+
+    /// Queues a job that another job is blocked on.
+    pub fn push_blocking(&mut self, workspace_pk: Option<WorkspacePk>, job: T) {
+        self.blocking.push_back((workspace_pk, job));
+        self.len += 1;
+    }
+
+    /// Takes the next job that may run right now, counting it as running until
+    /// [`finished`](Self::finished) is called with the workspace it was returned with.
+    pub fn next(&mut self) -> Option<(Option<WorkspacePk>, T)> {
+        if let Some(next) = self.next_blocking() {
+            return Some(next);
+        }
+
+        let mut taken = None;
+        for (priority, queue) in self.pending.iter_mut().rev() {
+            if let Some(next) = queue.pop(|workspace_pk| {
+                has_capacity(
+                    &self.running,
+                    self.workspace_limit,
+                    &self.workspace_limits,
+                    workspace_pk,
+                )
+            }) {
+                taken = Some((*priority, next));
+                break;
+            }
+        }
+
+        let (priority, (workspace_pk, job)) = taken?;
+        if self
+            .pending
+            .get(&priority)
+            .map_or(false, RoundRobin::is_empty)
+        {
+            self.pending.remove(&priority);
+        }
+        self.len -= 1;
+        *self.running.entry(workspace_pk).or_default() += 1;
+
+        Some((workspace_pk, job))
+    }
+
+    /// Takes the next job that another job is blocked on, if there is one. These may run even
+    /// when every slot is taken, as they run in place of the jobs waiting on them.
+    pub fn next_blocking(&mut self) -> Option<(Option<WorkspacePk>, T)> {
+        let (workspace_pk, job) = self.blocking.pop_front()?;
+        self.len -= 1;
+        *self.running.entry(workspace_pk).or_default() += 1;
+
+        Some((workspace_pk, job))
+    }
+
+    pub fn finished(&mut self, workspace_pk: Option<WorkspacePk>) {
+        if let Some(running) = self.running.get_mut(&workspace_pk) {
+            *running = running.saturating_sub(1);
+            if *running == 0 {
+                self.running.remove(&workspace_pk);
+            }
+        }
+    }
+}
+
+fn has_capacity(
+    running: &HashMap<Option<WorkspacePk>, usize>,
+    workspace_limit: Option<usize>,
+    workspace_limits: &HashMap<WorkspacePk, usize>,
+    workspace_pk: Option<WorkspacePk>,
+) -> bool {
+    // Jobs without a workspace aren't tenant work, so they are never held back.
+    let Some(pk) = workspace_pk else {
+        return true;
+    };
+    let limit = match workspace_limits.get(&pk) {
+        Some(limit) => *limit,
+        None => match workspace_limit {
+            Some(limit) => limit,
+            None => return true,
+        },
+    };
+
+    running.get(&workspace_pk).copied().unwrap_or(0) < limit
+}
+
+/// Pending jobs of a single priority, taking turns between workspaces.
+#[derive(Debug)]
+struct RoundRobin<T> {
+    turns: VecDeque<Option<WorkspacePk>>,
+    jobs: HashMap<Option<WorkspacePk>, VecDeque<T>>,
+}
+
+impl<T> RoundRobin<T> {
+    fn new() -> Self {
+        Self {
+            turns: VecDeque::new(),
+            jobs: HashMap::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    fn push(&mut self, workspace_pk: Option<WorkspacePk>, job: T) {
+        let jobs = self.jobs.entry(workspace_pk).or_default();
+        if jobs.is_empty() {
+            self.turns.push_back(workspace_pk);
+        }
+        jobs.push_back(job);
+    }
+
+    /// Pops a job from the first workspace in line that `may_run`, sending that workspace to the
+    /// back of the line.
+    fn pop(
+        &mut self,
+        may_run: impl Fn(Option<WorkspacePk>) -> bool,
+    ) -> Option<(Option<WorkspacePk>, T)> {
+        let position = self
+            .turns
+            .iter()
+            .position(|workspace_pk| may_run(*workspace_pk))?;
+        let workspace_pk = self.turns.remove(position)?;
+
+        let jobs = self.jobs.get_mut(&workspace_pk)?;
+        let job = jobs.pop_front()?;
+        if jobs.is_empty() {
+            self.jobs.remove(&workspace_pk);
+        } else {
+            self.turns.push_back(workspace_pk);
+        }
+
+        Some((workspace_pk, job))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(scheduler: &mut JobScheduler<&'static str>) -> Vec<&'static str> {
+        std::iter::from_fn(|| scheduler.next().map(|(_, job)| job)).collect()
+    }
+
+    #[test]
+    fn higher_priorities_go_first() {
+        let mut scheduler = JobScheduler::new(None, HashMap::new());
+        let workspace_pk = Some(WorkspacePk::generate());
+        scheduler.push(workspace_pk, JobPriority::Background, "background");
+        scheduler.push(workspace_pk, JobPriority::Normal, "normal");
+        scheduler.push(workspace_pk, JobPriority::Interactive, "interactive");
+
+        assert_eq!(
+            vec!["interactive", "normal", "background"],
+            drain(&mut scheduler)
+        );
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn workspaces_take_turns() {
+        let mut scheduler = JobScheduler::new(None, HashMap::new());
+        let busy = Some(WorkspacePk::generate());
+        let quiet = Some(WorkspacePk::generate());
+        scheduler.push(busy, JobPriority::Normal, "busy 1");
+        scheduler.push(busy, JobPriority::Normal, "busy 2");
+        scheduler.push(busy, JobPriority::Normal, "busy 3");
+        scheduler.push(quiet, JobPriority::Normal, "quiet 1");
+
+        assert_eq!(
+            vec!["busy 1", "quiet 1", "busy 2", "busy 3"],
+            drain(&mut scheduler)
+        );
+    }
+
+    #[test]
+    fn workspace_at_its_cap_waits() {
+        let capped = WorkspacePk::generate();
+        let mut scheduler = JobScheduler::new(Some(2), HashMap::from([(capped, 1)]));
+        let other = Some(WorkspacePk::generate());
+        scheduler.push(Some(capped), JobPriority::Normal, "capped 1");
+        scheduler.push(Some(capped), JobPriority::Normal, "capped 2");
+        scheduler.push(other, JobPriority::Normal, "other 1");
+        scheduler.push(other, JobPriority::Normal, "other 2");
+        scheduler.push(other, JobPriority::Normal, "other 3");
+
+        assert_eq!(
+            vec!["capped 1", "other 1", "other 2"],
+            drain(&mut scheduler)
+        );
+
+        scheduler.finished(Some(capped));
+        assert_eq!(vec!["capped 2"], drain(&mut scheduler));
+        scheduler.finished(other);
+        assert_eq!(vec!["other 3"], drain(&mut scheduler));
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn blocking_children_run_despite_the_cap() {
+        let workspace_pk = Some(WorkspacePk::generate());
+        let mut scheduler = JobScheduler::new(Some(1), HashMap::new());
+        scheduler.push(workspace_pk, JobPriority::Interactive, "parent");
+        scheduler.push(workspace_pk, JobPriority::Interactive, "sibling");
+        assert_eq!(vec!["parent"], drain(&mut scheduler));
+
+        // The parent holds the workspace's only slot while it waits on its child.
+        scheduler.push_blocking(workspace_pk, "child");
+        assert_eq!(vec!["child"], drain(&mut scheduler));
+
+        scheduler.finished(workspace_pk);
+        scheduler.finished(workspace_pk);
+        assert_eq!(vec!["sibling"], drain(&mut scheduler));
+    }
+
+    #[test]
+    fn blocking_children_go_ahead_of_everything_else() {
+        let mut scheduler = JobScheduler::new(None, HashMap::new());
+        let workspace_pk = Some(WorkspacePk::generate());
+        scheduler.push(workspace_pk, JobPriority::Interactive, "interactive");
+        scheduler.push_blocking(workspace_pk, "child");
+
+        assert_eq!(vec!["child", "interactive"], drain(&mut scheduler));
+    }
+}
@@ -1,17 +1,26 @@
-use std::{io, path::Path, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
 
 use dal::{
     job::{
         consumer::{JobConsumer, JobConsumerError, JobInfo},
-        definition::{FixesJob, RefreshJob},
-        producer::BlockingJobError,
+        definition::{FixesJob, KeyPairRotationJob, RefreshJob},
+        processor::{jetstream_processor::REPLY_MAILBOX_HEADER, JobQueueProcessorError},
+        producer::{BlockingJobError, JobPriority},
+        retry::DeadLetter,
     },
-    DalContext, DalContextBuilder, DependentValuesUpdate, InitializationError, JobFailure,
-    JobFailureError, JobQueueProcessor, NatsProcessor, ServicesContext, TransactionsError,
+    DalContext, DalContextBuilder, DependentValuesUpdate, InitializationError, JetStreamProcessor,
+    JobFailure, JobFailureError, JobQueueProcessor, NatsProcessor, SecretBackends, ServicesContext,
+    TransactionsError, WorkspacePk,
 };
-use futures::{FutureExt, Stream, StreamExt};
+use futures::{stream, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
 use nats_subscriber::{Request, SubscriberError};
-use si_data_nats::{NatsClient, NatsConfig, NatsError};
+use si_data_nats::{JetStreamMessage, NatsClient, NatsConfig, NatsError};
 use si_data_pg::{PgPool, PgPoolConfig, PgPoolError};
 use stream_cancel::StreamExt as StreamCancelStreamExt;
 use telemetry::prelude::*;
@@ -23,11 +32,21 @@ use tokio::{
         oneshot, watch,
     },
     task,
+    time::Instant,
 };
-use tokio_stream::wrappers::UnboundedReceiverStream;
 use veritech_client::{Client as VeritechClient, EncryptionKey, EncryptionKeyError};
 
-use crate::{nats_jobs_subject, Config, NATS_JOBS_DEFAULT_QUEUE};
+use crate::{nats_jobs_subject, scheduler::JobScheduler, Config, NATS_JOBS_DEFAULT_QUEUE};
+
+/// How long a job pulled off the durable queue may go without news before JetStream hands it to
+/// another instance.
+const DURABLE_ACK_WAIT: Duration = Duration::from_secs(30);
+/// How often a running durable job tells JetStream it is still being worked on.
+const DURABLE_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+/// The durable consumer dead letters are replayed through.
+const DEAD_LETTER_REPLAY_CONSUMER: &str = "pinga-dead-letter-replay";
+/// How many dead letters are pulled off the dead letter queue at a time when replaying them.
+const DEAD_LETTER_REPLAY_BATCH: usize = 100;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -41,6 +60,8 @@ pub enum ServerError {
     #[error(transparent)]
     JobFailure(#[from] Box<JobFailureError>),
     #[error(transparent)]
+    JobQueueProcessor(#[from] JobQueueProcessorError),
+    #[error(transparent)]
     Nats(#[from] NatsError),
     #[error(transparent)]
     PgPool(#[from] Box<PgPoolError>),
@@ -78,7 +99,11 @@ type Result<T> = std::result::Result<T, ServerError>;
 
 pub struct Server {
     concurrency_limit: usize,
+    workspace_concurrency_limit: Option<usize>,
+    workspace_concurrency_overrides: HashMap<WorkspacePk, usize>,
+    durable_queue: bool,
     encryption_key: Arc<EncryptionKey>,
+    secret_backends: SecretBackends,
     nats: NatsClient,
     pg_pool: PgPool,
     veritech: VeritechClient,
@@ -105,12 +130,16 @@ impl Server {
         let nats = Self::connect_to_nats(config.nats()).await?;
         let pg_pool = Self::create_pg_pool(config.pg_pool()).await?;
         let veritech = Self::create_veritech_client(nats.clone());
-        let job_processor = Self::create_job_processor(nats.clone());
+        let job_processor = Self::create_job_processor(nats.clone(), config.durable_queue());
 
         Self::from_services(
             config.instance_id().to_string(),
             config.concurrency(),
+            config.workspace_concurrency(),
+            config.workspace_concurrency_overrides().clone(),
+            config.durable_queue(),
             encryption_key,
+            SecretBackends::from_config(config.secret_backends()),
             nats,
             pg_pool,
             veritech,
@@ -123,7 +152,11 @@ impl Server {
     pub fn from_services(
         instance_id: impl Into<String>,
         concurrency_limit: usize,
+        workspace_concurrency_limit: Option<usize>,
+        workspace_concurrency_overrides: HashMap<WorkspacePk, usize>,
+        durable_queue: bool,
         encryption_key: Arc<EncryptionKey>,
+        secret_backends: SecretBackends,
         nats: NatsClient,
         pg_pool: PgPool,
         veritech: VeritechClient,
@@ -148,10 +181,14 @@ impl Server {
 
         Ok(Server {
             concurrency_limit,
+            workspace_concurrency_limit,
+            workspace_concurrency_overrides,
+            durable_queue,
             pg_pool,
             nats,
             veritech,
             encryption_key,
+            secret_backends,
             job_processor,
             shutdown_watch_rx,
             external_shutdown_tx,
@@ -167,6 +204,10 @@ impl Server {
         drop(task::spawn(process_job_requests_task(
             rx,
             self.concurrency_limit,
+            JobScheduler::new(
+                self.workspace_concurrency_limit,
+                self.workspace_concurrency_overrides,
+            ),
         )));
 
         // Run "the main loop" which pulls message from a subscription off NATS and forwards each
@@ -179,6 +220,8 @@ impl Server {
             self.veritech,
             self.job_processor,
             self.encryption_key,
+            self.secret_backends,
+            self.durable_queue,
             self.shutdown_watch_rx,
         )
         .await;
@@ -189,6 +232,47 @@ impl Server {
         Ok(())
     }
 
+    /// Puts every job on the dead letter queue back on the job queue, through the job processor
+    /// `config` selects, and returns how many were replayed.
+    #[instrument(name = "pinga.replay_dead_letters", skip_all)]
+    pub async fn replay_dead_letters(config: Config) -> Result<usize> {
+        let nats = Self::connect_to_nats(config.nats()).await?;
+        let job_processor = Self::create_job_processor(nats.clone(), config.durable_queue());
+        let dead_letters = DeadLetter::queue(&nats).await?;
+
+        let mut replayed = 0;
+        loop {
+            let messages = dead_letters
+                .fetch(
+                    DEAD_LETTER_REPLAY_CONSUMER,
+                    DURABLE_ACK_WAIT,
+                    DEAD_LETTER_REPLAY_BATCH,
+                )
+                .await?;
+            if messages.is_empty() {
+                break;
+            }
+
+            for message in messages {
+                match serde_json::from_slice::<DeadLetter>(message.payload()) {
+                    Ok(dead_letter) => {
+                        info!(
+                            job.id = %dead_letter.job.id,
+                            job.kind = %dead_letter.job.kind,
+                            "replaying dead letter",
+                        );
+                        dead_letter.replay(job_processor.as_ref()).await?;
+                        replayed += 1;
+                    }
+                    Err(err) => warn!(error = ?err, "dropping unreadable dead letter"),
+                }
+                message.ack().await?;
+            }
+        }
+
+        Ok(replayed)
+    }
+
     /// Gets a [`ShutdownHandle`](PingaShutdownHandle) that can externally or on demand trigger the server's shutdown
     /// process.
     pub fn shutdown_handle(&self) -> PingaShutdownHandle {
@@ -222,8 +306,15 @@ impl Server {
     }
 
     #[instrument(name = "pinga.init.create_job_processor", skip_all)]
-    fn create_job_processor(nats: NatsClient) -> Box<dyn JobQueueProcessor + Send + Sync> {
-        Box::new(NatsProcessor::new(nats)) as Box<dyn JobQueueProcessor + Send + Sync>
+    fn create_job_processor(
+        nats: NatsClient,
+        durable_queue: bool,
+    ) -> Box<dyn JobQueueProcessor + Send + Sync> {
+        if durable_queue {
+            Box::new(JetStreamProcessor::new(nats)) as Box<dyn JobQueueProcessor + Send + Sync>
+        } else {
+            Box::new(NatsProcessor::new(nats)) as Box<dyn JobQueueProcessor + Send + Sync>
+        }
     }
 }
 
@@ -262,6 +353,18 @@ pub struct JobItem {
     messaging_destination: Arc<String>,
     ctx_builder: DalContextBuilder,
     request: Result<Request<JobInfo>>,
+    /// Set for jobs pulled off the durable queue, which must be acknowledged once they are done.
+    durable: Option<JetStreamMessage>,
+}
+
+impl JobItem {
+    /// How long until a retry has waited out its backoff, if it has not yet.
+    fn delay(&self) -> Option<Duration> {
+        self.request
+            .as_ref()
+            .ok()
+            .and_then(|request| request.payload.delay())
+    }
 }
 
 pub struct Subscriber;
@@ -274,6 +377,8 @@ impl Subscriber {
         veritech: veritech_client::Client,
         job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
         encryption_key: Arc<veritech_client::EncryptionKey>,
+        secret_backends: SecretBackends,
+        durable_queue: bool,
     ) -> Result<impl Stream<Item = JobItem>> {
         let subject = nats_jobs_subject(nats.metadata().subject_prefix());
         debug!(
@@ -289,6 +394,7 @@ impl Subscriber {
             encryption_key,
             None,
             None,
+            secret_backends,
         );
 
         // Make non blocking context here, and update it for each job
@@ -297,16 +403,80 @@ impl Subscriber {
 
         let messaging_destination = Arc::new(subject.clone());
 
-        Ok(nats_subscriber::Subscriber::create(subject)
-            .queue_name(NATS_JOBS_DEFAULT_QUEUE)
-            .start(&nats)
+        let core_jobs = {
+            let metadata = metadata.clone();
+            let ctx_builder = ctx_builder.clone();
+            nats_subscriber::Subscriber::create(subject)
+                .queue_name(NATS_JOBS_DEFAULT_QUEUE)
+                .start(&nats)
+                .await?
+                .map(move |request| JobItem {
+                    metadata: metadata.clone(),
+                    messaging_destination: messaging_destination.clone(),
+                    ctx_builder: ctx_builder.clone(),
+                    request: request.map_err(Into::into),
+                    durable: None,
+                })
+        };
+
+        if !durable_queue {
+            return Ok(core_jobs.boxed());
+        }
+
+        let prefix = nats.metadata().subject_prefix();
+        let durable_subject = JetStreamProcessor::subject(prefix);
+        debug!(
+            messaging.destination = &durable_subject.as_str(),
+            "consuming durable job requests"
+        );
+
+        let durable_jobs = nats
+            .jetstream()
+            .get_or_create_work_queue(
+                JetStreamProcessor::stream_name(prefix),
+                vec![durable_subject.clone()],
+            )
             .await?
-            .map(move |request| JobItem {
+            .consume(NATS_JOBS_DEFAULT_QUEUE, DURABLE_ACK_WAIT)
+            .await?;
+
+        let messaging_destination = Arc::new(durable_subject);
+        let durable_jobs = durable_jobs.map(move |message| {
+            let (request, durable): (Result<Request<JobInfo>>, Option<JetStreamMessage>) =
+                match message {
+                    Ok(message) => {
+                        let reply_mailbox = message
+                            .headers()
+                            .and_then(|headers| headers.get(REPLY_MAILBOX_HEADER))
+                            .map(|value| value.as_str().to_owned());
+                        // Durable jobs are retried by having JetStream redeliver them, so every
+                        // delivery after the first is another attempt.
+                        let redeliveries = u32::try_from(message.delivered().saturating_sub(1))
+                            .unwrap_or(u32::MAX);
+                        let request = serde_json::from_slice::<JobInfo>(message.payload())
+                            .map(|mut payload| {
+                                payload.attempt = payload.attempt.saturating_add(redeliveries);
+                                Request {
+                                    payload,
+                                    reply_mailbox,
+                                }
+                            })
+                            .map_err(Into::into);
+                        (request, Some(message))
+                    }
+                    Err(err) => (Err(err.into()), None),
+                };
+
+            JobItem {
                 metadata: metadata.clone(),
                 messaging_destination: messaging_destination.clone(),
                 ctx_builder: ctx_builder.clone(),
-                request: request.map_err(Into::into),
-            }))
+                request,
+                durable,
+            }
+        });
+
+        Ok(stream::select(core_jobs, durable_jobs).boxed())
     }
 }
 
@@ -319,6 +489,8 @@ async fn receive_job_requests_task(
     veritech: veritech_client::Client,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     encryption_key: Arc<veritech_client::EncryptionKey>,
+    secret_backends: SecretBackends,
+    durable_queue: bool,
     shutdown_watch_rx: watch::Receiver<()>,
 ) {
     if let Err(err) = receive_job_requests(
@@ -329,6 +501,8 @@ async fn receive_job_requests_task(
         veritech,
         job_processor,
         encryption_key,
+        secret_backends,
+        durable_queue,
         shutdown_watch_rx,
     )
     .await
@@ -346,6 +520,8 @@ async fn receive_job_requests(
     veritech: veritech_client::Client,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     encryption_key: Arc<veritech_client::EncryptionKey>,
+    secret_backends: SecretBackends,
+    durable_queue: bool,
     mut shutdown_watch_rx: watch::Receiver<()>,
 ) -> Result<()> {
     let mut requests = Subscriber::jobs(
@@ -355,6 +531,8 @@ async fn receive_job_requests(
         veritech,
         job_processor,
         encryption_key,
+        secret_backends,
+        durable_queue,
     )
     .await?
     .take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
@@ -370,39 +548,187 @@ async fn receive_job_requests(
     Ok(())
 }
 
-async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_limit: usize) {
-    UnboundedReceiverStream::new(rx)
-        .for_each_concurrent(concurrency_limit, |job| async move {
-            // Got the next message from the subscriber
-            trace!("pulled request into an available concurrent task");
-
-            match job.request {
-                Ok(request) => {
-                    // Spawn a task and process the request
-                    let join_handle = task::spawn(execute_job_task(
-                        job.metadata,
-                        job.messaging_destination,
-                        job.ctx_builder,
-                        request,
-                    ));
-                    if let Err(err) = join_handle.await {
-                        // NOTE(fnichol): This likely happens when there is contention or
-                        // an error in the Tokio runtime so we will be loud and log an
-                        // error under the assumptions that 1) this event rarely
-                        // happens and 2) the task code did not contribute to trigger
-                        // the `JoinError`.
-                        error!(
-                            error = ?err,
-                            "execute-job-task failed to execute to completion"
-                        );
-                    };
+async fn process_job_requests_task(
+    mut rx: UnboundedReceiver<JobItem>,
+    concurrency_limit: usize,
+    mut scheduler: JobScheduler<JobItem>,
+) {
+    let mut running = FuturesUnordered::new();
+    let mut progress_interval = tokio::time::interval(DURABLE_PROGRESS_INTERVAL);
+    let mut receiving = true;
+    // Retries off the core job queue waiting out their backoff, keyed by when they may run (and
+    // an arrival counter to keep the keys unique). They don't count against any limit until then.
+    let mut delayed: BTreeMap<(Instant, u64), JobItem> = BTreeMap::new();
+    let mut arrivals: u64 = 0;
+
+    loop {
+        loop {
+            // Jobs other jobs are blocked on run in place of the jobs waiting for them, so they
+            // don't wait for a free slot.
+            let next = if running.len() < concurrency_limit {
+                scheduler.next()
+            } else {
+                scheduler.next_blocking()
+            };
+            match next {
+                Some((workspace_pk, job)) => running.push(async move {
+                    process_job_item(job).await;
+                    workspace_pk
+                }),
+                None => break,
+            }
+        }
+
+        // Once the subscription has closed, keep going until everything it handed us has run.
+        if !receiving && running.is_empty() {
+            if !delayed.is_empty() {
+                warn!(
+                    retries = delayed.len(),
+                    "dropping job retries still waiting out their backoff"
+                );
+            }
+            break;
+        }
+
+        let next_delayed = delayed.keys().next().map(|(at, _)| *at);
+
+        tokio::select! {
+            job = rx.recv(), if receiving => match job {
+                Some(job) => match (job.delay(), &job.durable) {
+                    // JetStream can hold on to a durable job until it may run.
+                    (Some(delay), Some(message)) => {
+                        if let Err(err) = message.nak(Some(delay)).await {
+                            error!(error = ?err, "unable to delay durable job");
+                        }
+                    }
+                    (Some(delay), None) => {
+                        delayed.insert((Instant::now() + delay, arrivals), job);
+                        arrivals += 1;
+                    }
+                    (None, _) => schedule(&mut scheduler, job),
+                },
+                None => receiving = false,
+            },
+            _ = tokio::time::sleep_until(next_delayed.unwrap_or_else(Instant::now)),
+                if next_delayed.is_some() =>
+            {
+                let now = Instant::now();
+                while let Some(entry) = delayed.first_entry() {
+                    if entry.key().0 > now {
+                        break;
+                    }
+                    schedule(&mut scheduler, entry.remove());
+                }
+            }
+            Some(workspace_pk) = running.next(), if !running.is_empty() => {
+                scheduler.finished(workspace_pk);
+            }
+            _ = progress_interval.tick(), if !scheduler.is_empty() => {
+                // Durable jobs waiting their turn would otherwise be redelivered to another
+                // instance once their ack deadline passes.
+                futures::future::join_all(
+                    scheduler
+                        .pending()
+                        .filter_map(|job| job.durable.as_ref().map(|message| message.in_progress())),
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Queues a job according to which workspace it is run on behalf of, how urgent it is, and
+/// whether another job is blocked on it.
+fn schedule(scheduler: &mut JobScheduler<JobItem>, job: JobItem) {
+    match &job.request {
+        Ok(request) => {
+            let workspace_pk = request.payload.access_builder.tenancy().workspace_pk();
+            if request.payload.blocking {
+                scheduler.push_blocking(workspace_pk, job);
+            } else {
+                scheduler.push(workspace_pk, request.payload.priority, job);
+            }
+        }
+        // Nothing to run, so get it out of the way.
+        Err(_) => scheduler.push(None, JobPriority::Interactive, job),
+    }
+}
+
+async fn process_job_item(job: JobItem) {
+    // Got the next message from the subscriber
+    trace!("pulled request into an available concurrent task");
+
+    match job.request {
+        Ok(request) => {
+            let message = job.durable;
+
+            // Spawn a task and process the request
+            let mut join_handle = task::spawn(execute_job_task(
+                job.metadata,
+                job.messaging_destination,
+                job.ctx_builder,
+                request,
+                message.is_some(),
+            ));
+            let result = match &message {
+                Some(message) => keep_in_progress(message, &mut join_handle).await,
+                None => (&mut join_handle).await,
+            };
+            if let Some(message) = message {
+                let settled = match &result {
+                    // JetStream redelivers the job once its backoff has passed.
+                    Ok(JobOutcome::Retrying { backoff }) => message.nak(Some(*backoff)).await,
+                    // Whatever else happened, the job has had its go, so it must not be
+                    // redelivered.
+                    _ => message.ack().await,
+                };
+                if let Err(err) = settled {
+                    error!(error = ?err, "unable to acknowledge durable job");
                 }
-                Err(err) => {
-                    warn!(error = ?err, "next job request had an error, job will not be executed");
+            }
+            if let Err(err) = result {
+                // NOTE(fnichol): This likely happens when there is contention or
+                // an error in the Tokio runtime so we will be loud and log an
+                // error under the assumptions that 1) this event rarely
+                // happens and 2) the task code did not contribute to trigger
+                // the `JoinError`.
+                error!(
+                    error = ?err,
+                    "execute-job-task failed to execute to completion"
+                );
+            };
+        }
+        Err(err) => {
+            warn!(error = ?err, "next job request had an error, job will not be executed");
+            // Redelivering a message that can't be read won't make it readable.
+            if let Some(message) = job.durable {
+                if let Err(err) = message.ack().await {
+                    error!(error = ?err, "unable to acknowledge durable job");
                 }
             }
-        })
-        .await;
+        }
+    }
+}
+
+/// Waits for a durable job to finish, pushing back its ack deadline while it runs.
+async fn keep_in_progress(
+    message: &JetStreamMessage,
+    join_handle: &mut task::JoinHandle<JobOutcome>,
+) -> std::result::Result<JobOutcome, task::JoinError> {
+    let mut interval = tokio::time::interval(DURABLE_PROGRESS_INTERVAL);
+    // The first tick completes immediately.
+    interval.tick().await;
+
+    loop {
+        tokio::select! {
+            result = &mut *join_handle => return result,
+            _ = interval.tick() => {
+                if let Err(err) = message.in_progress().await {
+                    warn!(error = ?err, "unable to mark durable job as in progress");
+                }
+            }
+        }
+    }
 }
 
 #[instrument(
@@ -429,7 +755,8 @@ async fn execute_job_task(
     messaging_destination: Arc<String>,
     ctx_builder: DalContextBuilder,
     request: Request<JobInfo>,
-) {
+    durable: bool,
+) -> JobOutcome {
     let span = Span::current();
     let id = request.payload.id.clone();
 
@@ -449,13 +776,20 @@ async fn execute_job_task(
         messaging_destination,
         ctx_builder.clone(),
         request,
+        durable,
     )
     .await
     {
-        Ok(_) => {
+        Ok(JobOutcome::Completed) => {
             span.record_ok();
             Ok(())
         }
+        // The retry carries the reply mailbox along, so whoever is blocking on this job will
+        // hear back once the final attempt is done.
+        Ok(outcome @ JobOutcome::Retrying { .. }) => {
+            span.record_ok();
+            return outcome;
+        }
         Err(err) => {
             error!(
                 error = ?err,
@@ -481,15 +815,27 @@ async fn execute_job_task(
             };
         }
     }
+
+    JobOutcome::Completed
+}
+
+#[remain::sorted]
+#[derive(Debug, Eq, PartialEq)]
+enum JobOutcome {
+    /// The job ran, successfully or not, and will not run again.
+    Completed,
+    /// The job failed and should run again once `backoff` has passed.
+    Retrying { backoff: Duration },
 }
 
 async fn execute_job(
     _metadata: &Arc<ServerMetadata>,
-    _messaging_destination: Arc<String>,
+    messaging_destination: Arc<String>,
     mut ctx_builder: DalContextBuilder,
     request: Request<JobInfo>,
-) -> Result<()> {
-    let (job_info, _) = request.into_parts();
+    durable: bool,
+) -> Result<JobOutcome> {
+    let (job_info, reply_mailbox) = request.into_parts();
     if job_info.blocking {
         ctx_builder.set_blocking();
     }
@@ -511,29 +857,88 @@ async fn execute_job(
         tracing::Span::current().record("job_info.blocking", job_info.blocking);
     }
 
-    let job =
-        match job_info.kind.as_str() {
-            stringify!(DependentValuesUpdate) => {
-                Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
-                    as Box<dyn JobConsumer + Send + Sync>
-            }
-            stringify!(FixesJob) => Box::new(FixesJob::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>,
-            stringify!(RefreshJob) => Box::new(RefreshJob::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>,
-            kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
-        };
+    let job = match job_info.kind.as_str() {
+        stringify!(DependentValuesUpdate) => {
+            Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
+                as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(FixesJob) => {
+            Box::new(FixesJob::try_from(job_info.clone())?) as Box<dyn JobConsumer + Send + Sync>
+        }
+        stringify!(KeyPairRotationJob) => Box::new(KeyPairRotationJob::try_from(job_info.clone())?)
+            as Box<dyn JobConsumer + Send + Sync>,
+        stringify!(RefreshJob) => {
+            Box::new(RefreshJob::try_from(job_info.clone())?) as Box<dyn JobConsumer + Send + Sync>
+        }
+        kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
+    };
 
     info!("Processing job");
 
     if let Err(err) = job.run_job(ctx_builder.clone()).await {
+        let retry_policy = job.retry_policy();
+        if retry_policy.should_retry(&err, job_info.attempt) {
+            let backoff = retry_policy.backoff(job_info.attempt);
+            warn!(
+                error = ?err,
+                attempt = job_info.attempt,
+                max_attempts = retry_policy.max_attempts,
+                ?backoff,
+                "job execution failed, scheduling a retry",
+            );
+            // A durable job stays on the durable queue and is redelivered once its backoff has
+            // passed. Core NATS keeps nothing, so the next attempt goes back out as a new job.
+            if !durable {
+                requeue_retry(
+                    ctx_builder.nats_conn(),
+                    &messaging_destination,
+                    reply_mailbox,
+                    job_info.next_attempt(backoff),
+                )
+                .await;
+            }
+            return Ok(JobOutcome::Retrying { backoff });
+        }
+
+        let dead_letter = DeadLetter::new(job_info, err.to_string());
+        if let Err(err) = dead_letter.publish(ctx_builder.nats_conn()).await {
+            error!(error = ?err, "unable to publish job to the dead letter queue");
+        }
+
         // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
         record_job_failure(ctx_builder, job, err).await?;
     }
 
     info!("Finished processing job");
 
-    Ok(())
+    Ok(JobOutcome::Completed)
+}
+
+/// Puts the next attempt at a job back on the job queue. It waits out its backoff wherever it
+/// lands, without holding up anything else.
+async fn requeue_retry(
+    nats: &NatsClient,
+    subject: &str,
+    reply_mailbox: Option<String>,
+    job_info: JobInfo,
+) {
+    let message = match serde_json::to_vec(&job_info) {
+        Ok(message) => message,
+        Err(err) => {
+            error!(error = ?err, job.id = %job_info.id, "unable to serialize job retry");
+            return;
+        }
+    };
+    let result = match reply_mailbox {
+        Some(reply_mailbox) => {
+            nats.publish_with_reply(subject, reply_mailbox, message)
+                .await
+        }
+        None => nats.publish(subject, message).await,
+    };
+    if let Err(err) = result {
+        error!(error = ?err, job.id = %job_info.id, "unable to enqueue job retry, job will be dropped");
+    }
 }
 
 async fn record_job_failure(
@@ -16,6 +16,6 @@ mod server;
 pub use server::{
     build_service, build_service_for_tests, detect_and_configure_development,
     job_processor::JobProcessorClientCloser, job_processor::JobProcessorConnector, service, Config,
-    ConfigError, ConfigFile, IncomingStream, JobQueueProcessor, MigrationMode, NatsProcessor,
-    Server, StandardConfig, StandardConfigFile,
+    ConfigError, ConfigFile, IncomingStream, JetStreamProcessor, JobQueueProcessor, MigrationMode,
+    NatsProcessor, Server, StandardConfig, StandardConfigFile,
 };
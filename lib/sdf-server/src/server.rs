@@ -2,11 +2,12 @@ pub use config::{
     detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
     IncomingStream, StandardConfig, StandardConfigFile,
 };
-pub use dal::{JobQueueProcessor, MigrationMode, NatsProcessor};
+pub use dal::{JetStreamProcessor, JobQueueProcessor, MigrationMode, NatsProcessor};
 pub use routes::{routes, AppError};
 pub use server::{build_service, build_service_for_tests, Server};
 pub use uds::{UdsIncomingStream, UdsIncomingStreamError};
 
+mod audit;
 mod config;
 pub(crate) mod extract;
 pub(crate) mod job_processor;
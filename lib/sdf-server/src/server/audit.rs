@@ -0,0 +1,106 @@
+//! Records a [`HistoryEvent`](dal::HistoryEvent) for every mutating request, so the audit log
+//! says who changed what and when, regardless of what the handler itself records. The event is
+//! handed to the handler's [`DalContextBuilder`](dal::DalContextBuilder) and written in the same
+//! transaction as the request's changes, so it is only recorded if they are.
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{header, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dal::PendingAudit;
+use hyper::StatusCode;
+use serde_json::{Map, Value};
+
+use super::{extract::AccessBuilder, state::AppState};
+
+/// Audits the requests that aren't reads. Requests that aren't authenticated are passed through
+/// untouched, as there is nobody to attribute them to.
+pub async fn audit_mutations(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    if AccessBuilder::from_request_parts(&mut parts, &state)
+        .await
+        .is_err()
+    {
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    // Only JSON bodies are read, to learn which objects the request is about; anything else
+    // (e.g. uploaded packages) is passed along unread.
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map_or(false, |content_type| {
+            content_type.starts_with("application/json")
+        });
+    let (body, request_body) = if is_json {
+        let bytes = match hyper::body::to_bytes(body).await {
+            Ok(bytes) => bytes,
+            Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+        };
+        let request_body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (Body::from(bytes), request_body)
+    } else {
+        (body, Value::Null)
+    };
+
+    let method = parts.method.clone();
+    let path = parts.uri.path().to_owned();
+    let data = serde_json::json!({
+        "method": method.as_str(),
+        "path": path,
+        "request": identifiers(&request_body),
+    });
+    // Picked up by the `HandlerContext` extractor.
+    parts.extensions.insert(PendingAudit::new(
+        label(&path),
+        format!("{method} {path}"),
+        data,
+    ));
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Turns `/api/change_set/apply_change_set` into `sdf.change_set.apply_change_set`.
+fn label(path: &str) -> String {
+    let route = path
+        .trim_start_matches("/api/")
+        .trim_matches('/')
+        .replace('/', ".")
+        .replace('-', "_");
+    format!("sdf.{route}")
+}
+
+/// The ids and pks in the request body, which say what the request was about without recording
+/// any values (such as secrets) it carried.
+fn identifiers(request_body: &Value) -> Value {
+    let Some(fields) = request_body.as_object() else {
+        return Value::Null;
+    };
+
+    let identifiers: Map<String, Value> = fields
+        .iter()
+        .filter(|(name, _)| {
+            ["Id", "Pk", "_id", "_pk", "Ids", "Pks", "_ids", "_pks"]
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    Value::Object(identifiers)
+}
@@ -15,7 +15,10 @@ use si_std::SensitiveString;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-pub use dal::{CycloneKeyPair, MigrationMode};
+pub use dal::{
+    tasks::{ChangeSetGarbageCollectorConfig, HistoryEventPurgerConfig, ResourceSchedulerConfig},
+    CycloneKeyPair, MigrationMode, SecretBackendsConfig,
+};
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
 const DEFAULT_SIGNUP_SECRET: &str = "cool-steam";
@@ -65,6 +68,21 @@ pub struct Config {
     #[builder(default = "MigrationMode::default()")]
     migration_mode: MigrationMode,
 
+    #[builder(default = "ResourceSchedulerConfig::default()")]
+    resource_scheduler: ResourceSchedulerConfig,
+
+    #[builder(default = "ChangeSetGarbageCollectorConfig::default()")]
+    change_set_garbage_collector: ChangeSetGarbageCollectorConfig,
+
+    #[builder(default = "HistoryEventPurgerConfig::default()")]
+    history_event_purger: HistoryEventPurgerConfig,
+
+    #[builder(default = "SecretBackendsConfig::default()")]
+    secret_backends: SecretBackendsConfig,
+
+    #[builder(default)]
+    durable_queue: bool,
+
     jwt_signing_public_key_path: CanonicalFile,
 
     cyclone_encryption_key_path: CanonicalFile,
@@ -135,6 +153,36 @@ impl Config {
         &self.posthog
     }
 
+    /// Gets a reference to the config's resource scheduler config.
+    #[must_use]
+    pub fn resource_scheduler(&self) -> &ResourceSchedulerConfig {
+        &self.resource_scheduler
+    }
+
+    /// Gets a reference to the config's change set garbage collector config.
+    #[must_use]
+    pub fn change_set_garbage_collector(&self) -> &ChangeSetGarbageCollectorConfig {
+        &self.change_set_garbage_collector
+    }
+
+    /// Gets a reference to the config's history event purger config.
+    #[must_use]
+    pub fn history_event_purger(&self) -> &HistoryEventPurgerConfig {
+        &self.history_event_purger
+    }
+
+    /// Gets a reference to the config's secret backends config.
+    #[must_use]
+    pub fn secret_backends(&self) -> &SecretBackendsConfig {
+        &self.secret_backends
+    }
+
+    /// Whether jobs are enqueued onto the durable JetStream job queue.
+    #[must_use]
+    pub fn durable_queue(&self) -> bool {
+        self.durable_queue
+    }
+
     /// URL to the module index service
     #[must_use]
     pub fn module_index_url(&self) -> &str {
@@ -172,6 +220,16 @@ pub struct ConfigFile {
     pub posthog: PosthogConfig,
     #[serde(default)]
     pub module_index_url: String,
+    #[serde(default)]
+    pub resource_scheduler: ResourceSchedulerConfig,
+    #[serde(default)]
+    pub change_set_garbage_collector: ChangeSetGarbageCollectorConfig,
+    #[serde(default)]
+    pub history_event_purger: HistoryEventPurgerConfig,
+    #[serde(default)]
+    pub secret_backends: SecretBackendsConfig,
+    #[serde(default)]
+    pub durable_queue: bool,
 }
 
 impl Default for ConfigFile {
@@ -186,6 +244,11 @@ impl Default for ConfigFile {
             pkgs_path: default_pkgs_path(),
             posthog: Default::default(),
             module_index_url: default_module_index_url(),
+            resource_scheduler: Default::default(),
+            change_set_garbage_collector: Default::default(),
+            history_event_purger: Default::default(),
+            secret_backends: Default::default(),
+            durable_queue: false,
         }
     }
 }
@@ -210,6 +273,11 @@ impl TryFrom<ConfigFile> for Config {
         config.pkgs_path(value.pkgs_path.try_into()?);
         config.posthog(value.posthog);
         config.module_index_url(value.module_index_url);
+        config.resource_scheduler(value.resource_scheduler);
+        config.change_set_garbage_collector(value.change_set_garbage_collector);
+        config.history_event_purger(value.history_event_purger);
+        config.secret_backends(value.secret_backends);
+        config.durable_queue(value.durable_queue);
         config.build().map_err(Into::into)
     }
 }
@@ -8,7 +8,7 @@ use axum::{
 };
 use dal::{
     context::{self, DalContextBuilder},
-    User, UserClaim,
+    PendingAudit, User, UserClaim,
 };
 use hyper::StatusCode;
 
@@ -70,14 +70,18 @@ impl FromRequestParts<AppState> for HandlerContext {
     type Rejection = (StatusCode, Json<serde_json::Value>);
 
     async fn from_request_parts(
-        _parts: &mut Parts,
+        parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let builder = state
+        let mut builder = state
             .services_context()
             .clone()
             .into_inner()
             .into_builder(state.for_tests());
+        // Set by the audit middleware on mutating requests.
+        if let Some(audit) = parts.extensions.get::<PendingAudit>() {
+            builder.set_audit(audit.clone());
+        }
         Ok(Self(builder))
     }
 }
@@ -1,6 +1,6 @@
 use crate::{server::server::ServerError, Config, Server};
 use async_trait::async_trait;
-use dal::{JobQueueProcessor, NatsProcessor};
+use dal::{JetStreamProcessor, JobQueueProcessor, NatsProcessor};
 use si_data_nats::NatsClient;
 
 #[async_trait]
@@ -24,6 +24,20 @@ pub trait JobProcessorConnector: JobQueueProcessor {
     ) -> Result<(Self::Client, Box<dyn JobQueueProcessor + Send + Sync>), ServerError>;
 }
 
+impl Server {
+    /// Connects the job processor `config` selects: the durable JetStream one when the durable
+    /// queue is enabled, the core NATS one otherwise.
+    pub async fn connect_job_processor(
+        config: &Config,
+    ) -> Result<(NatsClient, Box<dyn JobQueueProcessor + Send + Sync>), ServerError> {
+        if config.durable_queue() {
+            JetStreamProcessor::connect(config).await
+        } else {
+            NatsProcessor::connect(config).await
+        }
+    }
+}
+
 #[async_trait]
 impl JobProcessorConnector for NatsProcessor {
     type Client = NatsClient;
@@ -37,3 +51,17 @@ impl JobProcessorConnector for NatsProcessor {
         Ok((job_client, job_processor))
     }
 }
+
+#[async_trait]
+impl JobProcessorConnector for JetStreamProcessor {
+    type Client = NatsClient;
+
+    async fn connect(
+        config: &Config,
+    ) -> Result<(Self::Client, Box<dyn JobQueueProcessor + Send + Sync>), ServerError> {
+        let job_client = Server::connect_to_nats(config.nats()).await?;
+        let job_processor = Box::new(JetStreamProcessor::new(job_client.clone()))
+            as Box<dyn JobQueueProcessor + Send + Sync>;
+        Ok((job_client, job_processor))
+    }
+}
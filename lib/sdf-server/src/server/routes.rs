@@ -1,4 +1,5 @@
 use axum::{
+    middleware,
     response::Json,
     response::{IntoResponse, Response},
     routing::get,
@@ -11,7 +12,7 @@ use si_data_pg::PgError;
 use thiserror::Error;
 use tower_http::cors::CorsLayer;
 
-use super::{server::ServerError, state::AppState};
+use super::{audit::audit_mutations, server::ServerError, state::AppState};
 
 #[allow(clippy::too_many_arguments)]
 pub fn routes(state: AppState) -> Router {
@@ -22,6 +23,7 @@ pub fn routes(state: AppState) -> Router {
             "/api/",
             Router::new().route("/", get(system_status_route).layer(CorsLayer::permissive())),
         )
+        .nest("/api/audit", crate::server::service::audit::routes())
         .nest(
             "/api/change_set",
             crate::server::service::change_set::routes(),
@@ -52,7 +54,12 @@ pub fn routes(state: AppState) -> Router {
     // Load dev routes if we are in dev mode (decided by "opt-level" at the moment).
     router = dev_routes(router);
 
-    router.with_state(state)
+    router
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            audit_mutations,
+        ))
+        .with_state(state)
 }
 
 async fn system_status_route() -> Json<Value> {
@@ -6,8 +6,14 @@ use axum::Router;
 use dal::tasks::{StatusReceiver, StatusReceiverError};
 use dal::JwtPublicSigningKey;
 use dal::{
-    cyclone_key_pair::CycloneKeyPairError, job::processor::JobQueueProcessor,
-    tasks::ResourceScheduler, ServicesContext,
+    cyclone_key_pair::CycloneKeyPairError,
+    job::processor::JobQueueProcessor,
+    tasks::{
+        ChangeSetGarbageCollector, ChangeSetGarbageCollectorConfig, HistoryEventPurger,
+        HistoryEventPurgerConfig, ResourceScheduler, ResourceSchedulerConfig,
+        ResourceSchedulerHandle,
+    },
+    SecretBackends, ServicesContext,
 };
 use hyper::server::{accept::Accept, conn::AddrIncoming};
 use si_data_nats::{NatsClient, NatsConfig, NatsError};
@@ -103,6 +109,7 @@ impl Server<(), ()> {
                     Arc::new(encryption_key),
                     Some(pkgs_path),
                     Some(module_index_url),
+                    SecretBackends::from_config(config.secret_backends()),
                 );
 
                 let (service, shutdown_rx, shutdown_broadcast_rx) = build_service(
@@ -155,6 +162,7 @@ impl Server<(), ()> {
                     Arc::new(encryption_key),
                     Some(pkgs_path),
                     Some(module_index_url),
+                    SecretBackends::from_config(config.secret_backends()),
                 );
 
                 let (service, shutdown_rx, shutdown_broadcast_rx) = build_service(
@@ -242,13 +250,60 @@ impl Server<(), ()> {
         Ok(())
     }
 
-    /// Start the basic resource refresh scheduler
+    /// Start the basic resource refresh scheduler, returning a handle that reports on it
     pub async fn start_resource_refresh_scheduler(
         pg: PgPool,
         nats: NatsClient,
         job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
         veritech: VeritechClient,
         encryption_key: EncryptionKey,
+        config: ResourceSchedulerConfig,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) -> ResourceSchedulerHandle {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+            SecretBackends::default(),
+        );
+        ResourceScheduler::new_with_config(services_context, config).start(shutdown_broadcast_rx)
+    }
+
+    /// Start the change set garbage collector
+    pub async fn start_change_set_garbage_collector(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        config: ChangeSetGarbageCollectorConfig,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+            SecretBackends::default(),
+        );
+        ChangeSetGarbageCollector::new(services_context, config).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the history event purger
+    pub async fn start_history_event_purger(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        config: HistoryEventPurgerConfig,
         shutdown_broadcast_rx: broadcast::Receiver<()>,
     ) {
         let services_context = ServicesContext::new(
@@ -259,8 +314,9 @@ impl Server<(), ()> {
             Arc::new(encryption_key),
             None,
             None,
+            SecretBackends::default(),
         );
-        ResourceScheduler::new(services_context).start(shutdown_broadcast_rx);
+        HistoryEventPurger::new(services_context, config).start(shutdown_broadcast_rx);
     }
 
     pub async fn start_status_updater(
@@ -279,6 +335,7 @@ impl Server<(), ()> {
             Arc::new(encryption_key),
             None,
             None,
+            SecretBackends::default(),
         );
         StatusReceiver::new(services_context)
             .await?
@@ -0,0 +1,49 @@
+use axum::{
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use dal::{HistoryEventError, TransactionsError, UserError, UserPk};
+use hyper::StatusCode;
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod list_history_events;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error(transparent)]
+    ContextTransaction(#[from] TransactionsError),
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("user {0} is not an admin of the workspace")]
+    NotWorkspaceAdmin(UserPk),
+    #[error(transparent)]
+    User(#[from] UserError),
+}
+
+pub type AuditResult<T> = std::result::Result<T, AuditError>;
+
+impl IntoResponse for AuditError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            AuditError::NotWorkspaceAdmin(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/list_history_events",
+        get(list_history_events::list_history_events),
+    )
+}
@@ -0,0 +1,62 @@
+use axum::{extract::Query, Json};
+use chrono::{DateTime, Utc};
+use dal::{ChangeSetPk, HistoryActor, HistoryEvent, HistoryEventFilter, User, UserPk};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
+
+use super::{AuditError, AuditResult};
+
+/// The most events returned at once, unless the request asks for fewer.
+const MAX_LIMIT: u32 = 1000;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHistoryEventsRequest {
+    /// Only events of this user.
+    pub user_pk: Option<UserPk>,
+    /// Only events of the system itself, rather than of a user.
+    #[serde(default)]
+    pub system: bool,
+    pub label: Option<String>,
+    pub label_prefix: Option<String>,
+    pub change_set_pk: Option<ChangeSetPk>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+pub type ListHistoryEventsResponse = Vec<HistoryEvent>;
+
+pub async fn list_history_events(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Authorization(claim): Authorization,
+    Query(request): Query<ListHistoryEventsRequest>,
+) -> AuditResult<Json<ListHistoryEventsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    if !User::is_workspace_admin(&ctx, claim.user_pk, claim.workspace_pk).await? {
+        return Err(AuditError::NotWorkspaceAdmin(claim.user_pk));
+    }
+
+    let actor = match (request.user_pk, request.system) {
+        (Some(user_pk), _) => Some(HistoryActor::User(user_pk)),
+        (None, true) => Some(HistoryActor::SystemInit),
+        (None, false) => None,
+    };
+    let filter = HistoryEventFilter {
+        actor,
+        label: request.label,
+        label_prefix: request.label_prefix,
+        change_set_pk: request.change_set_pk,
+        since: request.since,
+        until: request.until,
+        limit: Some(request.limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT)),
+    };
+    let events = HistoryEvent::list(&ctx, &filter).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(events))
+}
@@ -7,7 +7,7 @@ use axum::{
 use dal::{
     change_status::ChangeStatusError, ChangeSetError as DalChangeSetError,
     ComponentError as DalComponentError, FixError, StandardModelError, TransactionsError,
-    UserError, UserPk,
+    UndoError, UserError, UserPk, WsEventError,
 };
 use module_index_client::IndexClientError;
 use telemetry::prelude::*;
@@ -15,11 +15,17 @@ use thiserror::Error;
 
 use crate::{server::state::AppState, service::pkg::PkgError};
 
+pub mod abandon_change_set;
 pub mod apply_change_set;
 pub mod create_change_set;
 pub mod get_change_set;
+pub mod get_change_set_diff;
 pub mod get_stats;
+pub mod list_conflicts;
 pub mod list_open_change_sets;
+pub mod rebase_change_set;
+pub mod resolve_conflict;
+pub mod undo;
 pub mod update_selected_change_set;
 
 #[remain::sorted]
@@ -54,9 +60,15 @@ pub enum ChangeSetError {
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
+    Undo(#[from] UndoError),
+    #[error("change set has {0} unresolved conflicts")]
+    UnresolvedConflicts(usize),
+    #[error(transparent)]
     UrlParse(#[from] url::ParseError),
     #[error(transparent)]
     User(#[from] UserError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
 }
 
 pub type ChangeSetResult<T> = std::result::Result<T, ChangeSetError>;
@@ -65,6 +77,11 @@ impl IntoResponse for ChangeSetError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             ChangeSetError::ChangeSetNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            ChangeSetError::ChangeSet(
+                DalChangeSetError::Expired(_) | DalChangeSetError::NotOpen(_),
+            ) => (StatusCode::CONFLICT, self.to_string()),
+            ChangeSetError::UnresolvedConflicts(_) => (StatusCode::CONFLICT, self.to_string()),
+            ChangeSetError::Undo(UndoError::Head) => (StatusCode::BAD_REQUEST, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -87,15 +104,34 @@ pub fn routes() -> Router<AppState> {
             post(create_change_set::create_change_set),
         )
         .route("/get_change_set", get(get_change_set::get_change_set))
+        .route(
+            "/get_change_set_diff",
+            get(get_change_set_diff::get_change_set_diff),
+        )
         .route("/get_stats", get(get_stats::get_stats))
         .route(
             "/apply_change_set",
             post(apply_change_set::apply_change_set),
         )
+        .route(
+            "/abandon_change_set",
+            post(abandon_change_set::abandon_change_set),
+        )
         .route(
             "/update_selected_change_set",
             post(update_selected_change_set::update_selected_change_set),
         )
+        .route("/list_conflicts", get(list_conflicts::list_conflicts))
+        .route(
+            "/rebase_change_set",
+            post(rebase_change_set::rebase_change_set),
+        )
+        .route(
+            "/resolve_conflict",
+            post(resolve_conflict::resolve_conflict),
+        )
+        .route("/undo", post(undo::undo))
+        .route("/redo", post(undo::redo))
 }
 
 // Ideally, this would be in a background job (and triggered directly by ChangeSet::apply_raw),
@@ -123,6 +159,7 @@ async fn upload_workspace_backup_module(
         Some("Backup of all schema variants on HEAD."),
         "Sally Signup",
         schema_variant_ids,
+        vec![],
     )
     .instrument(debug_span!("Generating workspace backup module"))
     .await?;
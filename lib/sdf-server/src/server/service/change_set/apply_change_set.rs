@@ -45,6 +45,10 @@ pub async fn apply_change_set(
     let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
         .await?
         .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let conflicts = change_set.conflicts(&ctx).await?;
+    if !conflicts.is_empty() {
+        return Err(ChangeSetError::UnresolvedConflicts(conflicts.len()));
+    }
     change_set.apply_raw(&mut ctx, false).await?;
 
     track(
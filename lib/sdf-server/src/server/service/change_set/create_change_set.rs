@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use axum::extract::OriginalUri;
 use axum::Json;
+use chrono::Utc;
 use dal::ChangeSet;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +14,9 @@ use crate::server::tracking::track;
 #[serde(rename_all = "camelCase")]
 pub struct CreateChangeSetRequest {
     pub change_set_name: String,
+    /// How long the change set may stay open before it's abandoned.
+    #[serde(default)]
+    pub expires_in_secs: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -29,7 +35,14 @@ pub async fn create_change_set(
     let ctx = builder.build_head(access_builder).await?;
 
     let change_set_name = &request.change_set_name;
-    let change_set = ChangeSet::new(&ctx, change_set_name, None).await?;
+    let mut change_set = ChangeSet::new(&ctx, change_set_name, None).await?;
+    if let Some(expires_in_secs) = request.expires_in_secs {
+        // A time to live too long to represent just means the change set never expires.
+        let expires_at = chrono::Duration::from_std(Duration::from_secs(expires_in_secs))
+            .ok()
+            .and_then(|expires_in| Utc::now().checked_add_signed(expires_in));
+        change_set.set_expires_at(&ctx, expires_at).await?;
+    }
 
     track(
         &posthog_client,
@@ -38,6 +51,7 @@ pub async fn create_change_set(
         "create_change_set",
         serde_json::json!({
                     "change_set_name": change_set_name,
+                    "expires_in_secs": request.expires_in_secs,
         }),
     );
 
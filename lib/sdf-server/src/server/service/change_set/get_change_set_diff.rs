@@ -0,0 +1,31 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::extract::Query;
+use axum::Json;
+use dal::{ChangeSet, ChangeSetDiff, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChangeSetDiffRequest {
+    pub change_set_pk: ChangeSetPk,
+}
+
+pub type GetChangeSetDiffResponse = ChangeSetDiff;
+
+pub async fn get_change_set_diff(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetChangeSetDiffRequest>,
+) -> ChangeSetResult<Json<GetChangeSetDiffResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let diff = change_set.diff(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(diff))
+}
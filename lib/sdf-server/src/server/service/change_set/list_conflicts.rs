@@ -0,0 +1,35 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::extract::Query;
+use axum::Json;
+use dal::{ChangeSet, ChangeSetConflict, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListConflictsRequest {
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListConflictsResponse {
+    pub conflicts: Vec<ChangeSetConflict>,
+}
+
+pub async fn list_conflicts(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListConflictsRequest>,
+) -> ChangeSetResult<Json<ListConflictsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let conflicts = change_set.conflicts(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ListConflictsResponse { conflicts }))
+}
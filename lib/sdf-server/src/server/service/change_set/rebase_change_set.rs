@@ -0,0 +1,46 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{ChangeSet, ChangeSetPk, ChangeSetRebase};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseChangeSetRequest {
+    pub change_set_pk: ChangeSetPk,
+}
+
+pub type RebaseChangeSetResponse = ChangeSetRebase;
+
+pub async fn rebase_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RebaseChangeSetRequest>,
+) -> ChangeSetResult<Json<RebaseChangeSetResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let rebase = change_set.rebase(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "rebase_change_set",
+        serde_json::json!({
+            "change_set_pk": request.change_set_pk,
+            "refreshed": rebase.refreshed,
+            "conflicts": rebase.conflicts.len(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(rebase))
+}
@@ -0,0 +1,62 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{ChangeSet, ChangeSetConflict, ChangeSetPk, ConflictResolution};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveConflictRequest {
+    pub change_set_pk: ChangeSetPk,
+    pub table_name: String,
+    pub object_id: String,
+    pub resolution: ConflictResolution,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveConflictResponse {
+    /// The conflicts still left to resolve.
+    pub conflicts: Vec<ChangeSetConflict>,
+}
+
+pub async fn resolve_conflict(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ResolveConflictRequest>,
+) -> ChangeSetResult<Json<ResolveConflictResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    change_set
+        .resolve_conflict(
+            &ctx,
+            &request.table_name,
+            &request.object_id,
+            request.resolution,
+        )
+        .await?;
+    let conflicts = change_set.conflicts(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "resolve_change_set_conflict",
+        serde_json::json!({
+            "change_set_pk": request.change_set_pk,
+            "table_name": request.table_name,
+            "resolution": request.resolution,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ResolveConflictResponse { conflicts }))
+}
@@ -0,0 +1,92 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{UndoEntry, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoResponse {
+    /// The edit that was undone (or redone), if there was any.
+    pub entry: Option<UndoEntry>,
+}
+
+/// Undo the most recent edit the user made in the change set.
+pub async fn undo(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UndoRequest>,
+) -> ChangeSetResult<Json<UndoResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let entry = UndoEntry::undo(&ctx).await?;
+
+    if let Some(entry) = &entry {
+        WsEvent::change_set_written(&ctx)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+
+        track(
+            &posthog_client,
+            &ctx,
+            &original_uri,
+            "undo",
+            serde_json::json!({
+                "undo_entry_pk": entry.pk(),
+                "operations": entry.operations().len(),
+            }),
+        );
+    }
+
+    ctx.commit().await?;
+
+    Ok(Json(UndoResponse { entry }))
+}
+
+/// Redo the edit the user most recently undid in the change set.
+pub async fn redo(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UndoRequest>,
+) -> ChangeSetResult<Json<UndoResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let entry = UndoEntry::redo(&ctx).await?;
+
+    if let Some(entry) = &entry {
+        WsEvent::change_set_written(&ctx)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+
+        track(
+            &posthog_client,
+            &ctx,
+            &original_uri,
+            "redo",
+            serde_json::json!({
+                "undo_entry_pk": entry.pk(),
+                "operations": entry.operations().len(),
+            }),
+        );
+    }
+
+    ctx.commit().await?;
+
+    Ok(Json(UndoResponse { entry }))
+}
@@ -10,13 +10,14 @@ use dal::{
     AttributePrototypeArgumentError, AttributePrototypeError, AttributeValueError, ChangeSetError,
     ComponentError as DalComponentError, ComponentId, DiagramError, ExternalProviderError,
     FuncBindingError, FuncError, InternalProviderError, PropId, ReconciliationPrototypeError,
-    SchemaError as DalSchemaError, StandardModelError, TransactionsError, WsEventError,
+    SchemaError as DalSchemaError, StandardModelError, TransactionsError, UndoError, WsEventError,
 };
 use thiserror::Error;
 
 use crate::{server::state::AppState, service::schema::SchemaError};
 
 pub mod alter_simulation;
+pub mod bulk_update_property_editor_value;
 pub mod get_code;
 pub mod get_components_metadata;
 pub mod get_diff;
@@ -102,6 +103,8 @@ pub enum ComponentError {
     SystemIdRequired,
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
+    #[error("undo error: {0}")]
+    Undo(#[from] UndoError),
     #[error("ws event error: {0}")]
     WsEvent(#[from] WsEventError),
 }
@@ -149,6 +152,10 @@ pub fn routes() -> Router<AppState> {
             "/update_property_editor_value",
             post(update_property_editor_value::update_property_editor_value),
         )
+        .route(
+            "/bulk_update_property_editor_value",
+            post(bulk_update_property_editor_value::bulk_update_property_editor_value),
+        )
         .route(
             "/insert_property_editor_value",
             post(insert_property_editor_value::insert_property_editor_value),
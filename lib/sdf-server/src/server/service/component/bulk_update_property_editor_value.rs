@@ -0,0 +1,80 @@
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+use dal::property_editor::values::PropertyEditorValues;
+use dal::{prop::PropPath, ChangeSet, ComponentId, UndoEntry, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkUpdatePropertyEditorValueRequest {
+    /// The names of the props leading to the prop to update, starting with "root".
+    pub prop_path: Vec<String>,
+    pub component_ids: Vec<ComponentId>,
+    pub value: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn bulk_update_property_editor_value(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<BulkUpdatePropertyEditorValueRequest>,
+) -> ComponentResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let prop_path = PropPath::new(&request.prop_path);
+    let undo_operations = PropertyEditorValues::update_for_components(
+        &ctx,
+        &prop_path,
+        &request.component_ids,
+        request.value,
+    )
+    .await?;
+    UndoEntry::record(&ctx, undo_operations).await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "property_value_bulk_updated",
+        serde_json::json!({
+            "component_ids": request.component_ids,
+            "prop_path": request.prop_path,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(response.body(axum::body::Empty::new())?)
+}
@@ -1,7 +1,7 @@
 use axum::{response::IntoResponse, Json};
 use dal::{
-    AttributeContext, AttributeValue, AttributeValueId, ChangeSet, ComponentId, PropId, Visibility,
-    WsEvent,
+    AttributeContext, AttributeValue, AttributeValueId, ChangeSet, ComponentId, PropId, UndoEntry,
+    UndoOperation, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -47,12 +47,23 @@ pub async fn insert_property_editor_value(
         .set_prop_id(request.prop_id)
         .set_component_id(request.component_id)
         .to_context()?;
-    let _ = AttributeValue::insert_for_context(
+    let attribute_value_id = AttributeValue::insert_for_context(
         &ctx,
         attribute_context,
         request.parent_attribute_value_id,
-        request.value,
-        request.key,
+        request.value.clone(),
+        request.key.clone(),
+    )
+    .await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::InsertAttributeValue {
+            attribute_value_id,
+            parent_attribute_value_id: request.parent_attribute_value_id,
+            context: attribute_context,
+            value: request.value,
+            key: request.key,
+        }],
     )
     .await?;
 
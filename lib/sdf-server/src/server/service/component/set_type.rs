@@ -1,7 +1,10 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 
-use dal::{ChangeSet, Component, ComponentId, ComponentType, StandardModel, Visibility, WsEvent};
+use dal::{
+    ChangeSet, Component, ComponentId, ComponentType, StandardModel, UndoEntry, UndoOperation,
+    Visibility, WsEvent,
+};
 use serde::{Deserialize, Serialize};
 
 use super::ComponentResult;
@@ -57,7 +60,17 @@ pub async fn set_type(
         Some(value) => serde_json::from_value(value)?,
         None => ComponentType::Component,
     };
+    let before = component.get_type(&ctx).await?;
     component.set_type(&ctx, component_type).await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::SetComponentType {
+            component_id: request.component_id,
+            before,
+            after: component_type,
+        }],
+    )
+    .await?;
 
     track(
         &posthog_client,
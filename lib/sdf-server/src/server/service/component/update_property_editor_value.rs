@@ -1,8 +1,8 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 use dal::{
-    AttributeContext, AttributeValue, AttributeValueId, ChangeSet, Component, ComponentId, Prop,
-    PropId, StandardModel, Visibility, WsEvent,
+    AttributeContext, AttributeValue, AttributeValueId, AttributeValueSource, ChangeSet, Component,
+    ComponentId, Prop, PropId, StandardModel, UndoEntry, UndoOperation, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -53,13 +53,32 @@ pub async fn update_property_editor_value(
         .set_prop_id(request.prop_id)
         .set_component_id(request.component_id)
         .to_context()?;
-    let (_, _) = AttributeValue::update_for_context(
+    let before = AttributeValueSource::of(
+        &ctx,
+        &AttributeValue::get_by_id(&ctx, &request.attribute_value_id)
+            .await?
+            .ok_or(ComponentError::AttributeValueNotFound)?,
+    )
+    .await?;
+    let (_, attribute_value_id) = AttributeValue::update_for_context(
         &ctx,
         request.attribute_value_id,
         request.parent_attribute_value_id,
         attribute_context,
-        request.value,
-        request.key,
+        request.value.clone(),
+        request.key.clone(),
+    )
+    .await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::SetAttributeValue {
+            attribute_value_id,
+            parent_attribute_value_id: request.parent_attribute_value_id,
+            context: attribute_context,
+            key: request.key,
+            before,
+            after: request.value,
+        }],
     )
     .await?;
 
@@ -7,11 +7,11 @@ use dal::provider::external::ExternalProviderError as DalExternalProviderError;
 use dal::socket::{SocketError, SocketId};
 use dal::{
     node::NodeId, schema::variant::SchemaVariantError, AttributeValueError, ChangeSetError,
-    ComponentError, ComponentType, DiagramError as DalDiagramError, EdgeError,
-    InternalProviderError, NodeError, NodeKind, NodeMenuError, SchemaError as DalSchemaError,
-    SchemaVariantId, StandardModelError, TransactionsError,
+    ComponentError, ComponentTemplateError, ComponentTemplateId, DiagramError as DalDiagramError,
+    EdgeError, InternalProviderError, NodeError, NodeKind, NodeMenuError,
+    SchemaError as DalSchemaError, SchemaVariantId, StandardModelError, TransactionsError,
 };
-use dal::{AttributeReadContext, WsEventError};
+use dal::{AttributeReadContext, UndoError, WsEventError};
 use thiserror::Error;
 
 use crate::server::state::AppState;
@@ -24,9 +24,12 @@ pub mod delete_component;
 pub mod delete_connection;
 pub mod get_diagram;
 pub mod get_node_add_menu;
+pub mod instantiate_template;
 pub mod list_schema_variants;
+pub mod list_templates;
 mod restore_component;
 pub mod restore_connection;
+pub mod save_template;
 pub mod set_node_position;
 
 #[remain::sorted]
@@ -42,6 +45,10 @@ pub enum DiagramError {
     Component(#[from] ComponentError),
     #[error("component not found")]
     ComponentNotFound,
+    #[error("component template error: {0}")]
+    ComponentTemplate(#[from] ComponentTemplateError),
+    #[error("component template not found: {0}")]
+    ComponentTemplateNotFound(ComponentTemplateId),
     #[error(transparent)]
     ContextTransaction(#[from] TransactionsError),
     #[error("dal schema error: {0}")]
@@ -66,8 +73,6 @@ pub enum DiagramError {
     InternalProvider(#[from] InternalProviderError),
     #[error("internal provider not found for socket id: {0}")]
     InternalProviderNotFoundForSocket(SocketId),
-    #[error("invalid component type ({0:?}) for frame")]
-    InvalidComponentTypeForFrame(ComponentType),
     #[error("invalid parent node kind {0:?}")]
     InvalidParentNode(NodeKind),
     #[error("invalid request")]
@@ -106,6 +111,8 @@ pub enum DiagramError {
     SocketNotFound,
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
+    #[error("undo error: {0}")]
+    Undo(#[from] UndoError),
     #[error("ws event error: {0}")]
     WsEvent(#[from] WsEventError),
 }
@@ -115,7 +122,12 @@ pub type DiagramResult<T> = Result<T, DiagramError>;
 impl IntoResponse for DiagramError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            DiagramError::SchemaNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            DiagramError::SchemaNotFound | DiagramError::ComponentTemplateNotFound(_) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            DiagramError::ComponentTemplate(
+                ComponentTemplateError::MissingName | ComponentTemplateError::NoComponents,
+            ) => (StatusCode::BAD_REQUEST, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -175,4 +187,10 @@ pub fn routes() -> Router<AppState> {
             "/list_schema_variants",
             get(list_schema_variants::list_schema_variants),
         )
+        .route("/list_templates", get(list_templates::list_templates))
+        .route("/save_template", post(save_template::save_template))
+        .route(
+            "/instantiate_template",
+            post(instantiate_template::instantiate_template),
+        )
 }
@@ -4,7 +4,7 @@ use dal::edge::EdgeKind;
 use dal::{
     job::definition::DependentValuesUpdate, node::NodeId, socket::SocketId, AttributeReadContext,
     AttributeValue, ChangeSet, Connection, ExternalProvider, Node, Socket, StandardModel,
-    Visibility, WsEvent,
+    UndoEntry, UndoOperation, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
@@ -124,6 +124,18 @@ pub async fn create_connection(
     ))
     .await?;
 
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::CreateConnection {
+            edge_id: connection.id,
+            from_node_id: request.from_node_id,
+            from_socket_id: request.from_socket_id,
+            to_node_id: request.to_node_id,
+            to_socket_id: request.to_socket_id,
+        }],
+    )
+    .await?;
+
     WsEvent::change_set_written(&ctx)
         .await?
         .publish_on_commit(&ctx)
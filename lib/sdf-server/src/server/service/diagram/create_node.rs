@@ -7,12 +7,11 @@ use dal::node::NodeId;
 use dal::socket::SocketEdgeKind;
 use dal::{
     generate_name, ChangeSet, Component, ComponentId, Connection, Node, Schema, SchemaId, Socket,
-    StandardModel, Visibility, WsEvent,
+    StandardModel, UndoEntry, UndoOperation, Visibility, WsEvent,
 };
 
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
-use crate::service::diagram::connect_component_to_frame::connect_component_sockets_to_frame;
 use crate::service::diagram::{DiagramError, DiagramResult};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -99,7 +98,7 @@ pub async fn create_node(
         )
         .await?;
 
-        connect_component_sockets_to_frame(&ctx, frame_id, *node.id()).await?;
+        Connection::connect_sockets_to_frame(&ctx, frame_id, *node.id()).await?;
 
         let child_comp = Node::get_by_id(&ctx, node.id())
             .await?
@@ -143,6 +142,14 @@ pub async fn create_node(
         );
     }
 
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::CreateComponent {
+            component_id: *component.id(),
+        }],
+    )
+    .await?;
+
     WsEvent::component_created(&ctx)
         .await?
         .publish_on_commit(&ctx)
@@ -1,6 +1,9 @@
 use axum::{extract::OriginalUri, http::uri::Uri};
 use axum::{response::IntoResponse, Json};
-use dal::{ChangeSet, Component, ComponentId, DalContext, StandardModel, Visibility, WsEvent};
+use dal::{
+    ChangeSet, Component, ComponentId, DalContext, StandardModel, UndoEntry, UndoOperation,
+    Visibility, WsEvent,
+};
 use serde::{Deserialize, Serialize};
 
 use super::{DiagramError, DiagramResult};
@@ -78,6 +81,13 @@ pub async fn delete_component(
     };
 
     delete_single_component(&ctx, request.component_id, &original_uri, &posthog_client).await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::DeleteComponent {
+            component_id: request.component_id,
+        }],
+    )
+    .await?;
 
     ctx.commit().await?;
 
@@ -122,10 +132,12 @@ pub async fn delete_components(
             .await?;
     };
 
+    let mut undo_operations = Vec::with_capacity(request.component_ids.len());
     for component_id in request.component_ids {
         delete_single_component(&ctx, component_id, &original_uri, &posthog_client).await?;
-        ctx.commit().await?;
+        undo_operations.push(UndoOperation::DeleteComponent { component_id });
     }
+    UndoEntry::record(&ctx, undo_operations).await?;
 
     ctx.commit().await?;
 
@@ -1,7 +1,9 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 use dal::edge::EdgeId;
-use dal::{ChangeSet, Connection, Edge, Node, Socket, Visibility, WsEvent};
+use dal::{
+    ChangeSet, Connection, Edge, Node, Socket, UndoEntry, UndoOperation, Visibility, WsEvent,
+};
 use serde::{Deserialize, Serialize};
 
 use super::DiagramResult;
@@ -78,6 +80,13 @@ pub async fn delete_connection(
         .ok_or(DiagramError::SocketNotFound)?;
 
     Connection::delete_for_edge(&ctx, request.edge_id).await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::DeleteConnection {
+            edge_id: request.edge_id,
+        }],
+    )
+    .await?;
 
     track(
         &posthog_client,
@@ -0,0 +1,112 @@
+use axum::extract::OriginalUri;
+use axum::{response::IntoResponse, Json};
+use dal::node::NodeId;
+use dal::{
+    ChangeSet, ComponentId, ComponentTemplate, ComponentTemplateId, StandardModel, UndoEntry,
+    Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{DiagramError, DiagramResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiateTemplateRequest {
+    pub template_id: ComponentTemplateId,
+    pub x: i64,
+    pub y: i64,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiatedComponentView {
+    pub component_id: ComponentId,
+    pub node_id: NodeId,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantiateTemplateResponse {
+    pub components: Vec<InstantiatedComponentView>,
+}
+
+/// Creates the components of a [`ComponentTemplate`](dal::ComponentTemplate), with new names,
+/// with the top left corner of the template at the given position. Creating a change set if on
+/// head.
+pub async fn instantiate_template(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<InstantiateTemplateRequest>,
+) -> DiagramResult<impl IntoResponse> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut force_changeset_pk = None;
+    if ctx.visibility().is_head() {
+        let change_set = ChangeSet::new(&ctx, ChangeSet::generate_name(), None).await?;
+
+        let new_visibility = Visibility::new(change_set.pk, request.visibility.deleted_at);
+
+        ctx.update_visibility(new_visibility);
+
+        force_changeset_pk = Some(change_set.pk);
+
+        WsEvent::change_set_created(&ctx, change_set.pk)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    };
+
+    let template = ComponentTemplate::get_by_id(&ctx, &request.template_id)
+        .await?
+        .ok_or(DiagramError::ComponentTemplateNotFound(request.template_id))?;
+
+    let instance = template.instantiate(&ctx, request.x, request.y).await?;
+
+    UndoEntry::record(&ctx, instance.undo_operations()).await?;
+
+    WsEvent::component_created(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "component_template_instantiated",
+        serde_json::json!({
+                    "template_id": template.id(),
+                    "template_name": template.name(),
+                    "component_ids": instance
+                        .components
+                        .iter()
+                        .map(|(component_id, _)| *component_id)
+                        .collect::<Vec<_>>(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    let mut response = axum::response::Response::builder();
+    if let Some(force_changeset_pk) = force_changeset_pk {
+        response = response.header("force_changeset_pk", force_changeset_pk.to_string());
+    }
+    Ok(
+        response.body(serde_json::to_string(&InstantiateTemplateResponse {
+            components: instance
+                .components
+                .into_iter()
+                .map(|(component_id, node_id)| InstantiatedComponentView {
+                    component_id,
+                    node_id,
+                })
+                .collect(),
+        })?)?,
+    )
+}
@@ -0,0 +1,55 @@
+use axum::extract::{Json, Query};
+use dal::{ComponentTemplate, ComponentTemplateId, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTemplatesRequest {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateView {
+    id: ComponentTemplateId,
+    name: String,
+    description: Option<String>,
+    schema_names: Vec<String>,
+}
+
+pub type ListTemplatesResponse = Vec<TemplateView>;
+
+pub async fn list_templates(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListTemplatesRequest>,
+) -> DiagramResult<Json<ListTemplatesResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let templates = ComponentTemplate::list(&ctx)
+        .await?
+        .into_iter()
+        .map(|template| {
+            let mut schema_names: Vec<String> = template
+                .components()
+                .iter()
+                .map(|component| component.schema.clone())
+                .collect();
+            schema_names.sort();
+            schema_names.dedup();
+
+            TemplateView {
+                id: *template.id(),
+                name: template.name().to_owned(),
+                description: template.description().map(ToOwned::to_owned),
+                schema_names,
+            }
+        })
+        .collect();
+
+    Ok(Json(templates))
+}
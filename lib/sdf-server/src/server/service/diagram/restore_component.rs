@@ -1,6 +1,8 @@
 use axum::Json;
 use axum::{extract::OriginalUri, http::uri::Uri, response::IntoResponse};
-use dal::{ChangeSet, Component, ComponentId, DalContext, Visibility, WsEvent};
+use dal::{
+    ChangeSet, Component, ComponentId, DalContext, UndoEntry, UndoOperation, Visibility, WsEvent,
+};
 use serde::{Deserialize, Serialize};
 
 use super::DiagramResult;
@@ -85,6 +87,13 @@ pub async fn restore_component(
     };
 
     restore_single_component(&ctx, request.component_id, &original_uri, &posthog_client).await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::RestoreComponent {
+            component_id: request.component_id,
+        }],
+    )
+    .await?;
 
     ctx.commit().await?;
 
@@ -129,10 +138,12 @@ pub async fn restore_components(
             .await?;
     };
 
+    let mut undo_operations = Vec::with_capacity(request.component_ids.len());
     for component_id in request.component_ids {
         restore_single_component(&ctx, component_id, &original_uri, &posthog_client).await?;
-        ctx.commit().await?;
+        undo_operations.push(UndoOperation::RestoreComponent { component_id });
     }
+    UndoEntry::record(&ctx, undo_operations).await?;
 
     ctx.commit().await?;
 
@@ -1,7 +1,9 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
 use dal::edge::EdgeId;
-use dal::{ChangeSet, Connection, Edge, Node, Socket, Visibility, WsEvent};
+use dal::{
+    ChangeSet, Connection, Edge, Node, Socket, UndoEntry, UndoOperation, Visibility, WsEvent,
+};
 use serde::{Deserialize, Serialize};
 
 use super::DiagramResult;
@@ -45,6 +47,13 @@ pub async fn restore_connection(
     };
 
     Connection::restore_for_edge(&ctx, request.edge_id).await?;
+    UndoEntry::record(
+        &ctx,
+        vec![UndoOperation::RestoreConnection {
+            edge_id: request.edge_id,
+        }],
+    )
+    .await?;
 
     let edge = Edge::get_by_id(&ctx, &request.edge_id)
         .await?
@@ -0,0 +1,64 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{ComponentId, ComponentTemplate, ComponentTemplateId, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub component_ids: Vec<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveTemplateResponse {
+    pub template_id: ComponentTemplateId,
+}
+
+/// Saves the given components, and the connections between them, as a
+/// [`ComponentTemplate`](dal::ComponentTemplate). Templates live in the change set they were
+/// saved in, like any other model.
+pub async fn save_template(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<SaveTemplateRequest>,
+) -> DiagramResult<Json<SaveTemplateResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let template = ComponentTemplate::new_from_components(
+        &ctx,
+        &request.name,
+        request.description.clone(),
+        &request.component_ids,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "component_template_saved",
+        serde_json::json!({
+                    "template_id": template.id(),
+                    "template_name": template.name(),
+                    "component_count": template.components().len(),
+                    "edge_count": template.edges().len(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(SaveTemplateResponse {
+        template_id: *template.id(),
+    }))
+}
@@ -4,7 +4,7 @@ use crate::service::diagram::DiagramError;
 use axum::Json;
 use dal::node::NodeId;
 use dal::socket::SocketEdgeKind;
-use dal::{Node, StandardModel, Visibility, WsEvent};
+use dal::{Node, NodePosition, StandardModel, UndoEntry, UndoOperation, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -70,6 +70,7 @@ pub async fn set_node_position(
         size
     };
 
+    let before = NodePosition::of(&node);
     {
         if node.visibility().deleted_at.is_some() {
             node.set_geometry(&ctx, &request.x, &request.y, width, height)
@@ -85,6 +86,19 @@ pub async fn set_node_position(
         };
     }
 
+    // Nodes can be moved on head, but only edits made in a change set can be undone
+    if !ctx.visibility().is_head() {
+        UndoEntry::record(
+            &ctx,
+            vec![UndoOperation::SetNodePosition {
+                node_id: request.node_id,
+                before,
+                after: NodePosition::of(&node),
+            }],
+        )
+        .await?;
+    }
+
     WsEvent::change_set_written(&ctx)
         .await?
         .publish_on_commit(&ctx)
@@ -125,6 +125,8 @@ pub enum FuncError {
     FuncOptionsAndVariantMismatch,
     #[error("internal provider error: {0}")]
     InternalProvider(#[from] InternalProviderError),
+    #[error("func timeout must be a positive number of seconds")]
+    InvalidTimeout,
     #[error("Missing required options for creating a function")]
     MissingOptions,
     #[error("Function is read-only")]
@@ -602,6 +604,9 @@ pub async fn get_func_view(ctx: &DalContext, func: &Func) -> FuncResult<GetFuncR
         is_revertible,
         associations,
         types,
+        timeout_secs: func.timeout_secs().copied(),
+        memoizable: func.memoizable(),
+        egress_policy: *func.egress_policy(),
     })
 }
 
@@ -4,7 +4,7 @@ use axum::{extract::Query, Json};
 use dal::func::execution::{FuncExecution, FuncExecutionState};
 use dal::{Func, FuncId, StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
-use veritech_client::{FunctionResultFailure, OutputStream};
+use veritech_client::{EgressPolicy, FunctionResultFailure, OutputStream};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -46,6 +46,9 @@ pub struct GetFuncResponse {
     pub is_builtin: bool,
     pub is_revertible: bool,
     pub associations: Option<FuncAssociations>,
+    pub timeout_secs: Option<i64>,
+    pub memoizable: bool,
+    pub egress_policy: EgressPolicy,
 }
 
 pub async fn get_func(
@@ -21,6 +21,7 @@ use dal::{
     SchemaVariantId, StandardModel, Visibility, WsEvent,
 };
 use dal::{FuncBackendResponseType, FuncDescription, PropKind, SchemaVariant, ValidationPrototype};
+use veritech_client::EgressPolicy;
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +33,9 @@ pub struct SaveFuncRequest {
     pub description: Option<String>,
     pub code: Option<String>,
     pub associations: Option<FuncAssociations>,
+    pub timeout_secs: Option<i64>,
+    pub memoizable: Option<bool>,
+    pub egress_policy: Option<EgressPolicy>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -617,12 +621,26 @@ pub async fn do_save_func(
         return Err(FuncError::NotWritable);
     }
 
+    if request
+        .timeout_secs
+        .map_or(false, |timeout_secs| timeout_secs <= 0)
+    {
+        return Err(FuncError::InvalidTimeout);
+    }
+
     func.set_display_name(ctx, request.display_name).await?;
     func.set_name(ctx, request.name).await?;
     func.set_description(ctx, request.description).await?;
     func.set_handler(ctx, request.handler).await?;
     func.set_code_plaintext(ctx, request.code.as_deref())
         .await?;
+    func.set_timeout_secs(ctx, request.timeout_secs).await?;
+    if let Some(memoizable) = request.memoizable {
+        func.set_memoizable(ctx, memoizable).await?;
+    }
+    if let Some(egress_policy) = request.egress_policy {
+        func.set_egress_policy(ctx, egress_policy).await?;
+    }
 
     match func.backend_kind() {
         FuncBackendKind::JsAction => {
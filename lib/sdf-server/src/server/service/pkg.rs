@@ -53,7 +53,7 @@ pub enum PkgError {
     PackageAlreadyInstalled(String),
     #[error("That package already exists: {0}")]
     PackageAlreadyOnDisk(String),
-    #[error("No schema variants added to package export")]
+    #[error("No schema variants or component templates added to package export")]
     PackageExportEmpty,
     #[error("Package name required")]
     PackageNameEmpty,
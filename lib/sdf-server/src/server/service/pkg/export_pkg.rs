@@ -3,7 +3,7 @@ use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient, RawAc
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::Json;
-use dal::{HistoryActor, SchemaVariantId, User, Visibility, WsEvent};
+use dal::{ComponentTemplateId, HistoryActor, SchemaVariantId, User, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 
@@ -14,6 +14,8 @@ pub struct ExportPkgRequest {
     pub version: String,
     pub description: Option<String>,
     pub schema_variants: Vec<SchemaVariantId>,
+    #[serde(default)]
+    pub templates: Vec<ComponentTemplateId>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -43,7 +45,7 @@ pub async fn export_pkg(
         return Err(PkgError::PackageVersionEmpty);
     }
 
-    if request.schema_variants.is_empty() {
+    if request.schema_variants.is_empty() && request.templates.is_empty() {
         return Err(PkgError::PackageExportEmpty);
     }
 
@@ -72,6 +74,7 @@ pub async fn export_pkg(
         request.description.as_ref(),
         &created_by_email,
         request.schema_variants.clone(),
+        request.templates.clone(),
     )
     .await?;
 
@@ -93,6 +96,7 @@ pub async fn export_pkg(
                     "pkg_created_by_name": created_by_name,
                     "pkg_created_by_email": created_by_email,
                     "pkg_schema_count": request.schema_variants.len(),
+                    "pkg_template_count": request.templates.len(),
                     "pkg_hash": response.latest_hash,
         }),
     );
@@ -0,0 +1,55 @@
+use axum::Json;
+use dal::{
+    EncryptedSecret, Secret, SecretBackendKind, SecretKind, SecretObjectType, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+use super::SecretResult;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExternalSecretRequest {
+    pub name: String,
+    pub object_type: SecretObjectType,
+    pub kind: SecretKind,
+    pub backend: SecretBackendKind,
+    /// Where the backend finds the secret material, e.g. a path for the file backend.
+    pub reference: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExternalSecretResponse {
+    pub secret: Secret,
+}
+
+pub async fn create_external_secret(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_tx): AccessBuilder,
+    Json(request): Json<CreateExternalSecretRequest>,
+) -> SecretResult<Json<CreateExternalSecretResponse>> {
+    let ctx = builder.build(request_tx.build(request.visibility)).await?;
+
+    let secret = EncryptedSecret::new_external(
+        &ctx,
+        request.name,
+        request.object_type,
+        request.kind,
+        request.backend,
+        request.reference,
+    )
+    .await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateExternalSecretResponse { secret }))
+}
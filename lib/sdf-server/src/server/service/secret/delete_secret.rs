@@ -0,0 +1,39 @@
+use axum::Json;
+use dal::{Secret, SecretId, StandardModel, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::{SecretError, SecretResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSecretRequest {
+    pub id: SecretId,
+    /// Confirms deleting a secret that components still reference.
+    #[serde(default)]
+    pub force: bool,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn delete_secret(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<DeleteSecretRequest>,
+) -> SecretResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut secret = Secret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+    secret.delete(&ctx, request.force).await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
@@ -0,0 +1,36 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{Secret, SecretId, SecretUsage, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::{SecretError, SecretResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSecretUsagesRequest {
+    pub id: SecretId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSecretUsagesResponse {
+    pub list: Vec<SecretUsage>,
+}
+
+pub async fn list_secret_usages(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListSecretUsagesRequest>,
+) -> SecretResult<Json<ListSecretUsagesResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let secret = Secret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+    let list = secret.where_used(&ctx).await?;
+
+    Ok(Json(ListSecretUsagesResponse { list }))
+}
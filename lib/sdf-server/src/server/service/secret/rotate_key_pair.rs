@@ -0,0 +1,26 @@
+use axum::Json;
+use dal::{job::definition::KeyPairRotationJob, KeyPair, KeyPairRotation};
+
+use super::SecretResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+pub type RotateKeyPairResponse = KeyPairRotation;
+
+pub async fn rotate_key_pair(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> SecretResult<Json<RotateKeyPairResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    // Resumes an interrupted rotation if there is one; the secrets are re-sealed by the job
+    let response: RotateKeyPairResponse = KeyPair::start_rotation(&ctx).await?;
+    ctx.enqueue_job(KeyPairRotationJob::new(
+        ctx.access_builder(),
+        *ctx.visibility(),
+    ))
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(response))
+}
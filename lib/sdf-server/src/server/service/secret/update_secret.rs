@@ -0,0 +1,64 @@
+use axum::Json;
+use dal::{
+    key_pair::KeyPairPk, EncryptedSecret, Secret, SecretId, StandardModel, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{SecretError, SecretResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretRequest {
+    pub id: SecretId,
+    pub name: Option<String>,
+    /// The new payload, sealed with the key pair identified by `key_pair_pk`.
+    pub crypted: Option<Vec<u8>>,
+    pub key_pair_pk: Option<KeyPairPk>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretResponse {
+    pub secret: Secret,
+}
+
+pub async fn update_secret(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<UpdateSecretRequest>,
+) -> SecretResult<Json<UpdateSecretResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut encrypted_secret = EncryptedSecret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+
+    if let Some(name) = request.name {
+        encrypted_secret.set_name(&ctx, name).await?;
+    }
+    match (request.crypted, request.key_pair_pk) {
+        (Some(crypted), Some(key_pair_pk)) => {
+            encrypted_secret
+                .set_crypted(&ctx, &crypted, key_pair_pk)
+                .await?;
+        }
+        (None, None) => {}
+        _ => return Err(SecretError::CryptedWithoutKeyPair),
+    }
+
+    let secret = Secret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateSecretResponse { secret }))
+}
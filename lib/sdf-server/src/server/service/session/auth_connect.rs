@@ -50,8 +50,8 @@ pub struct AuthApiUser {
 pub struct AuthApiWorkspace {
     pub id: WorkspacePk,
     pub display_name: String,
-    // dont need to do anything with these for now
     pub creator_user_id: UserPk,
+    // dont need to do anything with these for now
     pub instance_url: String,
     pub instance_env_type: String,
 }
@@ -115,8 +115,12 @@ async fn find_or_create_user_and_workspace(
         }
     };
 
-    // ensure workspace is associated to user
-    user.associate_workspace(&ctx, *workspace.pk()).await?;
+    // ensure workspace is associated to user, and that its creator administers it
+    if auth_api_workspace.creator_user_id == user.pk() {
+        user.grant_workspace_admin(&ctx, *workspace.pk()).await?;
+    } else {
+        user.associate_workspace(&ctx, *workspace.pk()).await?;
+    }
 
     ctx.commit().await?;
 
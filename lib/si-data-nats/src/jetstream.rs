@@ -0,0 +1,254 @@
+//! A thin wrapper around [JetStream](https://docs.nats.io/nats-concepts/jetstream), for work that
+//! must survive subscribers or publishers going away.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use async_nats::jetstream::{self, consumer, stream, AckKind};
+use futures::{Stream, StreamExt};
+use telemetry::prelude::*;
+
+use super::{ConnectionMetadata, Error, HeaderMap, Result};
+
+fn jetstream_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Error {
+    Error::JetStream(err.into())
+}
+
+/// A JetStream context bound to a [`Client`](crate::Client).
+#[derive(Clone)]
+pub struct JetStream {
+    inner: jetstream::Context,
+    metadata: Arc<ConnectionMetadata>,
+}
+
+impl JetStream {
+    pub(crate) fn new(inner: jetstream::Context, metadata: Arc<ConnectionMetadata>) -> Self {
+        Self { inner, metadata }
+    }
+
+    /// Ensures a stream with work queue retention exists over `subjects`. Each message in such
+    /// a stream is delivered to a single consumer and removed once it is acknowledged.
+    #[instrument(name = "jetstream.get_or_create_work_queue", skip_all, level = "debug")]
+    pub async fn get_or_create_work_queue(
+        &self,
+        name: impl Into<String>,
+        subjects: Vec<String>,
+    ) -> Result<WorkQueue> {
+        let name = name.into();
+        let inner = self
+            .inner
+            .get_or_create_stream(stream::Config {
+                name: name.clone(),
+                subjects,
+                retention: stream::RetentionPolicy::WorkQueue,
+                ..Default::default()
+            })
+            .await
+            .map_err(jetstream_err)?;
+
+        Ok(WorkQueue { inner, name })
+    }
+
+    /// Publishes a message and waits for the server to acknowledge it has been persisted.
+    #[instrument(
+        name = "jetstream.publish",
+        skip_all,
+        level = "debug",
+        fields(messaging.destination = Empty)
+    )]
+    pub async fn publish(&self, subject: impl Into<String>, msg: impl Into<Vec<u8>>) -> Result<()> {
+        self.publish_with_headers(subject, HeaderMap::new(), msg)
+            .await
+    }
+
+    /// Publishes a message with headers and waits for the server to acknowledge it has been
+    /// persisted.
+    #[instrument(
+        name = "jetstream.publish_with_headers",
+        skip_all,
+        level = "debug",
+        fields(messaging.destination = Empty)
+    )]
+    pub async fn publish_with_headers(
+        &self,
+        subject: impl Into<String>,
+        headers: HeaderMap,
+        msg: impl Into<Vec<u8>>,
+    ) -> Result<()> {
+        let span = Span::current();
+
+        let subject = subject.into();
+        span.record("messaging.destination", subject.as_str());
+        let msg: Vec<u8> = msg.into();
+        self.inner
+            .publish_with_headers(subject, headers, msg.into())
+            .await
+            .map_err(|err| span.record_err(jetstream_err(err)))?
+            .await
+            .map_err(|err| span.record_err(jetstream_err(err)))?;
+
+        span.record_ok();
+        Ok(())
+    }
+}
+
+impl fmt::Debug for JetStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JetStream")
+            .field("metadata", &self.metadata)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A stream with work queue retention, see [`JetStream::get_or_create_work_queue`].
+pub struct WorkQueue {
+    inner: stream::Stream,
+    name: String,
+}
+
+impl WorkQueue {
+    /// Gets the name of the underlying stream.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Binds to (creating it if needed) a durable pull consumer. Several processes binding to
+    /// the same `durable_name` share the work. Messages must be explicitly acknowledged within
+    /// `ack_wait`, or they are redelivered.
+    #[instrument(name = "jetstream.work_queue.consume", skip_all, level = "debug")]
+    pub async fn consume(
+        &self,
+        durable_name: impl Into<String>,
+        ack_wait: Duration,
+    ) -> Result<impl Stream<Item = Result<JetStreamMessage>>> {
+        Ok(self
+            .consumer(durable_name, ack_wait)
+            .await?
+            .messages()
+            .await
+            .map_err(jetstream_err)?
+            .map(|message| {
+                message
+                    .map(|inner| JetStreamMessage { inner })
+                    .map_err(jetstream_err)
+            }))
+    }
+
+    /// Takes up to `max_messages` of the messages waiting for the durable pull consumer
+    /// `durable_name`, without waiting for more to arrive. An empty batch means the queue has
+    /// been drained.
+    #[instrument(name = "jetstream.work_queue.fetch", skip_all, level = "debug")]
+    pub async fn fetch(
+        &self,
+        durable_name: impl Into<String>,
+        ack_wait: Duration,
+        max_messages: usize,
+    ) -> Result<Vec<JetStreamMessage>> {
+        self.consumer(durable_name, ack_wait)
+            .await?
+            .fetch()
+            .max_messages(max_messages)
+            .messages()
+            .await
+            .map_err(jetstream_err)?
+            .map(|message| {
+                message
+                    .map(|inner| JetStreamMessage { inner })
+                    .map_err(jetstream_err)
+            })
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn consumer(
+        &self,
+        durable_name: impl Into<String>,
+        ack_wait: Duration,
+    ) -> Result<consumer::PullConsumer> {
+        let durable_name = durable_name.into();
+        self.inner
+            .get_or_create_consumer(
+                &durable_name,
+                consumer::pull::Config {
+                    durable_name: Some(durable_name.clone()),
+                    ack_policy: consumer::AckPolicy::Explicit,
+                    ack_wait,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(jetstream_err)
+    }
+}
+
+impl fmt::Debug for WorkQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkQueue")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A message delivered by JetStream, which must be acknowledged once it has been processed.
+pub struct JetStreamMessage {
+    inner: jetstream::Message,
+}
+
+impl JetStreamMessage {
+    /// Gets a reference to the subject of this message.
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        &self.inner.message.subject
+    }
+
+    /// Gets a reference to the message contents.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.inner.message.payload
+    }
+
+    /// Gets a reference to the headers of this message.
+    #[must_use]
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        self.inner.message.headers.as_ref()
+    }
+
+    /// How many times this message has been delivered, counting this delivery.
+    #[must_use]
+    pub fn delivered(&self) -> u64 {
+        self.inner
+            .info()
+            .map(|info| u64::try_from(info.delivered).unwrap_or(1))
+            .unwrap_or(1)
+    }
+
+    /// The message was processed and must not be redelivered.
+    pub async fn ack(&self) -> Result<()> {
+        self.inner.ack().await.map_err(jetstream_err)
+    }
+
+    /// The message is still being worked on, so its ack deadline should be pushed back.
+    pub async fn in_progress(&self) -> Result<()> {
+        self.inner
+            .ack_with(AckKind::Progress)
+            .await
+            .map_err(jetstream_err)
+    }
+
+    /// The message could not be processed now and should be redelivered, optionally after
+    /// `delay`.
+    pub async fn nak(&self, delay: Option<Duration>) -> Result<()> {
+        self.inner
+            .ack_with(AckKind::Nak(delay))
+            .await
+            .map_err(jetstream_err)
+    }
+}
+
+impl fmt::Debug for JetStreamMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.message.fmt(f)
+    }
+}
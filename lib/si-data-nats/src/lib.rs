@@ -16,11 +16,13 @@ use thiserror::Error;
 use tokio::sync::Mutex;
 
 mod connect_options;
+mod jetstream;
 mod message;
 mod subscriber;
 
 pub use async_nats::{header::HeaderMap, rustls};
 pub use connect_options::ConnectOptions;
+pub use jetstream::{JetStream, JetStreamMessage, WorkQueue};
 pub use message::Message;
 pub use subscriber::Subscriber;
 
@@ -33,6 +35,8 @@ pub enum Error {
     CrossBeamChannel(#[from] RecvError),
     #[error("io error: {0}")]
     Io(#[from] io::Error),
+    #[error("jetstream error: {0}")]
+    JetStream(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("nats connect error: {0}")]
     NatsConnect(#[from] async_nats::ConnectError),
     #[error("nats flush error: {0}")]
@@ -138,6 +142,15 @@ impl Client {
         )
     }
 
+    /// Gets a [`JetStream`] context over this client's connection.
+    #[must_use]
+    pub fn jetstream(&self) -> JetStream {
+        JetStream::new(
+            async_nats::jetstream::new(self.inner.clone()),
+            self.metadata.clone(),
+        )
+    }
+
     /// Establish a `Connection` with a NATS server.
     ///
     /// Multiple servers may be specified by separating them with commas.
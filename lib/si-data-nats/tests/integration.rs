@@ -0,0 +1,109 @@
+use std::{env, time::Duration};
+
+use futures::{Stream, StreamExt};
+use si_data_nats::{JetStreamMessage, NatsClient, NatsConfig, WorkQueue};
+use uuid::Uuid;
+
+const ACK_WAIT: Duration = Duration::from_secs(30);
+const CONSUMER: &str = "test-consumer";
+
+fn nats_config(subject_prefix: String) -> NatsConfig {
+    let mut config = NatsConfig::default();
+    #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+    if let Ok(value) = env::var("SI_TEST_NATS_URL") {
+        config.url = value;
+    }
+    config.subject_prefix = Some(subject_prefix);
+    config
+}
+
+async fn nats(subject_prefix: String) -> NatsClient {
+    NatsClient::new(&nats_config(subject_prefix))
+        .await
+        .expect("failed to connect to NATS")
+}
+
+fn nats_prefix() -> String {
+    Uuid::new_v4().as_simple().to_string()
+}
+
+async fn work_queue(nats: &NatsClient, prefix: &str) -> (WorkQueue, String) {
+    let subject = format!("{prefix}.jobs");
+    let queue = nats
+        .jetstream()
+        .get_or_create_work_queue(format!("TEST_{prefix}"), vec![subject.clone()])
+        .await
+        .expect("failed to create work queue");
+    (queue, subject)
+}
+
+async fn next_message(
+    messages: &mut (impl Stream<Item = si_data_nats::Result<JetStreamMessage>> + Unpin),
+) -> JetStreamMessage {
+    tokio::time::timeout(Duration::from_secs(10), messages.next())
+        .await
+        .expect("timed out waiting for a message")
+        .expect("message stream closed")
+        .expect("failed to receive message")
+}
+
+#[tokio::test]
+async fn enqueue_consume_ack() {
+    let prefix = nats_prefix();
+    let nats = nats(prefix.clone()).await;
+    let (queue, subject) = work_queue(&nats, &prefix).await;
+
+    nats.jetstream()
+        .publish(&subject, b"job".to_vec())
+        .await
+        .expect("failed to publish");
+
+    let mut messages = Box::pin(
+        queue
+            .consume(CONSUMER, ACK_WAIT)
+            .await
+            .expect("failed to consume"),
+    );
+    let message = next_message(&mut messages).await;
+    assert_eq!(b"job", message.payload());
+    assert_eq!(1, message.delivered());
+    message.ack().await.expect("failed to ack");
+    drop(messages);
+
+    // Acknowledged messages are removed from a work queue.
+    let remaining = queue
+        .fetch(CONSUMER, ACK_WAIT, 10)
+        .await
+        .expect("failed to fetch");
+    assert!(remaining.is_empty());
+}
+
+#[tokio::test]
+async fn redelivered_after_nak() {
+    let prefix = nats_prefix();
+    let nats = nats(prefix.clone()).await;
+    let (queue, subject) = work_queue(&nats, &prefix).await;
+
+    nats.jetstream()
+        .publish(&subject, b"job".to_vec())
+        .await
+        .expect("failed to publish");
+
+    let mut messages = Box::pin(
+        queue
+            .consume(CONSUMER, ACK_WAIT)
+            .await
+            .expect("failed to consume"),
+    );
+    let first = next_message(&mut messages).await;
+    assert_eq!(1, first.delivered());
+    first
+        .nak(Some(Duration::from_millis(200)))
+        .await
+        .expect("failed to nak");
+
+    let second = next_message(&mut messages).await;
+    assert_eq!(b"job", second.payload());
+    assert_eq!(2, second.delivered());
+    second.ack().await.expect("failed to ack");
+}
@@ -5,18 +5,22 @@ mod spec;
 pub use pkg::{
     SiPkg, SiPkgActionFunc, SiPkgAttrFuncInput, SiPkgAttrFuncInputView, SiPkgError, SiPkgFunc,
     SiPkgFuncDescription, SiPkgKind, SiPkgLeafFunction, SiPkgMapKeyFunc, SiPkgMetadata, SiPkgProp,
-    SiPkgSchema, SiPkgSchemaVariant, SiPkgSocket, SiPkgValidation,
+    SiPkgSchema, SiPkgSchemaVariant, SiPkgSecretKind, SiPkgSocket, SiPkgTemplate, SiPkgValidation,
 };
 pub use spec::{
     ActionFuncSpec, ActionFuncSpecBuilder, ActionFuncSpecKind, AttrFuncInputSpec,
     AttrFuncInputSpecKind, FuncArgumentKind, FuncArgumentSpec, FuncArgumentSpecBuilder,
     FuncDescriptionSpec, FuncDescriptionSpecBuilder, FuncSpec, FuncSpecBackendKind,
-    FuncSpecBackendResponseType, FuncUniqueId, LeafFunctionSpec, LeafFunctionSpecBuilder,
-    LeafInputLocation, LeafKind, MapKeyFuncSpec, MapKeyFuncSpecBuilder, PkgSpec, PkgSpecBuilder,
-    PropSpec, PropSpecBuilder, PropSpecKind, PropSpecWidgetKind, SchemaSpec, SchemaSpecBuilder,
-    SchemaVariantSpec, SchemaVariantSpecBuilder, SchemaVariantSpecComponentType,
-    SchemaVariantSpecPropRoot, SiPropFuncSpec, SiPropFuncSpecBuilder, SiPropFuncSpecKind,
-    SocketSpec, SocketSpecArity, SocketSpecKind, SpecError, ValidationSpec, ValidationSpecKind,
+    FuncSpecBackendResponseType, FuncSpecEgressPolicy, FuncUniqueId, LeafFunctionSpec,
+    LeafFunctionSpecBuilder, LeafInputLocation, LeafKind, MapKeyFuncSpec, MapKeyFuncSpecBuilder,
+    PkgSpec, PkgSpecBuilder, PropSpec, PropSpecBuilder, PropSpecKind, PropSpecWidgetKind,
+    SchemaSpec, SchemaSpecBuilder, SchemaVariantSpec, SchemaVariantSpecBuilder,
+    SchemaVariantSpecComponentType, SchemaVariantSpecPropRoot, SecretKindFieldSpec,
+    SecretKindFieldSpecBuilder, SecretKindSpec, SecretKindSpecBuilder, SiPropFuncSpec,
+    SiPropFuncSpecBuilder, SiPropFuncSpecKind, SocketSpec, SocketSpecArity, SocketSpecKind,
+    SpecError, TemplateComponentSpec, TemplateComponentSpecBuilder, TemplateEdgeKind,
+    TemplateEdgeSpec, TemplateEdgeSpecBuilder, TemplateSpec, TemplateSpecBuilder,
+    TemplateValueSpec, TemplateValueSpecBuilder, ValidationSpec, ValidationSpecKind,
 };
 
 #[cfg(test)]
@@ -85,6 +89,134 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn pkg_secret_kinds_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec.clone()).expect("failed to load spec");
+        assert!(pkg
+            .secret_kinds()
+            .expect("failed to get secret kinds")
+            .is_empty());
+
+        let mut spec = spec;
+        spec.secret_kinds.push(
+            SecretKindSpec::builder()
+                .name("digitalOceanToken")
+                .description("A DigitalOcean personal access token")
+                .field(
+                    SecretKindFieldSpec::builder()
+                        .name("token")
+                        .required(true)
+                        .build()
+                        .expect("failed to build field"),
+                )
+                .build()
+                .expect("failed to build secret kind"),
+        );
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(pkg_data).expect("failed to load pkg from bytes");
+
+        let secret_kinds = read_pkg.secret_kinds().expect("failed to get secret kinds");
+        assert_eq!(1, secret_kinds.len());
+        let secret_kind = secret_kinds.get(0).expect("has a secret kind");
+        assert_eq!("digitalOceanToken", secret_kind.name());
+        assert_eq!(
+            Some("A DigitalOcean personal access token"),
+            secret_kind.description()
+        );
+        assert_eq!(
+            &[SecretKindFieldSpec {
+                name: "token".to_owned(),
+                required: true,
+            }],
+            secret_kind.fields()
+        );
+    }
+
+    #[tokio::test]
+    async fn pkg_templates_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec.clone()).expect("failed to load spec");
+        assert!(pkg.templates().expect("failed to get templates").is_empty());
+
+        let component = TemplateComponentSpec::builder()
+            .name("starfield")
+            .schema("starfield")
+            .x(100)
+            .y(50)
+            .value(
+                TemplateValueSpec::builder()
+                    .path_part("root")
+                    .path_part("domain")
+                    .path_part("name")
+                    .value(serde_json::json!("constellation"))
+                    .build()
+                    .expect("failed to build value"),
+            )
+            .build()
+            .expect("failed to build component");
+        let mut spec = spec;
+        spec.templates.push(
+            TemplateSpec::builder()
+                .name("constellation")
+                .component(component.clone())
+                .edge(
+                    TemplateEdgeSpec::builder()
+                        .kind(TemplateEdgeKind::Configuration)
+                        .from_component("starfield")
+                        .from_socket("bethesda")
+                        .to_component("starfield")
+                        .to_socket("fallout")
+                        .build()
+                        .expect("failed to build edge"),
+                )
+                .build()
+                .expect("failed to build template"),
+        );
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(pkg_data).expect("failed to load pkg from bytes");
+
+        let templates = read_pkg.templates().expect("failed to get templates");
+        assert_eq!(1, templates.len());
+        let template = templates.get(0).expect("has a template");
+        assert_eq!("constellation", template.name());
+        assert_eq!(None, template.description());
+        assert_eq!(&[component], template.components());
+        assert_eq!(1, template.edges().len());
+        assert_eq!(TemplateEdgeKind::Configuration, template.edges()[0].kind);
+    }
+
+    #[tokio::test]
+    async fn pkg_func_execution_settings_round_trip() {
+        let mut spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let func_name = spec.funcs[0].name.clone();
+        spec.funcs[0].timeout_secs = Some(30);
+        spec.funcs[0].memoizable = true;
+        spec.funcs[0].egress_policy = Some(FuncSpecEgressPolicy::Deny);
+
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(pkg_data).expect("failed to load pkg from bytes");
+
+        let funcs = read_pkg.funcs().expect("failed to get funcs");
+        let func = funcs
+            .iter()
+            .find(|func| func.name() == func_name)
+            .expect("func not in pkg");
+        assert_eq!(Some(30), func.timeout_secs());
+        assert!(func.memoizable());
+        assert_eq!(Some(FuncSpecEgressPolicy::Deny), func.egress_policy());
+        let other_func = funcs
+            .iter()
+            .find(|func| func.name() != func_name)
+            .expect("other func not in pkg");
+        assert_eq!(None, other_func.timeout_secs());
+        assert!(!other_func.memoizable());
+        assert_eq!(None, other_func.egress_policy());
+    }
+
     #[tokio::test]
     async fn pkg_bytes_round_trip() {
         let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
@@ -6,12 +6,14 @@ use object_tree::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{FuncSpec, SchemaSpec};
+use crate::{FuncSpec, SchemaSpec, SecretKindSpec, TemplateSpec};
 
 use super::PkgNode;
 
 const CATEGORY_TYPE_SCHEMAS: &str = "schemas";
 const CATEGORY_TYPE_FUNCS: &str = "funcs";
+const CATEGORY_TYPE_SECRET_KINDS: &str = "secret_kinds";
+const CATEGORY_TYPE_TEMPLATES: &str = "templates";
 
 const KEY_KIND_STR: &str = "kind";
 
@@ -21,6 +23,8 @@ const KEY_KIND_STR: &str = "kind";
 pub enum PackageCategory {
     Funcs(Vec<FuncSpec>),
     Schemas(Vec<SchemaSpec>),
+    SecretKinds(Vec<SecretKindSpec>),
+    Templates(Vec<TemplateSpec>),
 }
 
 #[remain::sorted]
@@ -28,6 +32,8 @@ pub enum PackageCategory {
 pub enum CategoryNode {
     Funcs,
     Schemas,
+    SecretKinds,
+    Templates,
 }
 
 impl CategoryNode {
@@ -35,6 +41,8 @@ impl CategoryNode {
         match self {
             Self::Schemas => CATEGORY_TYPE_SCHEMAS,
             Self::Funcs => CATEGORY_TYPE_FUNCS,
+            Self::SecretKinds => CATEGORY_TYPE_SECRET_KINDS,
+            Self::Templates => CATEGORY_TYPE_TEMPLATES,
         }
     }
 }
@@ -44,6 +52,8 @@ impl NameStr for CategoryNode {
         match self {
             Self::Schemas => CATEGORY_TYPE_SCHEMAS,
             Self::Funcs => CATEGORY_TYPE_FUNCS,
+            Self::SecretKinds => CATEGORY_TYPE_SECRET_KINDS,
+            Self::Templates => CATEGORY_TYPE_TEMPLATES,
         }
     }
 }
@@ -65,6 +75,8 @@ impl ReadBytes for CategoryNode {
         let node = match kind_str.as_str() {
             CATEGORY_TYPE_SCHEMAS => Self::Schemas,
             CATEGORY_TYPE_FUNCS => Self::Funcs,
+            CATEGORY_TYPE_SECRET_KINDS => Self::SecretKinds,
+            CATEGORY_TYPE_TEMPLATES => Self::Templates,
             invalid_kind => {
                 return Err(GraphError::parse_custom(format!(
                     "invalid package category node kind: {invalid_kind}"
@@ -109,6 +121,34 @@ impl NodeChild for PackageCategory {
                     children,
                 )
             }
+            Self::SecretKinds(entries) => {
+                let mut children = Vec::new();
+                for entry in entries {
+                    children
+                        .push(Box::new(entry.clone())
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>);
+                }
+
+                NodeWithChildren::new(
+                    NodeKind::Tree,
+                    Self::NodeType::Category(CategoryNode::SecretKinds),
+                    children,
+                )
+            }
+            Self::Templates(entries) => {
+                let mut children = Vec::new();
+                for entry in entries {
+                    children
+                        .push(Box::new(entry.clone())
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>);
+                }
+
+                NodeWithChildren::new(
+                    NodeKind::Tree,
+                    Self::NodeType::Category(CategoryNode::Templates),
+                    children,
+                )
+            }
         }
     }
 }
@@ -5,11 +5,13 @@ use std::{
 use url::Url;
 
 use object_tree::{
-    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
-    NodeWithChildren, ReadBytes, WriteBytes,
+    read_key_value_line, read_key_value_line_opt, write_key_value_line, GraphError, NameStr,
+    NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
 };
 
-use crate::spec::{FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, FuncUniqueId};
+use crate::spec::{
+    FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecEgressPolicy, FuncUniqueId,
+};
 
 use super::PkgNode;
 
@@ -23,6 +25,9 @@ const KEY_RESPONSE_TYPE_STR: &str = "response_type";
 const KEY_HIDDEN_STR: &str = "hidden";
 const KEY_LINK_STR: &str = "link";
 const KEY_UNIQUE_ID_STR: &str = "unique_id";
+const KEY_TIMEOUT_SECS_STR: &str = "timeout_secs";
+const KEY_MEMOIZABLE_STR: &str = "memoizable";
+const KEY_EGRESS_POLICY_STR: &str = "egress_policy";
 
 #[derive(Clone, Debug)]
 pub struct FuncNode {
@@ -36,6 +41,9 @@ pub struct FuncNode {
     pub hidden: bool,
     pub link: Option<Url>,
     pub unique_id: FuncUniqueId,
+    pub timeout_secs: Option<u64>,
+    pub memoizable: bool,
+    pub egress_policy: Option<FuncSpecEgressPolicy>,
 }
 
 impl NameStr for FuncNode {
@@ -68,6 +76,17 @@ impl WriteBytes for FuncNode {
             self.link.as_ref().map(|l| l.as_str()).unwrap_or(""),
         )?;
         write_key_value_line(writer, KEY_UNIQUE_ID_STR, self.unique_id.to_string())?;
+        // Only written when set, so that the hashes of funcs without a timeout stay the same
+        if let Some(timeout_secs) = self.timeout_secs {
+            write_key_value_line(writer, KEY_TIMEOUT_SECS_STR, timeout_secs)?;
+        }
+        // Likewise only written for memoizable funcs
+        if self.memoizable {
+            write_key_value_line(writer, KEY_MEMOIZABLE_STR, self.memoizable)?;
+        }
+        if let Some(egress_policy) = self.egress_policy {
+            write_key_value_line(writer, KEY_EGRESS_POLICY_STR, egress_policy)?;
+        }
 
         Ok(())
     }
@@ -109,6 +128,22 @@ impl ReadBytes for FuncNode {
         };
         let unique_id_str = read_key_value_line(reader, KEY_UNIQUE_ID_STR)?;
         let unique_id = FuncUniqueId::from_str(&unique_id_str).map_err(GraphError::parse)?;
+        let timeout_secs = match read_key_value_line_opt(reader, KEY_TIMEOUT_SECS_STR)? {
+            None => None,
+            Some(timeout_secs_str) => {
+                Some(u64::from_str(&timeout_secs_str).map_err(GraphError::parse)?)
+            }
+        };
+        let memoizable = match read_key_value_line_opt(reader, KEY_MEMOIZABLE_STR)? {
+            None => false,
+            Some(memoizable_str) => bool::from_str(&memoizable_str).map_err(GraphError::parse)?,
+        };
+        let egress_policy = match read_key_value_line_opt(reader, KEY_EGRESS_POLICY_STR)? {
+            None => None,
+            Some(egress_policy_str) => Some(
+                FuncSpecEgressPolicy::from_str(&egress_policy_str).map_err(GraphError::parse)?,
+            ),
+        };
 
         Ok(Self {
             name,
@@ -121,6 +156,9 @@ impl ReadBytes for FuncNode {
             hidden,
             link,
             unique_id,
+            timeout_secs,
+            memoizable,
+            egress_policy,
         })
     }
 }
@@ -148,6 +186,9 @@ impl NodeChild for FuncSpec {
                 hidden: self.hidden,
                 link: self.link.as_ref().cloned(),
                 unique_id: self.unique_id,
+                timeout_secs: self.timeout_secs,
+                memoizable: self.memoizable,
+                egress_policy: self.egress_policy,
             }),
             children,
         )
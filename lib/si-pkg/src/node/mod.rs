@@ -18,8 +18,10 @@ mod prop_child;
 mod schema;
 mod schema_variant;
 mod schema_variant_child;
+mod secret_kind;
 mod si_prop_func;
 mod socket;
+mod template;
 mod validation;
 
 pub(crate) use self::{
@@ -37,8 +39,10 @@ pub(crate) use self::{
     schema::SchemaNode,
     schema_variant::SchemaVariantNode,
     schema_variant_child::{SchemaVariantChild, SchemaVariantChildNode},
+    secret_kind::SecretKindNode,
     si_prop_func::SiPropFuncNode,
     socket::SocketNode,
+    template::TemplateNode,
     validation::ValidationNode,
 };
 
@@ -56,8 +60,10 @@ const NODE_KIND_PROP_CHILD: &str = "prop_child";
 const NODE_KIND_SCHEMA: &str = "schema";
 const NODE_KIND_SCHEMA_VARIANT: &str = "schema_variant";
 const NODE_KIND_SCHEMA_VARIANT_CHILD: &str = "schema_variant_child";
+const NODE_KIND_SECRET_KIND: &str = "secret_kind";
 const NODE_KIND_SOCKET: &str = "socket";
 const NODE_KIND_SI_PROP_FUNC: &str = "si_prop_func";
+const NODE_KIND_TEMPLATE: &str = "template";
 const NODE_KIND_VALIDATION: &str = "validation";
 
 const KEY_NODE_KIND_STR: &str = "node_kind";
@@ -79,8 +85,10 @@ pub enum PkgNode {
     Schema(SchemaNode),
     SchemaVariant(SchemaVariantNode),
     SchemaVariantChild(SchemaVariantChildNode),
+    SecretKind(SecretKindNode),
     SiPropFunc(SiPropFuncNode),
     Socket(SocketNode),
+    Template(TemplateNode),
     Validation(ValidationNode),
 }
 
@@ -99,8 +107,10 @@ impl PkgNode {
     pub const SCHEMA_KIND_STR: &str = NODE_KIND_SCHEMA;
     pub const SCHEMA_VARIANT_KIND_STR: &str = NODE_KIND_SCHEMA_VARIANT;
     pub const SCHEMA_VARIANT_KIND_CHILD_STR: &str = NODE_KIND_SCHEMA_VARIANT_CHILD;
+    pub const SECRET_KIND_KIND_STR: &str = NODE_KIND_SECRET_KIND;
     pub const SOCKET_KIND_STR: &str = NODE_KIND_SOCKET;
     pub const SI_PROP_FUNC_KIND_STR: &str = NODE_KIND_SI_PROP_FUNC;
+    pub const TEMPLATE_KIND_STR: &str = NODE_KIND_TEMPLATE;
     pub const VALIDATION_KIND_STR: &str = NODE_KIND_VALIDATION;
 
     pub fn node_kind_str(&self) -> &'static str {
@@ -119,8 +129,10 @@ impl PkgNode {
             Self::Schema(_) => NODE_KIND_SCHEMA,
             Self::SchemaVariant(_) => NODE_KIND_SCHEMA_VARIANT,
             Self::SchemaVariantChild(_) => NODE_KIND_SCHEMA_VARIANT_CHILD,
+            Self::SecretKind(_) => NODE_KIND_SECRET_KIND,
             Self::Socket(_) => NODE_KIND_SOCKET,
             Self::SiPropFunc(_) => NODE_KIND_SI_PROP_FUNC,
+            Self::Template(_) => NODE_KIND_TEMPLATE,
             Self::Validation(_) => NODE_KIND_VALIDATION,
         }
     }
@@ -143,8 +155,10 @@ impl NameStr for PkgNode {
             Self::Schema(node) => node.name(),
             Self::SchemaVariant(node) => node.name(),
             Self::SchemaVariantChild(node) => node.name(),
+            Self::SecretKind(node) => node.name(),
             Self::Socket(node) => node.name(),
             Self::SiPropFunc(_) => NODE_KIND_SI_PROP_FUNC,
+            Self::Template(node) => node.name(),
             Self::Validation(_) => NODE_KIND_VALIDATION,
         }
     }
@@ -169,8 +183,10 @@ impl WriteBytes for PkgNode {
             Self::Schema(node) => node.write_bytes(writer)?,
             Self::SchemaVariant(node) => node.write_bytes(writer)?,
             Self::SchemaVariantChild(node) => node.write_bytes(writer)?,
+            Self::SecretKind(node) => node.write_bytes(writer)?,
             Self::Socket(node) => node.write_bytes(writer)?,
             Self::SiPropFunc(node) => node.write_bytes(writer)?,
+            Self::Template(node) => node.write_bytes(writer)?,
             Self::Validation(node) => node.write_bytes(writer)?,
         };
 
@@ -206,8 +222,10 @@ impl ReadBytes for PkgNode {
             NODE_KIND_SCHEMA_VARIANT_CHILD => {
                 Self::SchemaVariantChild(SchemaVariantChildNode::read_bytes(reader)?)
             }
+            NODE_KIND_SECRET_KIND => Self::SecretKind(SecretKindNode::read_bytes(reader)?),
             NODE_KIND_SOCKET => Self::Socket(SocketNode::read_bytes(reader)?),
             NODE_KIND_SI_PROP_FUNC => Self::SiPropFunc(SiPropFuncNode::read_bytes(reader)?),
+            NODE_KIND_TEMPLATE => Self::Template(TemplateNode::read_bytes(reader)?),
             NODE_KIND_VALIDATION => Self::Validation(ValidationNode::read_bytes(reader)?),
             invalid_kind => {
                 return Err(GraphError::parse_custom(format!(
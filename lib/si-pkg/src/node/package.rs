@@ -82,6 +82,23 @@ impl NodeChild for PkgSpec {
     type NodeType = PkgNode;
 
     fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        let mut children = vec![
+            Box::new(PackageCategory::Schemas(self.schemas.clone()))
+                as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+            Box::new(PackageCategory::Funcs(self.funcs.clone()))
+                as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+        ];
+        // Only packages declaring secret kinds (or templates) get the category, so that the
+        // hashes of every other package stay the same
+        if !self.secret_kinds.is_empty() {
+            children.push(Box::new(PackageCategory::SecretKinds(
+                self.secret_kinds.clone(),
+            )));
+        }
+        if !self.templates.is_empty() {
+            children.push(Box::new(PackageCategory::Templates(self.templates.clone())));
+        }
+
         NodeWithChildren::new(
             NodeKind::Tree,
             Self::NodeType::Package(PackageNode {
@@ -92,12 +109,7 @@ impl NodeChild for PkgSpec {
                 created_at: self.created_at,
                 created_by: self.created_by.clone(),
             }),
-            vec![
-                Box::new(PackageCategory::Schemas(self.schemas.clone()))
-                    as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-                Box::new(PackageCategory::Funcs(self.funcs.clone()))
-                    as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-            ],
+            children,
         )
     }
 }
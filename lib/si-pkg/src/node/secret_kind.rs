@@ -0,0 +1,85 @@
+use std::io::{BufRead, Write};
+
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
+    NodeWithChildren, ReadBytes, WriteBytes,
+};
+
+use crate::{SecretKindFieldSpec, SecretKindSpec};
+
+use super::PkgNode;
+
+const KEY_NAME_STR: &str = "name";
+const KEY_DESCRIPTION_STR: &str = "description";
+const KEY_FIELDS_STR: &str = "fields";
+
+#[derive(Clone, Debug)]
+pub struct SecretKindNode {
+    pub name: String,
+    pub description: Option<String>,
+    pub fields: Vec<SecretKindFieldSpec>,
+}
+
+impl NameStr for SecretKindNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WriteBytes for SecretKindNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_NAME_STR, self.name())?;
+        write_key_value_line(
+            writer,
+            KEY_DESCRIPTION_STR,
+            self.description.as_deref().unwrap_or(""),
+        )?;
+        write_key_value_line(
+            writer,
+            KEY_FIELDS_STR,
+            serde_json::to_string(&self.fields).map_err(GraphError::parse)?,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for SecretKindNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Self, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let name = read_key_value_line(reader, KEY_NAME_STR)?;
+        let description_str = read_key_value_line(reader, KEY_DESCRIPTION_STR)?;
+        let description = if description_str.is_empty() {
+            None
+        } else {
+            Some(description_str)
+        };
+        let fields_str = read_key_value_line(reader, KEY_FIELDS_STR)?;
+        let fields: Vec<SecretKindFieldSpec> =
+            serde_json::from_str(&fields_str).map_err(GraphError::parse)?;
+
+        Ok(Self {
+            name,
+            description,
+            fields,
+        })
+    }
+}
+
+impl NodeChild for SecretKindSpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::SecretKind(SecretKindNode {
+                name: self.name.to_owned(),
+                description: self.description.to_owned(),
+                fields: self.fields.to_owned(),
+            }),
+            vec![],
+        )
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::{BufRead, Write};
+
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
+    NodeWithChildren, ReadBytes, WriteBytes,
+};
+
+use crate::{TemplateComponentSpec, TemplateEdgeSpec, TemplateSpec};
+
+use super::PkgNode;
+
+const KEY_NAME_STR: &str = "name";
+const KEY_DESCRIPTION_STR: &str = "description";
+const KEY_COMPONENTS_STR: &str = "components";
+const KEY_EDGES_STR: &str = "edges";
+
+#[derive(Clone, Debug)]
+pub struct TemplateNode {
+    pub name: String,
+    pub description: Option<String>,
+    pub components: Vec<TemplateComponentSpec>,
+    pub edges: Vec<TemplateEdgeSpec>,
+}
+
+impl NameStr for TemplateNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WriteBytes for TemplateNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_NAME_STR, self.name())?;
+        write_key_value_line(
+            writer,
+            KEY_DESCRIPTION_STR,
+            self.description.as_deref().unwrap_or(""),
+        )?;
+        write_key_value_line(
+            writer,
+            KEY_COMPONENTS_STR,
+            serde_json::to_string(&self.components).map_err(GraphError::parse)?,
+        )?;
+        write_key_value_line(
+            writer,
+            KEY_EDGES_STR,
+            serde_json::to_string(&self.edges).map_err(GraphError::parse)?,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for TemplateNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Self, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let name = read_key_value_line(reader, KEY_NAME_STR)?;
+        let description_str = read_key_value_line(reader, KEY_DESCRIPTION_STR)?;
+        let description = if description_str.is_empty() {
+            None
+        } else {
+            Some(description_str)
+        };
+        let components_str = read_key_value_line(reader, KEY_COMPONENTS_STR)?;
+        let components: Vec<TemplateComponentSpec> =
+            serde_json::from_str(&components_str).map_err(GraphError::parse)?;
+        let edges_str = read_key_value_line(reader, KEY_EDGES_STR)?;
+        let edges: Vec<TemplateEdgeSpec> =
+            serde_json::from_str(&edges_str).map_err(GraphError::parse)?;
+
+        Ok(Self {
+            name,
+            description,
+            components,
+            edges,
+        })
+    }
+}
+
+impl NodeChild for TemplateSpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::Template(TemplateNode {
+                name: self.name.to_owned(),
+                description: self.description.to_owned(),
+                components: self.components.to_owned(),
+                edges: self.edges.to_owned(),
+            }),
+            vec![],
+        )
+    }
+}
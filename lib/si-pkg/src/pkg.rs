@@ -19,19 +19,22 @@ mod leaf_function;
 mod map_key_func;
 mod prop;
 mod schema;
+mod secret_kind;
 mod si_prop_func;
 mod socket;
+mod template;
 mod validation;
 mod variant;
 
 pub use {
     action_func::*, attr_func_input::*, func::*, func_description::*, leaf_function::*,
-    map_key_func::*, prop::*, schema::*, si_prop_func::*, socket::*, validation::*, variant::*,
+    map_key_func::*, prop::*, schema::*, secret_kind::*, si_prop_func::*, socket::*, template::*,
+    validation::*, variant::*,
 };
 
 use crate::{
     node::{CategoryNode, PkgNode},
-    spec::{FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
+    spec::{FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SecretKindSpec, SpecError, TemplateSpec},
 };
 
 #[remain::sorted]
@@ -172,6 +175,42 @@ impl SiPkg {
         Ok(schemas)
     }
 
+    /// Returns the secret kinds declared by the package. Packages without any don't carry the
+    /// category at all.
+    pub fn secret_kinds(&self) -> PkgResult<Vec<SiPkgSecretKind>> {
+        let (graph, root_idx) = self.as_petgraph();
+
+        let node_idxs = match category_node_idxs(CategoryNode::SecretKinds, graph, root_idx) {
+            Ok(node_idxs) => node_idxs,
+            Err(SiPkgError::CategoryNotFound(_)) => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut secret_kinds = Vec::with_capacity(node_idxs.len());
+        for node_idx in node_idxs {
+            secret_kinds.push(SiPkgSecretKind::from_graph(graph, node_idx)?);
+        }
+
+        Ok(secret_kinds)
+    }
+
+    /// Returns the component templates carried by the package. Packages without any don't carry
+    /// the category at all.
+    pub fn templates(&self) -> PkgResult<Vec<SiPkgTemplate>> {
+        let (graph, root_idx) = self.as_petgraph();
+
+        let node_idxs = match category_node_idxs(CategoryNode::Templates, graph, root_idx) {
+            Ok(node_idxs) => node_idxs,
+            Err(SiPkgError::CategoryNotFound(_)) => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut templates = Vec::with_capacity(node_idxs.len());
+        for node_idx in node_idxs {
+            templates.push(SiPkgTemplate::from_graph(graph, node_idx)?);
+        }
+
+        Ok(templates)
+    }
+
     pub fn schema_by_name(&self, name: impl AsRef<str>) -> PkgResult<SiPkgSchema> {
         let (graph, root_idx) = self.as_petgraph();
 
@@ -213,6 +252,14 @@ impl SiPkg {
             builder.schema(schema.to_spec().await?);
         }
 
+        for secret_kind in self.secret_kinds()? {
+            builder.secret_kind(SecretKindSpec::try_from(secret_kind)?);
+        }
+
+        for template in self.templates()? {
+            builder.template(TemplateSpec::try_from(template)?);
+        }
+
         Ok(builder.build()?)
     }
 }
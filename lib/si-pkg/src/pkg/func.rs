@@ -7,7 +7,7 @@ use crate::{
     node::PkgNode,
     spec::{
         FuncArgumentKind, FuncArgumentSpec, FuncSpec, FuncSpecBackendKind,
-        FuncSpecBackendResponseType,
+        FuncSpecBackendResponseType, FuncSpecEgressPolicy,
     },
 };
 
@@ -94,6 +94,9 @@ pub struct SiPkgFunc<'a> {
     hidden: bool,
     link: Option<Url>,
     unique_id: Hash,
+    timeout_secs: Option<u64>,
+    memoizable: bool,
+    egress_policy: Option<FuncSpecEgressPolicy>,
 
     hash: Hash,
     source: Source<'a>,
@@ -127,6 +130,9 @@ impl<'a> SiPkgFunc<'a> {
             link: func_node.link,
             hash: func_hashed_node.hash(),
             unique_id: func_node.unique_id,
+            timeout_secs: func_node.timeout_secs,
+            memoizable: func_node.memoizable,
+            egress_policy: func_node.egress_policy,
             source: Source::new(graph, node_idx),
         })
     }
@@ -188,6 +194,18 @@ impl<'a> SiPkgFunc<'a> {
         self.unique_id
     }
 
+    pub fn timeout_secs(&self) -> Option<u64> {
+        self.timeout_secs
+    }
+
+    pub fn memoizable(&self) -> bool {
+        self.memoizable
+    }
+
+    pub fn egress_policy(&self) -> Option<FuncSpecEgressPolicy> {
+        self.egress_policy
+    }
+
     pub fn source(&self) -> &Source<'a> {
         &self.source
     }
@@ -223,6 +241,16 @@ impl<'a> TryFrom<SiPkgFunc<'a>> for FuncSpec {
             builder.link(link);
         }
 
+        if let Some(timeout_secs) = value.timeout_secs {
+            builder.timeout_secs(timeout_secs);
+        }
+
+        builder.memoizable(value.memoizable);
+
+        if let Some(egress_policy) = value.egress_policy {
+            builder.egress_policy(egress_policy);
+        }
+
         Ok(builder.build()?)
     }
 }
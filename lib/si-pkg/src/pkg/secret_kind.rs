@@ -0,0 +1,75 @@
+use object_tree::{Hash, HashedNode};
+use petgraph::prelude::*;
+
+use super::{PkgResult, SiPkgError, Source};
+
+use crate::{node::PkgNode, SecretKindFieldSpec, SecretKindSpec};
+
+#[derive(Clone, Debug)]
+pub struct SiPkgSecretKind<'a> {
+    name: String,
+    description: Option<String>,
+    fields: Vec<SecretKindFieldSpec>,
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgSecretKind<'a> {
+    pub fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::SecretKind(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::SECRET_KIND_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: node.name,
+            description: node.description,
+            fields: node.fields,
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn fields(&self) -> &[SecretKindFieldSpec] {
+        &self.fields
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgSecretKind<'a>> for SecretKindSpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgSecretKind<'a>) -> Result<Self, Self::Error> {
+        let mut builder = SecretKindSpec::builder();
+        builder.name(value.name).fields(value.fields);
+        if let Some(description) = value.description {
+            builder.description(description);
+        }
+
+        Ok(builder.build()?)
+    }
+}
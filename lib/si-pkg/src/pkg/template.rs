@@ -0,0 +1,84 @@
+use object_tree::{Hash, HashedNode};
+use petgraph::prelude::*;
+
+use super::{PkgResult, SiPkgError, Source};
+
+use crate::{node::PkgNode, TemplateComponentSpec, TemplateEdgeSpec, TemplateSpec};
+
+#[derive(Clone, Debug)]
+pub struct SiPkgTemplate<'a> {
+    name: String,
+    description: Option<String>,
+    components: Vec<TemplateComponentSpec>,
+    edges: Vec<TemplateEdgeSpec>,
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgTemplate<'a> {
+    pub fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::Template(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::TEMPLATE_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: node.name,
+            description: node.description,
+            components: node.components,
+            edges: node.edges,
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn components(&self) -> &[TemplateComponentSpec] {
+        &self.components
+    }
+
+    pub fn edges(&self) -> &[TemplateEdgeSpec] {
+        &self.edges
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgTemplate<'a>> for TemplateSpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgTemplate<'a>) -> Result<Self, Self::Error> {
+        let mut builder = TemplateSpec::builder();
+        builder
+            .name(value.name)
+            .components(value.components)
+            .edges(value.edges);
+        if let Some(description) = value.description {
+            builder.description(description);
+        }
+
+        Ok(builder.build()?)
+    }
+}
@@ -11,14 +11,17 @@ mod leaf_function;
 mod map_key_func;
 mod prop;
 mod schema;
+mod secret_kind;
 mod si_prop_func;
 mod socket;
+mod template;
 mod validation;
 mod variant;
 
 pub use {
     action_func::*, attr_func_input::*, func::*, func_description::*, leaf_function::*,
-    map_key_func::*, prop::*, schema::*, si_prop_func::*, socket::*, validation::*, variant::*,
+    map_key_func::*, prop::*, schema::*, secret_kind::*, si_prop_func::*, socket::*, template::*,
+    validation::*, variant::*,
 };
 
 use super::SiPkgKind;
@@ -45,6 +48,14 @@ pub struct PkgSpec {
 
     #[builder(setter(each(name = "func", into)), default)]
     pub funcs: Vec<FuncSpec>,
+
+    #[builder(setter(each(name = "secret_kind", into)), default)]
+    #[serde(default)]
+    pub secret_kinds: Vec<SecretKindSpec>,
+
+    #[builder(setter(each(name = "template", into)), default)]
+    #[serde(default)]
+    pub templates: Vec<TemplateSpec>,
 }
 
 impl PkgSpec {
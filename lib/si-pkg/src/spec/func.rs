@@ -93,6 +93,26 @@ pub enum FuncSpecBackendResponseType {
     Validation,
 }
 
+#[remain::sorted]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Serialize,
+    AsRefStr,
+    Display,
+    EnumIter,
+    EnumString,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum FuncSpecEgressPolicy {
+    Allow,
+    Deny,
+}
+
 pub type FuncUniqueId = Hash;
 
 #[derive(Builder, Clone, Debug, Deserialize, Serialize)]
@@ -120,6 +140,17 @@ pub struct FuncSpec {
 
     #[builder(setter(into, strip_option), default)]
     pub link: Option<Url>,
+    /// Seconds an execution of the func may run for, on top of the limits veritech enforces.
+    #[builder(setter(into, strip_option), default)]
+    pub timeout_secs: Option<u64>,
+    /// Whether executions of the func depend only on their arguments, so that their results may
+    /// be reused for the same arguments.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub memoizable: bool,
+    /// Whether executions of the func may reach the network, when its kind allows it at all.
+    #[builder(setter(into, strip_option), default)]
+    pub egress_policy: Option<FuncSpecEgressPolicy>,
 
     #[builder(setter(each(name = "argument"), into), default)]
     pub arguments: Vec<FuncArgumentSpec>,
@@ -0,0 +1,45 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use super::SpecError;
+
+/// A kind of secret declared by a package, for credentials that SI has no built-in kind for.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct SecretKindSpec {
+    #[builder(setter(into))]
+    pub name: String,
+
+    #[builder(setter(into, strip_option), default)]
+    pub description: Option<String>,
+
+    /// The top level fields of the decrypted secret message.
+    #[builder(setter(each(name = "field", into)), default)]
+    #[serde(default)]
+    pub fields: Vec<SecretKindFieldSpec>,
+}
+
+impl SecretKindSpec {
+    pub fn builder() -> SecretKindSpecBuilder {
+        SecretKindSpecBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct SecretKindFieldSpec {
+    #[builder(setter(into))]
+    pub name: String,
+
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl SecretKindFieldSpec {
+    pub fn builder() -> SecretKindFieldSpecBuilder {
+        SecretKindFieldSpecBuilder::default()
+    }
+}
@@ -0,0 +1,139 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+
+use super::SpecError;
+
+/// A group of components, wired together by edges, that can be instantiated over and over.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct TemplateSpec {
+    #[builder(setter(into))]
+    pub name: String,
+
+    #[builder(setter(into, strip_option), default)]
+    pub description: Option<String>,
+
+    #[builder(setter(each(name = "component", into)), default)]
+    #[serde(default)]
+    pub components: Vec<TemplateComponentSpec>,
+
+    #[builder(setter(each(name = "edge", into)), default)]
+    #[serde(default)]
+    pub edges: Vec<TemplateEdgeSpec>,
+}
+
+impl TemplateSpec {
+    pub fn builder() -> TemplateSpecBuilder {
+        TemplateSpecBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct TemplateComponentSpec {
+    /// Identifies the component within the template, edges refer to components by it.
+    #[builder(setter(into))]
+    pub name: String,
+
+    /// The name of the schema the component is an instance of.
+    #[builder(setter(into))]
+    pub schema: String,
+
+    /// The position of the component, relative to the top left corner of the template.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub x: i64,
+
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub y: i64,
+
+    #[builder(setter(into, strip_option), default)]
+    pub width: Option<String>,
+
+    #[builder(setter(into, strip_option), default)]
+    pub height: Option<String>,
+
+    /// The values set on the component.
+    #[builder(setter(each(name = "value", into)), default)]
+    #[serde(default)]
+    pub values: Vec<TemplateValueSpec>,
+}
+
+impl TemplateComponentSpec {
+    pub fn builder() -> TemplateComponentSpecBuilder {
+        TemplateComponentSpecBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct TemplateValueSpec {
+    /// The path of the prop the value is set for, e.g. `["root", "domain", "region"]`.
+    #[builder(setter(each(name = "path_part", into)), default)]
+    pub path: Vec<String>,
+
+    #[builder(setter(into))]
+    pub value: serde_json::Value,
+}
+
+impl TemplateValueSpec {
+    pub fn builder() -> TemplateValueSpecBuilder {
+        TemplateValueSpecBuilder::default()
+    }
+}
+
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum TemplateEdgeKind {
+    /// Connects an output socket to an input socket.
+    Configuration,
+    /// Puts a component inside a frame.
+    Frame,
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct TemplateEdgeSpec {
+    #[builder(setter(into))]
+    pub kind: TemplateEdgeKind,
+
+    /// The name of the component the edge comes from (the child, for a frame edge).
+    #[builder(setter(into))]
+    pub from_component: String,
+
+    #[builder(setter(into))]
+    pub from_socket: String,
+
+    /// The name of the component the edge goes to (the frame, for a frame edge).
+    #[builder(setter(into))]
+    pub to_component: String,
+
+    #[builder(setter(into))]
+    pub to_socket: String,
+}
+
+impl TemplateEdgeSpec {
+    pub fn builder() -> TemplateEdgeSpecBuilder {
+        TemplateEdgeSpecBuilder::default()
+    }
+}
@@ -12,12 +12,13 @@ use veritech_core::{
 };
 
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, ComponentKind, ComponentView, EncryptionKey,
-    EncryptionKeyError, FunctionResult, FunctionResultFailure, OutputStream, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionComponent, ResolverFunctionRequest,
+    ActionRunRequest, ActionRunResultSuccess, ComponentKind, ComponentView, EgressPolicy,
+    EncryptionKey, EncryptionKeyError, ExecutionLimit, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureErrorKind, OutputStream, ReconciliationRequest,
+    ReconciliationResultSuccess, Redactor, ResolverFunctionComponent, ResolverFunctionRequest,
     ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
     SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
-    ValidationRequest, ValidationResultSuccess,
+    ValidationRequest, ValidationResultSuccess, REDACTED,
 };
 use si_data_nats::NatsClient;
 
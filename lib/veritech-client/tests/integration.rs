@@ -13,7 +13,8 @@ use tracing::info;
 use uuid::Uuid;
 use veritech_client::Client;
 use veritech_server::{
-    Config, CycloneSpec, Instance, LocalUdsInstance, Server, ServerError, StandardConfig,
+    Config, CycloneSpec, Instance, LocalUdsInstance, SandboxedUdsInstance,
+    SandboxedUdsInstanceSpec, Server, ServerError, StandardConfig,
 };
 
 fn nats_config(subject_prefix: String) -> NatsConfig {
@@ -62,6 +63,40 @@ async fn veritech_server_for_uds_cyclone(subject_prefix: String) -> Server {
         .expect("failed to create server")
 }
 
+/// Returns a server running cyclone in a bubblewrap sandbox, or `None` if bubblewrap isn't
+/// installed.
+async fn veritech_server_for_sandboxed_uds_cyclone(
+    subject_prefix: String,
+) -> Option<Server<SandboxedUdsInstanceSpec>> {
+    let mut config_file = veritech_server::ConfigFile::default_local_uds();
+    veritech_server::detect_and_configure_development(&mut config_file)
+        .expect("failed to determine test configuration");
+
+    let mut builder = SandboxedUdsInstance::spec();
+    builder.try_runtime_cmd_path("bwrap").ok()?;
+    builder
+        .cyclone_cmd_path(config_file.cyclone.cyclone_cmd_path())
+        .cyclone_decryption_key_path(config_file.cyclone.cyclone_decryption_key_path())
+        .lang_server_cmd_path(config_file.cyclone.lang_server_cmd_path())
+        .all_endpoints();
+    // Development builds may link against libraries in the Nix store
+    if std::path::Path::new("/nix/store").is_dir() {
+        builder.install_path("/nix/store");
+    }
+    let cyclone_spec =
+        CycloneSpec::SandboxedUds(builder.build().expect("failed to build cyclone spec"));
+    let config = Config::builder()
+        .nats(nats_config(subject_prefix))
+        .cyclone_spec(cyclone_spec)
+        .build()
+        .expect("failed to build spec");
+    Some(
+        Server::for_cyclone_sandboxed_uds(config)
+            .await
+            .expect("failed to create server"),
+    )
+}
+
 async fn client(subject_prefix: String) -> Client {
     Client::new(nats(subject_prefix).await)
 }
@@ -105,6 +140,7 @@ async fn executes_simple_resolver_function() {
         code_base64: base64_encode(
             "function numberOfInputs(input) { return Object.keys(input)?.length ?? 0; }",
         ),
+        timeout_secs: None,
     };
 
     let result = client
@@ -171,6 +207,7 @@ async fn type_checks_resolve_function() {
             },
             response_type,
             code_base64: base64_encode("function returnInputValue(input) { return input.value; }"),
+            timeout_secs: None,
         };
 
         let result = client
@@ -232,6 +269,7 @@ async fn type_checks_resolve_function() {
             },
             response_type: response_type.clone(),
             code_base64: base64_encode("function returnInputValue(input) { return input.value; }"),
+            timeout_secs: None,
         };
 
         let result = client
@@ -245,7 +283,7 @@ async fn type_checks_resolve_function() {
                 panic!("should have failed :(");
             }
             FunctionResult::Failure(failure) => {
-                assert_eq!(failure.error.kind, "InvalidReturnType");
+                assert_eq!(failure.error.kind.as_str(), "InvalidReturnType");
                 assert_eq!(failure.execution_id, "1234");
             }
         }
@@ -274,6 +312,7 @@ async fn executes_simple_validation() {
         code_base64: base64_encode(
             "function isThirtyThree(value) { return { valid: value === 33 }; };",
         ),
+        timeout_secs: None,
     };
 
     let result = client
@@ -318,6 +357,7 @@ async fn executes_simple_schema_variant_definition() {
                     };
                 }",
         ),
+        timeout_secs: None,
     };
 
     let result = client
@@ -345,3 +385,109 @@ async fn executes_simple_schema_variant_definition() {
         }
     }
 }
+
+#[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+#[test(tokio::test)]
+async fn executes_simple_resolver_function_in_sandbox() {
+    let prefix = nats_prefix();
+    let server = match veritech_server_for_sandboxed_uds_cyclone(prefix.clone()).await {
+        Some(server) => server,
+        None => {
+            info!("skipping test, bwrap is not installed");
+            return;
+        }
+    };
+    let pool_metrics = server.cyclone_pool_metrics();
+    tokio::spawn(server.run());
+    let client = client(prefix).await;
+
+    // Not going to check output here--we aren't emitting anything
+    let (tx, mut rx) = mpsc::channel(64);
+    tokio::spawn(async move {
+        while let Some(output) = rx.recv().await {
+            info!("output: {:?}", output)
+        }
+    });
+
+    let request = ResolverFunctionRequest {
+        execution_id: "5678".to_string(),
+        handler: "numberOfInputs".to_string(),
+        component: ResolverFunctionComponent {
+            data: ComponentView {
+                properties: serde_json::json!({ "foo": "bar", "baz": "quux" }),
+                kind: ComponentKind::Standard,
+            },
+            parents: vec![],
+        },
+        response_type: ResolverFunctionResponseType::Integer,
+        code_base64: base64_encode(
+            "function numberOfInputs(input) { return Object.keys(input)?.length ?? 0; }",
+        ),
+        timeout_secs: None,
+    };
+
+    let result = client
+        .execute_resolver_function(tx, &request)
+        .await
+        .expect("failed to execute resolver function");
+
+    match result {
+        FunctionResult::Success(success) => {
+            assert_eq!(success.execution_id, "5678");
+            assert_eq!(success.data, serde_json::json!(2));
+        }
+        FunctionResult::Failure(failure) => {
+            panic!("function did not succeed and should have: {failure:?}")
+        }
+    }
+    assert!(pool_metrics.snapshot().spawned >= 1);
+}
+
+#[test(tokio::test)]
+async fn rejects_wrong_cyclone_spec() {
+    let sandboxed_spec = CycloneSpec::SandboxedUds(
+        SandboxedUdsInstance::spec()
+            // The server is never started, so any program will do
+            .try_runtime_cmd_path("sh")
+            .expect("failed to find sh")
+            .cyclone_cmd_path("/usr/local/bin/cyclone")
+            .cyclone_decryption_key_path("/run/cyclone/decryption.key")
+            .lang_server_cmd_path("/usr/local/bin/lang-js")
+            .build()
+            .expect("failed to build cyclone spec"),
+    );
+    let config = Config::builder()
+        .nats(nats_config(nats_prefix()))
+        .cyclone_spec(sandboxed_spec)
+        .build()
+        .expect("failed to build spec");
+    match Server::for_cyclone_uds(config).await {
+        Err(ServerError::WrongCycloneSpec("LocalUds", _)) => {}
+        Err(err) => panic!("unexpected error: {err:?}"),
+        Ok(_) => panic!("local uds server should not use a sandboxed spec"),
+    }
+
+    let mut config_file = veritech_server::ConfigFile::default_local_uds();
+    veritech_server::detect_and_configure_development(&mut config_file)
+        .expect("failed to determine test configuration");
+    let local_spec = CycloneSpec::LocalUds(
+        LocalUdsInstance::spec()
+            .try_cyclone_cmd_path(config_file.cyclone.cyclone_cmd_path())
+            .expect("failed to setup cyclone_cmd_path")
+            .cyclone_decryption_key_path(config_file.cyclone.cyclone_decryption_key_path())
+            .try_lang_server_cmd_path(config_file.cyclone.lang_server_cmd_path())
+            .expect("failed to setup lang_js_cmd_path")
+            .build()
+            .expect("failed to build cyclone spec"),
+    );
+    let config = Config::builder()
+        .nats(nats_config(nats_prefix()))
+        .cyclone_spec(local_spec)
+        .build()
+        .expect("failed to build spec");
+    match Server::for_cyclone_sandboxed_uds(config).await {
+        Err(ServerError::WrongCycloneSpec("SandboxedUds", _)) => {}
+        Err(err) => panic!("unexpected error: {err:?}"),
+        Ok(_) => panic!("sandboxed server should not use a local uds spec"),
+    }
+}
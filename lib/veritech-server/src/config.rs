@@ -9,7 +9,8 @@ use buck2_resources::Buck2Resources;
 use deadpool_cyclone::{
     instance::cyclone::{
         LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpSocketStrategy, LocalUdsInstance,
-        LocalUdsInstanceSpec, LocalUdsSocketStrategy,
+        LocalUdsInstanceSpec, LocalUdsSocketStrategy, SandboxRuntime, SandboxedUdsInstance,
+        SandboxedUdsInstanceSpec,
     },
     Instance,
 };
@@ -50,6 +51,9 @@ pub struct Config {
     nats: NatsConfig,
 
     cyclone_spec: CycloneSpec,
+
+    #[builder(default)]
+    cyclone_pool_warm_instances: usize,
 }
 
 #[remain::sorted]
@@ -57,6 +61,7 @@ pub struct Config {
 pub enum CycloneSpec {
     LocalHttp(LocalHttpInstanceSpec),
     LocalUds(LocalUdsInstanceSpec),
+    SandboxedUds(SandboxedUdsInstanceSpec),
 }
 
 impl StandardConfig for Config {
@@ -97,6 +102,7 @@ impl TryFrom<ConfigFile> for Config {
 
         let mut config = Config::builder();
         config.nats(value.nats);
+        config.cyclone_pool_warm_instances(value.cyclone.pool_warm_instances());
         config.cyclone_spec(value.cyclone.try_into()?);
         config.build().map_err(Into::into)
     }
@@ -108,6 +114,11 @@ impl Config {
         &self.cyclone_spec
     }
 
+    /// Gets the number of spawned cyclone instances the pool keeps ready.
+    pub fn cyclone_pool_warm_instances(&self) -> usize {
+        self.cyclone_pool_warm_instances
+    }
+
     /// Gets a reference to the config's nats.
     #[must_use]
     pub fn nats(&self) -> &NatsConfig {
@@ -170,12 +181,24 @@ pub enum CycloneConfig {
         watch_timeout: Option<Duration>,
         #[serde(default = "default_limit_requests")]
         limit_requets: Option<u32>,
+        #[serde(default)]
+        execution_timeout: Option<Duration>,
+        #[serde(default)]
+        lang_server_memory_limit_mib: Option<u64>,
+        #[serde(default)]
+        lang_server_cpu_limit: Option<Duration>,
+        #[serde(default)]
+        output_limit_bytes: Option<usize>,
+        #[serde(default)]
+        lang_server_network_isolation: bool,
         #[serde(default = "default_enable_endpoint")]
         ping: bool,
         #[serde(default = "default_enable_endpoint")]
         resolver: bool,
         #[serde(default = "default_enable_endpoint")]
         action: bool,
+        #[serde(default)]
+        pool_warm_instances: usize,
     },
     LocalUds {
         #[serde(default = "default_cyclone_cmd_path")]
@@ -190,12 +213,68 @@ pub enum CycloneConfig {
         watch_timeout: Option<Duration>,
         #[serde(default = "default_limit_requests")]
         limit_requets: Option<u32>,
+        #[serde(default)]
+        execution_timeout: Option<Duration>,
+        #[serde(default)]
+        lang_server_memory_limit_mib: Option<u64>,
+        #[serde(default)]
+        lang_server_cpu_limit: Option<Duration>,
+        #[serde(default)]
+        output_limit_bytes: Option<usize>,
+        #[serde(default)]
+        lang_server_network_isolation: bool,
+        #[serde(default = "default_enable_endpoint")]
+        ping: bool,
+        #[serde(default = "default_enable_endpoint")]
+        resolver: bool,
+        #[serde(default = "default_enable_endpoint")]
+        action: bool,
+        #[serde(default)]
+        pool_warm_instances: usize,
+    },
+    SandboxedUds {
+        #[serde(default)]
+        runtime: SandboxRuntime,
+        #[serde(default = "default_sandbox_runtime_cmd_path")]
+        runtime_cmd_path: String,
+        #[serde(default)]
+        image: Option<String>,
+        #[serde(default = "default_cyclone_cmd_path")]
+        cyclone_cmd_path: String,
+        #[serde(default = "default_cyclone_decryption_key_path")]
+        cyclone_decryption_key_path: String,
+        #[serde(default = "default_lang_server_cmd_path")]
+        lang_server_cmd_path: String,
+        #[serde(default)]
+        install_paths: Vec<PathBuf>,
+        #[serde(default)]
+        runtime_dir: Option<PathBuf>,
+        #[serde(default)]
+        network: bool,
+        #[serde(default)]
+        seccomp_profile_path: Option<PathBuf>,
+        #[serde(default)]
+        watch_timeout: Option<Duration>,
+        #[serde(default = "default_limit_requests")]
+        limit_requets: Option<u32>,
+        #[serde(default)]
+        execution_timeout: Option<Duration>,
+        #[serde(default)]
+        lang_server_memory_limit_mib: Option<u64>,
+        #[serde(default)]
+        lang_server_cpu_limit: Option<Duration>,
+        #[serde(default)]
+        output_limit_bytes: Option<usize>,
+        #[serde(default)]
+        lang_server_network_isolation: bool,
         #[serde(default = "default_enable_endpoint")]
         ping: bool,
         #[serde(default = "default_enable_endpoint")]
         resolver: bool,
         #[serde(default = "default_enable_endpoint")]
         action: bool,
+        #[serde(default)]
+        pool_warm_instances: usize,
     },
 }
 
@@ -208,9 +287,15 @@ impl CycloneConfig {
             socket_strategy: Default::default(),
             watch_timeout: Default::default(),
             limit_requets: default_limit_requests(),
+            execution_timeout: Default::default(),
+            lang_server_memory_limit_mib: Default::default(),
+            lang_server_cpu_limit: Default::default(),
+            output_limit_bytes: Default::default(),
+            lang_server_network_isolation: Default::default(),
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            pool_warm_instances: Default::default(),
         }
     }
 
@@ -222,9 +307,15 @@ impl CycloneConfig {
             socket_strategy: Default::default(),
             watch_timeout: Default::default(),
             limit_requets: default_limit_requests(),
+            execution_timeout: Default::default(),
+            lang_server_memory_limit_mib: Default::default(),
+            lang_server_cpu_limit: Default::default(),
+            output_limit_bytes: Default::default(),
+            lang_server_network_isolation: Default::default(),
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            pool_warm_instances: Default::default(),
         }
     }
 
@@ -236,6 +327,9 @@ impl CycloneConfig {
             CycloneConfig::LocalHttp {
                 cyclone_cmd_path, ..
             } => cyclone_cmd_path,
+            CycloneConfig::SandboxedUds {
+                cyclone_cmd_path, ..
+            } => cyclone_cmd_path,
         }
     }
 
@@ -247,6 +341,9 @@ impl CycloneConfig {
             CycloneConfig::LocalHttp {
                 cyclone_cmd_path, ..
             } => *cyclone_cmd_path = value,
+            CycloneConfig::SandboxedUds {
+                cyclone_cmd_path, ..
+            } => *cyclone_cmd_path = value,
         };
     }
 
@@ -260,6 +357,10 @@ impl CycloneConfig {
                 cyclone_decryption_key_path,
                 ..
             } => cyclone_decryption_key_path,
+            CycloneConfig::SandboxedUds {
+                cyclone_decryption_key_path,
+                ..
+            } => cyclone_decryption_key_path,
         }
     }
 
@@ -273,6 +374,10 @@ impl CycloneConfig {
                 cyclone_decryption_key_path,
                 ..
             } => *cyclone_decryption_key_path = value,
+            CycloneConfig::SandboxedUds {
+                cyclone_decryption_key_path,
+                ..
+            } => *cyclone_decryption_key_path = value,
         };
     }
 
@@ -286,6 +391,10 @@ impl CycloneConfig {
                 lang_server_cmd_path,
                 ..
             } => lang_server_cmd_path,
+            CycloneConfig::SandboxedUds {
+                lang_server_cmd_path,
+                ..
+            } => lang_server_cmd_path,
         }
     }
 
@@ -299,13 +408,35 @@ impl CycloneConfig {
                 lang_server_cmd_path,
                 ..
             } => *lang_server_cmd_path = value,
+            CycloneConfig::SandboxedUds {
+                lang_server_cmd_path,
+                ..
+            } => *lang_server_cmd_path = value,
         };
     }
 
+    pub fn pool_warm_instances(&self) -> usize {
+        match self {
+            CycloneConfig::LocalUds {
+                pool_warm_instances,
+                ..
+            } => *pool_warm_instances,
+            CycloneConfig::LocalHttp {
+                pool_warm_instances,
+                ..
+            } => *pool_warm_instances,
+            CycloneConfig::SandboxedUds {
+                pool_warm_instances,
+                ..
+            } => *pool_warm_instances,
+        }
+    }
+
     pub fn set_limit_requests(&mut self, value: impl Into<Option<u32>>) {
         match self {
             CycloneConfig::LocalUds { limit_requets, .. } => *limit_requets = value.into(),
             CycloneConfig::LocalHttp { limit_requets, .. } => *limit_requets = value.into(),
+            CycloneConfig::SandboxedUds { limit_requets, .. } => *limit_requets = value.into(),
         };
     }
 
@@ -313,6 +444,7 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { ping, .. } => *ping = value,
             CycloneConfig::LocalHttp { ping, .. } => *ping = value,
+            CycloneConfig::SandboxedUds { ping, .. } => *ping = value,
         };
     }
 
@@ -320,6 +452,7 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { resolver, .. } => *resolver = value,
             CycloneConfig::LocalHttp { resolver, .. } => *resolver = value,
+            CycloneConfig::SandboxedUds { resolver, .. } => *resolver = value,
         };
     }
 
@@ -327,6 +460,7 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { action, .. } => *action = value,
             CycloneConfig::LocalHttp { action, .. } => *action = value,
+            CycloneConfig::SandboxedUds { action, .. } => *action = value,
         };
     }
 }
@@ -349,9 +483,15 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 socket_strategy,
                 watch_timeout,
                 limit_requets,
+                execution_timeout,
+                lang_server_memory_limit_mib,
+                lang_server_cpu_limit,
+                output_limit_bytes,
+                lang_server_network_isolation,
                 ping,
                 resolver,
                 action,
+                pool_warm_instances: _,
             } => {
                 let mut builder = LocalUdsInstance::spec();
                 builder
@@ -366,6 +506,19 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                     builder.watch_timeout(watch_timeout);
                 }
                 builder.limit_requests(limit_requets);
+                if let Some(execution_timeout) = execution_timeout {
+                    builder.execution_timeout(execution_timeout);
+                }
+                if let Some(lang_server_memory_limit_mib) = lang_server_memory_limit_mib {
+                    builder.lang_server_memory_limit_mib(lang_server_memory_limit_mib);
+                }
+                if let Some(lang_server_cpu_limit) = lang_server_cpu_limit {
+                    builder.lang_server_cpu_limit(lang_server_cpu_limit);
+                }
+                if let Some(output_limit_bytes) = output_limit_bytes {
+                    builder.output_limit_bytes(output_limit_bytes);
+                }
+                builder.lang_server_network_isolation(lang_server_network_isolation);
                 if ping {
                     builder.ping();
                 }
@@ -387,9 +540,15 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 socket_strategy,
                 watch_timeout,
                 limit_requets,
+                execution_timeout,
+                lang_server_memory_limit_mib,
+                lang_server_cpu_limit,
+                output_limit_bytes,
+                lang_server_network_isolation,
                 ping,
                 resolver,
                 action,
+                pool_warm_instances: _,
             } => {
                 let mut builder = LocalHttpInstance::spec();
                 builder
@@ -404,6 +563,19 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                     builder.watch_timeout(watch_timeout);
                 }
                 builder.limit_requests(limit_requets);
+                if let Some(execution_timeout) = execution_timeout {
+                    builder.execution_timeout(execution_timeout);
+                }
+                if let Some(lang_server_memory_limit_mib) = lang_server_memory_limit_mib {
+                    builder.lang_server_memory_limit_mib(lang_server_memory_limit_mib);
+                }
+                if let Some(lang_server_cpu_limit) = lang_server_cpu_limit {
+                    builder.lang_server_cpu_limit(lang_server_cpu_limit);
+                }
+                if let Some(output_limit_bytes) = output_limit_bytes {
+                    builder.output_limit_bytes(output_limit_bytes);
+                }
+                builder.lang_server_network_isolation(lang_server_network_isolation);
                 if ping {
                     builder.ping();
                 }
@@ -418,6 +590,81 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
                 ))
             }
+            CycloneConfig::SandboxedUds {
+                runtime,
+                runtime_cmd_path,
+                image,
+                cyclone_cmd_path,
+                cyclone_decryption_key_path,
+                lang_server_cmd_path,
+                install_paths,
+                runtime_dir,
+                network,
+                seccomp_profile_path,
+                watch_timeout,
+                limit_requets,
+                execution_timeout,
+                lang_server_memory_limit_mib,
+                lang_server_cpu_limit,
+                output_limit_bytes,
+                lang_server_network_isolation,
+                ping,
+                resolver,
+                action,
+                pool_warm_instances: _,
+            } => {
+                let mut builder = SandboxedUdsInstance::spec();
+                builder.runtime(runtime);
+                builder
+                    .try_runtime_cmd_path(runtime_cmd_path)
+                    .map_err(ConfigError::cyclone_spec_build)?;
+                if let Some(image) = image {
+                    builder.image(image);
+                }
+                builder.cyclone_cmd_path(cyclone_cmd_path);
+                builder.cyclone_decryption_key_path(cyclone_decryption_key_path);
+                builder.lang_server_cmd_path(lang_server_cmd_path);
+                for install_path in install_paths {
+                    builder.install_path(install_path);
+                }
+                if let Some(runtime_dir) = runtime_dir {
+                    builder.runtime_dir(runtime_dir);
+                }
+                builder.network(network);
+                if let Some(seccomp_profile_path) = seccomp_profile_path {
+                    builder.seccomp_profile_path(seccomp_profile_path);
+                }
+                if let Some(watch_timeout) = watch_timeout {
+                    builder.watch_timeout(watch_timeout);
+                }
+                builder.limit_requests(limit_requets);
+                if let Some(execution_timeout) = execution_timeout {
+                    builder.execution_timeout(execution_timeout);
+                }
+                if let Some(lang_server_memory_limit_mib) = lang_server_memory_limit_mib {
+                    builder.lang_server_memory_limit_mib(lang_server_memory_limit_mib);
+                }
+                if let Some(lang_server_cpu_limit) = lang_server_cpu_limit {
+                    builder.lang_server_cpu_limit(lang_server_cpu_limit);
+                }
+                if let Some(output_limit_bytes) = output_limit_bytes {
+                    builder.output_limit_bytes(output_limit_bytes);
+                }
+                builder.lang_server_network_isolation(lang_server_network_isolation);
+                if ping {
+                    builder.ping();
+                }
+                if resolver {
+                    builder.resolver();
+                }
+                if action {
+                    builder.action();
+                }
+
+                Ok(Self::SandboxedUds(
+                    builder.build().map_err(ConfigError::cyclone_spec_build)?,
+                ))
+            }
         }
     }
 }
@@ -434,6 +681,10 @@ fn default_lang_server_cmd_path() -> String {
     "/usr/local/bin/lang-js".to_string()
 }
 
+fn default_sandbox_runtime_cmd_path() -> String {
+    "/usr/bin/bwrap".to_string()
+}
+
 fn default_limit_requests() -> Option<u32> {
     Some(1)
 }
@@ -14,4 +14,7 @@ pub(crate) use crate::{
     publisher::{Publisher, PublisherError},
     subscriber::FunctionSubscriber,
 };
-pub use deadpool_cyclone::{instance::cyclone::LocalUdsInstance, Instance};
+pub use deadpool_cyclone::{
+    instance::cyclone::{LocalUdsInstance, SandboxedUdsInstance, SandboxedUdsInstanceSpec},
+    Instance,
+};
@@ -1,15 +1,17 @@
 use chrono::Utc;
 use deadpool_cyclone::{
-    instance::cyclone::LocalUdsInstanceSpec, ActionRunRequest, ActionRunResultSuccess,
-    CycloneClient, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Manager,
+    instance::cyclone::{LocalUdsInstanceSpec, SandboxedUdsInstanceSpec},
+    ActionRunRequest, ActionRunResultSuccess, CycloneClient, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, FunctionResultFailureErrorKind, Instance, Manager, ManagerMetrics,
     Pool, ProgressMessage, ReconciliationRequest, ReconciliationResultSuccess,
     ResolverFunctionRequest, ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
+    SchemaVariantDefinitionResultSuccess, Spec, UnixStream, ValidationRequest,
+    ValidationResultSuccess,
 };
 use futures::{channel::oneshot, join, StreamExt};
 use nats_subscriber::Request;
 use si_data_nats::NatsClient;
-use std::io;
+use std::{fmt, io, sync::Arc, time::Duration};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
@@ -19,6 +21,9 @@ use tokio::{
 
 use crate::{config::CycloneSpec, Config, FunctionSubscriber, Publisher, PublisherError};
 
+/// How often the metrics of the cyclone instance pool are reported.
+const CYCLONE_POOL_METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ServerError {
@@ -58,10 +63,17 @@ pub enum ServerError {
 
 type ServerResult<T> = Result<T, ServerError>;
 
-pub struct Server {
+/// A veritech server, running function executions on instances of a cyclone [`Spec`].
+pub struct Server<S = LocalUdsInstanceSpec>
+where
+    S: Spec + Send + Sync + 'static,
+    S::Instance: Send + 'static,
+    S::Error: fmt::Debug + Send + 'static,
+{
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
+    cyclone_pool_metrics: Arc<ManagerMetrics>,
     shutdown_broadcast_tx: broadcast::Sender<()>,
     shutdown_tx: mpsc::Sender<ShutdownSource>,
     shutdown_rx: oneshot::Receiver<()>,
@@ -88,48 +100,77 @@ impl Server {
                 // Ok(Server { nats, cyclone_pool })
                 unimplemented!("get ready for a surprise!!")
             }
-            wrong @ CycloneSpec::LocalUds(_) => Err(ServerError::WrongCycloneSpec(
-                "LocalHttp",
-                Box::new(wrong.clone()),
-            )),
+            wrong @ (CycloneSpec::LocalUds(_) | CycloneSpec::SandboxedUds(_)) => Err(
+                ServerError::WrongCycloneSpec("LocalHttp", Box::new(wrong.clone())),
+            ),
         }
     }
 
     #[instrument(name = "veritech.init.cyclone.uds", skip(config))]
     pub async fn for_cyclone_uds(config: Config) -> ServerResult<Server> {
         match config.cyclone_spec() {
-            CycloneSpec::LocalUds(spec) => {
-                let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
-                // Note the channel parameter corresponds to the number of channels that may be
-                // maintained when the sender is guaranteeing delivery. While this number may end
-                // of being related to the number of subscribers, it's not
-                // necessarily the same number.
-                let (shutdown_broadcast_tx, _) = broadcast::channel(16);
-
-                let nats = connect_to_nats(&config).await?;
-                let manager = Manager::new(spec.clone());
-                let cyclone_pool = Pool::builder(manager)
-                    .build()
-                    .map_err(|err| ServerError::CycloneSpec(Box::new(err)))?;
-
-                let graceful_shutdown_rx =
-                    prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx.clone())?;
-
-                Ok(Server {
-                    nats,
-                    subject_prefix: config.subject_prefix().map(|s| s.to_string()),
-                    cyclone_pool,
-                    shutdown_broadcast_tx,
-                    shutdown_tx,
-                    shutdown_rx: graceful_shutdown_rx,
-                })
-            }
-            wrong @ CycloneSpec::LocalHttp(_) => Err(ServerError::WrongCycloneSpec(
-                "LocalUds",
-                Box::new(wrong.clone()),
-            )),
+            CycloneSpec::LocalUds(spec) => Self::from_spec(&config, spec.clone()).await,
+            wrong @ (CycloneSpec::LocalHttp(_) | CycloneSpec::SandboxedUds(_)) => Err(
+                ServerError::WrongCycloneSpec("LocalUds", Box::new(wrong.clone())),
+            ),
         }
     }
+}
+
+impl Server<SandboxedUdsInstanceSpec> {
+    #[instrument(name = "veritech.init.cyclone.sandboxed_uds", skip(config))]
+    pub async fn for_cyclone_sandboxed_uds(
+        config: Config,
+    ) -> ServerResult<Server<SandboxedUdsInstanceSpec>> {
+        match config.cyclone_spec() {
+            CycloneSpec::SandboxedUds(spec) => Self::from_spec(&config, spec.clone()).await,
+            wrong @ (CycloneSpec::LocalHttp(_) | CycloneSpec::LocalUds(_)) => Err(
+                ServerError::WrongCycloneSpec("SandboxedUds", Box::new(wrong.clone())),
+            ),
+        }
+    }
+}
+
+impl<S, I, E> Server<S>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    async fn from_spec(config: &Config, spec: S) -> ServerResult<Self> {
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
+        // Note the channel parameter corresponds to the number of channels that may be
+        // maintained when the sender is guaranteeing delivery. While this number may end
+        // of being related to the number of subscribers, it's not
+        // necessarily the same number.
+        let (shutdown_broadcast_tx, _) = broadcast::channel(16);
+
+        let nats = connect_to_nats(config).await?;
+        let manager = Manager::with_warm_instances(spec, config.cyclone_pool_warm_instances());
+        manager.warm_up();
+        let cyclone_pool_metrics = manager.metrics();
+        let cyclone_pool = Pool::builder(manager)
+            .build()
+            .map_err(|err| ServerError::CycloneSpec(Box::new(err)))?;
+
+        let graceful_shutdown_rx =
+            prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx.clone())?;
+
+        Ok(Server {
+            nats,
+            subject_prefix: config.subject_prefix().map(|s| s.to_string()),
+            cyclone_pool,
+            cyclone_pool_metrics,
+            shutdown_broadcast_tx,
+            shutdown_tx,
+            shutdown_rx: graceful_shutdown_rx,
+        })
+    }
+
+    /// Gets the metrics of the cyclone instance pool, such as the number of warm instances.
+    pub fn cyclone_pool_metrics(&self) -> Arc<ManagerMetrics> {
+        self.cyclone_pool_metrics.clone()
+    }
 
     /// Gets a shutdown handle that can trigger the server's graceful shutdown process.
     pub fn shutdown_handle(&self) -> VeritechShutdownHandle {
@@ -137,10 +178,13 @@ impl Server {
             shutdown_tx: self.shutdown_tx.clone(),
         }
     }
-}
 
-impl Server {
     pub async fn run(self) -> ServerResult<()> {
+        tokio::spawn(report_cyclone_pool_metrics_task(
+            self.cyclone_pool_metrics.clone(),
+            self.shutdown_broadcast_tx.subscribe(),
+        ));
+
         let _ = join!(
             process_resolver_function_requests_task(
                 self.nats.clone(),
@@ -176,11 +220,47 @@ impl Server {
 
         let _ = self.shutdown_rx.await;
         info!("received graceful shutdown, terminating server instance");
+        self.cyclone_pool.manager().shutdown().await;
 
         Ok(())
     }
 }
 
+async fn report_cyclone_pool_metrics_task(
+    metrics: Arc<ManagerMetrics>,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    let mut interval = tokio::time::interval(CYCLONE_POOL_METRICS_INTERVAL);
+
+    loop {
+        tokio::select! {
+            // Got a broadcasted shutdown message
+            _ = shutdown_broadcast_rx.recv() => {
+                trace!("report cyclone pool metrics task received shutdown");
+                break;
+            }
+            _ = interval.tick() => {
+                let snapshot = metrics.snapshot();
+                let millis =
+                    |latency: Duration| u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+                info!(
+                    warm = snapshot.warm,
+                    warm_hits = snapshot.warm_hits,
+                    warm_misses = snapshot.warm_misses,
+                    spawned = snapshot.spawned,
+                    spawn_failures = snapshot.spawn_failures,
+                    last_spawn_latency_ms = millis(snapshot.last_spawn_latency),
+                    mean_spawn_latency_ms = millis(snapshot.mean_spawn_latency),
+                    recycled = snapshot.recycled,
+                    recycle_failures = snapshot.recycle_failures,
+                    retired = snapshot.retired,
+                    "cyclone pool metrics",
+                );
+            }
+        }
+    }
+}
+
 pub struct VeritechShutdownHandle {
     shutdown_tx: mpsc::Sender<ShutdownSource>,
 }
@@ -198,12 +278,16 @@ impl VeritechShutdownHandle {
 // these would do the trick, and as a result the first 2 impls are here and not split apart into
 // their own modules.
 
-async fn process_resolver_function_requests_task(
+async fn process_resolver_function_requests_task<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) = process_resolver_function_requests(
         nats,
         subject_prefix,
@@ -216,12 +300,17 @@ async fn process_resolver_function_requests_task(
     }
 }
 
-async fn process_resolver_function_requests(
+async fn process_resolver_function_requests<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let mut requests =
         FunctionSubscriber::resolver_function(&nats, subject_prefix.as_deref()).await?;
 
@@ -266,11 +355,15 @@ async fn process_resolver_function_requests(
     Ok(())
 }
 
-async fn resolver_function_request_task(
+async fn resolver_function_request_task<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ResolverFunctionRequest>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = match reply_mailbox {
         Some(reply_mailbox) => reply_mailbox,
@@ -291,7 +384,7 @@ async fn resolver_function_request_task(
             FunctionResultFailure {
                 execution_id,
                 error: FunctionResultFailureError {
-                    kind: "veritechServer".to_string(),
+                    kind: FunctionResultFailureErrorKind::VeritechServer,
                     message: "failed to finalize output by sending final message".to_string(),
                 },
                 timestamp: timestamp(),
@@ -311,7 +404,7 @@ async fn resolver_function_request_task(
                 FunctionResultFailure {
                     execution_id,
                     error: FunctionResultFailureError {
-                        kind: "veritechServer".to_string(),
+                        kind: FunctionResultFailureErrorKind::VeritechServer,
                         message: err.to_string(),
                     },
                     timestamp: timestamp(),
@@ -325,11 +418,16 @@ async fn resolver_function_request_task(
     };
 }
 
-async fn resolver_function_request(
+async fn resolver_function_request<S, I, E>(
     publisher: &Publisher<'_>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     cyclone_request: ResolverFunctionRequest,
-) -> ServerResult<FunctionResult<ResolverFunctionResultSuccess>> {
+) -> ServerResult<FunctionResult<ResolverFunctionResultSuccess>>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let mut client = cyclone_pool
         .get()
         .await
@@ -360,12 +458,16 @@ async fn resolver_function_request(
     Ok(function_result)
 }
 
-async fn process_validation_requests_task(
+async fn process_validation_requests_task<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) =
         process_validation_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx).await
     {
@@ -373,12 +475,17 @@ async fn process_validation_requests_task(
     }
 }
 
-async fn process_validation_requests(
+async fn process_validation_requests<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let mut requests = FunctionSubscriber::validation(&nats, subject_prefix.as_deref()).await?;
 
     loop {
@@ -422,21 +529,30 @@ async fn process_validation_requests(
     Ok(())
 }
 
-async fn validation_request_task(
+async fn validation_request_task<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ValidationRequest>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) = validation_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "validation execution failed");
     }
 }
 
-async fn validation_request(
+async fn validation_request<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ValidationRequest>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
 
@@ -473,12 +589,16 @@ async fn validation_request(
     Ok(())
 }
 
-async fn process_schema_variant_definition_requests_task(
+async fn process_schema_variant_definition_requests_task<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) = process_schema_variant_definition_requests(
         nats,
         subject_prefix,
@@ -491,12 +611,17 @@ async fn process_schema_variant_definition_requests_task(
     }
 }
 
-async fn process_schema_variant_definition_requests(
+async fn process_schema_variant_definition_requests<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let mut requests =
         FunctionSubscriber::schema_variant_definition(&nats, subject_prefix.as_deref()).await?;
 
@@ -541,21 +666,30 @@ async fn process_schema_variant_definition_requests(
     Ok(())
 }
 
-async fn schema_variant_definition_request_task(
+async fn schema_variant_definition_request_task<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<SchemaVariantDefinitionRequest>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) = schema_variant_definition_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "schema variant definition execution failed");
     }
 }
 
-async fn schema_variant_definition_request(
+async fn schema_variant_definition_request<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<SchemaVariantDefinitionRequest>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
 
@@ -593,12 +727,16 @@ async fn schema_variant_definition_request(
     Ok(())
 }
 
-async fn process_action_run_requests_task(
+async fn process_action_run_requests_task<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) =
         process_action_run_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx).await
     {
@@ -606,12 +744,17 @@ async fn process_action_run_requests_task(
     }
 }
 
-async fn process_action_run_requests(
+async fn process_action_run_requests<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let mut requests = FunctionSubscriber::action_run(&nats, subject_prefix.as_deref()).await?;
 
     loop {
@@ -655,21 +798,30 @@ async fn process_action_run_requests(
     Ok(())
 }
 
-async fn action_run_request_task(
+async fn action_run_request_task<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ActionRunRequest>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) = action_run_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "action run execution failed");
     }
 }
 
-async fn action_run_request(
+async fn action_run_request<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ActionRunRequest>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
 
@@ -707,12 +859,16 @@ async fn action_run_request(
     Ok(())
 }
 
-async fn process_reconciliation_requests_task(
+async fn process_reconciliation_requests_task<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) =
         process_reconciliation_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx)
             .await
@@ -721,12 +877,17 @@ async fn process_reconciliation_requests_task(
     }
 }
 
-async fn process_reconciliation_requests(
+async fn process_reconciliation_requests<S, I, E>(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let mut requests = FunctionSubscriber::reconciliation(&nats, subject_prefix.as_deref()).await?;
 
     loop {
@@ -770,21 +931,30 @@ async fn process_reconciliation_requests(
     Ok(())
 }
 
-async fn reconciliation_request_task(
+async fn reconciliation_request_task<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ReconciliationRequest>,
-) {
+) where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     if let Err(err) = reconciliation_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "reconciliation execution failed");
     }
 }
 
-async fn reconciliation_request(
+async fn reconciliation_request<S, I, E>(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: Pool<S>,
     request: Request<ReconciliationRequest>,
-) -> ServerResult<()> {
+) -> ServerResult<()>
+where
+    S: Spec<Instance = I, Error = E> + Send + Sync + 'static,
+    I: Instance<Error = E> + CycloneClient<UnixStream> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
 